@@ -0,0 +1,121 @@
+//! Loading job files for `--config`
+
+use anyhow::{Context, Result};
+use imewlconverter_core::pipeline::JobSpec;
+use std::path::Path;
+
+/// Parse a job file, TOML or YAML by extension
+pub fn parse_job(content: &str, yaml: bool) -> Result<JobSpec> {
+    if yaml {
+        Ok(serde_yaml::from_str(content)?)
+    } else {
+        Ok(toml::from_str(content)?)
+    }
+}
+
+/// Load a job file, with relative paths resolved against its directory
+pub fn load_job(path: &Path) -> Result<JobSpec> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read job file {}", path.display()))?;
+    let yaml = matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yaml" | "yml")
+    );
+    let mut spec = parse_job(&content, yaml)
+        .with_context(|| format!("Invalid job file {}", path.display()))?;
+    spec.resolve_paths(path.parent().unwrap_or(Path::new(".")));
+    Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imewlconverter_core::merge::MergeStrategy;
+    use imewlconverter_core::pipeline::job::RankStrategy;
+    use imewlconverter_core::rank::{CombineMode, RankFallback};
+
+    #[test]
+    fn test_parse_job() {
+        let toml = r#"
+            merge_strategy = "sum"
+            rank = { strategy = "position", start = 100, step = 1 }
+
+            [[inputs]]
+            path = "a.txt"
+            format = "rime"
+            weight = 0.5
+
+            [[outputs]]
+            path = "out.txt"
+            format = "sogou"
+        "#;
+        let yaml = "
+merge_strategy: sum
+rank: { strategy: position, start: 100, step: 1 }
+inputs:
+  - path: a.txt
+    format: rime
+    weight: 0.5
+outputs:
+  - { path: out.txt, format: sogou }
+";
+        let spec = parse_job(toml, false).unwrap();
+        assert_eq!(spec, parse_job(yaml, true).unwrap());
+        assert_eq!(spec.merge_strategy, MergeStrategy::SumRanks);
+        assert_eq!(
+            spec.rank,
+            Some(RankStrategy::Position {
+                start: 100,
+                step: 1
+            })
+        );
+        assert_eq!(spec.inputs[0].weight, 0.5);
+
+        assert!(parse_job("inputs = []\noutputs = []\nunknown = 1", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_combined_rank() {
+        let toml = r#"
+            inputs = []
+            outputs = []
+
+            [rank]
+            strategy = "combine"
+            mode = "max"
+            existing = 0.3
+
+            [[rank.generators]]
+            strategy = "corpus"
+            path = "freq.tsv"
+            frequencies = true
+            fallback = "characters"
+            weight = 0.7
+
+            [[rank.generators]]
+            strategy = "frequency"
+        "#;
+        let mut spec = parse_job(toml, false).unwrap();
+        spec.resolve_paths(Path::new("jobs"));
+        let Some(RankStrategy::Combine {
+            mode,
+            existing,
+            generators,
+        }) = spec.rank
+        else {
+            panic!("expected a combined rank strategy");
+        };
+        assert_eq!(mode, CombineMode::Max);
+        assert_eq!(existing, 0.3);
+        assert_eq!(
+            generators[0].strategy,
+            RankStrategy::Corpus {
+                path: Path::new("jobs").join("freq.tsv"),
+                frequencies: true,
+                fallback: RankFallback::Characters,
+            }
+        );
+        assert_eq!(generators[0].weight, 0.7);
+        assert_eq!(generators[1].weight, 1.0);
+    }
+}
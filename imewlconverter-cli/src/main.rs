@@ -3,55 +3,412 @@
 //! Command-line interface for converting between different IME dictionary formats.
 
 use anyhow::{Context, Result};
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use imewlconverter_core::*;
+use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
+use tracing_subscriber::filter::LevelFilter;
+
+mod job;
+#[cfg(feature = "server")]
+mod server;
+
+/// Progress bar on stderr showing the current pipeline stage
+struct ProgressBarSink {
+    bar: ProgressBar,
+}
+
+impl ProgressBarSink {
+    fn new() -> Self {
+        let bar = ProgressBar::new(0);
+        bar.set_style(
+            ProgressStyle::with_template("{msg:<16} [{bar:40}] {pos}/{len} ({eta})")
+                .expect("valid progress template")
+                .progress_chars("=> "),
+        );
+        ProgressBarSink { bar }
+    }
+}
+
+impl pipeline::ProgressSink for ProgressBarSink {
+    fn start(&self, stage: pipeline::ProgressStage, total: usize) {
+        self.bar.reset();
+        self.bar.set_length(total as u64);
+        self.bar.set_message(stage.name());
+    }
+
+    fn advance(&self, _stage: pipeline::ProgressStage, processed: usize) {
+        self.bar.set_position(processed as u64);
+    }
+
+    fn finish(&self, _stage: pipeline::ProgressStage) {
+        self.bar.finish_and_clear();
+    }
+}
+
+/// Print a status message, to stderr when the words are written to stdout
+macro_rules! status {
+    ($to_stderr:expr, $($arg:tt)*) => {
+        if $to_stderr {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// Write the conversion statistics as pretty-printed JSON
+fn write_report(path: &std::path::Path, report: &report::ConversionReport) -> Result<()> {
+    let json = serde_json::to_string_pretty(report)?;
+    std::fs::write(path, json).with_context(|| format!("Failed to write report {}", path.display()))
+}
+
+/// Print the registered formats with their direction and default encoding
+fn list_formats(registry: &registry::FormatRegistry) {
+    println!(
+        "{:<16} {:<14} {:<10} Description",
+        "Format", "Direction", "Encoding"
+    );
+    for format in registry.formats() {
+        let direction = match (format.can_import(), format.can_export()) {
+            (true, true) => "import/export",
+            (true, false) => "import",
+            (false, true) => "export",
+            (false, false) => continue,
+        };
+        let mut description = format.description().to_string();
+        if !format.aliases().is_empty() {
+            description.push_str(&format!(" (alias: {})", format.aliases().join(", ")));
+        }
+        println!(
+            "{:<16} {:<14} {:<10} {}",
+            format.name(),
+            direction,
+            format.encoding().unwrap_or("binary"),
+            description
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Translation {
+    /// Simplified to Traditional Chinese
+    S2t,
+    /// Traditional to Simplified Chinese
+    T2s,
+    /// Simplified to Traditional Chinese (Taiwan)
+    S2tw,
+    /// Simplified to Traditional Chinese (Hong Kong)
+    S2hk,
+    /// Traditional Chinese to Japanese Shinjitai
+    T2jp,
+}
+
+impl From<Translation> for translate::TranslationType {
+    fn from(translation: Translation) -> Self {
+        match translation {
+            Translation::S2t => translate::TranslationType::ToTraditional,
+            Translation::T2s => translate::TranslationType::ToSimplified,
+            Translation::S2tw => translate::TranslationType::ToTraditionalTaiwan,
+            Translation::S2hk => translate::TranslationType::ToTraditionalHongKong,
+            Translation::T2jp => translate::TranslationType::ToJapanese,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OnError {
+    /// Stop at the first malformed line
+    Strict,
+    /// Print a warning for each malformed line and skip it
+    Warn,
+    /// Skip malformed lines silently
+    Skip,
+}
+
+impl From<OnError> for import::ErrorPolicy {
+    fn from(on_error: OnError) -> Self {
+        match on_error {
+            OnError::Strict => import::ErrorPolicy::Strict,
+            OnError::Warn => import::ErrorPolicy::Warn,
+            OnError::Skip => import::ErrorPolicy::Skip,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
-enum InputFormat {
-    /// Baidu Pinyin format
-    BaiduPinyin,
-    /// Chinese Pyim format
-    ChinesePyim,
-    /// FIT input format
-    FitInput,
-    /// Google Pinyin format
-    GooglePinyin,
-    /// Libpinyin format
-    Libpinyin,
-    /// Microsoft Pinyin format
-    MsPinyin,
-    /// Pinyin Jiajia format
+enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => LevelFilter::OFF,
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per event, for log collectors
+    Json,
+}
+
+/// Log the diagnostics of the core library to stderr
+fn init_logging(level: LogLevel, format: LogFormat) {
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(LevelFilter::from(level));
+    match format {
+        LogFormat::Text => subscriber.without_time().with_target(false).init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CodeNormalization {
+    /// Lowercase the codes
+    Lowercase,
+    /// Remove whitespace inside the codes
+    StripWhitespace,
+    /// Convert full-width letters and digits to half-width
+    HalfWidth,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum MergeStrategy {
+    /// Keep every entry of every file
+    Concat,
+    /// The entry of the earliest file wins
+    KeepFirst,
+    /// Entries of later files override earlier ones
+    Override,
+    /// Add up the ranks of the same entry
+    Sum,
+    /// Keep the highest rank of the same entry
+    Max,
+}
+
+impl From<MergeStrategy> for merge::MergeStrategy {
+    fn from(strategy: MergeStrategy) -> Self {
+        match strategy {
+            MergeStrategy::Concat => merge::MergeStrategy::Concat,
+            MergeStrategy::KeepFirst => merge::MergeStrategy::KeepFirst,
+            MergeStrategy::Override => merge::MergeStrategy::Override,
+            MergeStrategy::Sum => merge::MergeStrategy::SumRanks,
+            MergeStrategy::Max => merge::MergeStrategy::MaxRank,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Sort {
+    /// Keep the input order
+    Input,
+    /// Highest rank first
+    Rank,
+    /// By code
+    Code,
+    /// By Unicode code point of the word
+    Word,
+    /// By pinyin of the word
+    Pinyin,
+}
+
+impl From<Sort> for sort::SortOrder {
+    fn from(order: Sort) -> Self {
+        match order {
+            Sort::Input => sort::SortOrder::Input,
+            Sort::Rank => sort::SortOrder::RankDescending,
+            Sort::Code => sort::SortOrder::Code,
+            Sort::Word => sort::SortOrder::Word,
+            Sort::Pinyin => sort::SortOrder::WordPinyin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum English {
+    /// Export English words with their spelling as code
+    Keep,
+    /// Leave English words out
+    Drop,
+    /// Write English words to a separate output file
+    Separate,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Idioms {
+    /// Export only idioms
+    Only,
+    /// Leave idioms out
+    Exclude,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Shuangpin {
+    /// 小鹤双拼
+    Xiaohe,
+    /// 自然码
+    Ziranma,
+    /// 微软双拼
+    Microsoft,
+    /// 智能ABC
+    ZhinengAbc,
+    /// 紫光拼音
+    Ziguang,
+    /// 拼音加加
     PinyinJiajia,
-    /// QQ Pinyin text format
-    QqPinyin,
-    /// QQ Wubi format
-    QqWubi,
-    /// Rime format
-    Rime,
-    /// Sina Pinyin format
-    SinaPinyin,
-    /// Sogou Pinyin text format
-    SogouPinyin,
-    /// Sogou Scel binary format
-    SogouScel,
-    /// Wubi 86 format
-    Wubi86,
-    /// Wubi 98 format
-    Wubi98,
-    /// Wubi New Age format
-    WubiNewAge,
-    /// Ziguang Pinyin format
-    ZiguangPinyin,
-}
-
-#[derive(Debug, Clone, Copy, ValueEnum)]
-enum OutputFormat {
-    /// QQ Pinyin text format
-    QqPinyin,
-    /// Rime format
-    Rime,
-    // TODO: Add more formats as they are implemented
+    /// 星空键道
+    Xingkong,
+    /// 大牛双拼
+    Daniu,
+    /// 小浪双拼
+    Xiaolang,
+}
+
+impl From<Shuangpin> for generate::ShuangpinScheme {
+    fn from(scheme: Shuangpin) -> Self {
+        match scheme {
+            Shuangpin::Xiaohe => generate::ShuangpinScheme::Xiaohe,
+            Shuangpin::Ziranma => generate::ShuangpinScheme::Ziranma,
+            Shuangpin::Microsoft => generate::ShuangpinScheme::Microsoft,
+            Shuangpin::ZhinengAbc => generate::ShuangpinScheme::ZhinengAbc,
+            Shuangpin::Ziguang => generate::ShuangpinScheme::Ziguang,
+            Shuangpin::PinyinJiajia => generate::ShuangpinScheme::PinyinJiajia,
+            Shuangpin::Xingkong => generate::ShuangpinScheme::Xingkong,
+            Shuangpin::Daniu => generate::ShuangpinScheme::Daniu,
+            Shuangpin::Xiaolang => generate::ShuangpinScheme::Xiaolang,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PinyinTones {
+    /// Drop the tones: ni hao
+    Plain,
+    /// Tone numbers: ni3 hao3
+    Numbered,
+}
+
+impl From<PinyinTones> for helpers::pinyin::ToneStyle {
+    fn from(tones: PinyinTones) -> Self {
+        match tones {
+            PinyinTones::Plain => helpers::pinyin::ToneStyle::Plain,
+            PinyinTones::Numbered => helpers::pinyin::ToneStyle::Numbered,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Fuzzy {
+    /// Replace the codes with their fuzzy form
+    Rewrite,
+    /// Add an entry for every fuzzy variant
+    Expand,
+}
+
+impl From<Fuzzy> for transform::fuzzy_pinyin::FuzzyMode {
+    fn from(fuzzy: Fuzzy) -> Self {
+        match fuzzy {
+            Fuzzy::Rewrite => transform::fuzzy_pinyin::FuzzyMode::Rewrite,
+            Fuzzy::Expand => transform::fuzzy_pinyin::FuzzyMode::Expand,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum RankFallback {
+    /// Keep the rank of the input
+    Keep,
+    /// Use the count of the rarest character
+    Characters,
+}
+
+impl From<RankFallback> for rank::RankFallback {
+    fn from(fallback: RankFallback) -> Self {
+        match fallback {
+            RankFallback::Keep => rank::RankFallback::Keep,
+            RankFallback::Characters => rank::RankFallback::Characters,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Abbreviation {
+    /// First letter of each syllable (知识 → zs)
+    First,
+    /// Keep the initials zh, ch and sh whole (知识 → zhsh)
+    Compound,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Transcode {
+    /// Zhuyin (注音) codes to pinyin
+    ToPinyin,
+    /// Pinyin codes to Zhuyin
+    ToZhuyin,
+}
+
+impl From<Transcode> for transform::zhuyin::Transcode {
+    fn from(transcode: Transcode) -> Self {
+        match transcode {
+            Transcode::ToPinyin => transform::zhuyin::Transcode::ToPinyin,
+            Transcode::ToZhuyin => transform::zhuyin::Transcode::ToZhuyin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CodeLengthPolicy {
+    /// Cut longer codes to the maximum length
+    Truncate,
+    /// Drop words with longer codes
+    Drop,
+}
+
+impl From<CodeLengthPolicy> for filter::code_length::CodeLengthPolicy {
+    fn from(policy: CodeLengthPolicy) -> Self {
+        match policy {
+            CodeLengthPolicy::Truncate => filter::code_length::CodeLengthPolicy::Truncate,
+            CodeLengthPolicy::Drop => filter::code_length::CodeLengthPolicy::Drop,
+        }
+    }
+}
+
+fn export_split(args: &Args) -> export::ExportSplit {
+    if let Some(max) = args.split_entries {
+        export::ExportSplit::MaxEntries(max)
+    } else if let Some(max) = args.split_bytes {
+        export::ExportSplit::MaxBytes(max)
+    } else if args.split_by_letter {
+        export::ExportSplit::FirstLetter
+    } else {
+        export::ExportSplit::None
+    }
+}
+
+impl From<English> for export::EnglishPolicy {
+    fn from(english: English) -> Self {
+        match english {
+            English::Keep => export::EnglishPolicy::Keep,
+            English::Drop => export::EnglishPolicy::Drop,
+            English::Separate => export::EnglishPolicy::Separate,
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -59,22 +416,77 @@ enum OutputFormat {
 #[command(author = "studyzy <studyzy@163.com>")]
 #[command(version = VERSION)]
 #[command(about = "IME Word List Converter - Convert between different IME dictionary formats", long_about = None)]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 struct Args {
-    /// Input format
-    #[arg(short = 'i', long, value_enum)]
-    input_format: InputFormat,
+    /// Run the conversion declared in a TOML or YAML job file
+    #[arg(long, value_name = "FILE", exclusive = true)]
+    config: Option<PathBuf>,
+
+    /// Input format name or alias (see `list-formats`)
+    #[arg(short = 'i', long, required = true)]
+    input_format: Option<String>,
 
-    /// Input files
+    /// Input files, directories or quoted glob patterns (e.g. "dicts/*.scel");
+    /// `-` reads stdin. Files in .zip archives are imported by their extension
     #[arg(required = true)]
     input_files: Vec<PathBuf>,
 
-    /// Output format
-    #[arg(short = 'o', long, value_enum)]
-    output_format: OutputFormat,
+    /// Also convert the files in subdirectories of input directories
+    #[arg(short = 'r', long)]
+    recursive: bool,
+
+    /// Text encoding of the input files: auto, gbk, utf-8, utf-16le, ...
+    /// (defaults to the input format's encoding)
+    #[arg(long, value_parser = parse_encoding)]
+    input_encoding: Option<String>,
+
+    /// How to handle malformed input lines
+    #[arg(long, value_enum, default_value = "skip")]
+    on_error: OnError,
+
+    /// Import what can be recovered from corrupt binary inputs (SCEL),
+    /// reporting the skipped byte ranges unless --on-error is skip
+    #[arg(long)]
+    salvage: bool,
+
+    /// Convert tone-marked pinyin codes of the input (nǐ hǎo)
+    #[arg(long, value_enum)]
+    pinyin_tones: Option<PinyinTones>,
+
+    /// Rank of input entries without a frequency column
+    #[arg(long, value_name = "RANK")]
+    default_rank: Option<i32>,
+
+    /// Re-rank the words with the counts of a word frequency table
+    /// ("word<TAB>frequency" lines)
+    #[arg(long, value_name = "FILE", conflicts_with = "rank_corpus")]
+    rank_frequencies: Option<PathBuf>,
+
+    /// Re-rank the words with their counts in a plain text corpus
+    #[arg(long, value_name = "FILE")]
+    rank_corpus: Option<PathBuf>,
+
+    /// Rank of words missing from --rank-frequencies or --rank-corpus
+    #[arg(long, value_enum, default_value = "keep")]
+    rank_fallback: RankFallback,
+
+    /// Output format name or alias (see `list-formats`)
+    #[arg(short = 'o', long, required = true)]
+    output_format: Option<String>,
 
-    /// Output file
+    /// Output file (`-` for stdout), or output directory with --output-template
     #[arg(required = true)]
-    output: PathBuf,
+    output: Option<PathBuf>,
+
+    /// Convert each input file on its own, naming the outputs after a template
+    /// with {stem}, {name} and {ext} placeholders (e.g. "{stem}.dict.yaml")
+    #[arg(long)]
+    output_template: Option<String>,
+
+    /// Merge into an existing Rime dictionary, keeping its header, comments
+    /// and entries (rime output; may be the output file itself)
+    #[arg(long, conflicts_with_all = ["output_template", "split"])]
+    merge_into: Option<PathBuf>,
 
     /// Minimum word length
     #[arg(long, default_value = "1")]
@@ -92,111 +504,1086 @@ struct Args {
     #[arg(long, default_value = "2147483647")]
     max_rank: i32,
 
+    /// Maximum number of words exported per code, highest rank first
+    #[arg(long)]
+    max_candidates: Option<usize>,
+
+    /// Append the position to the code of words sharing a code (klwl, klwl2)
+    #[arg(long)]
+    ordinal_suffix: bool,
+
+    /// Maximum code length of table codes (Wubi, Cangjie, ...), e.g. 4
+    #[arg(long)]
+    max_code_length: Option<usize>,
+
+    /// What to do with table codes longer than --max-code-length
+    #[arg(
+        long,
+        value_enum,
+        default_value = "truncate",
+        requires = "max_code_length"
+    )]
+    code_length_policy: CodeLengthPolicy,
+
+    /// Convert words between Simplified and Traditional Chinese before export
+    #[arg(long, value_enum)]
+    translate: Option<Translation>,
+
+    /// Regenerate glyph-based codes (Wubi, Cangjie, ...) after translation
+    #[arg(long, requires = "translate")]
+    retranslate_codes: bool,
+
+    /// Export every code combination of polyphonic words (多音字全输出)
+    #[arg(long)]
+    all_polyphonic: bool,
+
+    /// Maximum number of combinations exported per polyphonic word
+    #[arg(long, default_value_t = export::DEFAULT_POLYPHONIC_LIMIT)]
+    polyphonic_limit: usize,
+
+    /// Separator between per-character codes (rime and qq-pinyin output;
+    /// e.g. "-", or "" for none)
+    #[arg(long)]
+    code_separator: Option<String>,
+
+    /// Keyboard scheme of shuangpin output (rime-shuangpin)
+    #[arg(long, value_enum, default_value = "xiaohe")]
+    shuangpin_scheme: Shuangpin,
+
+    /// Line template of the custom output format, with {word}, {code},
+    /// {code:SEP} and {rank} placeholders (e.g. "{word}\t{code:'}\t{rank}")
+    #[arg(long)]
+    template: Option<String>,
+
+    /// Rescale ranks to weights from 1 to this value (rime-essay output)
+    #[arg(long)]
+    essay_max_weight: Option<u32>,
+
+    /// Convert codes between Zhuyin (注音) and pinyin
+    #[arg(long, value_enum)]
+    transcode: Option<Transcode>,
+
+    /// Normalize the exported codes
+    #[arg(long, value_enum, value_delimiter = ',')]
+    normalize_codes: Vec<CodeNormalization>,
+
+    /// Apply fuzzy pinyin (模糊音) to the exported pinyin codes
+    #[arg(long, value_enum)]
+    fuzzy_pinyin: Option<Fuzzy>,
+
+    /// Fuzzy pinyin pairs, "from=to" (e.g. "zh=z,ang=an"; defaults to the
+    /// usual zh/z, ch/c, sh/s, n/l, ang/an, eng/en, ing/in, iang/ian, uang/uan)
+    #[arg(long, value_delimiter = ',', requires = "fuzzy_pinyin")]
+    fuzzy_rules: Vec<String>,
+
+    /// Also export pinyin abbreviations (首字母简拼) of the words, e.g. "nh"
+    /// for 你好, as extra entries
+    #[arg(long, value_enum)]
+    abbreviations: Option<Abbreviation>,
+
+    /// Order of the exported entries
+    #[arg(long, value_enum, default_value = "input")]
+    sort: Sort,
+
+    /// How to export English words
+    #[arg(long, value_enum, default_value = "keep")]
+    english: English,
+
+    /// Tag idioms (成语) from the bundled list with the is_idiom attribute
+    #[arg(long)]
+    tag_idioms: bool,
+
+    /// Export only idioms, or leave them out
+    #[arg(long, value_enum)]
+    idioms: Option<Idioms>,
+
+    /// Leave out words containing a term of the bundled sensitive word list
+    #[arg(long)]
+    filter_sensitive: bool,
+
+    /// Leave out words containing a term of this file (one per line), in
+    /// addition to --filter-sensitive
+    #[arg(long, value_name = "FILE")]
+    sensitive_words: Vec<PathBuf>,
+
+    /// Keep only words absent from this reference dictionary, e.g. the IME's
+    /// system dictionary, to extract user phrases
+    #[arg(long, value_name = "FILE")]
+    exclude_dict: Option<PathBuf>,
+
+    /// Format of the --exclude-dict file (defaults to the input format)
+    #[arg(long, value_name = "FORMAT", requires = "exclude_dict")]
+    exclude_dict_format: Option<String>,
+
+    /// Only exclude words whose codes match the reference dictionary too
+    #[arg(long, requires = "exclude_dict")]
+    exclude_match_codes: bool,
+
+    /// Discover new words among the n-grams of a corpus (input format `corpus` or `html`)
+    #[arg(long)]
+    extract_ngrams: bool,
+
+    /// Maximum length in characters of discovered words
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 8,
+        requires = "extract_ngrams"
+    )]
+    ngram_max_length: usize,
+
+    /// Minimum number of occurrences of discovered words
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 5,
+        requires = "extract_ngrams"
+    )]
+    ngram_min_count: usize,
+
+    /// Minimum mutual information of discovered words
+    #[arg(
+        long,
+        value_name = "BITS",
+        default_value_t = 3.0,
+        requires = "extract_ngrams"
+    )]
+    ngram_min_mi: f64,
+
+    /// Split the output into files of at most this many entries
+    #[arg(long, value_name = "N", group = "split")]
+    split_entries: Option<usize>,
+
+    /// Split the output into files of at most this many bytes
+    #[arg(long, value_name = "BYTES", group = "split")]
+    split_bytes: Option<usize>,
+
+    /// Split the output into one file per first letter of the codes
+    #[arg(long, group = "split")]
+    split_by_letter: bool,
+
+    /// How entries found in several input files are combined
+    #[arg(long, value_enum, default_value = "concat")]
+    merge_strategy: MergeStrategy,
+
+    /// Rank multipliers of the input files, in the order they are read
+    /// (e.g. "1,0.5"; missing weights are 1)
+    #[arg(long, value_delimiter = ',')]
+    source_weights: Vec<f64>,
+
+    /// Merge entries with the same word and codes, keeping the highest rank
+    #[arg(long)]
+    dedup: bool,
+
+    /// Convert a single input one entry at a time, with bounded memory.
+    /// Needs line-based formats and per-entry options; writes no header and
+    /// keeps duplicates
+    #[arg(long, conflicts_with_all = ["output_template", "merge_into", "split", "dedup"])]
+    stream: bool,
+
+    /// Keep imported words as they are instead of normalizing them to NFC
+    /// and mapping variant characters (e.g. 﨑 to 崎)
+    #[arg(long)]
+    no_normalize: bool,
+
+    /// Write conversion statistics as JSON to this file
+    #[arg(long)]
+    report: Option<PathBuf>,
+
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// Do not show a progress bar
+    #[arg(long)]
+    no_progress: bool,
+
+    /// Level of the diagnostics logged to stderr
+    #[arg(long, value_enum, default_value = "warn", global = true)]
+    log_level: LogLevel,
+
+    /// Format of the diagnostics logged to stderr
+    #[arg(long, value_enum, default_value = "text", global = true)]
+    log_format: LogFormat,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the supported formats with their direction and default encoding
+    ListFormats,
+    /// Show the words added, removed and changed between two word libraries
+    Diff {
+        /// Format of the word libraries
+        #[arg(short = 'i', long)]
+        input_format: String,
+
+        /// Format of the second word library, if it differs
+        #[arg(long)]
+        new_format: Option<String>,
+
+        /// Output format of the differences
+        #[arg(long, value_enum, default_value = "text")]
+        output: DiffOutput,
+
+        /// Word library before the changes
+        old: PathBuf,
+
+        /// Word library after the changes
+        new: PathBuf,
+    },
+    /// Check the codes of a word library against generated codes
+    Verify {
+        /// Format of the word library
+        #[arg(short = 'i', long)]
+        input_format: String,
+
+        /// Write the library with mismatched codes replaced to this file
+        #[arg(long, value_name = "FILE")]
+        fix: Option<PathBuf>,
+
+        /// Format of the fixed library, defaults to the input format
+        #[arg(short = 'o', long, requires = "fix")]
+        output_format: Option<String>,
+
+        /// Output format of the mismatches
+        #[arg(long, value_enum, default_value = "text")]
+        output: DiffOutput,
+
+        /// Word library to check
+        input: PathBuf,
+    },
+    /// Parse a word library strictly and report structural problems
+    ///
+    /// Exits with a non-zero status when a problem is found.
+    Validate {
+        /// Format of the word library
+        #[arg(short = 'i', long)]
+        input_format: String,
+
+        /// Output format of the problems
+        #[arg(long, value_enum, default_value = "text")]
+        output: DiffOutput,
+
+        /// Word library to check
+        input: PathBuf,
+    },
+    /// Generate seed phrases (numbers, dates, quantities) with their pinyin
+    GeneratePhrases {
+        /// Phrase sets to generate
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "numbers,dates,units"
+        )]
+        sets: Vec<PhraseSet>,
+
+        /// Output format
+        #[arg(short = 'o', long)]
+        output_format: String,
+
+        /// Output file
+        output: PathBuf,
+    },
+    /// Download a cell dictionary from the Sogou cell store
+    #[cfg(feature = "network")]
+    FetchSogou {
+        /// Cell ID or URL of its detail page
+        cell: String,
+
+        /// Convert the cell to this format instead of saving the .scel file
+        #[arg(short = 'o', long)]
+        output_format: Option<String>,
+
+        /// Output file
+        output: PathBuf,
+    },
+    /// Convert the Microsoft Pinyin user phrases of the current user
+    #[cfg(windows)]
+    ExportMspy {
+        /// Output format
+        #[arg(short = 'o', long)]
+        output_format: String,
+
+        /// Output file
+        output: PathBuf,
+    },
+    /// Serve conversions over HTTP (/convert, /formats and /info)
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+
+        /// Number of worker threads
+        #[arg(long, default_value_t = 4)]
+        threads: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum DiffOutput {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PhraseSet {
+    Numbers,
+    Dates,
+    Units,
+}
+
+impl From<PhraseSet> for generate::phrases::PhraseSet {
+    fn from(set: PhraseSet) -> Self {
+        match set {
+            PhraseSet::Numbers => generate::phrases::PhraseSet::Numbers,
+            PhraseSet::Dates => generate::phrases::PhraseSet::Dates,
+            PhraseSet::Units => generate::phrases::PhraseSet::Units,
+        }
+    }
+}
+
+/// Import a whole word library, e.g. for the diff subcommand
+fn import_library(
+    registry: &registry::FormatRegistry,
+    format: &str,
+    path: &std::path::Path,
+) -> Result<WordLibraryList> {
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", path.display()))?;
+    let mut words: WordLibraryList = registry
+        .importer(format)?
+        .import_from_file(path_str)
+        .with_context(|| format!("Failed to import {}", path.display()))?
+        .into();
+    words.source_format = Some(format.to_string());
+    Ok(words)
+}
+
+fn run_diff(
+    registry: &registry::FormatRegistry,
+    input_format: &str,
+    new_format: Option<&str>,
+    output: DiffOutput,
+    old: &std::path::Path,
+    new: &std::path::Path,
+) -> Result<()> {
+    let before = import_library(registry, input_format, old)?;
+    let after = import_library(registry, new_format.unwrap_or(input_format), new)?;
+    let result = diff::diff(&before, &after);
+
+    match output {
+        DiffOutput::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+        DiffOutput::Text => {
+            let codes = |word: &WordLibrary| word.codes.to_string_with_separator(" ");
+            for word in &result.removed {
+                println!("- {}\t{}\t{}", word.word, codes(word), word.rank);
+            }
+            for word in &result.added {
+                println!("+ {}\t{}\t{}", word.word, codes(word), word.rank);
+            }
+            for change in &result.changed {
+                println!(
+                    "~ {}\t{} -> {}\t{} -> {}",
+                    change.before.word,
+                    codes(&change.before),
+                    codes(&change.after),
+                    change.before.rank,
+                    change.after.rank
+                );
+            }
+            println!(
+                "{} removed, {} added, {} changed",
+                result.removed.len(),
+                result.added.len(),
+                result.changed.len()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn run_verify(
+    registry: &registry::FormatRegistry,
+    input_format: &str,
+    fix: Option<&std::path::Path>,
+    output_format: Option<&str>,
+    output: DiffOutput,
+    input: &std::path::Path,
+) -> Result<()> {
+    let mut words = import_library(registry, input_format, input)?;
+    let verifier = verify::CodeVerifier::new();
+    let report = match fix {
+        Some(_) => verifier.fix(&mut words),
+        None => verifier.verify(&words),
+    };
+
+    match output {
+        DiffOutput::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        DiffOutput::Text => {
+            for mismatch in &report.mismatches {
+                println!(
+                    "! {}\t{} -> {}",
+                    mismatch.word.word,
+                    mismatch.word.codes.to_string_with_separator(" "),
+                    mismatch.expected.to_string_with_separator(" ")
+                );
+            }
+            println!(
+                "{} checked, {} skipped, {} mismatched",
+                report.checked,
+                report.skipped,
+                report.mismatches.len()
+            );
+        }
+    }
+
+    if let Some(path) = fix {
+        let exporter = registry.exporter(output_format.unwrap_or(input_format))?;
+        for (i, content) in exporter.export(&words)?.iter().enumerate() {
+            let path = batch::numbered_path(path, i);
+            helpers::write_file(&path, content, exporter.encoding())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn run_validate(
+    registry: &registry::FormatRegistry,
+    input_format: &str,
+    output: DiffOutput,
+    input: &std::path::Path,
+) -> Result<()> {
+    let path = input
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", input.display()))?;
+    let importer = registry.importer(input_format)?;
+    let report = validate::validate_file(importer.as_ref(), path, &import::ImportOptions::new());
+
+    match output {
+        DiffOutput::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        DiffOutput::Text => {
+            for problem in &report.problems {
+                match (problem.index, &problem.word) {
+                    (Some(index), Some(word)) => {
+                        println!("! #{} {}\t{}", index + 1, word, problem.message)
+                    }
+                    _ => println!("! {}", problem.message),
+                }
+            }
+            println!("{} words, {} problems", report.words, report.problems.len());
+        }
+    }
+
+    if !report.is_valid() {
+        anyhow::bail!("{} is not a valid {} file", input.display(), input_format);
+    }
+    Ok(())
+}
+
+fn run_generate_phrases(
+    registry: &registry::FormatRegistry,
+    sets: &[PhraseSet],
+    output_format: &str,
+    output: &std::path::Path,
+) -> Result<()> {
+    let sets: Vec<generate::phrases::PhraseSet> = sets.iter().map(|&s| s.into()).collect();
+    let words = generate::phrases::generate_phrases(&sets);
+    let exporter = registry.exporter(output_format)?;
+    for (i, content) in exporter.export(&words)?.iter().enumerate() {
+        let path = batch::numbered_path(output, i);
+        helpers::write_file(&path, content, exporter.encoding())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    println!("{} phrases written to {}", words.len(), output.display());
+    Ok(())
+}
+
+#[cfg(feature = "network")]
+fn run_fetch_sogou(
+    registry: &registry::FormatRegistry,
+    cell: &str,
+    output_format: Option<&str>,
+    output: &std::path::Path,
+) -> Result<()> {
+    let Some(format) = output_format else {
+        let data = fetch::sogou::download_cell(cell)?;
+        std::fs::write(output, data)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        println!("Saved to {}", output.display());
+        return Ok(());
+    };
+
+    let exporter = registry.exporter(format)?;
+    let words = fetch::sogou::import_cell(cell)?;
+    for (i, content) in exporter.export(&words)?.iter().enumerate() {
+        let path = batch::numbered_path(output, i);
+        helpers::write_file(&path, content, exporter.encoding())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    println!("{} words written to {}", words.len(), output.display());
+    Ok(())
+}
+
+#[cfg(windows)]
+fn run_export_mspy(
+    registry: &registry::FormatRegistry,
+    output_format: &str,
+    output: &std::path::Path,
+) -> Result<()> {
+    let exporter = registry.exporter(output_format)?;
+    let found = import::ms_pinyin_auto::discover()?;
+    if found.is_empty() {
+        anyhow::bail!("No Microsoft Pinyin user lexicon found");
+    }
+    for path in &found {
+        println!("Reading {}", path.display());
+    }
+    let words = import::ms_pinyin_auto::import_discovered(&import::ImportOptions::new())?;
+    for (i, content) in exporter.export(&words)?.iter().enumerate() {
+        let path = batch::numbered_path(output, i);
+        helpers::write_file(&path, content, exporter.encoding())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    println!("{} words written to {}", words.len(), output.display());
+    Ok(())
+}
+
+fn parse_encoding(name: &str) -> std::result::Result<String, String> {
+    if helpers::is_encoding_label(name) {
+        Ok(name.to_string())
+    } else {
+        Err(format!("unknown encoding '{}'", name))
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    init_logging(args.log_level, args.log_format);
 
-    if args.verbose {
-        println!("IME Word List Converter v{}", VERSION);
-        println!("Input format: {:?}", args.input_format);
-        println!("Output format: {:?}", args.output_format);
-        println!("Input files: {} file(s)", args.input_files.len());
+    let registry = registry::FormatRegistry::with_builtin_formats();
+
+    match &args.command {
+        Some(Command::ListFormats) => {
+            list_formats(&registry);
+            return Ok(());
+        }
+        Some(Command::Diff {
+            input_format,
+            new_format,
+            output,
+            old,
+            new,
+        }) => {
+            return run_diff(
+                &registry,
+                input_format,
+                new_format.as_deref(),
+                *output,
+                old,
+                new,
+            );
+        }
+        Some(Command::Verify {
+            input_format,
+            fix,
+            output_format,
+            output,
+            input,
+        }) => {
+            return run_verify(
+                &registry,
+                input_format,
+                fix.as_deref(),
+                output_format.as_deref(),
+                *output,
+                input,
+            );
+        }
+        Some(Command::Validate {
+            input_format,
+            output,
+            input,
+        }) => {
+            return run_validate(&registry, input_format, *output, input);
+        }
+        Some(Command::GeneratePhrases {
+            sets,
+            output_format,
+            output,
+        }) => {
+            return run_generate_phrases(&registry, sets, output_format, output);
+        }
+        #[cfg(feature = "network")]
+        Some(Command::FetchSogou {
+            cell,
+            output_format,
+            output,
+        }) => {
+            return run_fetch_sogou(&registry, cell, output_format.as_deref(), output);
+        }
+        #[cfg(windows)]
+        Some(Command::ExportMspy {
+            output_format,
+            output,
+        }) => {
+            return run_export_mspy(&registry, output_format, output);
+        }
+        #[cfg(feature = "server")]
+        Some(Command::Serve {
+            host,
+            port,
+            threads,
+        }) => {
+            return server::serve(host, *port, *threads);
+        }
+        None => {}
     }
 
-    // Create filters
-    let length_filter = filter::length::LengthFilter::new(args.min_length, args.max_length);
-    let rank_filter = filter::rank::RankFilter::new(args.min_rank, args.max_rank);
+    if let Some(path) = &args.config {
+        let spec = job::load_job(path)?;
+        let counts = pipeline::run_job(&spec).context("Failed to run job")?;
+        for (output, count) in spec.outputs.iter().zip(counts) {
+            println!("{} ({} words)", output.path.display(), count);
+        }
+        println!("Conversion completed successfully!");
+        return Ok(());
+    }
 
-    // Import all files
-    let mut all_words = Vec::new();
-
-    for input_file in &args.input_files {
-        if args.verbose {
-            println!("Processing: {}", input_file.display());
-        }
-
-        let importer: Box<dyn import::WordLibraryImport> = match args.input_format {
-            InputFormat::BaiduPinyin => Box::new(import::BaiduPinyinImport),
-            InputFormat::ChinesePyim => Box::new(import::ChinesePyimImport::new()),
-            InputFormat::FitInput => Box::new(import::FitInputImport::new()),
-            InputFormat::GooglePinyin => Box::new(import::GooglePinyinImport),
-            InputFormat::Libpinyin => Box::new(import::LibpinyinImport::new()),
-            InputFormat::MsPinyin => Box::new(import::MsPinyinImport::new()),
-            InputFormat::PinyinJiajia => Box::new(import::PinyinJiajiaImport::new()),
-            InputFormat::QqPinyin => Box::new(import::QQPinyinImport::new()),
-            InputFormat::QqWubi => Box::new(import::QQWubiImport::new()),
-            InputFormat::Rime => Box::new(import::RimeImport::new()),
-            InputFormat::SinaPinyin => Box::new(import::SinaPinyinImport::new()),
-            InputFormat::SogouPinyin => Box::new(import::SogouPinyinImport),
-            InputFormat::SogouScel => Box::new(import::SogouScelImport),
-            InputFormat::Wubi86 => Box::new(import::Wubi86Import),
-            InputFormat::Wubi98 => Box::new(import::Wubi98Import),
-            InputFormat::WubiNewAge => Box::new(import::WubiNewAgeImport),
-            InputFormat::ZiguangPinyin => Box::new(import::ZiguangPinyinImport::new()),
+    // Required unless a subcommand or job file is given
+    let (Some(input_format), Some(output_format), Some(output)) = (
+        args.input_format.as_deref(),
+        args.output_format.as_deref(),
+        args.output.clone(),
+    ) else {
+        unreachable!("clap enforces the required arguments");
+    };
+
+    let to_stdout = output.as_os_str() == import::STDIO_PATH;
+
+    if args.verbose {
+        status!(to_stdout, "IME Word List Converter v{}", VERSION);
+        status!(to_stdout, "Input format: {}", input_format);
+        status!(to_stdout, "Output format: {}", output_format);
+        status!(to_stdout, "Input files: {} file(s)", args.input_files.len());
+    }
+
+    let mut importer = registry.importer(input_format)?;
+    if args.extract_ngrams {
+        let extraction = import::corpus::NgramExtraction::new()
+            .with_length(2, args.ngram_max_length)
+            .with_min_count(args.ngram_min_count)
+            .with_min_mutual_information(args.ngram_min_mi);
+        let corpus = import::CorpusImport::new().with_ngrams(extraction);
+        importer = match registry.find(input_format).map(|f| f.name()) {
+            Some("corpus") => Box::new(corpus),
+            Some("html") => Box::new(import::HtmlImport::with_corpus(corpus)),
+            _ => anyhow::bail!("--extract-ngrams requires the corpus or html input format"),
+        };
+    }
+    let mut exporter: Box<dyn export::WordLibraryExport> = match &args.template {
+        Some(template) if output_format == "custom" => {
+            Box::new(export::custom_text::CustomTextExport::new(template)?)
+        }
+        Some(_) => anyhow::bail!("--template requires the custom output format"),
+        None if output_format == "custom" => {
+            anyhow::bail!("The custom output format requires --template")
+        }
+        None => registry.exporter(output_format)?,
+    };
+    if let Some(separator) = &args.code_separator {
+        exporter = match registry.find(output_format).map(|f| f.name()) {
+            Some("rime") => {
+                Box::new(export::rime::RimeExport::new().with_code_separator(separator))
+            }
+            Some("rime-jyutping") => Box::new(
+                export::rime::RimeExport::with_code_type(CodeType::Jyutping)
+                    .with_code_separator(separator),
+            ),
+            Some("rime-shuangpin") => Box::new(
+                export::rime::RimeExport::with_code_type(CodeType::Shuangpin)
+                    .with_code_separator(separator),
+            ),
+            Some("qq-pinyin") => {
+                Box::new(export::qq_pinyin::QQPinyinExport::new().with_code_separator(separator))
+            }
+            _ => anyhow::bail!("--code-separator requires the rime or qq-pinyin output format"),
         };
+    }
+    if let Some(max_weight) = args.essay_max_weight {
+        if registry.find(output_format).map(|f| f.name()) != Some("rime-essay") {
+            anyhow::bail!("--essay-max-weight requires the rime-essay output format");
+        }
+        exporter = Box::new(export::rime_essay::RimeEssayExport::new().with_max_weight(max_weight));
+    }
+    if args.merge_into.is_some()
+        && !matches!(
+            registry.find(output_format).map(|f| f.name()),
+            Some("rime" | "rime-jyutping" | "rime-shuangpin")
+        )
+    {
+        anyhow::bail!("--merge-into requires the rime output format");
+    }
+    let output_generator = generate::output_generator_for(
+        exporter.as_ref(),
+        resource::ResourceManager::shared(),
+        args.shuangpin_scheme.into(),
+    )?;
 
-        let input_path = input_file
-            .to_str()
-            .ok_or_else(|| anyhow::anyhow!("Invalid file path"))?;
+    let mut pipeline = pipeline::ConversionPipeline::new(importer, exporter)
+        .with_source_format(input_format)
+        .with_filter(Box::new(filter::length::LengthFilter::new(
+            args.min_length,
+            args.max_length,
+        )))
+        .with_filter(Box::new(filter::rank::RankFilter::new(
+            args.min_rank,
+            args.max_rank,
+        )))
+        .english_policy(args.english.into())
+        .with_split(export_split(&args))
+        .merge_duplicates(args.dedup)
+        .normalize_unicode(!args.no_normalize)
+        .with_error_policy(args.on_error.into())
+        .salvage(args.salvage)
+        .with_sorter(sort::WordLibrarySorter::new(args.sort.into()));
+
+    if let Some(idioms) = args.idioms {
+        let filter = match idioms {
+            Idioms::Only => filter::idiom::IdiomFilter::idioms_only(),
+            Idioms::Exclude => filter::idiom::IdiomFilter::new(),
+        };
+        pipeline = pipeline.with_filter(Box::new(filter));
+    }
+    if args.filter_sensitive || !args.sensitive_words.is_empty() {
+        let mut filter = if args.filter_sensitive {
+            filter::sensitive::SensitiveWordFilter::with_embedded()
+        } else {
+            filter::sensitive::SensitiveWordFilter::new()
+        };
+        for path in &args.sensitive_words {
+            filter = filter
+                .with_term_file(&path.to_string_lossy(), helpers::AUTO_ENCODING)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+        }
+        pipeline = pipeline.with_filter(Box::new(filter));
+    }
+    if args.tag_idioms {
+        pipeline = pipeline.with_idiom_tagger(transform::idiom::IdiomTagger::new());
+    }
 
-        let mut words = importer
-            .import_from_file(input_path)
-            .with_context(|| format!("Failed to import {}", input_file.display()))?;
+    // Imported codes of another type are regenerated for the output
+    if let Some(generator) = output_generator {
+        pipeline = pipeline.with_output_generator(generator);
+    }
 
-        if args.verbose {
-            println!("  Imported {} words", words.len());
+    if args.max_candidates.is_some() || args.ordinal_suffix {
+        let mut resolver =
+            export::CollisionResolver::new().with_ordinal_suffix(args.ordinal_suffix);
+        if let Some(max) = args.max_candidates {
+            resolver = resolver.with_max_candidates(max);
         }
+        pipeline = pipeline.with_collision_resolver(resolver);
+    }
+
+    if let Some(max) = args.max_code_length {
+        pipeline = pipeline.with_batch_filter(Box::new(
+            filter::code_length::CodeLengthFilter::new(max, args.code_length_policy.into()),
+        ));
+    }
+
+    if let Some(path) = &args.exclude_dict {
+        let format = args.exclude_dict_format.as_deref().unwrap_or(input_format);
+        let reference = import_library(&registry, format, path)?;
+        pipeline = pipeline.with_filter(Box::new(
+            filter::reference::ReferenceFilter::new(&reference)
+                .with_match_codes(args.exclude_match_codes),
+        ));
+    }
+
+    if let Some(translation) = args.translate {
+        pipeline = pipeline
+            .with_translator(
+                Box::new(translate::OpenCCConverter::new()?),
+                translation.into(),
+            )
+            .retranslate_codes(args.retranslate_codes);
+    }
+
+    if let Some(encoding) = &args.input_encoding {
+        pipeline = pipeline.with_input_encoding(encoding);
+    }
+
+    if let Some(tones) = args.pinyin_tones {
+        pipeline = pipeline.with_pinyin_tones(tones.into());
+    }
+
+    if let Some(rank) = args.default_rank {
+        pipeline = pipeline.with_default_rank(rank);
+    }
+
+    let reference = match (&args.rank_frequencies, &args.rank_corpus) {
+        (Some(path), _) => Some(rank::CorpusRankGenerator::from_frequency_file(
+            &path.to_string_lossy(),
+            helpers::AUTO_ENCODING,
+        )),
+        (None, Some(path)) => Some(rank::CorpusRankGenerator::from_corpus_file(
+            &path.to_string_lossy(),
+            helpers::AUTO_ENCODING,
+        )),
+        (None, None) => None,
+    };
+    if let Some(generator) = reference {
+        let generator = generator
+            .context("Failed to read the rank reference file")?
+            .with_fallback(args.rank_fallback.into());
+        pipeline = pipeline.with_rank_generator(Box::new(generator));
+    }
+
+    if let Some(transcode) = args.transcode {
+        pipeline = pipeline.with_transcode(transcode.into());
+    }
 
-        // Apply filters
-        use filter::SingleFilter;
-        words.retain(|w| length_filter.is_keep(w));
-        words.retain(|w| rank_filter.is_keep(w));
+    if !args.normalize_codes.is_empty() {
+        let normalizer = args.normalize_codes.iter().fold(
+            transform::code_normalize::CodeNormalizer::new(),
+            |normalizer, option| match option {
+                CodeNormalization::Lowercase => normalizer.with_lowercase(),
+                CodeNormalization::StripWhitespace => normalizer.with_strip_whitespace(),
+                CodeNormalization::HalfWidth => normalizer.with_half_width(),
+            },
+        );
+        pipeline = pipeline.with_code_normalizer(normalizer);
+    }
 
-        if args.verbose {
-            println!("  After filtering: {} words", words.len());
+    if let Some(mode) = args.fuzzy_pinyin {
+        let mut fuzzy = transform::fuzzy_pinyin::FuzzyPinyin::new(mode.into());
+        if !args.fuzzy_rules.is_empty() {
+            let rules = args
+                .fuzzy_rules
+                .iter()
+                .map(|rule| transform::fuzzy_pinyin::FuzzyRule::parse(rule))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            fuzzy = fuzzy.with_rules(rules);
         }
+        pipeline = pipeline.with_fuzzy_pinyin(fuzzy);
+    }
+
+    if let Some(abbreviation) = args.abbreviations {
+        let abbreviations = transform::abbreviation::PinyinAbbreviation::new()
+            .compound_initials(matches!(abbreviation, Abbreviation::Compound));
+        pipeline = pipeline.with_abbreviations(abbreviations);
+    }
+
+    if args.all_polyphonic {
+        pipeline = pipeline.expand_polyphonic(args.polyphonic_limit);
+    }
 
-        all_words.append(&mut words);
+    // Verbose output is line based and would garble the bar
+    if !args.verbose && !args.no_progress {
+        pipeline = pipeline.with_progress_sink(Box::new(ProgressBarSink::new()));
     }
 
     if args.verbose {
-        println!("Total words: {}", all_words.len());
+        pipeline = pipeline.on_progress(move |event| match event {
+            pipeline::ProgressEvent::Imported { path, count } => {
+                status!(to_stdout, "Processing: {}", path);
+                status!(to_stdout, "  Imported {} words", count);
+            }
+            pipeline::ProgressEvent::Filtered { count, .. } => {
+                status!(to_stdout, "  After filtering: {} words", count);
+            }
+            pipeline::ProgressEvent::Processed { count } => {
+                status!(to_stdout, "Total words: {}", count);
+            }
+            pipeline::ProgressEvent::Exported { .. } => {}
+        });
     }
 
-    // Export
-    let exporter: Box<dyn export::WordLibraryExport> = match args.output_format {
-        OutputFormat::QqPinyin => Box::new(export::qq_pinyin::QQPinyinExport::new()),
-        OutputFormat::Rime => Box::new(export::rime::RimeExport::new()),
-    };
+    // Expand directories and glob patterns, zip archives included
+    let mut extensions = registry
+        .find(input_format)
+        .map(|format| format.extensions().to_vec())
+        .unwrap_or_default();
+    if !extensions.is_empty() {
+        extensions.push("zip".to_string());
+    }
+    let input_paths = args
+        .input_files
+        .iter()
+        .map(|path| {
+            path.to_str()
+                .ok_or_else(|| anyhow::anyhow!("Invalid file path: {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let inputs = batch::expand_inputs(&input_paths, args.recursive, &extensions)
+        .context("Failed to collect input files")?;
+    if inputs.is_empty() {
+        anyhow::bail!("No input files found");
+    }
+
+    if let Some(template) = &args.output_template {
+        if to_stdout {
+            anyhow::bail!("--output-template needs an output directory, not stdout");
+        }
+        let template = batch::OutputTemplate::new(template);
+        let report = batch::convert_batch(&pipeline, &inputs, &output, &template);
 
-    let output_content = exporter.export(&all_words).context("Failed to export")?;
+        for entry in &report.entries {
+            match &entry.result {
+                Ok(count) => println!(
+                    "{} -> {} ({} words)",
+                    entry.input.display(),
+                    entry.output.display(),
+                    count
+                ),
+                Err(e) => eprintln!("{}: {}", entry.input.display(), e),
+            }
+        }
+        println!(
+            "Converted {} of {} file(s), {} words in total",
+            report.succeeded(),
+            report.entries.len(),
+            report.total_words()
+        );
 
-    // Write to file
-    for (i, content) in output_content.iter().enumerate() {
-        let output_path = if i == 0 {
-            args.output.clone()
-        } else {
-            let mut path = args.output.clone();
-            let stem = path.file_stem().unwrap().to_str().unwrap();
-            let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("txt");
-            path.set_file_name(format!("{}{}.{}", stem, i, ext));
-            path
+        if let Some(path) = &args.report {
+            write_report(path, &pipeline.take_report())?;
+        }
+
+        if report.failed() > 0 {
+            anyhow::bail!("{} file(s) failed to convert", report.failed());
+        }
+        return Ok(());
+    }
+
+    if args.stream {
+        let [input] = inputs.as_slice() else {
+            anyhow::bail!("--stream converts a single input file");
         };
+        let input_path = input
+            .path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid file path"))?;
+        let count = if to_stdout {
+            pipeline.convert_stream(input_path, &mut std::io::stdout().lock())
+        } else {
+            let file = std::fs::File::create(&output)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            pipeline.convert_stream(input_path, &mut std::io::BufWriter::new(file))
+        }
+        .with_context(|| format!("Failed to convert {}", input.path.display()))?;
+
+        if let Some(path) = &args.report {
+            write_report(path, &pipeline.take_report())?;
+        }
+        status!(to_stdout, "Conversion completed successfully!");
+        status!(to_stdout, "Total words converted: {}", count);
+        return Ok(());
+    }
+
+    if args.source_weights.len() > inputs.len() {
+        anyhow::bail!(
+            "{} source weights given for {} input file(s)",
+            args.source_weights.len(),
+            inputs.len()
+        );
+    }
+
+    // Import all files
+    let mut sources = Vec::new();
+    for (i, input) in inputs.iter().enumerate() {
+        let input_path = input
+            .path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Invalid file path"))?;
+
+        let words = pipeline
+            .import(&[input_path])
+            .with_context(|| format!("Failed to import {}", input.path.display()))?;
+        let weight = args.source_weights.get(i).copied().unwrap_or(1.0);
+        sources.push(merge::MergeSource::new(words).with_weight(weight));
+    }
+    let all_words = merge::merge_libraries(sources, args.merge_strategy.into());
+
+    let all_words = pipeline
+        .process(all_words)
+        .context("Failed to process words")?;
+
+    // Export
+    if let Some(existing) = &args.merge_into {
+        let existing = std::fs::read_to_string(existing)
+            .with_context(|| format!("Failed to read {}", existing.display()))?;
+        let exported = pipeline.export(&all_words).context("Failed to export")?;
+        let merged = export::rime::merge_dictionary(&existing, &exported.concat());
+        if to_stdout {
+            print!("{}", merged);
+        } else {
+            helpers::write_file(&output, &merged, pipeline.exporter().encoding())
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+        }
+    } else if to_stdout {
+        pipeline
+            .export_to_writer(&all_words, &mut std::io::stdout().lock())
+            .context("Failed to export to stdout")?;
+    } else {
+        let output_content = pipeline.export(&all_words).context("Failed to export")?;
+        // Fcitx5 loads binary dictionaries, compiled by libime
+        let binary_dict = registry.find(output_format).map(|f| f.name()) == Some("libime")
+            && output.extension().is_some_and(|ext| ext == "dict");
 
-        helpers::write_file(&output_path, content, exporter.encoding())
+        for (i, content) in output_content.iter().enumerate() {
+            let output_path = batch::numbered_path(&output, i);
+
+            if binary_dict {
+                export::libime::write_binary_dict(content, &output_path)
+            } else {
+                helpers::write_file(&output_path, content, pipeline.exporter().encoding())
+            }
             .with_context(|| format!("Failed to write {}", output_path.display()))?;
 
-        if args.verbose {
-            println!("Written to: {}", output_path.display());
+            if args.verbose {
+                println!("Written to: {}", output_path.display());
+            }
         }
     }
 
-    println!("Conversion completed successfully!");
-    println!("Total words converted: {}", all_words.len());
+    let report = pipeline.take_report();
+    if args.verbose {
+        status!(
+            to_stdout,
+            "Skipped: {} filtered, {} without codes, {} duplicates merged",
+            report.filtered(),
+            report.generation_failures.len(),
+            report.duplicates_merged
+        );
+        let stats = report.import_stats();
+        status!(
+            to_stdout,
+            "Import: {} bytes in {:.2?} ({:.0} entries/s, {:.2} MB/s)",
+            stats.bytes,
+            stats.elapsed,
+            stats.entries_per_sec(),
+            stats.bytes_per_sec() / 1_000_000.0
+        );
+    }
+    if let Some(path) = &args.report {
+        write_report(path, &report)?;
+    }
+
+    status!(to_stdout, "Conversion completed successfully!");
+    status!(to_stdout, "Total words converted: {}", all_words.len());
 
     Ok(())
 }
@@ -0,0 +1,248 @@
+//! Local HTTP conversion server for the `serve` subcommand
+//!
+//! Endpoints:
+//!
+//! - `GET /info`: version and format count
+//! - `GET /formats`: supported formats as JSON
+//! - `POST /convert?from=FMT&to=FMT[&encoding=ENC][&merge=true]`: converts the
+//!   request body and returns the converted file
+//!
+//! ```text
+//! curl --data-binary @words.scel 'http://localhost:8080/convert?from=scel&to=rime' -o words.txt
+//! ```
+
+use anyhow::{anyhow, Result};
+use imewlconverter_core::service::{ConversionRequest, Converter};
+use serde_json::json;
+use std::io::Read;
+use std::sync::Arc;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Largest accepted upload
+const MAX_BODY: u64 = 64 * 1024 * 1024;
+
+/// Response of a routed request
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+    headers: Vec<(&'static str, String)>,
+}
+
+impl Reply {
+    fn json(status: u16, value: serde_json::Value) -> Self {
+        Reply {
+            status,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self::json(status, json!({ "error": message.to_string() }))
+    }
+}
+
+/// Serve conversions on `host:port` until the process is stopped
+pub fn serve(host: &str, port: u16, threads: usize) -> Result<()> {
+    let server = Server::http((host, port)).map_err(|e| anyhow!("Failed to listen: {}", e))?;
+    let server = Arc::new(server);
+    let converter = Arc::new(Converter::new());
+    println!("Listening on http://{}:{}", host, port);
+
+    let workers: Vec<_> = (0..threads.max(1))
+        .map(|_| {
+            let server = server.clone();
+            let converter = converter.clone();
+            std::thread::spawn(move || {
+                while let Ok(request) = server.recv() {
+                    handle(&converter, request);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+fn handle(converter: &Converter, mut request: Request) {
+    let mut body = Vec::new();
+    let reply = match request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_end(&mut body)
+    {
+        Ok(_) if body.len() as u64 > MAX_BODY => Reply::error(413, "Upload too large"),
+        Ok(_) => route(converter, request.method(), request.url(), body),
+        Err(e) => Reply::error(400, e),
+    };
+    tracing::info!(
+        method = %request.method(),
+        url = request.url(),
+        status = reply.status,
+        "request"
+    );
+
+    let mut response = Response::from_data(reply.body).with_status_code(reply.status);
+    let headers =
+        std::iter::once(("Content-Type", reply.content_type.to_string())).chain(reply.headers);
+    for (name, value) in headers {
+        if let Ok(header) = Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+            response.add_header(header);
+        }
+    }
+    if let Err(e) = request.respond(response) {
+        tracing::warn!("Failed to send response: {}", e);
+    }
+}
+
+fn route(converter: &Converter, method: &Method, url: &str, body: Vec<u8>) -> Reply {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    match (method, path) {
+        (Method::Get, "/info") => Reply::json(
+            200,
+            json!({
+                "name": "imewlconverter",
+                "version": crate::VERSION,
+                "formats": converter.registry().formats().count(),
+            }),
+        ),
+        (Method::Get, "/formats") => formats(converter),
+        (Method::Post, "/convert") => convert(converter, query, body),
+        (_, "/info" | "/formats" | "/convert") => Reply::error(405, "Method not allowed"),
+        _ => Reply::error(404, "Not found"),
+    }
+}
+
+fn formats(converter: &Converter) -> Reply {
+    let formats: Vec<_> = converter
+        .registry()
+        .formats()
+        .map(|f| {
+            json!({
+                "name": f.name(),
+                "description": f.description(),
+                "aliases": f.aliases(),
+                "extensions": f.extensions(),
+                "encoding": f.encoding(),
+                "import": f.can_import(),
+                "export": f.can_export(),
+            })
+        })
+        .collect();
+    Reply::json(200, json!(formats))
+}
+
+fn convert(converter: &Converter, query: &str, body: Vec<u8>) -> Reply {
+    let (mut from, mut to, mut encoding, mut merge) = (None, None, None, false);
+    for (key, value) in query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| p.split_once('=').unwrap_or((p, "")))
+    {
+        let value = percent_decode(value);
+        match key {
+            "from" => from = Some(value),
+            "to" => to = Some(value),
+            "encoding" => encoding = Some(value),
+            "merge" => merge = value == "true" || value == "1",
+            _ => return Reply::error(400, format!("Unknown parameter '{}'", key)),
+        }
+    }
+    let (Some(from), Some(to)) = (from, to) else {
+        return Reply::error(400, "Parameters 'from' and 'to' are required");
+    };
+
+    let mut request = ConversionRequest::new(body, &from, &to).merge_duplicates(merge);
+    if let Some(encoding) = &encoding {
+        request = request.with_input_encoding(encoding);
+    }
+    match converter.convert(&request) {
+        Ok(response) => {
+            let filename = match &response.extension {
+                Some(ext) => format!("converted.{}", ext),
+                None => "converted".to_string(),
+            };
+            Reply {
+                status: 200,
+                content_type: "application/octet-stream",
+                body: response.output,
+                headers: vec![
+                    (
+                        "Content-Disposition",
+                        format!("attachment; filename=\"{}\"", filename),
+                    ),
+                    ("X-Word-Count", response.words.to_string()),
+                ],
+            }
+        }
+        Err(e) => Reply::error(422, e),
+    }
+}
+
+/// Decode `%XX` escapes and `+` of a query value
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        decoded.push(b);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes() {
+        let converter = Converter::new();
+        let info = route(&converter, &Method::Get, "/info", Vec::new());
+        assert_eq!(info.status, 200);
+        let formats = route(&converter, &Method::Get, "/formats", Vec::new());
+        let formats: serde_json::Value = serde_json::from_slice(&formats.body).unwrap();
+        assert!(formats
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|f| f["name"] == "rime"));
+
+        let body = "你好\tni hao\t1\n".as_bytes().to_vec();
+        let reply = route(
+            &converter,
+            &Method::Post,
+            "/convert?from=rime&to=libime",
+            body,
+        );
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.body, "你好 ni'hao 0".as_bytes());
+        assert!(reply.headers.contains(&("X-Word-Count", "1".to_string())));
+
+        let missing = route(&converter, &Method::Post, "/convert?from=rime", Vec::new());
+        assert_eq!(missing.status, 400);
+        assert_eq!(
+            route(&converter, &Method::Get, "/convert", Vec::new()).status,
+            405
+        );
+        assert_eq!(route(&converter, &Method::Get, "/", Vec::new()).status, 404);
+        assert_eq!(percent_decode("a%2Bb+c%zz"), "a+b c%zz");
+    }
+}
@@ -0,0 +1,66 @@
+//! Importer throughput on generated word lists
+//!
+//! Run with `cargo bench -p imewlconverter-core`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use imewlconverter_core::import::ImportOptions;
+use imewlconverter_core::registry::FormatRegistry;
+use imewlconverter_core::{Code, CodeType, WordLibrary, WordLibraryList};
+
+const CHARS: [(char, &str); 8] = [
+    ('你', "ni"),
+    ('好', "hao"),
+    ('世', "shi"),
+    ('界', "jie"),
+    ('中', "zhong"),
+    ('国', "guo"),
+    ('输', "shu"),
+    ('入', "ru"),
+];
+
+/// Every two- and three-character combination of `CHARS`
+fn word_list() -> WordLibraryList {
+    let mut words = WordLibraryList::new();
+    for a in CHARS {
+        for b in CHARS {
+            for c in [None].into_iter().chain(CHARS.map(Some)) {
+                let chars: Vec<(char, &str)> =
+                    [Some(a), Some(b), c].into_iter().flatten().collect();
+                let mut wl = WordLibrary::with_rank(chars.iter().map(|(c, _)| c).collect(), 1);
+                wl.code_type = CodeType::Pinyin;
+                wl.codes = Code::from_char_list(chars.iter().map(|(_, p)| p.to_string()).collect());
+                words.push(wl);
+            }
+        }
+    }
+    words
+}
+
+fn bench_importers(c: &mut Criterion) {
+    let registry = FormatRegistry::with_builtin_formats();
+    let words = word_list();
+    let mut group = c.benchmark_group("import");
+
+    for format in ["rime", "rime-userdb", "qq-pinyin"] {
+        let mut input = Vec::new();
+        registry
+            .exporter(format)
+            .unwrap()
+            .export_to_writer(&words, &mut input)
+            .unwrap();
+        let importer = registry.importer(format).unwrap();
+
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function(format, |b| {
+            b.iter(|| {
+                importer
+                    .import_from_reader(&mut input.as_slice(), &ImportOptions::new())
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_importers);
+criterion_main!(benches);
@@ -0,0 +1,303 @@
+//! Batch conversion of many input files
+//!
+//! Inputs may be files, directories or glob patterns. Each input file is
+//! converted on its own and written to an output directory, named after an
+//! `OutputTemplate` such as `{stem}.dict.yaml`.
+
+use crate::helpers::write_file;
+use crate::pipeline::ConversionPipeline;
+use crate::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An input file found while expanding the inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchInput {
+    pub path: PathBuf,
+    /// Path relative to the directory the file was found in; the directory
+    /// structure is recreated below the output directory
+    pub relative: PathBuf,
+}
+
+impl BatchInput {
+    fn file(path: PathBuf) -> Self {
+        let relative = path.file_name().map(PathBuf::from).unwrap_or_default();
+        BatchInput { path, relative }
+    }
+}
+
+/// Check whether an input is a glob pattern rather than a path
+fn is_glob(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+/// Check whether a file name ends with one of the extensions (all if empty)
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    extensions
+        .iter()
+        .any(|ext| name.ends_with(&format!(".{}", ext.to_lowercase())))
+}
+
+/// Collect the files of a directory, sorted by path
+fn walk_dir(
+    root: &Path,
+    dir: &Path,
+    recursive: bool,
+    extensions: &[String],
+    files: &mut Vec<BatchInput>,
+) -> Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            if recursive {
+                walk_dir(root, &path, recursive, extensions, files)?;
+            }
+        } else if has_extension(&path, extensions) {
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            files.push(BatchInput { path, relative });
+        }
+    }
+
+    Ok(())
+}
+
+/// Expand files, directories and glob patterns into the list of input files
+///
+/// Files in directories are kept only if they end with one of `extensions`
+/// (every file is kept if it is empty); subdirectories are entered only when
+/// `recursive` is set. Files given explicitly or matched by a glob pattern are
+/// always kept.
+pub fn expand_inputs(
+    inputs: &[&str],
+    recursive: bool,
+    extensions: &[String],
+) -> Result<Vec<BatchInput>> {
+    let mut files = Vec::new();
+
+    for &input in inputs {
+        let path = Path::new(input);
+        if path.is_dir() {
+            walk_dir(path, path, recursive, extensions, &mut files)?;
+        } else if path.exists() || !is_glob(input) {
+            files.push(BatchInput::file(path.to_path_buf()));
+        } else {
+            let paths = glob::glob(input)
+                .map_err(|e| Error::Parse(format!("Invalid glob pattern {}: {}", input, e)))?;
+            for path in paths {
+                let path = path.map_err(std::io::Error::from)?;
+                if path.is_file() {
+                    files.push(BatchInput::file(path));
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Output file name template
+///
+/// Supported placeholders: `{stem}` (file name without the last extension),
+/// `{name}` (full file name) and `{ext}` (last extension).
+#[derive(Debug, Clone)]
+pub struct OutputTemplate {
+    template: String,
+}
+
+impl OutputTemplate {
+    pub fn new(template: &str) -> Self {
+        OutputTemplate {
+            template: template.to_string(),
+        }
+    }
+
+    /// Render the output file name for an input file
+    pub fn render(&self, input: &Path) -> String {
+        let part = |s: Option<&std::ffi::OsStr>| {
+            s.map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        self.template
+            .replace("{stem}", &part(input.file_stem()))
+            .replace("{name}", &part(input.file_name()))
+            .replace("{ext}", &part(input.extension()))
+    }
+
+    /// Output path of an input below the output directory
+    pub fn output_path(&self, output_dir: &Path, input: &BatchInput) -> PathBuf {
+        let dir = match input.relative.parent() {
+            Some(parent) => output_dir.join(parent),
+            None => output_dir.to_path_buf(),
+        };
+        dir.join(self.render(&input.relative))
+    }
+}
+
+/// Path of the `index`th output of one conversion
+///
+/// The first output keeps the path, further outputs (e.g. separated English
+/// words) get the index appended to the file stem.
+pub fn numbered_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("txt");
+    path.with_file_name(format!("{}{}.{}", stem, index, ext))
+}
+
+/// Result of converting one input file
+#[derive(Debug)]
+pub struct BatchEntry {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    /// Number of exported words, or the error that stopped the conversion
+    pub result: Result<usize>,
+}
+
+/// Summary of a batch conversion
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchReport {
+    pub fn succeeded(&self) -> usize {
+        self.entries.iter().filter(|e| e.result.is_ok()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.entries.len() - self.succeeded()
+    }
+
+    /// Total number of words over the successful conversions
+    pub fn total_words(&self) -> usize {
+        self.entries
+            .iter()
+            .filter_map(|e| e.result.as_ref().ok())
+            .sum()
+    }
+}
+
+/// Convert every input into its own output file
+///
+/// A failing file does not stop the batch; its error is recorded in the report.
+pub fn convert_batch(
+    pipeline: &ConversionPipeline,
+    inputs: &[BatchInput],
+    output_dir: &Path,
+    template: &OutputTemplate,
+) -> BatchReport {
+    let mut report = BatchReport::default();
+
+    for input in inputs {
+        let output = template.output_path(output_dir, input);
+        let result = convert_one(pipeline, &input.path, &output);
+        report.entries.push(BatchEntry {
+            input: input.path.clone(),
+            output,
+            result,
+        });
+    }
+
+    report
+}
+
+fn convert_one(pipeline: &ConversionPipeline, input: &Path, output: &Path) -> Result<usize> {
+    let path = input
+        .to_str()
+        .ok_or_else(|| Error::Parse(format!("Invalid file path: {}", input.display())))?;
+
+    let words = pipeline.import(&[path])?;
+    let words = pipeline.process(words)?;
+    let contents = pipeline.export(&words)?;
+
+    if let Some(dir) = output.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    for (i, content) in contents.iter().enumerate() {
+        write_file(
+            &numbered_path(output, i),
+            content,
+            pipeline.exporter().encoding(),
+        )?;
+    }
+
+    Ok(words.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::rime::RimeExport;
+    use crate::import::RimeImport;
+
+    #[test]
+    fn test_output_template() {
+        let template = OutputTemplate::new("{stem}.dict.yaml");
+        assert_eq!(template.render(Path::new("a/城市.scel")), "城市.dict.yaml");
+
+        let input = BatchInput {
+            path: PathBuf::from("/in/sub/b.scel"),
+            relative: PathBuf::from("sub/b.scel"),
+        };
+        assert_eq!(
+            template.output_path(Path::new("/out"), &input),
+            PathBuf::from("/out/sub/b.dict.yaml")
+        );
+        assert_eq!(
+            numbered_path(Path::new("/out/b.txt"), 1),
+            PathBuf::from("/out/b1.txt")
+        );
+    }
+
+    #[test]
+    fn test_convert_batch() {
+        let root = std::env::temp_dir().join("imewlconverter_batch_test");
+        let input_dir = root.join("in");
+        let output_dir = root.join("out");
+        fs::remove_dir_all(&root).ok();
+        fs::create_dir_all(input_dir.join("sub")).unwrap();
+        fs::write(input_dir.join("a.txt"), "你好\tni hao\t1\n").unwrap();
+        fs::write(input_dir.join("sub/b.txt"), "世界\tshi jie\t2\n").unwrap();
+        fs::write(input_dir.join("skip.log"), "").unwrap();
+
+        let dir = input_dir.to_str().unwrap();
+        let extensions = vec!["txt".to_string()];
+        assert_eq!(expand_inputs(&[dir], false, &extensions).unwrap().len(), 1);
+        let inputs = expand_inputs(&[dir], true, &extensions).unwrap();
+        assert_eq!(inputs.len(), 2);
+
+        let pattern = format!("{}/*.txt", dir);
+        assert_eq!(expand_inputs(&[&pattern], false, &[]).unwrap().len(), 1);
+
+        let pipeline =
+            ConversionPipeline::new(Box::new(RimeImport::new()), Box::new(RimeExport::new()));
+        let report = convert_batch(
+            &pipeline,
+            &inputs,
+            &output_dir,
+            &OutputTemplate::new("{stem}.dict.yaml"),
+        );
+
+        assert_eq!(report.succeeded(), 2);
+        assert_eq!(report.total_words(), 2);
+        let converted = fs::read_to_string(output_dir.join("sub/b.dict.yaml")).unwrap();
+        fs::remove_dir_all(&root).ok();
+        assert!(converted.contains("世界"));
+    }
+}
@@ -2,11 +2,16 @@
 //!
 //! This module defines the fundamental data types used throughout the converter:
 //! - `WordLibrary`: Represents a dictionary entry (word + encoding + frequency)
+//! - `WordLibraryBuilder`: Fluent construction of entries
 //! - `Code`: Flexible encoding representation supporting various encoding schemes
 //! - `CodeType`: Enumeration of supported encoding types
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::ops::{Deref, DerefMut};
 
 /// Type of encoding used for the dictionary entry
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -29,6 +34,8 @@ pub enum CodeType {
     UserDefine,
     /// Pinyin
     Pinyin,
+    /// Double pinyin (Shuangpin)
+    Shuangpin,
     /// Yongma
     Yong,
     /// Qingsong Erbi
@@ -49,10 +56,36 @@ pub enum CodeType {
     TerraPinyin,
     /// Chaoyin
     Chaoyin,
+    /// Cantonese Jyutping
+    Jyutping,
+    /// Japanese kana, coded in romaji
+    Kana,
     /// No encoding
     NoCode,
 }
 
+impl CodeType {
+    /// Whether codes of this type are derived from the glyph of a character
+    ///
+    /// Such codes differ between the simplified and traditional form of the
+    /// same character, unlike phonetic codes such as pinyin.
+    pub fn is_glyph_based(&self) -> bool {
+        matches!(
+            self,
+            CodeType::Wubi
+                | CodeType::Wubi98
+                | CodeType::WubiNewAge
+                | CodeType::Zhengma
+                | CodeType::Cangjie
+                | CodeType::InnerCode
+                | CodeType::QingsongErbi
+                | CodeType::ChaoqiangErbi
+                | CodeType::ChaoqingYinxin
+                | CodeType::XiandaiErbi
+        )
+    }
+}
+
 impl fmt::Display for CodeType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -72,7 +105,7 @@ impl fmt::Display for CodeType {
 /// - For one-char-multi-code: `codes[n]` = nth character's possible codes
 /// - For one-word-one-code: `codes[0][0]` = the word's single code
 /// - For one-word-multi-code: `codes[0]` = the word's possible codes
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub struct Code(pub Vec<Vec<String>>);
 
 impl Code {
@@ -101,6 +134,18 @@ impl Code {
         Code(codes.into_iter().map(|c| vec![c]).collect())
     }
 
+    /// Create per-character codes from pinyin separated by `'` or spaces
+    /// (`ni'hao`, `ni hao`)
+    pub fn pinyin_str(pinyin: &str) -> Self {
+        Code::from_char_list(
+            pinyin
+                .split(|c: char| c == '\'' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
     /// Get the first code (most common usage)
     pub fn get_single_code(&self) -> Option<&str> {
         self.0.first()?.first().map(|s| s.as_str())
@@ -184,6 +229,54 @@ impl Code {
 
         result
     }
+
+    /// Number of combinations produced by the Cartesian product
+    pub fn combination_count(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|codes| !codes.is_empty())
+            .fold(1usize, |count, codes| count.saturating_mul(codes.len()))
+    }
+
+    /// Split into one-code-per-part codes, one for each combination
+    ///
+    /// Combinations are produced in the same order as `cartesian_product`,
+    /// stopping after `limit` of them so that long polyphonic words cannot
+    /// explode.
+    pub fn combinations(&self, limit: usize) -> Vec<Code> {
+        let parts: Vec<&Vec<String>> = self.0.iter().filter(|codes| !codes.is_empty()).collect();
+        if parts.is_empty() {
+            return vec![];
+        }
+
+        let mut result = Vec::new();
+        let mut indices = vec![0usize; parts.len()];
+        while result.len() < limit {
+            result.push(Code(
+                parts
+                    .iter()
+                    .zip(&indices)
+                    .map(|(codes, &i)| vec![codes[i].clone()])
+                    .collect(),
+            ));
+
+            // Advance the indices, last part varying fastest
+            let mut pos = parts.len();
+            loop {
+                if pos == 0 {
+                    return result;
+                }
+                pos -= 1;
+                indices[pos] += 1;
+                if indices[pos] < parts[pos].len() {
+                    break;
+                }
+                indices[pos] = 0;
+            }
+        }
+
+        result
+    }
 }
 
 impl From<Vec<Vec<String>>> for Code {
@@ -198,6 +291,16 @@ impl From<Vec<String>> for Code {
     }
 }
 
+/// Keys of `WordLibrary::attributes` known to the built-in formats
+pub mod attributes {
+    /// Decayed weight of a Rime userdb entry (`d=`)
+    pub const USERDB_WEIGHT: &str = "rime.userdb.weight";
+    /// Tick of the last commit of a Rime userdb entry (`t=`)
+    pub const USERDB_TICK: &str = "rime.userdb.tick";
+    /// `"true"` for idioms (成语), see `transform::idiom`
+    pub const IS_IDIOM: &str = "is_idiom";
+}
+
 /// Represents a dictionary entry
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WordLibrary {
@@ -215,6 +318,19 @@ pub struct WordLibrary {
 
     /// Whether this is an English word
     pub is_english: bool,
+
+    /// Whether the word is an emoji or symbol typed through its code
+    #[serde(default)]
+    pub is_symbol: bool,
+
+    /// Candidate position of a custom phrase (1 to 9), if the source has one
+    #[serde(default)]
+    pub position: Option<u8>,
+
+    /// Fields of the source format without a place in the model, kept so
+    /// the format can write them back; see `attributes` for the known keys
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
 }
 
 impl WordLibrary {
@@ -226,9 +342,17 @@ impl WordLibrary {
             code_type: CodeType::Pinyin,
             codes: Code::new(),
             is_english: false,
+            is_symbol: false,
+            position: None,
+            attributes: BTreeMap::new(),
         }
     }
 
+    /// Start building an entry, see `WordLibraryBuilder`
+    pub fn builder(word: impl Into<String>) -> WordLibraryBuilder {
+        WordLibraryBuilder::new(word)
+    }
+
     /// Create with rank
     pub fn with_rank(word: String, rank: i32) -> Self {
         WordLibrary {
@@ -237,9 +361,21 @@ impl WordLibrary {
             code_type: CodeType::Pinyin,
             codes: Code::new(),
             is_english: false,
+            is_symbol: false,
+            position: None,
+            attributes: BTreeMap::new(),
         }
     }
 
+    /// Value of a format-specific attribute
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+
+    pub fn set_attribute(&mut self, key: &str, value: &str) {
+        self.attributes.insert(key.to_string(), value.to_string());
+    }
+
     /// Set the code for this word
     pub fn set_code(&mut self, code_type: CodeType, codes: Code) {
         self.code_type = code_type;
@@ -260,6 +396,47 @@ impl WordLibrary {
         self.codes.get_single_code()
     }
 
+    /// Get the code of the whole word (for formats like Wubi)
+    ///
+    /// Per-character codes are joined, so `wq vb` gives `wqvb`.
+    pub fn get_word_code(&self) -> Option<String> {
+        let code = self.codes.to_string_with_separator("");
+        (!code.is_empty()).then_some(code)
+    }
+
+    /// Check if this is an English word
+    ///
+    /// Words are English when marked by the importer or when they only consist
+    /// of Latin letters (with spaces, apostrophes or hyphens).
+    pub fn is_english_word(&self) -> bool {
+        self.is_english
+            || self.code_type == CodeType::English
+            || (self.word.chars().any(|c| c.is_ascii_alphabetic())
+                && self
+                    .word
+                    .chars()
+                    .all(|c| c.is_ascii_alphabetic() || matches!(c, ' ' | '\'' | '-')))
+    }
+
+    /// Check if the word only consists of Japanese kana, e.g. `ありがとう`
+    pub fn is_kana_word(&self) -> bool {
+        !self.word.is_empty() && self.word.chars().all(crate::generate::romaji::is_kana)
+    }
+
+    /// Check if this is a symbol phrase, e.g. `😊` typed as `weixiao`
+    ///
+    /// Words are symbol phrases when marked by the importer or when they
+    /// contain no letters, digits or spaces and at least one non-ASCII
+    /// character, so ASCII-only words like `:)` are not caught.
+    pub fn is_symbol_phrase(&self) -> bool {
+        self.is_symbol
+            || (!self.word.is_ascii()
+                && self
+                    .word
+                    .chars()
+                    .all(|c| !c.is_alphanumeric() && !c.is_whitespace()))
+    }
+
     /// Check if the word has valid codes
     pub fn has_code(&self) -> bool {
         !self.codes.is_empty()
@@ -276,6 +453,81 @@ impl WordLibrary {
     }
 }
 
+/// Fluent construction of a `WordLibrary`
+///
+/// ```
+/// use imewlconverter_core::{CodeType, WordLibrary};
+///
+/// let word = WordLibrary::builder("你好").pinyin(["ni", "hao"]).rank(1000).build();
+/// assert_eq!(word.get_pinyin_string("'"), "ni'hao");
+/// assert_eq!(word.code_type, CodeType::Pinyin);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WordLibraryBuilder {
+    word: WordLibrary,
+}
+
+impl WordLibraryBuilder {
+    pub fn new(word: impl Into<String>) -> Self {
+        WordLibraryBuilder {
+            word: WordLibrary::new(word.into()),
+        }
+    }
+
+    pub fn rank(mut self, rank: i32) -> Self {
+        self.word.rank = rank;
+        self
+    }
+
+    /// Per-character pinyin codes
+    pub fn pinyin<I, S>(mut self, syllables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let codes = syllables.into_iter().map(Into::into).collect();
+        self.word
+            .set_code(CodeType::Pinyin, Code::from_char_list(codes));
+        self
+    }
+
+    /// Codes of any type
+    pub fn codes(mut self, code_type: CodeType, codes: Code) -> Self {
+        self.word.set_code(code_type, codes);
+        self
+    }
+
+    /// A single code for the whole word, e.g. a Wubi code
+    pub fn code(self, code_type: CodeType, code: impl Into<String>) -> Self {
+        self.codes(code_type, Code::from_single(code.into()))
+    }
+
+    pub fn english(mut self) -> Self {
+        self.word.is_english = true;
+        self
+    }
+
+    pub fn symbol(mut self) -> Self {
+        self.word.is_symbol = true;
+        self
+    }
+
+    /// Candidate position of a custom phrase (1 to 9)
+    pub fn position(mut self, position: u8) -> Self {
+        self.word.position = Some(position);
+        self
+    }
+
+    pub fn attribute(mut self, key: &str, value: &str) -> Self {
+        self.word.set_attribute(key, value);
+        self
+    }
+
+    pub fn build(self) -> WordLibrary {
+        self.word
+    }
+}
+
 impl fmt::Display for WordLibrary {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -288,8 +540,210 @@ impl fmt::Display for WordLibrary {
     }
 }
 
-/// A list of WordLibrary entries
-pub type WordLibraryList = Vec<WordLibrary>;
+/// Information a word library file carries about itself, e.g. the name and
+/// description of a Sogou cell dictionary or the header of a Rime dict.yaml
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryMetadata {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub category: Option<String>,
+    pub description: Option<String>,
+}
+
+impl LibraryMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A list of WordLibrary entries, with where it was read from
+///
+/// Derefs to a slice of the entries; converts from and into `Vec<WordLibrary>`.
+/// Serializes as the plain list of entries.
+#[derive(Debug, Clone, Default)]
+pub struct WordLibraryList {
+    words: Vec<WordLibrary>,
+    /// Name of the format the list was imported from
+    pub source_format: Option<String>,
+    /// Encoding the list was decoded from
+    pub source_encoding: Option<String>,
+    /// Name of the input, e.g. the file name
+    pub name: Option<String>,
+    /// Metadata read from the input itself
+    pub metadata: LibraryMetadata,
+}
+
+impl WordLibraryList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from(Vec::with_capacity(capacity))
+    }
+
+    pub fn push(&mut self, word: WordLibrary) {
+        self.words.push(word);
+    }
+
+    /// Move all entries of `other` to the end of the list
+    pub fn append(&mut self, other: &mut WordLibraryList) {
+        self.words.append(&mut other.words);
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.words.truncate(len);
+    }
+
+    /// Keep only the entries for which `keep` returns true; `keep` may also
+    /// modify the entries
+    pub fn filter_in_place<F>(&mut self, keep: F)
+    where
+        F: FnMut(&mut WordLibrary) -> bool,
+    {
+        self.words.retain_mut(keep);
+    }
+
+    /// Merge entries with the same word and codes, keeping the first entry
+    /// with the highest rank of its duplicates
+    pub fn dedup(&mut self) {
+        let mut index: HashMap<(String, Code), usize> = HashMap::new();
+        let mut merged: Vec<WordLibrary> = Vec::with_capacity(self.words.len());
+        for word in self.words.drain(..) {
+            match index.entry((word.word.clone(), word.codes.clone())) {
+                Entry::Occupied(entry) => {
+                    let existing = &mut merged[*entry.get()];
+                    existing.rank = existing.rank.max(word.rank);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(merged.len());
+                    merged.push(word);
+                }
+            }
+        }
+        self.words = merged;
+    }
+
+    /// Sort by rank, highest first; entries of equal rank keep their order
+    pub fn sort_by_rank(&mut self) {
+        self.words.sort_by_key(|w| Reverse(w.rank));
+    }
+
+    /// Number of characters of all words
+    pub fn total_chars(&self) -> usize {
+        self.words.iter().map(WordLibrary::len).sum()
+    }
+
+    /// Take over the source and metadata of `other`, e.g. for a list derived
+    /// from it
+    pub fn copy_metadata(&mut self, other: &WordLibraryList) {
+        self.source_format = other.source_format.clone();
+        self.source_encoding = other.source_encoding.clone();
+        self.name = other.name.clone();
+        self.metadata = other.metadata.clone();
+    }
+
+    pub fn into_vec(self) -> Vec<WordLibrary> {
+        self.words
+    }
+}
+
+impl Deref for WordLibraryList {
+    type Target = [WordLibrary];
+
+    fn deref(&self) -> &[WordLibrary] {
+        &self.words
+    }
+}
+
+impl DerefMut for WordLibraryList {
+    fn deref_mut(&mut self) -> &mut [WordLibrary] {
+        &mut self.words
+    }
+}
+
+/// Lists are equal when their entries are, whatever their metadata
+impl PartialEq for WordLibraryList {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl PartialEq<Vec<WordLibrary>> for WordLibraryList {
+    fn eq(&self, other: &Vec<WordLibrary>) -> bool {
+        &self.words == other
+    }
+}
+
+impl From<Vec<WordLibrary>> for WordLibraryList {
+    fn from(words: Vec<WordLibrary>) -> Self {
+        WordLibraryList {
+            words,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<WordLibraryList> for Vec<WordLibrary> {
+    fn from(list: WordLibraryList) -> Self {
+        list.words
+    }
+}
+
+impl FromIterator<WordLibrary> for WordLibraryList {
+    fn from_iter<I: IntoIterator<Item = WordLibrary>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl Extend<WordLibrary> for WordLibraryList {
+    fn extend<I: IntoIterator<Item = WordLibrary>>(&mut self, iter: I) {
+        self.words.extend(iter);
+    }
+}
+
+impl IntoIterator for WordLibraryList {
+    type Item = WordLibrary;
+    type IntoIter = std::vec::IntoIter<WordLibrary>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.words.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a WordLibraryList {
+    type Item = &'a WordLibrary;
+    type IntoIter = std::slice::Iter<'a, WordLibrary>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.words.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut WordLibraryList {
+    type Item = &'a mut WordLibrary;
+    type IntoIter = std::slice::IterMut<'a, WordLibrary>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.words.iter_mut()
+    }
+}
+
+impl Serialize for WordLibraryList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.words.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WordLibraryList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::from)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -321,6 +775,44 @@ mod tests {
         assert_eq!(result, vec!["ni'hao", "nv'hao"]);
     }
 
+    #[test]
+    fn test_code_combinations() {
+        let code = Code(vec![
+            vec!["chang".to_string(), "zhang".to_string()],
+            vec!["da".to_string()],
+            vec!["hang".to_string(), "xing".to_string()],
+        ]);
+        assert_eq!(code.combination_count(), 4);
+
+        let all = code.combinations(usize::MAX);
+        let strings: Vec<String> = all.iter().map(|c| c.to_string_with_separator("")).collect();
+        assert_eq!(strings, code.cartesian_product());
+
+        let capped = code.combinations(3);
+        assert_eq!(capped.len(), 3);
+        assert_eq!(capped[2].to_string_with_separator("'"), "zhang'da'hang");
+    }
+
+    #[test]
+    fn test_is_english_word() {
+        assert!(WordLibrary::new("hello world".to_string()).is_english_word());
+        assert!(!WordLibrary::new("卡拉OK".to_string()).is_english_word());
+        assert!(!WordLibrary::new("2008".to_string()).is_english_word());
+
+        let mut word = WordLibrary::new("C++".to_string());
+        assert!(!word.is_english_word());
+        word.is_english = true;
+        assert!(word.is_english_word());
+    }
+
+    #[test]
+    fn test_is_symbol_phrase() {
+        assert!(WordLibrary::new("😊".to_string()).is_symbol_phrase());
+        assert!(WordLibrary::new("→".to_string()).is_symbol_phrase());
+        assert!(!WordLibrary::new("你好😊".to_string()).is_symbol_phrase());
+        assert!(!WordLibrary::new(":)".to_string()).is_symbol_phrase());
+    }
+
     #[test]
     fn test_word_library() {
         let mut word = WordLibrary::new("你好".to_string());
@@ -333,5 +825,39 @@ mod tests {
         assert_eq!(word.word, "你好");
         assert_eq!(word.rank, 1000);
         assert_eq!(word.get_pinyin_string("'"), "ni'hao");
+
+        let built = WordLibrary::builder("你好")
+            .codes(CodeType::Pinyin, Code::pinyin_str("ni'hao"))
+            .rank(1000)
+            .build();
+        assert_eq!(built, word);
+        let wubi = WordLibrary::builder("工")
+            .code(CodeType::Wubi, "aaaa")
+            .build();
+        assert_eq!(wubi.get_word_code().as_deref(), Some("aaaa"));
+        assert_eq!(
+            Code::pinyin_str(" zhong guo'ren"),
+            Code::pinyin_str("zhong'guo'ren")
+        );
+    }
+
+    #[test]
+    fn test_word_library_list() {
+        let mut list: WordLibraryList = [("你好", 1), ("世界", 5), ("你好", 3), ("中", 2)]
+            .iter()
+            .map(|&(word, rank)| WordLibrary::with_rank(word.to_string(), rank))
+            .collect();
+        list.name = Some("test".to_string());
+        assert_eq!(list.total_chars(), 7);
+
+        list.dedup();
+        list.filter_in_place(|w| w.len() > 1);
+        list.sort_by_rank();
+        let entries: Vec<(&str, i32)> = list.iter().map(|w| (w.word.as_str(), w.rank)).collect();
+        assert_eq!(entries, vec![("世界", 5), ("你好", 3)]);
+        assert_eq!(list.name.as_deref(), Some("test"));
+
+        let words: Vec<WordLibrary> = list.clone().into();
+        assert_eq!(list, words);
     }
 }
@@ -0,0 +1,121 @@
+//! Comparing two word libraries
+//!
+//! Words are compared by their text. For words with several entries (e.g.
+//! polyphonic words) the first entry of each library is compared.
+
+use crate::{WordLibrary, WordLibraryList};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// A word present in both libraries with different codes or rank
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WordChange {
+    pub before: WordLibrary,
+    pub after: WordLibrary,
+}
+
+impl WordChange {
+    pub fn codes_changed(&self) -> bool {
+        self.before.codes != self.after.codes
+    }
+
+    pub fn rank_changed(&self) -> bool {
+        self.before.rank != self.after.rank
+    }
+}
+
+/// Differences between two word libraries, in the order of the libraries
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DiffResult {
+    /// Words only in the first library
+    pub removed: Vec<WordLibrary>,
+    /// Words only in the second library
+    pub added: Vec<WordLibrary>,
+    pub changed: Vec<WordChange>,
+}
+
+impl DiffResult {
+    /// Whether the libraries contain the same words with the same codes and ranks
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// First entry of every word
+fn first_entries(words: &WordLibraryList) -> HashMap<&str, &WordLibrary> {
+    let mut entries = HashMap::with_capacity(words.len());
+    for word in words {
+        entries.entry(word.word.as_str()).or_insert(word);
+    }
+    entries
+}
+
+/// Compare library `a` (before) with library `b` (after)
+pub fn diff(a: &WordLibraryList, b: &WordLibraryList) -> DiffResult {
+    let before = first_entries(a);
+    let after = first_entries(b);
+    let mut result = DiffResult::default();
+
+    let mut seen = HashSet::new();
+    for word in a {
+        if !seen.insert(word.word.as_str()) {
+            continue;
+        }
+        match after.get(word.word.as_str()) {
+            None => result.removed.push(word.clone()),
+            Some(&other) if other.codes != word.codes || other.rank != word.rank => {
+                result.changed.push(WordChange {
+                    before: word.clone(),
+                    after: other.clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for word in b {
+        if seen.insert(word.word.as_str()) && !before.contains_key(word.word.as_str()) {
+            result.added.push(word.clone());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    fn word(text: &str, code: &str, rank: i32) -> WordLibrary {
+        let mut word = WordLibrary::with_rank(text.to_string(), rank);
+        word.codes = Code::from_single(code.to_string());
+        word
+    }
+
+    #[test]
+    fn test_diff() {
+        let a: WordLibraryList = vec![
+            word("你好", "nihao", 10),
+            word("世界", "shijie", 5),
+            word("再见", "zaijian", 1),
+        ]
+        .into();
+        let b: WordLibraryList = vec![
+            word("你好", "nihao", 10),
+            word("世界", "shijie", 8),
+            word("中国", "zhongguo", 3),
+        ]
+        .into();
+
+        let result = diff(&a, &b);
+        assert_eq!(result.removed, vec![word("再见", "zaijian", 1)]);
+        assert_eq!(result.added, vec![word("中国", "zhongguo", 3)]);
+        assert_eq!(result.changed.len(), 1);
+        assert!(result.changed[0].rank_changed());
+        assert!(!result.changed[0].codes_changed());
+
+        assert!(diff(&a, &a).is_empty());
+    }
+}
@@ -18,6 +18,16 @@ pub enum Error {
     #[error("Parse error: {0}")]
     Parse(String),
 
+    #[error("Parse error at {path}:{line}: {message}: {content}")]
+    LineParse {
+        path: String,
+        /// 1-based line number
+        line: usize,
+        /// The offending line
+        content: String,
+        message: String,
+    },
+
     #[error("Invalid format: {0}")]
     InvalidFormat(String),
 
@@ -38,4 +48,43 @@ pub enum Error {
 
     #[error("Invalid file format: expected {expected}, got {actual}")]
     FormatMismatch { expected: String, actual: String },
+
+    #[error("Network error: {0}")]
+    Network(String),
+}
+
+impl Error {
+    /// Attach the location of the line that caused the error
+    ///
+    /// Errors that already carry a location are returned unchanged.
+    pub fn at_line(self, path: &str, line: usize, content: &str) -> Error {
+        let message = match self {
+            Error::LineParse { .. } => return self,
+            Error::Parse(message) => message,
+            other => other.to_string(),
+        };
+        Error::LineParse {
+            path: path.to_string(),
+            line,
+            content: content.to_string(),
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at_line() {
+        let error = Error::Parse("malformed line".to_string()).at_line("a.txt", 3, "broken");
+        assert_eq!(
+            error.to_string(),
+            "Parse error at a.txt:3: malformed line: broken"
+        );
+
+        let error = error.at_line("b.txt", 5, "other");
+        assert!(matches!(error, Error::LineParse { line: 3, .. }));
+    }
 }
@@ -1,9 +1,28 @@
 //! Export traits and implementations for various IME formats
 
-use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+use crate::helpers::encode_text;
+use crate::{Code, CodeType, Result, WordLibrary, WordLibraryList};
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
 
+pub mod apple_replacement;
+pub mod custom_text;
+pub mod english_phrase;
+pub mod ibus_table;
+pub mod jidian;
+pub mod libime;
+pub mod ms_wubi;
+pub mod ndjson;
 pub mod qq_pinyin;
 pub mod rime;
+pub mod rime_essay;
+pub mod rime_userdb;
+pub mod sogou_english;
+pub mod sogou_phrase;
+pub mod sogou_wubi;
+pub mod yong;
 
 /// Trait for exporting word libraries to files
 pub trait WordLibraryExport {
@@ -17,6 +36,12 @@ pub trait WordLibraryExport {
     /// Get the code type this exporter expects
     fn code_type(&self) -> CodeType;
 
+    /// Whether codes of any type are written as they are, so imported codes
+    /// need no conversion to `code_type`
+    fn accepts_any_code_type(&self) -> bool {
+        false
+    }
+
     /// Get the format name
     fn format_name(&self) -> &str;
 
@@ -24,14 +49,431 @@ pub trait WordLibraryExport {
     fn encoding(&self) -> &'static str {
         "utf-8"
     }
+
+    /// Export a word library list to a writer, e.g. stdout
+    ///
+    /// Split outputs are written one after another.
+    fn export_to_writer(&self, word_list: &WordLibraryList, writer: &mut dyn Write) -> Result<()> {
+        write_outputs(&self.export(word_list)?, self.encoding(), writer)
+    }
+
+    /// This exporter as a streaming one, for line-based formats
+    fn as_stream_export(&self) -> Option<&dyn WordLibraryStreamExport> {
+        None
+    }
+}
+
+/// Write exported outputs to one writer in the given encoding, separated by
+/// line breaks
+pub fn write_outputs(outputs: &[String], encoding: &str, writer: &mut dyn Write) -> Result<()> {
+    for (i, output) in outputs.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(&encode_text("\n", encoding))?;
+        }
+        writer.write_all(&encode_text(output, encoding))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Trait for exporters that write entries one at a time
+///
+/// Entries are encoded and written as they arrive, so memory use stays
+/// bounded regardless of the list size. Import errors in `words` abort the
+/// export.
+pub trait WordLibraryStreamExport: WordLibraryExport {
+    /// Write entries to `writer` in the exporter's encoding
+    ///
+    /// Returns the number of lines written.
+    fn export_stream(
+        &self,
+        words: &mut dyn Iterator<Item = Result<WordLibrary>>,
+        writer: &mut dyn Write,
+    ) -> Result<usize>;
+}
+
+/// How English words are exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EnglishPolicy {
+    /// Export English words with their lowercase spelling as code
+    #[default]
+    Keep,
+    /// Leave English words out
+    Drop,
+    /// Export English words as a separate output after the other ones
+    Separate,
+}
+
+/// Prepare an English word for an exporter expecting `code_type` codes
+pub(crate) fn english_entry(word: &WordLibrary, code_type: CodeType) -> WordLibrary {
+    let mut entry = word.clone();
+    // Custom phrases are typed through their shortcut, not the word itself
+    if word.code_type == CodeType::UserDefinePhrase && word.has_code() {
+        return entry;
+    }
+    entry.code_type = code_type;
+    entry.codes = Code::from_char_list(vec![word.word.to_lowercase()]);
+    entry
+}
+
+/// Export a word list, handling English words according to `policy`
+///
+/// Most exporters expect Chinese codes and would otherwise silently skip
+/// English words.
+pub fn export_with_english_policy(
+    exporter: &dyn WordLibraryExport,
+    word_list: &WordLibraryList,
+    policy: EnglishPolicy,
+) -> Result<Vec<String>> {
+    let (english, chinese): (WordLibraryList, WordLibraryList) =
+        word_list.iter().cloned().partition(|w| w.is_english_word());
+    if english.is_empty() {
+        return exporter.export(word_list);
+    }
+
+    let code_type = exporter.code_type();
+    let english: WordLibraryList = english
+        .iter()
+        .map(|w| english_entry(w, code_type))
+        .collect();
+
+    match policy {
+        EnglishPolicy::Keep => {
+            let words: WordLibraryList = word_list
+                .iter()
+                .map(|w| {
+                    if w.is_english_word() {
+                        english_entry(w, code_type)
+                    } else {
+                        w.clone()
+                    }
+                })
+                .collect();
+            exporter.export(&words)
+        }
+        EnglishPolicy::Drop => exporter.export(&chinese),
+        EnglishPolicy::Separate => {
+            let mut output = exporter.export(&chinese)?;
+            output.extend(exporter.export(&english)?);
+            Ok(output)
+        }
+    }
+}
+
+/// How the exported words are split into several outputs
+///
+/// Some IMEs refuse to import files over a certain size, e.g. 5,000 entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportSplit {
+    /// Export all words into one output
+    #[default]
+    None,
+    /// At most this many entries per output
+    MaxEntries(usize),
+    /// At most this many bytes of entries per output, in the exporter's
+    /// encoding; an entry larger than the limit gets an output of its own
+    MaxBytes(usize),
+    /// One output per first letter of the codes, in alphabetical order;
+    /// words whose codes do not start with a letter come first
+    FirstLetter,
+}
+
+/// First letter of the codes of a word, if it is an ASCII letter
+fn first_letter(word: &WordLibrary) -> Option<char> {
+    word.codes
+        .0
+        .first()
+        .and_then(|codes| codes.first())
+        .or_else(|| word.is_english_word().then_some(&word.word))
+        .and_then(|code| code.chars().next())
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_lowercase())
+}
+
+/// Split a word list into the chunks exported as separate outputs
+pub fn split_words(
+    exporter: &dyn WordLibraryExport,
+    word_list: &WordLibraryList,
+    split: ExportSplit,
+) -> Vec<WordLibraryList> {
+    let mut chunks: Vec<WordLibraryList> = match split {
+        ExportSplit::None => vec![word_list.clone()],
+        ExportSplit::MaxEntries(max) => word_list
+            .chunks(max.max(1))
+            .map(|chunk| chunk.to_vec().into())
+            .collect(),
+        ExportSplit::MaxBytes(max) => {
+            let mut chunks = Vec::new();
+            let mut chunk = WordLibraryList::new();
+            let mut size = 0;
+            for word in word_list {
+                // Entries the exporter cannot write take no space
+                let len = exporter
+                    .export_line(word)
+                    .ok()
+                    .filter(|line| !line.is_empty())
+                    .map(|line| encode_text(&line, exporter.encoding()).len() + 1)
+                    .unwrap_or(0);
+                if !chunk.is_empty() && size + len > max {
+                    chunks.push(std::mem::take(&mut chunk));
+                    size = 0;
+                }
+                chunk.push(word.clone());
+                size += len;
+            }
+            if !chunk.is_empty() {
+                chunks.push(chunk);
+            }
+            chunks
+        }
+        ExportSplit::FirstLetter => {
+            let mut groups: BTreeMap<Option<char>, WordLibraryList> = BTreeMap::new();
+            for word in word_list {
+                groups
+                    .entry(first_letter(word))
+                    .or_default()
+                    .push(word.clone());
+            }
+            groups.into_values().collect()
+        }
+    };
+    // Every output describes the same library
+    for chunk in &mut chunks {
+        chunk.copy_metadata(word_list);
+    }
+    chunks
+}
+
+/// Default cap on the number of combinations exported per polyphonic word
+pub const DEFAULT_POLYPHONIC_LIMIT: usize = 64;
+
+/// Expand polyphonic words into one entry per code combination
+///
+/// Equivalent to the "多音字全输出" option of the original tool: a word such as
+/// 长大 with codes `[chang, zhang] [da]` becomes two entries, `chang da` and
+/// `zhang da`. At most `limit` entries are emitted for each word.
+pub fn expand_polyphonic(word_list: &WordLibraryList, limit: usize) -> WordLibraryList {
+    let mut result = WordLibraryList::with_capacity(word_list.len());
+    for word in word_list {
+        if word.codes.combination_count() <= 1 {
+            result.push(word.clone());
+            continue;
+        }
+        for codes in word.codes.combinations(limit) {
+            let mut expanded = word.clone();
+            expanded.codes = codes;
+            result.push(expanded);
+        }
+    }
+    result
+}
+
+/// Group words by their word code, for formats listing all words of a code
+/// on one line
+///
+/// Codes keep the order of their first word; the words of a code are sorted
+/// by rank, highest first. Words without a code are left out.
+pub fn group_by_code(word_list: &WordLibraryList) -> Vec<(String, Vec<&WordLibrary>)> {
+    let mut groups: Vec<(String, Vec<&WordLibrary>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for word in word_list {
+        let Some(code) = word.get_word_code() else {
+            continue;
+        };
+        let i = *index.entry(code.clone()).or_insert_with(|| {
+            groups.push((code, Vec::new()));
+            groups.len() - 1
+        });
+        groups[i].1.push(word);
+    }
+    for (_, words) in &mut groups {
+        words.sort_by_key(|w| Reverse(w.rank));
+    }
+    groups
+}
+
+/// Resolves words sharing a code (重码) before export
+///
+/// Words are grouped by code as in `group_by_code`, highest rank first, and
+/// optionally capped per code. With ordinal suffixes every word but the first
+/// of a code gets its position appended (`klwl`, `klwl2`, `klwl3`), for
+/// targets accepting only one word per code.
+#[derive(Debug, Clone, Default)]
+pub struct CollisionResolver {
+    max_candidates: Option<usize>,
+    ordinal_suffix: bool,
+}
+
+impl CollisionResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `max` words per code
+    pub fn with_max_candidates(mut self, max: usize) -> Self {
+        self.max_candidates = Some(max);
+        self
+    }
+
+    /// Append the position within its code to the code of each word
+    pub fn with_ordinal_suffix(mut self, ordinal_suffix: bool) -> Self {
+        self.ordinal_suffix = ordinal_suffix;
+        self
+    }
+
+    /// Order words by code and rank; words without a code come last
+    pub fn resolve(&self, word_list: &WordLibraryList) -> WordLibraryList {
+        let mut result = WordLibraryList::with_capacity(word_list.len());
+        for (code, words) in group_by_code(word_list) {
+            let max = self.max_candidates.unwrap_or(words.len());
+            for (i, word) in words.into_iter().take(max).enumerate() {
+                let mut word = word.clone();
+                if self.ordinal_suffix && i > 0 {
+                    word.codes = Code::from_single(format!("{}{}", code, i + 1));
+                }
+                result.push(word);
+            }
+        }
+        result.extend(
+            word_list
+                .iter()
+                .filter(|w| w.get_word_code().is_none())
+                .cloned(),
+        );
+        result
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Code;
 
     #[test]
     fn test_export_trait_exists() {
         // Just test that the trait compiles
     }
+
+    #[test]
+    fn test_english_policy() {
+        use crate::export::rime::RimeExport;
+
+        let mut chinese = WordLibrary::with_rank("你好".to_string(), 2);
+        chinese.codes = Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]);
+        let mut english = WordLibrary::with_rank("Hello".to_string(), 1);
+        english.code_type = CodeType::English;
+        let words: WordLibraryList = vec![chinese, english].into();
+        let exporter = RimeExport::new();
+
+        let keep = export_with_english_policy(&exporter, &words, EnglishPolicy::Keep).unwrap();
+        assert_eq!(keep, vec!["你好\tni hao\t2\nHello\thello\t1".to_string()]);
+
+        let drop = export_with_english_policy(&exporter, &words, EnglishPolicy::Drop).unwrap();
+        assert_eq!(drop, vec!["你好\tni hao\t2".to_string()]);
+
+        let mut written = Vec::new();
+        exporter
+            .export_to_writer(&words[..1].to_vec().into(), &mut written)
+            .unwrap();
+        assert_eq!(written, "你好\tni hao\t2".as_bytes());
+
+        let separate =
+            export_with_english_policy(&exporter, &words, EnglishPolicy::Separate).unwrap();
+        assert_eq!(
+            separate,
+            vec!["你好\tni hao\t2".to_string(), "Hello\thello\t1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_split_words() {
+        use crate::export::rime::RimeExport;
+
+        let words: WordLibraryList = [("北京", "bei jing"), ("你好", "ni hao"), ("爱", "ai")]
+            .iter()
+            .map(|&(text, code)| {
+                let mut word = WordLibrary::new(text.to_string());
+                word.codes = Code::from_char_list(code.split(' ').map(String::from).collect());
+                word
+            })
+            .collect();
+        let exporter = RimeExport::new();
+        let texts = |chunks: Vec<WordLibraryList>| -> Vec<Vec<String>> {
+            chunks
+                .into_iter()
+                .map(|chunk| chunk.into_iter().map(|w| w.word).collect())
+                .collect()
+        };
+
+        assert_eq!(split_words(&exporter, &words, ExportSplit::None).len(), 1);
+        assert_eq!(
+            texts(split_words(&exporter, &words, ExportSplit::MaxEntries(2))),
+            vec![vec!["北京", "你好"], vec!["爱"]]
+        );
+        // "北京\tbei jing\t0" is 17 bytes with the line ending
+        assert_eq!(
+            texts(split_words(&exporter, &words, ExportSplit::MaxBytes(20))),
+            vec![vec!["北京"], vec!["你好"], vec!["爱"]]
+        );
+        assert_eq!(
+            texts(split_words(&exporter, &words, ExportSplit::FirstLetter)),
+            vec![vec!["爱"], vec!["北京"], vec!["你好"]]
+        );
+    }
+
+    #[test]
+    fn test_expand_polyphonic() {
+        let mut word = WordLibrary::new("长行".to_string());
+        word.codes = Code(vec![
+            vec!["chang".to_string(), "zhang".to_string()],
+            vec!["hang".to_string(), "xing".to_string()],
+        ]);
+        let mut plain = WordLibrary::new("你好".to_string());
+        plain.codes = Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]);
+
+        let expanded =
+            expand_polyphonic(&vec![word.clone(), plain].into(), DEFAULT_POLYPHONIC_LIMIT);
+        let codes: Vec<String> = expanded.iter().map(|w| w.get_pinyin_string("'")).collect();
+        assert_eq!(
+            codes,
+            vec![
+                "chang'hang",
+                "chang'xing",
+                "zhang'hang",
+                "zhang'xing",
+                "ni'hao"
+            ]
+        );
+
+        assert_eq!(expand_polyphonic(&vec![word].into(), 2).len(), 2);
+    }
+
+    #[test]
+    fn test_collision_resolver() {
+        let words: WordLibraryList = [("工", 1), ("式", 3), ("戒", 2), ("你", 5)]
+            .iter()
+            .map(|&(text, rank)| {
+                let mut word = WordLibrary::with_rank(text.to_string(), rank);
+                word.code_type = CodeType::Wubi;
+                let code = if text == "你" { "wq" } else { "aaaa" };
+                word.codes = Code::from_single(code.to_string());
+                word
+            })
+            .collect();
+
+        let resolved = CollisionResolver::new()
+            .with_max_candidates(2)
+            .with_ordinal_suffix(true)
+            .resolve(&words);
+        let entries: Vec<(String, String)> = resolved
+            .iter()
+            .map(|w| (w.word.clone(), w.get_word_code().unwrap()))
+            .collect();
+        let expected = [("式", "aaaa"), ("戒", "aaaa2"), ("你", "wq")];
+        assert_eq!(
+            entries,
+            expected.map(|(w, c)| (w.to_string(), c.to_string()))
+        );
+    }
 }
@@ -0,0 +1,121 @@
+//! Apple text replacement (文本替换) export
+//!
+//! The property list can be dragged into the text replacement list of
+//! System Settings on macOS, from where iCloud syncs it to iOS.
+
+use crate::export::sogou_phrase::phrase_code;
+use crate::export::WordLibraryExport;
+use crate::import::apple_replacement::{ReplacementFormat, CSV_HEADER};
+use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+
+const PLIST_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<array>"#;
+
+const PLIST_FOOTER: &str = "</array>\n</plist>";
+
+/// Apple text replacement exporter
+///
+/// The shortcut is the word code, e.g. the joined pinyin of the word.
+pub struct AppleReplacementExport {
+    format: ReplacementFormat,
+}
+
+impl AppleReplacementExport {
+    pub fn new(format: ReplacementFormat) -> Self {
+        AppleReplacementExport { format }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+impl WordLibraryExport for AppleReplacementExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let lines: Vec<String> = word_list
+            .iter()
+            .filter_map(|word| self.export_line(word).ok())
+            .collect();
+        let content = match self.format {
+            ReplacementFormat::Plist => {
+                format!("{}\n{}\n{}\n", PLIST_HEADER, lines.join("\n"), PLIST_FOOTER)
+            }
+            ReplacementFormat::Csv => {
+                let mut rows = vec![CSV_HEADER.to_string()];
+                rows.extend(lines);
+                rows.join("\r\n") + "\r\n"
+            }
+        };
+        Ok(vec![content])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        let shortcut = phrase_code(word)
+            .ok_or_else(|| Error::InvalidFormat(format!("No shortcut for {}", word.word)))?;
+        Ok(match self.format {
+            ReplacementFormat::Plist => format!(
+                "\t<dict>\n\t\t<key>phrase</key>\n\t\t<string>{}</string>\n\t\t<key>shortcut</key>\n\t\t<string>{}</string>\n\t</dict>",
+                escape_xml(&word.word),
+                shortcut
+            ),
+            ReplacementFormat::Csv => format!("{},{}", shortcut, escape_csv(&word.word)),
+        })
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Pinyin
+    }
+
+    fn format_name(&self) -> &str {
+        match self.format {
+            ReplacementFormat::Plist => "apple-plist",
+            ReplacementFormat::Csv => "apple-csv",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::apple_replacement::{parse_csv, parse_plist};
+    use crate::Code;
+
+    #[test]
+    fn test_round_trip() {
+        let mut word = WordLibrary::new("R&D, \"研发\"".to_string());
+        word.code_type = CodeType::UserDefinePhrase;
+        word.codes = Code::from_single("rd".to_string());
+        let mut pinyin = WordLibrary::new("你好".to_string());
+        pinyin.code_type = CodeType::Pinyin;
+        pinyin.codes = Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]);
+        let mut wubi = WordLibrary::new("工".to_string());
+        wubi.code_type = CodeType::Wubi;
+        wubi.codes = Code::from_single("A1".to_string());
+        let words: WordLibraryList = vec![word, pinyin, wubi].into();
+
+        let expected = vec![
+            ("rd".to_string(), "R&D, \"研发\"".to_string()),
+            ("nihao".to_string(), "你好".to_string()),
+        ];
+        let plist = AppleReplacementExport::new(ReplacementFormat::Plist)
+            .export(&words)
+            .unwrap();
+        assert_eq!(parse_plist(&plist[0]).unwrap(), expected);
+        let csv = AppleReplacementExport::new(ReplacementFormat::Csv)
+            .export(&words)
+            .unwrap();
+        assert_eq!(parse_csv(&csv[0]).unwrap(), expected);
+    }
+}
@@ -0,0 +1,244 @@
+//! Custom text export with a user line template
+//!
+//! Template: placeholders `{word}`, `{code}` (codes separated by a space),
+//! `{code:SEP}` (codes separated by SEP) and `{rank}`; `{{`, `}}`, `\t` and
+//! `\\` stand for `{`, `}`, a tab and a backslash
+//! Example: `{code:'}={word},{rank}` writes `ni'hao=你好,1000`
+
+use crate::export::WordLibraryExport;
+use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+
+/// Separator of `{code}` without an explicit one
+pub const DEFAULT_CODE_SEPARATOR: &str = " ";
+
+/// Field of a template placeholder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    Word,
+    Code,
+    Rank,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(Placeholder),
+}
+
+/// Parsed line template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateSpec {
+    /// Placeholders in order of appearance
+    pub placeholders: Vec<Placeholder>,
+    /// Separator between the codes of the characters, if `{code}` is used
+    pub code_separator: Option<String>,
+    segments: Vec<Segment>,
+}
+
+impl TemplateSpec {
+    pub fn uses(&self, placeholder: Placeholder) -> bool {
+        self.placeholders.contains(&placeholder)
+    }
+
+    fn render(&self, word: &WordLibrary) -> Option<String> {
+        let mut line = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => line.push_str(text),
+                Segment::Field(Placeholder::Word) => line.push_str(&word.word),
+                Segment::Field(Placeholder::Rank) => line.push_str(&word.rank.to_string()),
+                Segment::Field(Placeholder::Code) => {
+                    let separator = self
+                        .code_separator
+                        .as_deref()
+                        .unwrap_or(DEFAULT_CODE_SEPARATOR);
+                    let code = match word.code_type {
+                        CodeType::Pinyin | CodeType::TerraPinyin | CodeType::Jyutping => {
+                            word.get_pinyin_string(separator)
+                        }
+                        _ => word.get_word_code()?,
+                    };
+                    if code.is_empty() {
+                        return None;
+                    }
+                    line.push_str(&code);
+                }
+            }
+        }
+        Some(line)
+    }
+}
+
+fn template_error(template: &str, message: &str) -> Error {
+    Error::InvalidFormat(format!("Invalid template '{}': {}", template, message))
+}
+
+/// Exporter writing one template line per word
+pub struct CustomTextExport {
+    spec: TemplateSpec,
+}
+
+impl CustomTextExport {
+    /// Exporter for `template`, see `validate_template`
+    pub fn new(template: &str) -> Result<Self> {
+        Ok(CustomTextExport {
+            spec: Self::validate_template(template)?,
+        })
+    }
+
+    pub fn spec(&self) -> &TemplateSpec {
+        &self.spec
+    }
+
+    /// Parse a line template, failing on unknown placeholders, unbalanced
+    /// braces, conflicting code separators or a missing `{word}`
+    pub fn validate_template(template: &str) -> Result<TemplateSpec> {
+        let mut segments = Vec::new();
+        let mut placeholders = Vec::new();
+        let mut code_separator: Option<String> = None;
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(template_error(template, "unmatched '}'")),
+                '\\' => match chars.next() {
+                    Some('t') => literal.push('\t'),
+                    Some('\\') => literal.push('\\'),
+                    Some(other) => {
+                        literal.push('\\');
+                        literal.push(other);
+                    }
+                    None => literal.push('\\'),
+                },
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(template_error(template, "unclosed '{'")),
+                        }
+                    }
+                    let (field, separator) = match name.split_once(':') {
+                        Some((field, separator)) => (field, Some(separator)),
+                        None => (name.as_str(), None),
+                    };
+                    let placeholder = match (field, separator) {
+                        ("word", None) => Placeholder::Word,
+                        ("rank", None) => Placeholder::Rank,
+                        ("code", separator) => {
+                            let separator = separator.unwrap_or(DEFAULT_CODE_SEPARATOR);
+                            match &code_separator {
+                                Some(s) if s != separator => {
+                                    return Err(template_error(
+                                        template,
+                                        "conflicting code separators",
+                                    ))
+                                }
+                                _ => code_separator = Some(separator.to_string()),
+                            }
+                            Placeholder::Code
+                        }
+                        _ => {
+                            return Err(template_error(
+                                template,
+                                &format!("unknown placeholder {{{}}}", name),
+                            ))
+                        }
+                    };
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(placeholder));
+                    placeholders.push(placeholder);
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        if !placeholders.contains(&Placeholder::Word) {
+            return Err(template_error(template, "no {word} placeholder"));
+        }
+
+        Ok(TemplateSpec {
+            placeholders,
+            code_separator,
+            segments,
+        })
+    }
+}
+
+impl WordLibraryExport for CustomTextExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let lines: Vec<String> = word_list
+            .iter()
+            .filter_map(|w| self.spec.render(w))
+            .collect();
+        Ok(vec![lines.join("\n")])
+    }
+
+    /// Render one word, empty for words without the codes `{code}` needs
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        Ok(self.spec.render(word).unwrap_or_default())
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Pinyin
+    }
+
+    fn accepts_any_code_type(&self) -> bool {
+        true
+    }
+
+    fn format_name(&self) -> &str {
+        "Custom"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    #[test]
+    fn test_validate_template() {
+        let spec = CustomTextExport::validate_template("{word}\\t{code:'}\\t{rank}").unwrap();
+        assert_eq!(
+            spec.placeholders,
+            vec![Placeholder::Word, Placeholder::Code, Placeholder::Rank]
+        );
+        assert_eq!(spec.code_separator.as_deref(), Some("'"));
+        let spec = CustomTextExport::validate_template("{{{word}}}").unwrap();
+        assert_eq!(spec.code_separator, None);
+
+        for template in [
+            "{word",
+            "{word}}x}",
+            "{code}",
+            "{word} {freq}",
+            "{word} {code:'} {code}",
+        ] {
+            assert!(
+                CustomTextExport::validate_template(template).is_err(),
+                "{}",
+                template
+            );
+        }
+
+        let mut word = WordLibrary::with_rank("你好".to_string(), 1000);
+        word.codes = Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]);
+        let exporter = CustomTextExport::new("{code:'}={word},{rank}").unwrap();
+        assert_eq!(exporter.export_line(&word).unwrap(), "ni'hao=你好,1000");
+    }
+}
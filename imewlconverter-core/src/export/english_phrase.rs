@@ -0,0 +1,104 @@
+//! English custom phrase (英文自定义短语) export for QQ and Sogou Pinyin
+//!
+//! Format: see `import::english_phrase`
+//! Example: `omw,1=On my way!`
+
+use crate::export::sogou_phrase::candidate_position;
+use crate::export::{group_by_code, WordLibraryExport};
+use crate::import::english_phrase::PhraseDialect;
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+
+/// English custom phrase exporter
+///
+/// Only custom phrases (`UserDefinePhrase` codes) with an alphanumeric
+/// shortcut are written; expansions are kept on one line. Phrases sharing a
+/// shortcut keep their positions, or get positions 1 to 9 by rank.
+pub struct EnglishPhraseExport {
+    dialect: PhraseDialect,
+}
+
+impl EnglishPhraseExport {
+    pub fn new(dialect: PhraseDialect) -> Self {
+        EnglishPhraseExport { dialect }
+    }
+}
+
+/// Check if a word is a custom phrase the format can hold
+fn is_exportable(word: &WordLibrary) -> bool {
+    word.code_type == CodeType::UserDefinePhrase
+        && !word.word.trim().is_empty()
+        && !word.word.contains('\n')
+        && word
+            .get_word_code()
+            .is_some_and(|code| code.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+impl WordLibraryExport for EnglishPhraseExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let words: WordLibraryList = word_list
+            .iter()
+            .filter(|w| is_exportable(w))
+            .cloned()
+            .collect();
+        let mut lines = Vec::new();
+        for (shortcut, words) in group_by_code(&words) {
+            for (i, word) in words.iter().enumerate() {
+                let position = candidate_position(word, i);
+                lines.push(self.dialect.format(&shortcut, position, &word.word));
+            }
+        }
+        Ok(vec![lines.join("\r\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        match word.get_word_code() {
+            Some(shortcut) if is_exportable(word) => {
+                Ok(self
+                    .dialect
+                    .format(&shortcut, candidate_position(word, 0), &word.word))
+            }
+            _ => Ok(String::new()),
+        }
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::UserDefinePhrase
+    }
+
+    fn format_name(&self) -> &str {
+        match self.dialect {
+            PhraseDialect::Sogou => "Sogou English custom phrase",
+            PhraseDialect::QQ => "QQ English custom phrase",
+        }
+    }
+
+    fn encoding(&self) -> &'static str {
+        self.dialect.encoding()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{export_with_english_policy, EnglishPolicy};
+    use crate::import::{EnglishPhraseImport, ErrorPolicy, ImportOptions, WordLibraryImport};
+
+    #[test]
+    fn test_sogou_to_qq() {
+        let content = "omw,1=On my way!\nbrb,1=Be right back\nomw,2=on my way";
+        let words: WordLibraryList = EnglishPhraseImport::new(PhraseDialect::Sogou)
+            .import_from_reader(
+                &mut content.as_bytes(),
+                &ImportOptions::new().with_error_policy(ErrorPolicy::Strict),
+            )
+            .unwrap()
+            .into();
+
+        let exporter = EnglishPhraseExport::new(PhraseDialect::QQ);
+        let output = export_with_english_policy(&exporter, &words, EnglishPolicy::Keep).unwrap();
+        assert_eq!(
+            output[0],
+            "omw=1,On my way!\r\nomw=2,on my way\r\nbrb=1,Be right back"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! ibus-table source file export
+//!
+//! Format: `code\tword\tfreq` between `BEGIN_TABLE` and `END_TABLE`, after a
+//! `BEGIN_DEFINITION` section of `KEY = value` attributes
+//! Example: `vqkb\t你好\t1000`
+//!
+//! Compile the output with `ibus-table-createdb -s wubi.txt -n wubi.db`.
+
+use crate::export::WordLibraryExport;
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+use std::collections::BTreeSet;
+
+/// ibus-table source file exporter
+pub struct IbusTableExport {
+    code_type: CodeType,
+    /// Attributes replacing or adding to the generated ones
+    attributes: Vec<(String, String)>,
+}
+
+impl IbusTableExport {
+    pub fn new() -> Self {
+        Self::with_code_type(CodeType::Wubi)
+    }
+
+    pub fn with_code_type(code_type: CodeType) -> Self {
+        IbusTableExport {
+            code_type,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Set an attribute of the definition section, e.g. `NAME`
+    pub fn with_attribute(mut self, key: &str, value: &str) -> Self {
+        self.attributes.retain(|(k, _)| k != key);
+        self.attributes.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Code of a word in the table: pinyin without separators, other code
+    /// types use their single code
+    fn code(&self, word: &WordLibrary) -> Option<String> {
+        let code = if self.code_type == CodeType::Pinyin {
+            word.get_pinyin_string("")
+        } else {
+            word.get_word_code()?
+        };
+        (!code.is_empty()).then_some(code)
+    }
+
+    /// Attributes of the definition section for the given codes
+    fn definition(&self, codes: &[String]) -> Vec<(String, String)> {
+        let symbol = match self.code_type {
+            CodeType::Pinyin => "拼",
+            CodeType::Wubi | CodeType::Wubi98 | CodeType::WubiNewAge => "五",
+            CodeType::Cangjie => "倉",
+            CodeType::Zhengma => "郑",
+            _ => "码",
+        };
+        let input_chars: BTreeSet<char> = codes.iter().flat_map(|code| code.chars()).collect();
+        let max_key_length = codes.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+
+        let mut definition: Vec<(String, String)> = [
+            ("SERIAL_NUMBER", "1".to_string()),
+            ("ICON", "ibus-table.svg".to_string()),
+            ("SYMBOL", symbol.to_string()),
+            ("NAME", "imewlconverter".to_string()),
+            ("LANGUAGES", "zh_CN".to_string()),
+            ("VALID_INPUT_CHARS", input_chars.into_iter().collect()),
+            ("MAX_KEY_LENGTH", max_key_length.to_string()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+
+        for (key, value) in &self.attributes {
+            match definition.iter_mut().find(|(k, _)| k == key) {
+                Some(attribute) => attribute.1 = value.clone(),
+                None => definition.push((key.clone(), value.clone())),
+            }
+        }
+        definition
+    }
+}
+
+impl Default for IbusTableExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for IbusTableExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let mut codes = Vec::new();
+        let mut entries = Vec::new();
+        for word in word_list {
+            if let Some(code) = self.code(word) {
+                entries.push(format!("{}\t{}\t{}", code, word.word, word.rank));
+                codes.push(code);
+            }
+        }
+
+        let mut lines = vec![
+            "### File header must not be modified".to_string(),
+            "### This file must be encoded into UTF-8.".to_string(),
+            "SCHEMA_START".to_string(),
+            "BEGIN_DEFINITION".to_string(),
+        ];
+        lines.extend(
+            self.definition(&codes)
+                .into_iter()
+                .map(|(key, value)| format!("{} = {}", key, value)),
+        );
+        lines.push("END_DEFINITION".to_string());
+        lines.push("BEGIN_TABLE".to_string());
+        lines.extend(entries);
+        lines.push("END_TABLE".to_string());
+        Ok(vec![lines.join("\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        Ok(self
+            .code(word)
+            .map(|code| format!("{}\t{}\t{}", code, word.word, word.rank))
+            .unwrap_or_default())
+    }
+
+    fn code_type(&self) -> CodeType {
+        self.code_type
+    }
+
+    fn format_name(&self) -> &str {
+        "ibus-table"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{ibus_table::parse_definition, IbusTableImport, WordLibraryImport};
+    use crate::Code;
+
+    #[test]
+    fn test_round_trip() {
+        let mut word = WordLibrary::with_rank("你好".to_string(), 1000);
+        word.code_type = CodeType::Wubi;
+        word.codes = Code::from_single("vqkb".to_string());
+
+        let output = IbusTableExport::new()
+            .with_attribute("NAME", "Wubi")
+            .with_attribute("AUTO_COMMIT", "FALSE")
+            .export(&vec![word].into())
+            .unwrap();
+        let definition = parse_definition(&output[0]);
+        assert!(definition.contains(&("NAME".to_string(), "Wubi".to_string())));
+        assert!(definition.contains(&("VALID_INPUT_CHARS".to_string(), "bkqv".to_string())));
+        assert!(definition.contains(&("MAX_KEY_LENGTH".to_string(), "4".to_string())));
+        assert_eq!(definition.last().unwrap().0, "AUTO_COMMIT");
+
+        let words = IbusTableImport::new()
+            .import_from_reader(&mut output[0].as_bytes(), &Default::default())
+            .unwrap();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].get_single_code(), Some("vqkb"));
+        assert_eq!(words[0].rank, 1000);
+    }
+}
@@ -0,0 +1,76 @@
+//! Jidian Wubi (极点五笔) mb text export
+//!
+//! Format: `code\tword1 word2 ...`
+//! Example: `vqkb\t你好 你们好`
+
+use crate::export::{group_by_code, WordLibraryExport};
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+
+/// Jidian Wubi mb text exporter
+///
+/// Words sharing a code are regrouped on one line, highest rank first.
+pub struct JidianExport;
+
+impl JidianExport {
+    pub fn new() -> Self {
+        JidianExport
+    }
+}
+
+impl Default for JidianExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for JidianExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let lines: Vec<String> = group_by_code(word_list)
+            .into_iter()
+            .map(|(code, words)| {
+                let words: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+                format!("{}\t{}", code, words.join(" "))
+            })
+            .collect();
+        Ok(vec![lines.join("\r\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        Ok(word
+            .get_word_code()
+            .map(|code| format!("{}\t{}", code, word.word))
+            .unwrap_or_default())
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Wubi
+    }
+
+    fn format_name(&self) -> &str {
+        "Jidian"
+    }
+
+    fn encoding(&self) -> &'static str {
+        "gbk"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    #[test]
+    fn test_export_groups_codes() {
+        let words: WordLibraryList = [("你们好", "vqkb", 1), ("工", "a", 5), ("你好", "vqkb", 2)]
+            .iter()
+            .map(|&(word, code, rank)| {
+                let mut wl = WordLibrary::with_rank(word.to_string(), rank);
+                wl.codes = Code::from_single(code.to_string());
+                wl
+            })
+            .collect();
+        let output = JidianExport::new().export(&words).unwrap();
+        assert_eq!(output[0], "vqkb\t你好 你们好\r\na\t工");
+    }
+}
@@ -0,0 +1,118 @@
+//! Fcitx5 pinyin dictionary export (libime text format)
+//!
+//! Format: `word pinyin cost`
+//! Example: `你好 ni'hao 0`
+//!
+//! This is the text format read by `libime_pinyindict`, which compiles it
+//! into the binary `.dict` files loaded by Fcitx5. The cost is left at 0 so
+//! that libime ranks the words by its own language model.
+
+use crate::export::WordLibraryExport;
+use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+/// Tool of libime converting text dictionaries to binary ones
+pub const LIBIME_PINYINDICT: &str = "libime_pinyindict";
+
+/// libime pinyin dictionary text exporter
+pub struct LibimeExport;
+
+impl LibimeExport {
+    pub fn new() -> Self {
+        LibimeExport
+    }
+}
+
+impl Default for LibimeExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for LibimeExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let lines: Vec<String> = word_list
+            .iter()
+            .filter_map(|word| self.export_line(word).ok())
+            .filter(|line| !line.is_empty())
+            .collect();
+        Ok(vec![lines.join("\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        // libime has no tones
+        let pinyin = word
+            .get_pinyin_string("'")
+            .replace(|c: char| c.is_ascii_digit(), "");
+        if pinyin.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(format!("{} {} 0", word.word, pinyin))
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Pinyin
+    }
+
+    fn format_name(&self) -> &str {
+        "libime"
+    }
+}
+
+/// Compile a libime text dictionary into a binary `.dict` with
+/// `libime_pinyindict`
+///
+/// Returns `Error::Unsupported` when libime is not installed.
+pub fn compile_dict(text_path: &Path, dict_path: &Path) -> Result<()> {
+    let status = Command::new(LIBIME_PINYINDICT)
+        .arg(text_path)
+        .arg(dict_path)
+        .status()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => Error::Unsupported(format!(
+                "{} not found, install libime to write binary dictionaries",
+                LIBIME_PINYINDICT
+            )),
+            _ => Error::Io(e),
+        })?;
+    if !status.success() {
+        return Err(Error::InvalidFormat(format!(
+            "{} failed ({})",
+            LIBIME_PINYINDICT, status
+        )));
+    }
+    Ok(())
+}
+
+/// Write exported text as a binary `.dict`, through a temporary text file
+pub fn write_binary_dict(text: &str, dict_path: &Path) -> Result<()> {
+    let text_path =
+        std::env::temp_dir().join(format!("imewlconverter-libime-{}.txt", std::process::id()));
+    std::fs::write(&text_path, text)?;
+    let result = compile_dict(&text_path, dict_path);
+    std::fs::remove_file(&text_path).ok();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    #[test]
+    fn test_export() {
+        let mut word = WordLibrary::with_rank("你好".to_string(), 10);
+        word.code_type = CodeType::Pinyin;
+        word.codes = Code::from_char_list(vec!["ni3".to_string(), "hao3".to_string()]);
+        let mut wubi = WordLibrary::new("工".to_string());
+        wubi.code_type = CodeType::Wubi;
+        wubi.codes = Code::from_single("a".to_string());
+
+        let output = LibimeExport::new()
+            .export(&vec![word, wubi].into())
+            .unwrap();
+        assert_eq!(output[0], "你好 ni'hao 0");
+    }
+}
@@ -0,0 +1,96 @@
+//! Windows 10 Microsoft Wubi custom phrase export
+//!
+//! Format: `code\tword\tposition`, UTF-16LE with a `;` comment header line
+//! Example: `vqkb\t你好\t1`
+
+use crate::export::{group_by_code, WordLibraryExport};
+use crate::import::ms_wubi::MAX_POSITION;
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+
+/// Microsoft Wubi custom phrase exporter
+///
+/// Words sharing a code get positions 1 to 9 by rank; further words share
+/// the last position.
+pub struct MsWubiExport;
+
+impl MsWubiExport {
+    pub fn new() -> Self {
+        MsWubiExport
+    }
+}
+
+impl Default for MsWubiExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for MsWubiExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        // The IME only recognizes the file as UTF-16 with a byte order mark
+        let mut lines = vec!["\u{FEFF};Microsoft Wubi custom phrases".to_string()];
+        for (code, words) in group_by_code(word_list) {
+            for (i, word) in words.iter().enumerate() {
+                let position = (i as i32 + 1).min(MAX_POSITION);
+                lines.push(format!("{}\t{}\t{}", code, word.word, position));
+            }
+        }
+        Ok(vec![lines.join("\r\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        Ok(word
+            .get_word_code()
+            .map(|code| format!("{}\t{}\t1", code, word.word))
+            .unwrap_or_default())
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Wubi
+    }
+
+    fn format_name(&self) -> &str {
+        "Microsoft Wubi"
+    }
+
+    fn encoding(&self) -> &'static str {
+        "utf-16le"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{ErrorPolicy, ImportOptions, MsWubiImport, WordLibraryImport};
+    use crate::Code;
+
+    #[test]
+    fn test_round_trip() {
+        let words: WordLibraryList = [("你们好", 1), ("你好", 5)]
+            .iter()
+            .map(|&(word, rank)| {
+                let mut wl = WordLibrary::with_rank(word.to_string(), rank);
+                wl.codes = Code::from_single("vqkb".to_string());
+                wl
+            })
+            .collect();
+        let output = MsWubiExport::new().export(&words).unwrap();
+        assert_eq!(
+            output[0],
+            "\u{FEFF};Microsoft Wubi custom phrases\r\nvqkb\t你好\t1\r\nvqkb\t你们好\t2"
+        );
+
+        let mut bytes = Vec::new();
+        MsWubiExport::new()
+            .export_to_writer(&words, &mut bytes)
+            .unwrap();
+        let imported = MsWubiImport::new()
+            .import_from_reader(
+                &mut &bytes[..],
+                &ImportOptions::new().with_error_policy(ErrorPolicy::Strict),
+            )
+            .unwrap();
+        let ranks: Vec<(&str, i32)> = imported.iter().map(|w| (w.word.as_str(), w.rank)).collect();
+        assert_eq!(ranks, vec![("你好", 9), ("你们好", 8)]);
+    }
+}
@@ -0,0 +1,82 @@
+//! Canonical intermediate format export (versioned NDJSON)
+//!
+//! See `import::ndjson` for the layout. Every entry is written with all of
+//! its codes and attributes, so the file imports back unchanged.
+
+use crate::export::WordLibraryExport;
+use crate::import::ndjson::NdjsonHeader;
+use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+
+/// Canonical intermediate format exporter
+pub struct NdjsonExport;
+
+impl NdjsonExport {
+    pub fn new() -> Self {
+        NdjsonExport
+    }
+}
+
+impl Default for NdjsonExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| Error::InvalidFormat(e.to_string()))
+}
+
+impl WordLibraryExport for NdjsonExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let mut lines = vec![to_json(&NdjsonHeader::for_list(word_list))?];
+        for word in word_list.iter() {
+            lines.push(self.export_line(word)?);
+        }
+        Ok(vec![lines.join("\n") + "\n"])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        to_json(word)
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Pinyin
+    }
+
+    fn accepts_any_code_type(&self) -> bool {
+        true
+    }
+
+    fn format_name(&self) -> &str {
+        "imewl"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::ndjson::NdjsonImport;
+    use crate::import::{ImportOptions, WordLibraryImport};
+    use crate::Code;
+
+    #[test]
+    fn test_round_trip() {
+        let mut word = WordLibrary::with_rank("长城".to_string(), 42);
+        word.codes = Code::from_chars(vec![
+            vec!["chang".to_string(), "zhang".to_string()],
+            vec!["cheng".to_string()],
+        ]);
+        word.attributes.insert("t".to_string(), "693".to_string());
+        let mut words: WordLibraryList = vec![word].into();
+        words.source_format = Some("rime".to_string());
+        words.metadata.version = Some("2024.1".to_string());
+
+        let output = NdjsonExport::new().export(&words).unwrap();
+        let imported = NdjsonImport::new()
+            .import_library_from_reader(&mut output[0].as_bytes(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(imported.to_vec(), words.to_vec());
+        assert_eq!(imported.source_format, words.source_format);
+        assert_eq!(imported.metadata, words.metadata);
+    }
+}
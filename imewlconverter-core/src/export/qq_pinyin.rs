@@ -3,15 +3,28 @@
 //! Format: `pinyin word rank`
 //! Example: `ni'hao 你好 1000`
 
-use crate::export::WordLibraryExport;
+use crate::export::{WordLibraryExport, WordLibraryStreamExport};
+use crate::helpers::encode_text;
 use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+use std::io::Write;
 
 /// QQ Pinyin text format exporter
-pub struct QQPinyinExport;
+pub struct QQPinyinExport {
+    /// Separator between syllables
+    separator: String,
+}
 
 impl QQPinyinExport {
     pub fn new() -> Self {
-        QQPinyinExport
+        QQPinyinExport {
+            separator: "'".to_string(),
+        }
+    }
+
+    /// Separate syllables with `separator` instead of `'`
+    pub fn with_code_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
     }
 }
 
@@ -42,7 +55,7 @@ impl WordLibraryExport for QQPinyinExport {
         if let Some(last) = word_list.last() {
             let line = self.export_line(last)?;
             if !line.is_empty() {
-                let pinyin = last.get_pinyin_string("'");
+                let pinyin = last.get_pinyin_string(&self.separator);
                 lines.push(format!("{}, {} {}", line, pinyin, last.rank));
             }
         }
@@ -57,7 +70,7 @@ impl WordLibraryExport for QQPinyinExport {
             ));
         }
 
-        let pinyin = word.get_pinyin_string("'");
+        let pinyin = word.get_pinyin_string(&self.separator);
         if pinyin.is_empty() {
             return Ok(String::new());
         }
@@ -76,6 +89,53 @@ impl WordLibraryExport for QQPinyinExport {
     fn encoding(&self) -> &'static str {
         "utf-16le"
     }
+
+    fn as_stream_export(&self) -> Option<&dyn WordLibraryStreamExport> {
+        Some(self)
+    }
+}
+
+impl WordLibraryStreamExport for QQPinyinExport {
+    fn export_stream(
+        &self,
+        words: &mut dyn Iterator<Item = Result<WordLibrary>>,
+        writer: &mut dyn Write,
+    ) -> Result<usize> {
+        let mut words = words.peekable();
+        let mut count = 0;
+
+        while let Some(word) = words.next() {
+            let word = word?;
+            let is_last = words.peek().is_none();
+
+            let line = if is_last {
+                // Last line has special format: includes duplicate pinyin and rank
+                let line = self.export_line(&word)?;
+                if line.is_empty() {
+                    continue;
+                }
+                format!(
+                    "{}, {} {}",
+                    line,
+                    word.get_pinyin_string(&self.separator),
+                    word.rank
+                )
+            } else {
+                match self.export_line(&word) {
+                    Ok(line) if !line.is_empty() => line,
+                    _ => continue,
+                }
+            };
+
+            if count > 0 {
+                writer.write_all(&encode_text("\r\n", self.encoding()))?;
+            }
+            writer.write_all(&encode_text(&line, self.encoding()))?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
 }
 
 #[cfg(test)]
@@ -110,7 +170,7 @@ mod tests {
         word2.code_type = CodeType::Pinyin;
         word2.codes = Code::from_char_list(vec!["shi".to_string(), "jie".to_string()]);
 
-        let result = exporter.export(&vec![word1, word2]).unwrap();
+        let result = exporter.export(&vec![word1, word2].into()).unwrap();
         assert_eq!(result.len(), 1);
         assert!(result[0].contains("ni'hao 你好 1000"));
         assert!(result[0].contains("shi'jie 世界 500, shi'jie 500"));
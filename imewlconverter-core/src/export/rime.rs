@@ -3,8 +3,11 @@
 //! Format: `word\tcode\trank`
 //! Example: `你好\tni hao\t1000`
 
-use crate::export::WordLibraryExport;
-use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+use crate::export::{WordLibraryExport, WordLibraryStreamExport};
+use crate::helpers::encode_text;
+use crate::{CodeType, LibraryMetadata, Result, WordLibrary, WordLibraryList};
+use std::collections::HashMap;
+use std::io::Write;
 
 /// Operating system for line ending configuration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +31,8 @@ impl OperatingSystem {
 pub struct RimeExport {
     code_type: CodeType,
     os: OperatingSystem,
+    /// Separator between the codes of the characters
+    separator: String,
 }
 
 impl RimeExport {
@@ -35,6 +40,7 @@ impl RimeExport {
         RimeExport {
             code_type: CodeType::Pinyin,
             os: OperatingSystem::Linux,
+            separator: " ".to_string(),
         }
     }
 
@@ -42,6 +48,7 @@ impl RimeExport {
         RimeExport {
             code_type,
             os: OperatingSystem::Linux,
+            separator: " ".to_string(),
         }
     }
 
@@ -49,6 +56,12 @@ impl RimeExport {
         self.os = os;
         self
     }
+
+    /// Separate per-character codes with `separator` instead of a space
+    pub fn with_code_separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
 }
 
 impl Default for RimeExport {
@@ -57,9 +70,33 @@ impl Default for RimeExport {
     }
 }
 
+/// YAML header of a .dict.yaml file, for word libraries with a name
+fn dict_header(metadata: &LibraryMetadata) -> Vec<String> {
+    let Some(name) = &metadata.name else {
+        return Vec::new();
+    };
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut lines = Vec::new();
+    if let Some(description) = &metadata.description {
+        lines.extend(
+            description
+                .lines()
+                .map(|l| format!("# {}", l).trim_end().to_string()),
+        );
+    }
+    lines.push("---".to_string());
+    lines.push(format!("name: {}", quote(name)));
+    let version = metadata.version.as_deref().unwrap_or("1.0");
+    lines.push(format!("version: {}", quote(version)));
+    lines.push("sort: by_weight".to_string());
+    lines.push("...".to_string());
+    lines
+}
+
 impl WordLibraryExport for RimeExport {
     fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
-        let mut lines = Vec::new();
+        let mut lines = dict_header(&word_list.metadata);
         let line_ending = self.os.line_ending();
 
         for word in word_list {
@@ -74,10 +111,23 @@ impl WordLibraryExport for RimeExport {
     }
 
     fn export_line(&self, word: &WordLibrary) -> Result<String> {
-        let code_str = if self.code_type == CodeType::Pinyin {
-            word.get_pinyin_string(" ")
-        } else if let Some(code) = word.get_single_code() {
-            code.to_string()
+        // Symbol phrases keep whatever code they are typed with, Zhuyin,
+        // Jyutping and kana words their syllables
+        let code_str = if word.is_symbol_phrase()
+            || matches!(
+                word.code_type,
+                CodeType::Zhuyin | CodeType::Jyutping | CodeType::Kana
+            ) {
+            word.codes.to_string_with_separator(&self.separator)
+        } else if self.code_type == CodeType::Jyutping {
+            // Mandarin codes are no use to a Cantonese schema
+            return Ok(String::new());
+        } else if self.code_type == CodeType::Pinyin {
+            word.get_pinyin_string(&self.separator)
+        } else if self.code_type == CodeType::Shuangpin {
+            word.codes.to_string_with_separator(&self.separator)
+        } else if let Some(code) = word.get_word_code() {
+            code
         } else {
             return Ok(String::new());
         };
@@ -100,6 +150,108 @@ impl WordLibraryExport for RimeExport {
     fn encoding(&self) -> &'static str {
         "utf-8"
     }
+
+    fn as_stream_export(&self) -> Option<&dyn WordLibraryStreamExport> {
+        Some(self)
+    }
+}
+
+impl WordLibraryStreamExport for RimeExport {
+    fn export_stream(
+        &self,
+        words: &mut dyn Iterator<Item = Result<WordLibrary>>,
+        writer: &mut dyn Write,
+    ) -> Result<usize> {
+        let line_ending = encode_text(self.os.line_ending(), self.encoding());
+        let mut count = 0;
+
+        for word in words {
+            let line = match self.export_line(&word?) {
+                Ok(line) if !line.is_empty() => line,
+                _ => continue,
+            };
+            if count > 0 {
+                writer.write_all(&line_ending)?;
+            }
+            writer.write_all(&encode_text(&line, self.encoding()))?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Index of the first body line, after the `...` ending the YAML header
+fn body_start(lines: &[&str]) -> usize {
+    lines
+        .iter()
+        .position(|l| l.trim_end() == "...")
+        .map_or(0, |i| i + 1)
+}
+
+/// Word, code and weight of an entry line; None for comments and blanks
+fn parse_entry(line: &str) -> Option<(&str, &str, Option<&str>)> {
+    if line.trim().is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let mut fields = line.split('\t');
+    let word = fields.next()?;
+    Some((word, fields.next().unwrap_or(""), fields.next()))
+}
+
+/// Merge exported entries into the text of an existing .dict.yaml
+///
+/// The header, comments and entry order of `existing` are kept. Entries of
+/// `exported` with a word and code already present update its weight; the
+/// others are appended. A header in `exported` is ignored.
+pub fn merge_dictionary(existing: &str, exported: &str) -> String {
+    let line_ending = if existing.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let existing_lines: Vec<&str> = existing.lines().collect();
+    let mut lines: Vec<String> = existing_lines.iter().map(|l| l.to_string()).collect();
+
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+    for (i, line) in existing_lines
+        .iter()
+        .enumerate()
+        .skip(body_start(&existing_lines))
+    {
+        if let Some((word, code, _)) = parse_entry(line) {
+            index
+                .entry((word.to_string(), code.to_string()))
+                .or_insert(i);
+        }
+    }
+
+    let exported: Vec<&str> = exported.lines().collect();
+    for line in &exported[body_start(&exported)..] {
+        let Some((word, code, weight)) = parse_entry(line) else {
+            continue;
+        };
+        match index.get(&(word.to_string(), code.to_string())) {
+            Some(&i) => {
+                if let Some(weight) = weight {
+                    let mut fields: Vec<&str> = lines[i].split('\t').collect();
+                    fields.resize(fields.len().max(3), "");
+                    fields[2] = weight;
+                    lines[i] = fields.join("\t");
+                }
+            }
+            None => {
+                index.insert((word.to_string(), code.to_string()), lines.len());
+                lines.push(line.to_string());
+            }
+        }
+    }
+
+    let mut merged = lines.join(line_ending);
+    if existing.ends_with('\n') {
+        merged.push_str(line_ending);
+    }
+    merged
 }
 
 #[cfg(test)]
@@ -118,6 +270,10 @@ mod tests {
 
         let line = exporter.export_line(&word).unwrap();
         assert_eq!(line, "你好\tni hao\t1000");
+
+        let exporter = RimeExport::new().with_code_separator("-");
+        let line = exporter.export_line(&word).unwrap();
+        assert_eq!(line, "你好\tni-hao\t1000");
     }
 
     #[test]
@@ -133,10 +289,104 @@ mod tests {
         assert_eq!(line, "你好\tvqkb\t1000");
     }
 
+    #[test]
+    fn test_export_line_symbol() {
+        let exporter = RimeExport::new();
+
+        let mut word = WordLibrary::new("😊".to_string());
+        word.code_type = CodeType::UserDefinePhrase;
+        word.codes = Code::from_single("weixiao".to_string());
+
+        let line = exporter.export_line(&word).unwrap();
+        assert_eq!(line, "😊\tweixiao\t0");
+    }
+
+    #[test]
+    fn test_export_line_shuangpin() {
+        let exporter = RimeExport::with_code_type(CodeType::Shuangpin);
+
+        let mut word = WordLibrary::new("你好".to_string());
+        word.rank = 1000;
+        word.code_type = CodeType::Shuangpin;
+        word.codes = Code::from_char_list(vec!["ni".to_string(), "hc".to_string()]);
+
+        let line = exporter.export_line(&word).unwrap();
+        assert_eq!(line, "你好\tni hc\t1000");
+    }
+
+    #[test]
+    fn test_export_line_jyutping() {
+        let exporter = RimeExport::with_code_type(CodeType::Jyutping);
+
+        let mut word = WordLibrary::new("你好".to_string());
+        word.rank = 10;
+        word.code_type = CodeType::Jyutping;
+        word.codes = Code::from_char_list(vec!["nei5".to_string(), "hou2".to_string()]);
+        assert_eq!(exporter.export_line(&word).unwrap(), "你好\tnei5 hou2\t10");
+
+        word.code_type = CodeType::Pinyin;
+        word.codes = Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]);
+        assert_eq!(exporter.export_line(&word).unwrap(), "");
+    }
+
+    #[test]
+    fn test_export_stream() {
+        let exporter = RimeExport::new().with_os(OperatingSystem::Windows);
+
+        let mut words = WordLibraryList::new();
+        for (text, codes) in [("你好", ["ni", "hao"]), ("世界", ["shi", "jie"])] {
+            let mut word = WordLibrary::new(text.to_string());
+            word.rank = 10;
+            word.codes = Code::from_char_list(codes.iter().map(|c| c.to_string()).collect());
+            words.push(word);
+        }
+
+        let mut output = Vec::new();
+        let count = exporter
+            .export_stream(&mut words.clone().into_iter().map(Ok), &mut output)
+            .unwrap();
+
+        assert_eq!(count, 2);
+        let expected = exporter.export(&words).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected[0]);
+    }
+
     #[test]
     fn test_line_endings() {
         assert_eq!(OperatingSystem::Windows.line_ending(), "\r\n");
         assert_eq!(OperatingSystem::MacOS.line_ending(), "\r");
         assert_eq!(OperatingSystem::Linux.line_ending(), "\n");
     }
+
+    #[test]
+    fn test_dict_header_from_metadata() {
+        use crate::import::{ImportOptions, RimeImport, WordLibraryImport};
+
+        let content = "# comment\n---\nname: luna\nversion: \"2024.1\"\n...\n你好\tni hao\t1\n";
+        let words = RimeImport::new()
+            .import_library_from_reader(&mut content.as_bytes(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.metadata.name.as_deref(), Some("luna"));
+        assert_eq!(words.len(), 1);
+
+        let output = RimeExport::new().export(&words).unwrap();
+        assert_eq!(
+            output[0],
+            "---\nname: \"luna\"\nversion: \"2024.1\"\nsort: by_weight\n...\n你好\tni hao\t1"
+        );
+    }
+
+    #[test]
+    fn test_merge_dictionary() {
+        let existing = "# My dictionary\n---\nname: mine\n...\n\n# Greetings\n你好\tni hao\t1\n世界\tshi jie\n";
+        let exported = "---\nname: new\n...\n你好\tni hao\t5\n世界\tshi jie\t2\n早上\tzao shang\t3";
+
+        let merged = merge_dictionary(existing, exported);
+        assert_eq!(
+            merged,
+            "# My dictionary\n---\nname: mine\n...\n\n# Greetings\n你好\tni hao\t5\n世界\tshi jie\t2\n早上\tzao shang\t3\n"
+        );
+        // Merging again changes nothing
+        assert_eq!(merge_dictionary(&merged, exported), merged);
+    }
 }
@@ -0,0 +1,108 @@
+//! Rime essay.txt word frequency export
+//!
+//! Format: `word\tweight`
+//! Example: `你好\t1000`
+//!
+//! essay.txt is the language model of Rime's `essay` and Octagram tools. It
+//! has no codes and one weight per word.
+
+use crate::export::WordLibraryExport;
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+use std::collections::HashMap;
+
+/// Rime essay.txt exporter
+pub struct RimeEssayExport {
+    /// Rescale ranks so that the highest becomes this weight
+    max_weight: Option<u32>,
+}
+
+impl RimeEssayExport {
+    pub fn new() -> Self {
+        RimeEssayExport { max_weight: None }
+    }
+
+    /// Rescale ranks proportionally to weights from 1 to `max_weight`
+    ///
+    /// Ranks of other formats are often on small scales (1 to 5, or
+    /// candidate positions) that do not compare with essay counts.
+    pub fn with_max_weight(mut self, max_weight: u32) -> Self {
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    fn weight(&self, rank: i32, max_rank: i32) -> i64 {
+        let rank = i64::from(rank.max(0));
+        match self.max_weight {
+            Some(max_weight) if max_rank > 0 => {
+                (rank * i64::from(max_weight) / i64::from(max_rank)).max(1)
+            }
+            _ => rank,
+        }
+    }
+}
+
+impl Default for RimeEssayExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for RimeEssayExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        // Words listed with several codes keep their highest rank
+        let mut ranks: HashMap<&str, i32> = HashMap::new();
+        let mut order = Vec::new();
+        for word in word_list {
+            ranks
+                .entry(word.word.as_str())
+                .and_modify(|rank| *rank = (*rank).max(word.rank))
+                .or_insert_with(|| {
+                    order.push(word.word.as_str());
+                    word.rank
+                });
+        }
+
+        let max_rank = ranks.values().copied().max().unwrap_or(0);
+        let lines: Vec<String> = order
+            .into_iter()
+            .map(|word| format!("{}\t{}", word, self.weight(ranks[word], max_rank)))
+            .collect();
+        Ok(vec![lines.join("\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        Ok(format!("{}\t{}", word.word, word.rank.max(0)))
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::NoCode
+    }
+
+    fn format_name(&self) -> &str {
+        "Rime essay"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> WordLibraryList {
+        [("你好", 5), ("世界", 2), ("你好", 8), ("中国", 0)]
+            .iter()
+            .map(|&(word, rank)| WordLibrary::with_rank(word.to_string(), rank))
+            .collect()
+    }
+
+    #[test]
+    fn test_export() {
+        let output = RimeEssayExport::new().export(&words()).unwrap();
+        assert_eq!(output[0], "你好\t8\n世界\t2\n中国\t0");
+
+        let output = RimeEssayExport::new()
+            .with_max_weight(100_000)
+            .export(&words())
+            .unwrap();
+        assert_eq!(output[0], "你好\t100000\n世界\t25000\n中国\t1");
+    }
+}
@@ -0,0 +1,128 @@
+//! Rime user dictionary snapshot export
+//!
+//! Format: `code \tword\tc=commits d=weight t=tick`, after `#@/key\tvalue`
+//! metadata lines
+//! Example: `ni hao \t你好\tc=3 d=3 t=1`
+//!
+//! Restore the snapshot with `rime_dict_manager --restore`, or place it in the
+//! sync directory to merge it into the user dictionary.
+
+use crate::data::attributes;
+use crate::export::WordLibraryExport;
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+
+/// Rime `.userdb.txt` snapshot exporter
+pub struct RimeUserdbExport {
+    /// Dictionary name without the `.userdb` suffix, e.g. `luna_pinyin`
+    db_name: String,
+}
+
+impl RimeUserdbExport {
+    pub fn new() -> Self {
+        RimeUserdbExport {
+            db_name: "luna_pinyin".to_string(),
+        }
+    }
+
+    /// Set the name of the user dictionary, that of the schema's dictionary
+    pub fn with_db_name(mut self, db_name: &str) -> Self {
+        self.db_name = db_name.trim_end_matches(".userdb").to_string();
+        self
+    }
+}
+
+impl Default for RimeUserdbExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for RimeUserdbExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let entries: Vec<String> = word_list
+            .iter()
+            .filter_map(|word| self.export_line(word).ok())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        // Entries without a tick are committed at tick 1, so the snapshot is
+        // older than any later use of the words
+        let tick = word_list
+            .iter()
+            .filter_map(|w| w.attribute(attributes::USERDB_TICK)?.parse::<u64>().ok())
+            .max()
+            .unwrap_or(1);
+        let mut lines = vec![
+            "# Rime user dictionary".to_string(),
+            format!("#@/db_name\t{}.userdb", self.db_name),
+            "#@/db_type\tuserdb".to_string(),
+            format!("#@/tick\t{}", tick),
+        ];
+        lines.extend(entries);
+        Ok(vec![lines.join("\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        let code = word.get_pinyin_string(" ");
+        if code.is_empty() {
+            return Ok(String::new());
+        }
+        let commits = word.rank.max(1);
+        let commits_str = commits.to_string();
+        let weight = word
+            .attribute(attributes::USERDB_WEIGHT)
+            .unwrap_or(&commits_str);
+        let tick = word.attribute(attributes::USERDB_TICK).unwrap_or("1");
+        Ok(format!(
+            "{} \t{}\tc={} d={} t={}",
+            code, word.word, commits, weight, tick
+        ))
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Pinyin
+    }
+
+    fn format_name(&self) -> &str {
+        "Rime userdb"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{RimeUserdbImport, WordLibraryImport};
+    use crate::Code;
+
+    #[test]
+    fn test_round_trip() {
+        let mut word = WordLibrary::with_rank("你好".to_string(), 3);
+        word.codes = Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]);
+
+        let output = RimeUserdbExport::new()
+            .with_db_name("luna_pinyin.userdb")
+            .export(&vec![word].into())
+            .unwrap();
+        assert_eq!(
+            output[0],
+            "# Rime user dictionary\n#@/db_name\tluna_pinyin.userdb\n#@/db_type\tuserdb\n\
+             #@/tick\t1\nni hao \t你好\tc=3 d=3 t=1"
+        );
+
+        let words = RimeUserdbImport::new()
+            .import_from_reader(&mut output[0].as_bytes(), &Default::default())
+            .unwrap();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].rank, 3);
+        assert_eq!(words[0].get_pinyin_string(" "), "ni hao");
+
+        // Weights and ticks of imported entries are written back
+        let line = "shi jie \t世界\tc=2 d=1.8 t=693";
+        let words = RimeUserdbImport::new()
+            .import_from_reader(&mut line.as_bytes(), &Default::default())
+            .unwrap();
+        let output = RimeUserdbExport::new().export(&words.into()).unwrap();
+        assert!(output[0].contains("#@/tick\t693\n"));
+        assert!(output[0].ends_with(line));
+    }
+}
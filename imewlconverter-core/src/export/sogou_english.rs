@@ -0,0 +1,72 @@
+//! Sogou English thesaurus export
+//!
+//! Format: one English word per line, most frequent first
+//! Example: `hello`
+
+use crate::export::WordLibraryExport;
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+use std::cmp::Reverse;
+
+/// Sogou English thesaurus exporter
+///
+/// The thesaurus has no frequency field, so words are ordered by rank
+/// instead. Chinese words are left out.
+pub struct SogouEnglishExport;
+
+impl SogouEnglishExport {
+    pub fn new() -> Self {
+        SogouEnglishExport
+    }
+}
+
+impl Default for SogouEnglishExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for SogouEnglishExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let mut words: Vec<&WordLibrary> =
+            word_list.iter().filter(|w| w.is_english_word()).collect();
+        words.sort_by_key(|w| Reverse(w.rank));
+        let lines: Vec<&str> = words.iter().map(|w| w.word.trim()).collect();
+        Ok(vec![lines.join("\r\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        if word.is_english_word() {
+            Ok(word.word.trim().to_string())
+        } else {
+            Ok(String::new())
+        }
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::English
+    }
+
+    fn format_name(&self) -> &str {
+        "Sogou English"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{EnglishWordListImport, ImportOptions, WordLibraryImport};
+
+    #[test]
+    fn test_export_word_list() {
+        let mut words: WordLibraryList = EnglishWordListImport::new()
+            .import_from_reader(
+                &mut "hello 10\nworld 30\n".as_bytes(),
+                &ImportOptions::new(),
+            )
+            .unwrap()
+            .into();
+        words.push(WordLibrary::with_rank("你好".to_string(), 100));
+        let output = SogouEnglishExport::new().export(&words).unwrap();
+        assert_eq!(output, vec!["world\r\nhello"]);
+    }
+}
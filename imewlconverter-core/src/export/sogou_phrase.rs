@@ -0,0 +1,151 @@
+//! Sogou custom phrase (搜狗自定义短语, PhraseEdit.txt) export
+//!
+//! Format: `code,position=phrase`, GBK, with `;` comment lines
+//! Example: `dz,1=北京市海淀区`
+
+use crate::export::{group_by_code, WordLibraryExport};
+use crate::import::sogou_phrase::{parse_entry, MAX_POSITION};
+use crate::{Code, CodeType, Result, WordLibrary, WordLibraryList};
+
+/// Sogou custom phrase exporter
+///
+/// Words sharing a code keep their positions, or get positions 1 to 9 by
+/// rank; further words share the last position. Pinyin codes are written without separators and
+/// multi-line phrases span several lines.
+///
+/// The format has no escapes, so phrases with a continuation line that would
+/// read back as a comment (`;...`) or an entry (`code,position=...`) are left
+/// out, as are words whose code is not made of letters.
+pub struct SogouPhraseExport;
+
+impl SogouPhraseExport {
+    pub fn new() -> Self {
+        SogouPhraseExport
+    }
+}
+
+impl Default for SogouPhraseExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Code of a phrase, lowercase letters only
+pub(crate) fn phrase_code(word: &WordLibrary) -> Option<String> {
+    let code = match word.code_type {
+        CodeType::Pinyin | CodeType::TerraPinyin => word.get_pinyin_string(""),
+        _ => word.get_word_code()?,
+    };
+    let code = code.to_lowercase();
+    (!code.is_empty() && code.bytes().all(|b| b.is_ascii_lowercase())).then_some(code)
+}
+
+/// Check if a phrase reads back unchanged
+fn is_representable(phrase: &str) -> bool {
+    !phrase.trim().is_empty()
+        && phrase
+            .lines()
+            .skip(1)
+            .all(|line| !line.starts_with(';') && parse_entry(line).is_none())
+}
+
+/// Position of the `index`th phrase of a code: its own, else by order
+pub(crate) fn candidate_position(word: &WordLibrary, index: usize) -> i32 {
+    word.position
+        .map_or(index as i32 + 1, i32::from)
+        .clamp(1, MAX_POSITION)
+}
+
+fn entry_line(code: &str, position: i32, phrase: &str) -> String {
+    let phrase: Vec<&str> = phrase.lines().collect();
+    format!("{},{}={}", code, position, phrase.join("\r\n"))
+}
+
+impl WordLibraryExport for SogouPhraseExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let words: WordLibraryList = word_list
+            .iter()
+            .filter(|w| is_representable(&w.word))
+            .filter_map(|w| {
+                let mut entry = w.clone();
+                entry.codes = Code::from_single(phrase_code(w)?);
+                Some(entry)
+            })
+            .collect();
+
+        let mut lines = Vec::new();
+        for (code, words) in group_by_code(&words) {
+            for (i, word) in words.iter().enumerate() {
+                lines.push(entry_line(&code, candidate_position(word, i), &word.word));
+            }
+        }
+        Ok(vec![lines.join("\r\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        if !is_representable(&word.word) {
+            return Ok(String::new());
+        }
+        Ok(phrase_code(word)
+            .map(|code| entry_line(&code, candidate_position(word, 0), &word.word))
+            .unwrap_or_default())
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::UserDefinePhrase
+    }
+
+    fn format_name(&self) -> &str {
+        "Sogou custom phrase"
+    }
+
+    fn encoding(&self) -> &'static str {
+        "gbk"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{ErrorPolicy, ImportOptions, SogouPhraseImport, WordLibraryImport};
+
+    #[test]
+    fn test_round_trip() {
+        let mut words: WordLibraryList = [("北京市\n海淀区", "dz", 1), ("地址", "dz", 5)]
+            .iter()
+            .map(|&(word, code, rank)| {
+                let mut wl = WordLibrary::with_rank(word.to_string(), rank);
+                wl.code_type = CodeType::UserDefinePhrase;
+                wl.codes = Code::from_single(code.to_string());
+                wl
+            })
+            .collect();
+        let mut smile = WordLibrary::new("😊".to_string());
+        smile.codes = Code::from_char_list(vec!["wei".to_string(), "xiao".to_string()]);
+        smile.position = Some(4);
+        words.push(smile);
+        let mut ambiguous = WordLibrary::new("甲\nab,1=乙".to_string());
+        ambiguous.codes = Code::from_single("jia".to_string());
+        words.push(ambiguous);
+
+        let output = SogouPhraseExport::new().export(&words).unwrap();
+        assert_eq!(
+            output[0],
+            "dz,1=地址\r\ndz,2=北京市\r\n海淀区\r\nweixiao,4=😊"
+        );
+
+        let imported = SogouPhraseImport::new()
+            .import_from_reader(
+                &mut output[0].as_bytes(),
+                &ImportOptions::new()
+                    .with_encoding("utf-8")
+                    .with_error_policy(ErrorPolicy::Strict),
+            )
+            .unwrap();
+        let entries: Vec<&str> = imported.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(entries, vec!["地址", "北京市\n海淀区", "😊"]);
+        // Positions survive the round trip
+        let imported = WordLibraryList::from(imported);
+        assert_eq!(SogouPhraseExport::new().export(&imported).unwrap(), output);
+    }
+}
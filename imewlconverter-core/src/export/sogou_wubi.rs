@@ -0,0 +1,85 @@
+//! Sogou Wubi (搜狗五笔) user dictionary export
+//!
+//! Format: `code=word`, GBK
+//! Example: `vqkb=你好`
+
+use crate::export::{group_by_code, WordLibraryExport};
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+
+/// Sogou Wubi user dictionary exporter
+///
+/// Words sharing a code are written on consecutive lines, highest rank
+/// first, which Sogou Wubi takes as the candidate order. Words whose code is
+/// not made of letters are left out.
+pub struct SogouWubiExport;
+
+impl SogouWubiExport {
+    pub fn new() -> Self {
+        SogouWubiExport
+    }
+}
+
+impl Default for SogouWubiExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_wubi_code(code: &str) -> bool {
+    !code.is_empty() && code.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+impl WordLibraryExport for SogouWubiExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        for (code, words) in group_by_code(word_list) {
+            if !is_wubi_code(&code) {
+                continue;
+            }
+            let code = code.to_lowercase();
+            lines.extend(words.iter().map(|w| format!("{}={}", code, w.word)));
+        }
+        Ok(vec![lines.join("\r\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        Ok(word
+            .get_word_code()
+            .filter(|code| is_wubi_code(code))
+            .map(|code| format!("{}={}", code.to_lowercase(), word.word))
+            .unwrap_or_default())
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Wubi
+    }
+
+    fn format_name(&self) -> &str {
+        "Sogou Wubi"
+    }
+
+    fn encoding(&self) -> &'static str {
+        "gbk"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{ImportOptions, SogouWubiImport, WordLibraryImport};
+
+    #[test]
+    fn test_round_trip() {
+        let input = "vqkb=你好\r\na=工\r\nvqkb=你们好";
+        let words = SogouWubiImport::new()
+            .import_from_reader(
+                &mut input.as_bytes(),
+                &ImportOptions::new().with_encoding("utf-8"),
+            )
+            .unwrap();
+        let output = SogouWubiExport::new()
+            .export(&WordLibraryList::from(words))
+            .unwrap();
+        assert_eq!(output[0], "vqkb=你好\r\nvqkb=你们好\r\na=工");
+    }
+}
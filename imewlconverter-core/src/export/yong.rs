@@ -0,0 +1,111 @@
+//! Yong (小小输入法) code table export
+//!
+//! Format: `code word1 word2 ...` after a `[DATA]` line, preceded by
+//! `key=value` settings
+//! Example: `vqkb 你好 你们好`
+
+use crate::export::{group_by_code, WordLibraryExport};
+use crate::{CodeType, Result, WordLibrary, WordLibraryList};
+use std::collections::BTreeSet;
+
+/// Yong code table exporter
+///
+/// Words sharing a code are written on one line, highest rank first; codes
+/// keep the order of their first word.
+pub struct YongExport {
+    name: String,
+}
+
+impl YongExport {
+    pub fn new() -> Self {
+        YongExport {
+            name: "imewlconverter".to_string(),
+        }
+    }
+
+    /// Set the table name shown by the IME
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+}
+
+impl Default for YongExport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryExport for YongExport {
+    fn export(&self, word_list: &WordLibraryList) -> Result<Vec<String>> {
+        let groups = group_by_code(word_list);
+        let keys: BTreeSet<char> = groups.iter().flat_map(|(code, _)| code.chars()).collect();
+        let len = groups
+            .iter()
+            .map(|(code, _)| code.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![
+            format!("name={}", self.name),
+            format!("key={}", keys.into_iter().collect::<String>()),
+            format!("len={}", len),
+            "[DATA]".to_string(),
+        ];
+
+        for (code, words) in groups {
+            let words: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+            lines.push(format!("{} {}", code, words.join(" ")));
+        }
+
+        Ok(vec![lines.join("\n")])
+    }
+
+    fn export_line(&self, word: &WordLibrary) -> Result<String> {
+        Ok(word
+            .get_word_code()
+            .map(|code| format!("{} {}", code, word.word))
+            .unwrap_or_default())
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Yong
+    }
+
+    fn format_name(&self) -> &str {
+        "Yong"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{WordLibraryImport, YongImport};
+    use crate::Code;
+
+    fn entry(word: &str, code: &str, rank: i32) -> WordLibrary {
+        let mut wl = WordLibrary::with_rank(word.to_string(), rank);
+        wl.codes = Code::from_single(code.to_string());
+        wl
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let words: WordLibraryList = vec![
+            entry("式", "a", 1),
+            entry("你好", "vqkb", 5),
+            entry("工", "a", 2),
+        ]
+        .into();
+        let output = YongExport::new().with_name("五笔").export(&words).unwrap();
+        assert_eq!(
+            output[0],
+            "name=五笔\nkey=abkqv\nlen=4\n[DATA]\na 工 式\nvqkb 你好"
+        );
+
+        let imported = YongImport::new()
+            .import_from_reader(&mut output[0].as_bytes(), &Default::default())
+            .unwrap();
+        let words: Vec<&str> = imported.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["工", "式", "你好"]);
+    }
+}
@@ -0,0 +1,3 @@
+//! Downloading dictionaries from online stores (feature `network`)
+
+pub mod sogou;
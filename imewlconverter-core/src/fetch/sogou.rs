@@ -0,0 +1,79 @@
+//! Sogou cell dictionary store
+//!
+//! Cells are addressed by their numeric ID, as in the detail page
+//! `https://pinyin.sogou.com/dict/detail/index/15117`.
+
+use crate::import::{ImportOptions, SogouScelImport, WordLibraryImport};
+use crate::{Error, Result, WordLibraryList};
+use std::io::Read;
+
+/// Download endpoint of the cell store
+pub const CELL_DOWNLOAD_URL: &str = "https://pinyin.sogou.com/d/dict/download_cell.php";
+
+/// Refuse downloads larger than this, cells are a few MB at most
+const MAX_CELL_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Download URL of a cell given by ID, detail page URL or download URL
+pub fn cell_url(id_or_url: &str) -> Result<String> {
+    let id_or_url = id_or_url.trim();
+    if id_or_url.contains("download_cell.php") {
+        return Ok(id_or_url.to_string());
+    }
+    let id = id_or_url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::InvalidFormat(format!(
+            "Not a Sogou cell ID or URL: {}",
+            id_or_url
+        )));
+    }
+    Ok(format!("{}?id={}&name={}", CELL_DOWNLOAD_URL, id, id))
+}
+
+/// Download the `.scel` file of a cell
+pub fn download_cell(id_or_url: &str) -> Result<Vec<u8>> {
+    let url = cell_url(id_or_url)?;
+    let response = ureq::get(&url)
+        .call()
+        .map_err(|e| Error::Network(e.to_string()))?;
+
+    let mut data = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_CELL_SIZE)
+        .read_to_end(&mut data)?;
+    // The store answers unknown IDs with an HTML page
+    if !data.starts_with(b"\x40\x15\x00\x00\x44\x43\x53\x01") {
+        return Err(Error::FormatMismatch {
+            expected: "Sogou Scel".to_string(),
+            actual: format!("download of {}", url),
+        });
+    }
+    Ok(data)
+}
+
+/// Download a cell and import its words
+pub fn import_cell(id_or_url: &str) -> Result<WordLibraryList> {
+    let data = download_cell(id_or_url)?;
+    SogouScelImport.import_library_from_reader(&mut &data[..], &ImportOptions::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_url() {
+        let expected = format!("{}?id=15117&name=15117", CELL_DOWNLOAD_URL);
+        assert_eq!(cell_url("15117").unwrap(), expected);
+        assert_eq!(
+            cell_url("https://pinyin.sogou.com/dict/detail/index/15117/").unwrap(),
+            expected
+        );
+        assert_eq!(cell_url(&expected).unwrap(), expected);
+        assert!(cell_url("https://example.com/cell").is_err());
+    }
+}
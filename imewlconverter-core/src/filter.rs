@@ -1,9 +1,18 @@
 //! Filtering functionality for word libraries
 
+use crate::report::short_type_name;
 use crate::{Result, WordLibrary, WordLibraryList};
 
+pub mod charset;
+pub mod code_length;
+pub mod english;
+pub mod idiom;
 pub mod length;
 pub mod rank;
+pub mod reference;
+pub mod sensitive;
+pub mod symbols;
+pub mod wordlist;
 
 /// Trait for filters that process individual entries
 pub trait SingleFilter {
@@ -14,12 +23,22 @@ pub trait SingleFilter {
     fn filter(&self, words: &WordLibraryList) -> WordLibraryList {
         words.iter().filter(|w| self.is_keep(w)).cloned().collect()
     }
+
+    /// Name used in conversion reports
+    fn name(&self) -> &'static str {
+        short_type_name(std::any::type_name::<Self>())
+    }
 }
 
 /// Trait for filters that process entire word lists
 pub trait BatchFilter {
     /// Filter a word list
     fn filter(&self, words: WordLibraryList) -> Result<WordLibraryList>;
+
+    /// Name used in conversion reports
+    fn name(&self) -> &'static str {
+        short_type_name(std::any::type_name::<Self>())
+    }
 }
 
 /// Filter configuration for special character handling
@@ -0,0 +1,111 @@
+//! Charset filter - drops words with characters outside a repertoire
+
+use crate::filter::SingleFilter;
+use crate::WordLibrary;
+use encoding_rs::Encoding;
+use serde::Deserialize;
+
+/// Character repertoire accepted by the target IME
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Charset {
+    /// GB2312 level 1 (the 3755 common characters) plus its symbols
+    Gb2312Common,
+    /// GB2312
+    Gb2312,
+    /// GBK
+    Gbk,
+    /// Big5
+    Big5,
+    /// Any character except CJK Extension A and the ideographic planes
+    /// (Extension B and later); emoji are kept
+    NoCjkExtension,
+}
+
+impl Charset {
+    /// Check if a character belongs to the repertoire
+    pub fn contains(&self, c: char) -> bool {
+        if c.is_ascii() {
+            return true;
+        }
+        match self {
+            // Symbols (rows A1-A9) and level 1 characters (B0A1-D7F9)
+            Charset::Gb2312Common => matches!(gbk_bytes(c), Some([lead, trail])
+                if is_gb2312(lead, trail) && (lead, trail) <= (0xD7, 0xF9)),
+            Charset::Gb2312 => {
+                matches!(gbk_bytes(c), Some([lead, trail]) if is_gb2312(lead, trail))
+            }
+            Charset::Gbk => encodes(encoding_rs::GBK, c),
+            Charset::Big5 => encodes(encoding_rs::BIG5, c),
+            Charset::NoCjkExtension => !matches!(c as u32, 0x3400..=0x4DBF | 0x20000..=0x3FFFF),
+        }
+    }
+}
+
+/// GB2312 is the part of GBK with both bytes in 0xA1..=0xFE
+fn is_gb2312(lead: u8, trail: u8) -> bool {
+    (0xA1..=0xF7).contains(&lead) && (0xA1..=0xFE).contains(&trail)
+}
+
+/// Two-byte GBK encoding of a character
+fn gbk_bytes(c: char) -> Option<[u8; 2]> {
+    let mut buf = [0u8; 4];
+    let (bytes, _, had_errors) = encoding_rs::GBK.encode(c.encode_utf8(&mut buf));
+    match (had_errors, bytes.as_ref()) {
+        (false, &[lead, trail]) => Some([lead, trail]),
+        _ => None,
+    }
+}
+
+fn encodes(encoding: &'static Encoding, c: char) -> bool {
+    let mut buf = [0u8; 4];
+    !encoding.encode(c.encode_utf8(&mut buf)).2
+}
+
+/// Filter words by character repertoire
+pub struct CharsetFilter {
+    pub charset: Charset,
+}
+
+impl CharsetFilter {
+    pub fn new(charset: Charset) -> Self {
+        CharsetFilter { charset }
+    }
+}
+
+impl SingleFilter for CharsetFilter {
+    fn is_keep(&self, word: &WordLibrary) -> bool {
+        word.word.chars().all(|c| self.charset.contains(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charset_contains() {
+        // 中 is level 1, 亍 (D8A1) is level 2, 镕 is GBK only
+        assert!(Charset::Gb2312Common.contains('中'));
+        assert!(!Charset::Gb2312Common.contains('亍'));
+        assert!(Charset::Gb2312.contains('亍'));
+        assert!(!Charset::Gb2312.contains('镕'));
+        assert!(Charset::Gbk.contains('镕'));
+
+        assert!(Charset::Big5.contains('國'));
+        assert!(!Charset::Big5.contains('国'));
+
+        assert!(Charset::NoCjkExtension.contains('中'));
+        assert!(!Charset::NoCjkExtension.contains('㐀'));
+        assert!(!Charset::NoCjkExtension.contains('𠀀'));
+        assert!(Charset::NoCjkExtension.contains('😊'));
+    }
+
+    #[test]
+    fn test_charset_filter() {
+        let filter = CharsetFilter::new(Charset::Gb2312);
+
+        assert!(filter.is_keep(&WordLibrary::new("中国abc".to_string())));
+        assert!(!filter.is_keep(&WordLibrary::new("中國".to_string())));
+    }
+}
@@ -0,0 +1,112 @@
+//! Code length filter - limits the code length of table IMEs
+//!
+//! Table-based IMEs (Wubi, Cangjie, ...) accept codes of a fixed maximum
+//! length, usually 4. Longer codes of glyph-based words are either cut to the
+//! limit or their words dropped; other code types are left alone.
+
+use crate::filter::BatchFilter;
+use crate::{Code, Result, WordLibrary, WordLibraryList};
+
+/// What to do with words whose code exceeds the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CodeLengthPolicy {
+    /// Cut the code to the maximum length
+    #[default]
+    Truncate,
+    /// Drop the word
+    Drop,
+}
+
+/// Filter limiting the whole-word code length of glyph-based words
+pub struct CodeLengthFilter {
+    pub max_length: usize,
+    pub policy: CodeLengthPolicy,
+}
+
+impl CodeLengthFilter {
+    pub fn new(max_length: usize, policy: CodeLengthPolicy) -> Self {
+        CodeLengthFilter { max_length, policy }
+    }
+
+    /// Apply the limit to a word, returning None if it should be dropped
+    pub fn process(&self, mut word: WordLibrary) -> Option<WordLibrary> {
+        if !word.code_type.is_glyph_based() || word.is_symbol_phrase() {
+            return Some(word);
+        }
+        let too_long = |code: &String| code.chars().count() > self.max_length;
+
+        // A single code with alternatives, or a code split per character
+        let codes: Vec<String> = if word.codes.0.len() == 1 {
+            word.codes.0[0].clone()
+        } else {
+            word.get_word_code().into_iter().collect()
+        };
+        if !codes.iter().any(too_long) {
+            return Some(word);
+        }
+        match self.policy {
+            CodeLengthPolicy::Drop => None,
+            CodeLengthPolicy::Truncate => {
+                let mut truncated: Vec<String> = Vec::with_capacity(codes.len());
+                for code in codes {
+                    let code: String = code.chars().take(self.max_length).collect();
+                    if !truncated.contains(&code) {
+                        truncated.push(code);
+                    }
+                }
+                word.codes = Code::from_chars(vec![truncated]);
+                Some(word)
+            }
+        }
+    }
+}
+
+impl Default for CodeLengthFilter {
+    fn default() -> Self {
+        Self::new(4, CodeLengthPolicy::default())
+    }
+}
+
+impl BatchFilter for CodeLengthFilter {
+    fn filter(&self, words: WordLibraryList) -> Result<WordLibraryList> {
+        Ok(words.into_iter().filter_map(|w| self.process(w)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodeType;
+
+    fn word(text: &str, code_type: CodeType, codes: &[&str]) -> WordLibrary {
+        let mut wl = WordLibrary::new(text.to_string());
+        wl.code_type = code_type;
+        wl.codes = Code::from_char_list(codes.iter().map(|c| c.to_string()).collect());
+        wl
+    }
+
+    #[test]
+    fn test_code_length_policies() {
+        let words: WordLibraryList = vec![
+            word("中华人民共和国", CodeType::Wubi, &["klwlw"]),
+            word("你好", CodeType::Wubi, &["wq", "vb"]),
+            word("你们好", CodeType::Cangjie, &["onf", "oan", "vnd"]),
+            word(
+                "中华人民",
+                CodeType::Pinyin,
+                &["zhong", "hua", "ren", "min"],
+            ),
+        ]
+        .into();
+
+        let truncated = CodeLengthFilter::default().filter(words.clone()).unwrap();
+        let codes: Vec<String> = truncated.iter().filter_map(|w| w.get_word_code()).collect();
+        assert_eq!(codes, vec!["klwl", "wqvb", "onfo", "zhonghuarenmin"]);
+
+        let kept = CodeLengthFilter::new(4, CodeLengthPolicy::Drop)
+            .filter(words)
+            .unwrap();
+        let kept: Vec<&str> = kept.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(kept, vec!["你好", "中华人民"]);
+    }
+}
@@ -0,0 +1,55 @@
+//! English filter - separates English words from Chinese ones
+
+use crate::filter::SingleFilter;
+use crate::WordLibrary;
+
+/// Filter English words (see `WordLibrary::is_english_word`)
+pub struct EnglishFilter {
+    /// Keep only English words instead of removing them
+    pub english_only: bool,
+}
+
+impl EnglishFilter {
+    /// Remove English words
+    pub fn new() -> Self {
+        EnglishFilter {
+            english_only: false,
+        }
+    }
+
+    /// Keep only English words
+    pub fn english_only() -> Self {
+        EnglishFilter { english_only: true }
+    }
+}
+
+impl Default for EnglishFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SingleFilter for EnglishFilter {
+    fn is_keep(&self, word: &WordLibrary) -> bool {
+        word.is_english_word() == self.english_only
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_filter() {
+        let english = WordLibrary::new("hello".to_string());
+        let chinese = WordLibrary::new("你好".to_string());
+
+        let filter = EnglishFilter::new();
+        assert!(!filter.is_keep(&english));
+        assert!(filter.is_keep(&chinese));
+
+        let filter = EnglishFilter::english_only();
+        assert!(filter.is_keep(&english));
+        assert!(!filter.is_keep(&chinese));
+    }
+}
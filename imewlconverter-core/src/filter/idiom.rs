@@ -0,0 +1,59 @@
+//! Idiom filter - separates idioms (成语) from other words
+
+use crate::filter::SingleFilter;
+use crate::transform::idiom::is_idiom_entry;
+use crate::WordLibrary;
+
+/// Filter idioms, tagged or in the bundled list (see `transform::idiom`)
+pub struct IdiomFilter {
+    /// Keep only idioms instead of removing them
+    pub idioms_only: bool,
+}
+
+impl IdiomFilter {
+    /// Remove idioms
+    pub fn new() -> Self {
+        IdiomFilter { idioms_only: false }
+    }
+
+    /// Keep only idioms
+    pub fn idioms_only() -> Self {
+        IdiomFilter { idioms_only: true }
+    }
+}
+
+impl Default for IdiomFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SingleFilter for IdiomFilter {
+    fn is_keep(&self, word: &WordLibrary) -> bool {
+        is_idiom_entry(word) == self.idioms_only
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::attributes::IS_IDIOM;
+
+    #[test]
+    fn test_idiom_filter() {
+        let listed = WordLibrary::new("一心一意".to_string());
+        let mut tagged = WordLibrary::new("自定成语".to_string());
+        tagged.set_attribute(IS_IDIOM, "true");
+        let plain = WordLibrary::new("你好".to_string());
+
+        let remove = IdiomFilter::new();
+        assert!(!remove.is_keep(&listed));
+        assert!(!remove.is_keep(&tagged));
+        assert!(remove.is_keep(&plain));
+
+        let only = IdiomFilter::idioms_only();
+        assert!(only.is_keep(&listed));
+        assert!(only.is_keep(&tagged));
+        assert!(!only.is_keep(&plain));
+    }
+}
@@ -0,0 +1,85 @@
+//! Reference filter - keeps only words absent from a reference dictionary
+//!
+//! Used to extract the user's own phrases from a full export: filtering it
+//! against the IME's system dictionary leaves the personal additions.
+
+use crate::filter::SingleFilter;
+use crate::{Code, WordLibrary, WordLibraryList};
+use std::collections::HashSet;
+
+/// Filter removing the words of a reference dictionary
+#[derive(Debug, Clone)]
+pub struct ReferenceFilter {
+    words: HashSet<String>,
+    entries: HashSet<(String, Code)>,
+    match_codes: bool,
+}
+
+impl ReferenceFilter {
+    /// Create from the words of the reference dictionary
+    pub fn new(reference: &WordLibraryList) -> Self {
+        ReferenceFilter {
+            words: reference.iter().map(|w| w.word.clone()).collect(),
+            entries: reference
+                .iter()
+                .map(|w| (w.word.clone(), w.codes.clone()))
+                .collect(),
+            match_codes: false,
+        }
+    }
+
+    /// Only remove words whose codes match the reference too, keeping
+    /// user-added readings of system words
+    pub fn with_match_codes(mut self, match_codes: bool) -> Self {
+        self.match_codes = match_codes;
+        self
+    }
+
+    /// Number of distinct words in the reference dictionary
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Check if the reference dictionary is empty
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl SingleFilter for ReferenceFilter {
+    fn is_keep(&self, word: &WordLibrary) -> bool {
+        if self.match_codes {
+            !self
+                .entries
+                .contains(&(word.word.clone(), word.codes.clone()))
+        } else {
+            !self.words.contains(&word.word)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, code: &str) -> WordLibrary {
+        let mut word = WordLibrary::new(text.to_string());
+        word.codes = Code::from_single(code.to_string());
+        word
+    }
+
+    #[test]
+    fn test_reference_filter() {
+        let system: WordLibraryList = vec![word("你好", "nihao"), word("世界", "shijie")].into();
+        let filter = ReferenceFilter::new(&system);
+        assert_eq!(filter.len(), 2);
+
+        assert!(!filter.is_keep(&word("你好", "nihao")));
+        assert!(!filter.is_keep(&word("世界", "shijia")));
+        assert!(filter.is_keep(&word("测试", "ceshi")));
+
+        let filter = filter.with_match_codes(true);
+        assert!(!filter.is_keep(&word("你好", "nihao")));
+        assert!(filter.is_keep(&word("世界", "shijia")));
+    }
+}
@@ -0,0 +1,140 @@
+//! Sensitive word filter - removes words some IMEs or distribution channels
+//! reject
+
+use crate::filter::SingleFilter;
+use crate::import::read_file_with_encoding_str;
+use crate::{Result, WordLibrary};
+use std::collections::HashSet;
+
+/// Filter words containing a listed sensitive term
+///
+/// Terms compare case-insensitively. By default a word is removed when any
+/// part of it is a listed term; with `match_substrings(false)` only words
+/// equal to a term are.
+pub struct SensitiveWordFilter {
+    terms: HashSet<String>,
+    /// Length in characters of the longest term
+    max_len: usize,
+    substrings: bool,
+}
+
+impl SensitiveWordFilter {
+    /// Filter without any term
+    pub fn new() -> Self {
+        SensitiveWordFilter {
+            terms: HashSet::new(),
+            max_len: 0,
+            substrings: true,
+        }
+    }
+
+    /// Filter with the bundled baseline list (profanity)
+    pub fn with_embedded() -> Self {
+        Self::new().with_content(include_str!("../../resources/SensitiveWords.txt"))
+    }
+
+    /// Add terms
+    pub fn with_terms<I, S>(mut self, terms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for term in terms {
+            let term = term.as_ref().trim().to_lowercase();
+            if !term.is_empty() {
+                self.max_len = self.max_len.max(term.chars().count());
+                self.terms.insert(term);
+            }
+        }
+        self
+    }
+
+    /// Add newline-separated terms, `#` starting a comment line
+    pub fn with_content(self, content: &str) -> Self {
+        self.with_terms(
+            content
+                .lines()
+                .map(|line| line.trim_start_matches('\u{feff}'))
+                .filter(|line| !line.trim_start().starts_with('#')),
+        )
+    }
+
+    /// Add the terms of a newline-separated file with the given text encoding
+    pub fn with_term_file(self, path: &str, encoding: &str) -> Result<Self> {
+        let content = read_file_with_encoding_str(path, encoding)?;
+        Ok(self.with_content(&content))
+    }
+
+    /// Match terms anywhere in a word rather than whole words only
+    pub fn match_substrings(mut self, enabled: bool) -> Self {
+        self.substrings = enabled;
+        self
+    }
+
+    /// Number of listed terms
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Check if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Check if a word is or contains a listed term
+    pub fn is_sensitive(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        if self.terms.contains(&word) {
+            return true;
+        }
+        if !self.substrings {
+            return false;
+        }
+        let bounds: Vec<usize> = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(word.len()))
+            .collect();
+        (0..bounds.len() - 1).any(|start| {
+            (start + 1..bounds.len())
+                .take(self.max_len)
+                .any(|end| self.terms.contains(&word[bounds[start]..bounds[end]]))
+        })
+    }
+}
+
+impl Default for SensitiveWordFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SingleFilter for SensitiveWordFilter {
+    fn is_keep(&self, word: &WordLibrary) -> bool {
+        !self.is_sensitive(&word.word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensitive_word_filter() {
+        let filter = SensitiveWordFilter::with_embedded().with_content("# 自定义\n禁词\n");
+        assert!(filter.len() > 1);
+
+        let keep = |word: &str| filter.is_keep(&WordLibrary::new(word.to_string()));
+        assert!(!keep("禁词"));
+        assert!(!keep("这是禁词吗"));
+        assert!(!keep("Fuck"));
+        assert!(keep("你好"));
+        assert!(keep("# 自定义"));
+
+        let exact = SensitiveWordFilter::new()
+            .with_terms(["禁词"])
+            .match_substrings(false);
+        assert!(exact.is_sensitive("禁词"));
+        assert!(!exact.is_sensitive("这是禁词吗"));
+    }
+}
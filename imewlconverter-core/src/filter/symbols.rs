@@ -0,0 +1,172 @@
+//! Symbol filter - cleans up numbers, English, spaces and punctuation
+//!
+//! Applies a `FilterConfig` to each word: full-width characters are first
+//! converted to half-width and Arabic digits to Chinese numerals (when
+//! enabled), then words still containing a disallowed kind of character are
+//! dropped.
+
+use crate::filter::{BatchFilter, FilterConfig};
+use crate::{Result, WordLibrary, WordLibraryList};
+
+/// Chinese numerals for the digits 0-9
+const CHINESE_DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+/// Filter applying `FilterConfig` to each word
+pub struct SymbolFilter {
+    pub config: FilterConfig,
+}
+
+impl SymbolFilter {
+    pub fn new(config: FilterConfig) -> Self {
+        SymbolFilter { config }
+    }
+
+    /// Apply the configured conversions to a word
+    pub fn transform(&self, word: &str) -> String {
+        word.chars()
+            .map(|c| {
+                let c = if self.config.full_width_to_half {
+                    to_half_width(c)
+                } else {
+                    c
+                };
+                match c.to_digit(10) {
+                    Some(d) if self.config.number_to_chinese && c.is_ascii_digit() => {
+                        CHINESE_DIGITS[d as usize]
+                    }
+                    _ => c,
+                }
+            })
+            .collect()
+    }
+
+    /// Check if a (transformed) word only contains allowed characters
+    pub fn is_allowed(&self, word: &str) -> bool {
+        word.chars().all(|c| {
+            (self.config.keep_number || !is_digit(c))
+                && (self.config.keep_english || !is_latin(c))
+                && (self.config.keep_space || !c.is_whitespace())
+                && (self.config.keep_punctuation || !is_punctuation(c))
+        })
+    }
+
+    /// Transform a word, returning None if it should be dropped
+    ///
+    /// Symbol phrases (see `WordLibrary::is_symbol_phrase`) are kept as is.
+    pub fn process(&self, mut word: WordLibrary) -> Option<WordLibrary> {
+        if word.is_symbol_phrase() {
+            return Some(word);
+        }
+        word.word = self.transform(&word.word);
+        if word.word.is_empty() || !self.is_allowed(&word.word) {
+            return None;
+        }
+        Some(word)
+    }
+}
+
+impl Default for SymbolFilter {
+    fn default() -> Self {
+        Self::new(FilterConfig::default())
+    }
+}
+
+impl BatchFilter for SymbolFilter {
+    fn filter(&self, words: WordLibraryList) -> Result<WordLibraryList> {
+        Ok(words.into_iter().filter_map(|w| self.process(w)).collect())
+    }
+}
+
+/// Convert a full-width character (including the ideographic space) to half-width
+fn to_half_width(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Arabic digits, half- or full-width
+fn is_digit(c: char) -> bool {
+    c.is_ascii_digit() || matches!(c, '０'..='９')
+}
+
+/// Latin letters, half- or full-width
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphabetic() || matches!(c, 'Ａ'..='Ｚ' | 'ａ'..='ｚ')
+}
+
+/// ASCII, CJK and full-width punctuation
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(c,
+            '\u{2010}'..='\u{2027}'   // General punctuation (dashes, quotes, ellipsis)
+            | '\u{3001}'..='\u{303F}' // CJK symbols and punctuation
+            | '\u{FF01}'..='\u{FF0F}'
+            | '\u{FF1A}'..='\u{FF20}'
+            | '\u{FF3B}'..='\u{FF40}'
+            | '\u{FF5B}'..='\u{FF65}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(config: FilterConfig) -> SymbolFilter {
+        SymbolFilter::new(config)
+    }
+
+    #[test]
+    fn test_transform() {
+        let f = filter(FilterConfig {
+            full_width_to_half: true,
+            number_to_chinese: true,
+            ..FilterConfig::default()
+        });
+        assert_eq!(f.transform("ＱＱ２００８"), "QQ二〇〇八");
+        assert_eq!(f.transform("卡拉　OK"), "卡拉 OK");
+    }
+
+    #[test]
+    fn test_keep_flags() {
+        let f = filter(FilterConfig {
+            keep_number: false,
+            keep_english: false,
+            keep_space: false,
+            keep_punctuation: false,
+            ..FilterConfig::default()
+        });
+        assert!(f.is_allowed("你好"));
+        assert!(!f.is_allowed("3D打印"));
+        assert!(!f.is_allowed("卡拉OK"));
+        assert!(!f.is_allowed("你 好"));
+        assert!(!f.is_allowed("你好！"));
+        assert!(!f.is_allowed("《红楼梦》"));
+
+        // Chinese numerals are not Arabic numbers
+        let f = filter(FilterConfig {
+            keep_number: false,
+            number_to_chinese: true,
+            ..FilterConfig::default()
+        });
+        let word = f.process(WordLibrary::new("2008年".to_string())).unwrap();
+        assert_eq!(word.word, "二〇〇八年");
+    }
+
+    #[test]
+    fn test_batch_filter() {
+        let f = filter(FilterConfig {
+            keep_english: false,
+            full_width_to_half: true,
+            ..FilterConfig::default()
+        });
+        let words: WordLibraryList = vec![
+            WordLibrary::new("你好".to_string()),
+            WordLibrary::new("ＡＢ型".to_string()),
+        ]
+        .into();
+        let result = f.filter(words).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].word, "你好");
+    }
+}
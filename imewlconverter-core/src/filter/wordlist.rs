@@ -0,0 +1,84 @@
+//! Wordlist filter - removes or keeps words listed in a file
+
+use crate::filter::SingleFilter;
+use crate::import::read_file_with_encoding_str;
+use crate::{Result, WordLibrary};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// How the listed words are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WordlistMode {
+    /// Remove listed words (blacklist)
+    #[default]
+    Remove,
+    /// Keep only listed words (whitelist)
+    Keep,
+}
+
+/// Filter words against a list of words
+pub struct WordlistFilter {
+    words: HashSet<String>,
+    mode: WordlistMode,
+}
+
+impl WordlistFilter {
+    /// Create from newline-separated content
+    pub fn from_content(content: &str, mode: WordlistMode) -> Self {
+        let words = content
+            .lines()
+            .map(|line| line.trim_start_matches('\u{feff}').trim())
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        WordlistFilter { words, mode }
+    }
+
+    /// Load a newline-separated word file with the given text encoding
+    pub fn from_file(path: &str, encoding: &str, mode: WordlistMode) -> Result<Self> {
+        let content = read_file_with_encoding_str(path, encoding)?;
+        Ok(Self::from_content(&content, mode))
+    }
+
+    /// Number of listed words
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Check if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl SingleFilter for WordlistFilter {
+    fn is_keep(&self, word: &WordLibrary) -> bool {
+        let listed = self.words.contains(&word.word);
+        match self.mode {
+            WordlistMode::Remove => !listed,
+            WordlistMode::Keep => listed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wordlist_filter() {
+        let content = "\u{feff}你好\r\n\n 世界 \n";
+        let blacklist = WordlistFilter::from_content(content, WordlistMode::Remove);
+        let whitelist = WordlistFilter::from_content(content, WordlistMode::Keep);
+        assert_eq!(blacklist.len(), 2);
+
+        let listed = WordLibrary::new("世界".to_string());
+        let other = WordLibrary::new("测试".to_string());
+
+        assert!(!blacklist.is_keep(&listed));
+        assert!(blacklist.is_keep(&other));
+        assert!(whitelist.is_keep(&listed));
+        assert!(!whitelist.is_keep(&other));
+    }
+}
@@ -1,11 +1,29 @@
 //! Code generation for various encoding schemes
 
-use crate::{Code, CodeType, Result, WordLibrary};
+use crate::export::WordLibraryExport;
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::sync::Arc;
 
+pub mod cangjie;
+pub mod inner_code;
+pub mod jyutping;
+pub mod phrases;
 pub mod pinyin;
+pub mod romaji;
+pub mod self_defined;
+pub mod shuangpin;
+pub mod wubi;
 
 // Re-export common types
-pub use pinyin::PinyinGenerator;
+pub use cangjie::CangjieGenerator;
+pub use inner_code::{InnerCodeCharset, InnerCodeGenerator};
+pub use jyutping::JyutpingGenerator;
+pub use pinyin::{PinyinGenerator, PinyinSelection};
+pub use romaji::RomajiGenerator;
+pub use self_defined::SelfDefinedGenerator;
+pub use shuangpin::{ShuangpinGenerator, ShuangpinScheme};
+pub use wubi::WubiGenerator;
 
 /// Trait for code generators
 pub trait CodeGenerator {
@@ -28,6 +46,81 @@ pub trait CodeGenerator {
     fn code_type(&self) -> CodeType;
 }
 
+/// Create the built-in generator for a code type
+///
+/// Returns `Error::Unsupported` for code types without a generator.
+pub fn generator_for(
+    code_type: CodeType,
+    resources: Arc<ResourceManager>,
+) -> Result<Box<dyn CodeGenerator>> {
+    Ok(match code_type {
+        CodeType::Pinyin => Box::new(PinyinGenerator::with_resources(resources)),
+        CodeType::Shuangpin => Box::new(ShuangpinGenerator::with_resources(
+            resources,
+            ShuangpinScheme::default(),
+        )),
+        CodeType::Wubi | CodeType::Wubi98 | CodeType::WubiNewAge => {
+            Box::new(WubiGenerator::with_resources(resources).with_code_type(code_type)?)
+        }
+        CodeType::Cangjie => Box::new(CangjieGenerator::with_resources(resources)),
+        CodeType::InnerCode => Box::new(InnerCodeGenerator::new()),
+        CodeType::Jyutping => Box::new(JyutpingGenerator::with_resources(resources)),
+        CodeType::Kana => Box::new(RomajiGenerator::new()),
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "No code generator for {}",
+                code_type
+            )))
+        }
+    })
+}
+
+/// Create the generator converting imported codes for an exporter, if any
+///
+/// Shuangpin codes are generated with `scheme`. Exporters accepting any code
+/// type, and code types without a built-in generator, keep the imported
+/// codes.
+pub fn output_generator_for(
+    exporter: &dyn WordLibraryExport,
+    resources: Arc<ResourceManager>,
+    scheme: ShuangpinScheme,
+) -> Result<Option<Box<dyn CodeGenerator>>> {
+    if exporter.accepts_any_code_type() {
+        return Ok(None);
+    }
+    if exporter.code_type() == CodeType::Shuangpin {
+        return Ok(Some(Box::new(ShuangpinGenerator::with_resources(
+            resources, scheme,
+        ))));
+    }
+    match generator_for(exporter.code_type(), resources) {
+        Ok(generator) => Ok(Some(generator)),
+        Err(Error::Unsupported(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Enumerate every combination of per-character codes
+///
+/// Used by one-code-per-word generators to cover characters that have
+/// several candidate codes.
+pub(crate) fn code_combinations(per_char: &[Vec<String>]) -> Vec<Vec<&str>> {
+    let mut combos: Vec<Vec<&str>> = vec![vec![]];
+    for codes in per_char {
+        combos = combos
+            .into_iter()
+            .flat_map(|prefix| {
+                codes.iter().map(move |code| {
+                    let mut next = prefix.clone();
+                    next.push(code.as_str());
+                    next
+                })
+            })
+            .collect();
+    }
+    combos
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,4 +129,14 @@ mod tests {
     fn test_generator_trait_exists() {
         // Just test that the trait compiles
     }
+
+    #[test]
+    fn test_generator_for() {
+        let resources = ResourceManager::shared();
+
+        let wubi = generator_for(CodeType::Wubi98, resources.clone()).unwrap();
+        assert_eq!(wubi.code_type(), CodeType::Wubi98);
+
+        assert!(generator_for(CodeType::Zhengma, resources).is_err());
+    }
 }
@@ -0,0 +1,224 @@
+//! Cangjie code generator
+//!
+//! Generates Cangjie 5 codes from the embedded Cangjie5.txt. Words are encoded
+//! with the Cangjie phrase rules rather than by concatenating character codes
+//! (A/B/C = 1st/2nd/3rd char, Y/Z = second-to-last/last char,
+//! a/b/z = first/second/last code of that char):
+//! - 2 chars: `AaAzBaBbBz`
+//! - 3 chars: `AaAzBaYzZz`
+//! - 4+ chars: `AaBzCaYzZz`
+
+use crate::generate::{code_combinations, CodeGenerator};
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::sync::Arc;
+
+/// Which code of a character a rule picks
+#[derive(Debug, Clone, Copy)]
+enum Pick {
+    First,
+    Second,
+    Last,
+}
+
+/// Cangjie generator
+pub struct CangjieGenerator {
+    /// Resource manager with all dictionaries
+    resources: Arc<ResourceManager>,
+}
+
+impl CangjieGenerator {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_resources(ResourceManager::shared()))
+    }
+
+    /// Initialize with existing resource manager (for sharing)
+    pub fn with_resources(resources: Arc<ResourceManager>) -> Self {
+        Self { resources }
+    }
+
+    /// Coding rule for a word of `len` characters, as (char index, pick) pairs
+    fn rule(len: usize) -> Vec<(usize, Pick)> {
+        use Pick::*;
+        match len {
+            2 => vec![(0, First), (0, Last), (1, First), (1, Second), (1, Last)],
+            3 => vec![(0, First), (0, Last), (1, First), (1, Last), (2, Last)],
+            n => vec![
+                (0, First),
+                (1, Last),
+                (2, First),
+                (n - 2, Last),
+                (n - 1, Last),
+            ],
+        }
+    }
+
+    /// Compose a word code from the full codes of its characters
+    fn compose(char_codes: &[&str]) -> String {
+        if char_codes.len() == 1 {
+            return char_codes[0].to_string();
+        }
+
+        let mut result = String::new();
+        // Last used code position per character, so that a short code is not
+        // picked twice (e.g. head and tail of a one-key character)
+        let mut used: Vec<Option<usize>> = vec![None; char_codes.len()];
+
+        for (index, pick) in Self::rule(char_codes.len()) {
+            let code = char_codes[index].as_bytes();
+            if code.is_empty() {
+                continue;
+            }
+            let pos = match pick {
+                Pick::First => 0,
+                Pick::Second if code.len() > 1 => 1,
+                Pick::Second => continue,
+                Pick::Last => code.len() - 1,
+            };
+            if used[index].is_some_and(|last| pos <= last) {
+                continue;
+            }
+            used[index] = Some(pos);
+            result.push(code[pos] as char);
+        }
+
+        result
+    }
+
+    /// Codes of the characters taking part in the word rule
+    ///
+    /// Words of five or more characters are reduced to the first three and the
+    /// last two characters, which keeps `rule` picking the same characters.
+    fn rule_char_codes(&self, text: &str) -> Result<Vec<Vec<String>>> {
+        let chars: Vec<char> = text.chars().collect();
+        match chars.len() {
+            1 => Ok(vec![self.get_codes_for_char(chars[0])?]),
+            n => {
+                let used = if n > 5 {
+                    vec![chars[0], chars[1], chars[2], chars[n - 2], chars[n - 1]]
+                } else {
+                    chars
+                };
+                used.into_iter().map(|c| self.word_codes(c)).collect()
+            }
+        }
+    }
+
+    /// Codes usable for word composition
+    ///
+    /// Codes starting with `x` (difficult characters) or `z` (symbols) are only
+    /// used when the character has no regular code.
+    fn word_codes(&self, c: char) -> Result<Vec<String>> {
+        let codes = self.get_codes_for_char(c)?;
+        let regular: Vec<String> = codes
+            .iter()
+            .filter(|code| !code.starts_with('x') && !code.starts_with('z'))
+            .cloned()
+            .collect();
+        Ok(if regular.is_empty() { codes } else { regular })
+    }
+}
+
+impl Default for CangjieGenerator {
+    fn default() -> Self {
+        Self::new().expect("Failed to load cangjie resources")
+    }
+}
+
+impl CodeGenerator for CangjieGenerator {
+    fn generate_code(&self, word: &mut WordLibrary) -> Result<()> {
+        // If already has cangjie, skip
+        if word.code_type == CodeType::Cangjie && !word.codes.is_empty() {
+            return Ok(());
+        }
+
+        let code = self.generate_code_for_string(&word.word)?;
+        word.code_type = CodeType::Cangjie;
+        word.codes = code;
+        Ok(())
+    }
+
+    fn generate_code_for_string(&self, text: &str) -> Result<Code> {
+        // Only the characters taking part in the word rule need to be looked up
+        let per_char = self.rule_char_codes(text)?;
+
+        let mut result: Vec<String> = Vec::new();
+        for combo in code_combinations(&per_char) {
+            let code = Self::compose(&combo);
+            if !code.is_empty() && !result.contains(&code) {
+                result.push(code);
+            }
+        }
+
+        if result.is_empty() {
+            return Err(Error::CharacterNotFound(text.chars().next().unwrap_or('?')));
+        }
+        Ok(Code::from_multiple(result))
+    }
+
+    fn get_codes_for_char(&self, c: char) -> Result<Vec<String>> {
+        self.resources
+            .get_char_codes(c, &CodeType::Cangjie)
+            .filter(|codes| !codes.is_empty())
+            .ok_or(Error::CharacterNotFound(c))
+    }
+
+    fn is_multi_code_per_char(&self) -> bool {
+        false
+    }
+
+    fn is_one_code_per_char(&self) -> bool {
+        false // One code per word
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Cangjie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_char() {
+        let generator = CangjieGenerator::new().unwrap();
+        let code = generator.generate_code_for_string("你").unwrap();
+        assert_eq!(code.get_single_code(), Some("onf"));
+    }
+
+    #[test]
+    fn test_word_rules() {
+        let generator = CangjieGenerator::new().unwrap();
+
+        // 人(o) 民(rvp): head and tail of a one-key code are the same key
+        let two = generator.generate_code_for_string("人民").unwrap();
+        assert_eq!(two.get_single_code(), Some("orvp"));
+
+        // 中(l) 华(opj) 人(o) 民(rvp)
+        let four = generator.generate_code_for_string("中华人民").unwrap();
+        assert_eq!(four.get_single_code(), Some("ljop"));
+
+        let seven = generator
+            .generate_code_for_string("中华人民共和国")
+            .unwrap();
+        assert_eq!(seven.get_single_code(), Some("ljori"));
+
+        // Characters outside the rule are never looked up
+        let long = generator
+            .generate_code_for_string(&format!("中华人{}和国", "🙂".repeat(40)))
+            .unwrap();
+        assert_eq!(long.get_single_code(), Some("ljori"));
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let generator = CangjieGenerator::new().unwrap();
+
+        let mut word = WordLibrary::new("中国".to_string());
+        generator.generate_code(&mut word).unwrap();
+
+        assert_eq!(word.code_type, CodeType::Cangjie);
+        assert_eq!(word.get_single_code(), Some("lwmi"));
+    }
+}
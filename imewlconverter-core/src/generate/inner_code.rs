@@ -0,0 +1,103 @@
+//! Internal code generator
+//!
+//! Generates the hexadecimal internal code (GBK bytes or Unicode code point)
+//! of each character, as required by some legacy IME formats
+
+use crate::generate::CodeGenerator;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+
+/// Character set used for the internal code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InnerCodeCharset {
+    /// Unicode code point, e.g. 你 -> 4F60
+    #[default]
+    Unicode,
+    /// GBK byte sequence, e.g. 你 -> C4E3
+    Gbk,
+}
+
+/// Internal code generator
+#[derive(Debug, Clone, Default)]
+pub struct InnerCodeGenerator {
+    charset: InnerCodeCharset,
+}
+
+impl InnerCodeGenerator {
+    pub fn new() -> Self {
+        InnerCodeGenerator {
+            charset: InnerCodeCharset::Unicode,
+        }
+    }
+
+    pub fn with_charset(charset: InnerCodeCharset) -> Self {
+        InnerCodeGenerator { charset }
+    }
+
+    /// Get the internal code of a single character
+    pub fn get_inner_code(&self, c: char) -> Result<String> {
+        match self.charset {
+            InnerCodeCharset::Unicode => Ok(format!("{:04X}", c as u32)),
+            InnerCodeCharset::Gbk => {
+                let mut buf = [0u8; 4];
+                let (bytes, _, had_errors) = encoding_rs::GBK.encode(c.encode_utf8(&mut buf));
+                if had_errors {
+                    return Err(Error::CharacterNotFound(c));
+                }
+                Ok(bytes.iter().map(|b| format!("{:02X}", b)).collect())
+            }
+        }
+    }
+}
+
+impl CodeGenerator for InnerCodeGenerator {
+    fn generate_code(&self, word: &mut WordLibrary) -> Result<()> {
+        let code = self.generate_code_for_string(&word.word)?;
+        word.code_type = CodeType::InnerCode;
+        word.codes = code;
+        Ok(())
+    }
+
+    fn generate_code_for_string(&self, text: &str) -> Result<Code> {
+        let codes = text
+            .chars()
+            .map(|c| self.get_inner_code(c))
+            .collect::<Result<Vec<String>>>()?;
+        Ok(Code::from_char_list(codes))
+    }
+
+    fn get_codes_for_char(&self, c: char) -> Result<Vec<String>> {
+        Ok(vec![self.get_inner_code(c)?])
+    }
+
+    fn is_multi_code_per_char(&self) -> bool {
+        false
+    }
+
+    fn is_one_code_per_char(&self) -> bool {
+        true
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::InnerCode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode() {
+        let generator = InnerCodeGenerator::new();
+        let code = generator.generate_code_for_string("你好").unwrap();
+        assert_eq!(code.to_string_with_separator(" "), "4F60 597D");
+    }
+
+    #[test]
+    fn test_gbk() {
+        let generator = InnerCodeGenerator::with_charset(InnerCodeCharset::Gbk);
+        assert_eq!(generator.get_inner_code('你').unwrap(), "C4E3");
+        assert_eq!(generator.get_inner_code('a').unwrap(), "61");
+        assert!(generator.get_inner_code('😀').is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Cantonese Jyutping code generator
+//!
+//! Generates Jyutping syllables with tone numbers (你好 -> `nei5 hou2`) from
+//! the embedded Jyutping.txt, which lists the most common reading of each
+//! character first.
+
+use crate::generate::CodeGenerator;
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::sync::Arc;
+
+/// Jyutping generator
+pub struct JyutpingGenerator {
+    /// Resource manager with all dictionaries
+    resources: Arc<ResourceManager>,
+}
+
+impl JyutpingGenerator {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_resources(ResourceManager::shared()))
+    }
+
+    /// Initialize with existing resource manager (for sharing)
+    pub fn with_resources(resources: Arc<ResourceManager>) -> Self {
+        Self { resources }
+    }
+
+    /// Get the most common reading of a character
+    pub fn get_default_jyutping(&self, c: char) -> Result<String> {
+        self.get_codes_for_char(c)?
+            .into_iter()
+            .next()
+            .ok_or(Error::CharacterNotFound(c))
+    }
+}
+
+impl Default for JyutpingGenerator {
+    fn default() -> Self {
+        Self::new().expect("Failed to load jyutping resources")
+    }
+}
+
+impl CodeGenerator for JyutpingGenerator {
+    fn generate_code(&self, word: &mut WordLibrary) -> Result<()> {
+        // If already has jyutping, skip
+        if word.code_type == CodeType::Jyutping && !word.codes.is_empty() {
+            return Ok(());
+        }
+
+        let code = self.generate_code_for_string(&word.word)?;
+        word.code_type = CodeType::Jyutping;
+        word.codes = code;
+        Ok(())
+    }
+
+    fn generate_code_for_string(&self, text: &str) -> Result<Code> {
+        let codes = text
+            .chars()
+            .map(|c| self.get_default_jyutping(c))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Code::from_char_list(codes))
+    }
+
+    fn get_codes_for_char(&self, c: char) -> Result<Vec<String>> {
+        if c.is_ascii() {
+            return Ok(vec![c.to_lowercase().to_string()]);
+        }
+
+        self.resources
+            .get_char_codes(c, &CodeType::Jyutping)
+            .filter(|codes| !codes.is_empty())
+            .ok_or(Error::CharacterNotFound(c))
+    }
+
+    fn is_multi_code_per_char(&self) -> bool {
+        true
+    }
+
+    fn is_one_code_per_char(&self) -> bool {
+        true
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Jyutping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jyutping_generation() {
+        let generator = JyutpingGenerator::new().unwrap();
+
+        let mut word = WordLibrary::new("香港".to_string());
+        generator.generate_code(&mut word).unwrap();
+        assert_eq!(word.code_type, CodeType::Jyutping);
+        assert_eq!(word.codes.to_string_with_separator(" "), "hoeng1 gong2");
+
+        // Simplified and traditional forms share their readings
+        let code = generator.generate_code_for_string("广东话").unwrap();
+        assert_eq!(code.to_string_with_separator(" "), "gwong2 dung1 waa6");
+        let code = generator.generate_code_for_string("廣東話").unwrap();
+        assert_eq!(code.to_string_with_separator(" "), "gwong2 dung1 waa6");
+    }
+
+    #[test]
+    fn test_polyphonic_and_missing() {
+        let generator = JyutpingGenerator::new().unwrap();
+        assert_eq!(
+            generator.get_codes_for_char('行').unwrap(),
+            vec!["hang4", "hong4", "hang6"]
+        );
+        assert_eq!(generator.get_default_jyutping('行').unwrap(), "hang4");
+        assert!(matches!(
+            generator.generate_code_for_string("龘"),
+            Err(Error::CharacterNotFound('龘'))
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+//! Generated phrase sets for seed dictionaries
+//!
+//! Numbers, dates and quantities are tedious to collect by hand but follow
+//! fixed rules, so they are generated with their pinyin instead.
+
+use crate::{Code, CodeType, WordLibrary, WordLibraryList};
+
+const DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+/// Units of the digits of a number below 10000, from the thousands down
+const UNITS: [&str; 4] = ["千", "百", "十", ""];
+
+/// Measure words and units combined with the numbers 1 to 10
+const MEASURES: [&str; 10] = ["个", "次", "天", "年", "元", "块", "米", "岁", "斤", "点"];
+
+/// A set of generated phrases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhraseSet {
+    /// Numbers from 一 to 一万
+    Numbers,
+    /// Months, days of the month and weekdays
+    Dates,
+    /// The numbers 1 to 10 with common measure words (两个, 三天)
+    Units,
+}
+
+impl PhraseSet {
+    pub fn generate(self) -> WordLibraryList {
+        let words: Vec<String> = match self {
+            PhraseSet::Numbers => (1..=10000).map(chinese_number).collect(),
+            PhraseSet::Dates => {
+                let mut words: Vec<String> = (1..=12)
+                    .map(|m| format!("{}月", chinese_number(m)))
+                    .collect();
+                for suffix in ["日", "号"] {
+                    words.extend((1..=31).map(|d| format!("{}{}", chinese_number(d), suffix)));
+                }
+                for prefix in ["星期", "周"] {
+                    words.extend((1..=6).map(|d| format!("{}{}", prefix, chinese_number(d))));
+                    words.push(format!("{}日", prefix));
+                }
+                words.push("星期天".to_string());
+                words
+            }
+            PhraseSet::Units => (1..=10)
+                .flat_map(|n| {
+                    let number = if n == 2 {
+                        "两".to_string()
+                    } else {
+                        chinese_number(n)
+                    };
+                    MEASURES.iter().map(move |m| format!("{}{}", number, m))
+                })
+                .collect(),
+        };
+        words.into_iter().filter_map(|w| phrase(&w)).collect()
+    }
+}
+
+/// Generate the phrases of several sets
+pub fn generate_phrases(sets: &[PhraseSet]) -> WordLibraryList {
+    let mut words = WordLibraryList::new();
+    for set in sets {
+        words.append(&mut set.generate());
+    }
+    words
+}
+
+/// Write a number from 1 to 99999999 in Chinese (一百零一, 一万零五十)
+pub fn chinese_number(n: u32) -> String {
+    let mut result = String::new();
+    let (high, low) = (n / 10000, n % 10000);
+    if high > 0 {
+        push_below_10000(high, true, &mut result);
+        result.push('万');
+        if low > 0 && low < 1000 {
+            result.push('零');
+        }
+    }
+    if low > 0 {
+        push_below_10000(low, high == 0, &mut result);
+    }
+    result
+}
+
+/// Append a number from 1 to 9999; a leading 一十 is read as 十
+fn push_below_10000(n: u32, leading: bool, result: &mut String) {
+    let digits = [n / 1000, n / 100 % 10, n / 10 % 10, n % 10];
+    let mut pending_zero = false;
+    let mut started = false;
+    for (i, &digit) in digits.iter().enumerate() {
+        if digit == 0 {
+            pending_zero = started;
+            continue;
+        }
+        if pending_zero {
+            result.push('零');
+            pending_zero = false;
+        }
+        if !(leading && !started && i == 2 && digit == 1) {
+            result.push(DIGITS[digit as usize]);
+        }
+        result.push_str(UNITS[i]);
+        started = true;
+    }
+}
+
+fn pinyin_of(c: char) -> Option<&'static str> {
+    Some(match c {
+        '零' => "ling",
+        '一' => "yi",
+        '二' => "er",
+        '两' => "liang",
+        '三' => "san",
+        '四' => "si",
+        '五' => "wu",
+        '六' => "liu",
+        '七' => "qi",
+        '八' => "ba",
+        '九' => "jiu",
+        '十' => "shi",
+        '百' => "bai",
+        '千' => "qian",
+        '万' => "wan",
+        '月' => "yue",
+        '日' => "ri",
+        '号' => "hao",
+        '星' => "xing",
+        '期' => "qi",
+        '周' => "zhou",
+        '天' => "tian",
+        '个' => "ge",
+        '次' => "ci",
+        '年' => "nian",
+        '元' => "yuan",
+        '块' => "kuai",
+        '米' => "mi",
+        '岁' => "sui",
+        '斤' => "jin",
+        '点' => "dian",
+        _ => return None,
+    })
+}
+
+fn phrase(word: &str) -> Option<WordLibrary> {
+    let codes = word
+        .chars()
+        .map(|c| pinyin_of(c).map(str::to_string))
+        .collect::<Option<Vec<_>>>()?;
+    let mut wl = WordLibrary::new(word.to_string());
+    wl.code_type = CodeType::Pinyin;
+    wl.codes = Code::from_char_list(codes);
+    Some(wl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chinese_number() {
+        let cases = [
+            (1, "一"),
+            (10, "十"),
+            (15, "十五"),
+            (20, "二十"),
+            (101, "一百零一"),
+            (110, "一百一十"),
+            (1001, "一千零一"),
+            (1010, "一千零一十"),
+            (10000, "一万"),
+            (10010, "一万零一十"),
+            (100000, "十万"),
+            (1234_5678, "一千二百三十四万五千六百七十八"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(chinese_number(n), expected, "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_phrase_sets() {
+        let numbers = PhraseSet::Numbers.generate();
+        assert_eq!(numbers.len(), 10000);
+        assert_eq!(numbers[100].get_pinyin_string(" "), "yi bai ling yi");
+
+        let dates = PhraseSet::Dates.generate();
+        assert_eq!(dates.len(), 12 + 31 * 2 + 7 * 2 + 1);
+        let units = generate_phrases(&[PhraseSet::Units]);
+        assert_eq!(units[10].word, "两个");
+        assert_eq!(units[10].get_pinyin_string(" "), "liang ge");
+    }
+}
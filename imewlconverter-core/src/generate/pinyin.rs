@@ -7,30 +7,72 @@ use crate::resource::ResourceManager;
 use crate::{Code, CodeType, Error, Result, WordLibrary};
 use std::sync::Arc;
 
+/// How the reading of a polyphonic character is chosen when no word entry
+/// covers it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PinyinSelection {
+    /// The first reading listed in the dictionary
+    First,
+    /// The reading with the highest frequency weight, falling back to the
+    /// first one when no weights are known (most polyphonic characters, see
+    /// `ResourceManager::get_pinyin_weights`)
+    #[default]
+    MostFrequent,
+}
+
 /// Pinyin generator
 pub struct PinyinGenerator {
     /// Resource manager with all dictionaries
     resources: Arc<ResourceManager>,
+    /// Default reading selection for polyphonic characters
+    selection: PinyinSelection,
 }
 
 impl PinyinGenerator {
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            resources: Arc::new(ResourceManager::new()?),
-        })
+        Ok(Self::with_resources(ResourceManager::shared()))
     }
 
     /// Initialize with existing resource manager (for sharing)
     pub fn with_resources(resources: Arc<ResourceManager>) -> Self {
-        Self { resources }
+        Self {
+            resources,
+            selection: PinyinSelection::default(),
+        }
+    }
+
+    /// Set how the default reading of polyphonic characters is chosen
+    pub fn with_selection(mut self, selection: PinyinSelection) -> Self {
+        self.selection = selection;
+        self
     }
 
-    /// Get default pinyin for a character (first pronunciation)
+    /// Get default pinyin for a character according to the selection strategy
     pub fn get_default_pinyin(&self, c: char) -> Result<String> {
-        self.resources
+        let pinyins = self
+            .resources
             .get_char_codes(c, &CodeType::Pinyin)
-            .and_then(|pinyins| pinyins.first().cloned())
-            .ok_or(Error::CharacterNotFound(c))
+            .filter(|pinyins| !pinyins.is_empty())
+            .ok_or(Error::CharacterNotFound(c))?;
+
+        let index = match self.selection {
+            PinyinSelection::First => 0,
+            PinyinSelection::MostFrequent => self
+                .resources
+                .get_pinyin_weights(c)
+                .and_then(|weights| {
+                    // max_by keeps the last maximum, so iterate in reverse to
+                    // prefer the earliest reading on ties
+                    weights
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .max_by(|a, b| a.1.total_cmp(b.1))
+                })
+                .map_or(0, |(i, _)| i),
+        };
+
+        Ok(pinyins.get(index).unwrap_or(&pinyins[0]).clone())
     }
 
     /// Check if a character has multiple pronunciations
@@ -42,25 +84,31 @@ impl PinyinGenerator {
     }
 
     /// Get pinyin for a word, handling polyphonic words
-    fn get_word_pinyin(&self, word: &str) -> Option<String> {
-        // First check if there's a specific pronunciation for this word
-        if let Some(pinyin) = self.resources.get_word_pinyin(word) {
-            return Some(pinyin);
-        }
+    ///
+    /// The word is segmented by forward longest matching against the
+    /// polyphonic word table, so that e.g. 银行行长 reads the first 行 from
+    /// 银行 and the second from 行长. Characters not covered by any entry
+    /// fall back to their default pronunciation.
+    fn get_word_pinyin(&self, word: &str) -> Option<Vec<String>> {
+        let chars: Vec<char> = word.chars().collect();
+        let max_len = self.resources.max_word_pinyin_len();
+        let mut result = Vec::with_capacity(chars.len());
 
-        // Otherwise, concatenate character pinyin
-        let mut result = String::new();
-        for ch in word.chars() {
-            if let Some(pinyin) = self.resources.get_char_codes(ch, &CodeType::Pinyin) {
-                if !result.is_empty() {
-                    result.push('\'');
-                }
-                // Use first pronunciation if multiple
-                result.push_str(&pinyin[0]);
-            } else {
-                // Character not found in dictionary
-                return None;
+        let mut i = 0;
+        while i < chars.len() {
+            if let Some((len, pinyin)) = self.match_word_pinyin(&chars[i..], max_len) {
+                result.extend(pinyin);
+                i += len;
+                continue;
             }
+
+            let pinyin = if chars[i].is_ascii() {
+                chars[i].to_lowercase().to_string()
+            } else {
+                self.get_default_pinyin(chars[i]).ok()?
+            };
+            result.push(pinyin);
+            i += 1;
         }
 
         if result.is_empty() {
@@ -69,6 +117,29 @@ impl PinyinGenerator {
             Some(result)
         }
     }
+
+    /// Find the longest polyphonic word entry at the start of `chars`
+    ///
+    /// Returns the number of characters matched and their pinyin.
+    fn match_word_pinyin(&self, chars: &[char], max_len: usize) -> Option<(usize, Vec<String>)> {
+        for len in (2..=max_len.min(chars.len())).rev() {
+            let candidate: String = chars[..len].iter().collect();
+            let Some(pinyin) = self.resources.get_word_pinyin(&candidate) else {
+                continue;
+            };
+
+            // Format: 'yin'hang
+            let syllables: Vec<String> = pinyin
+                .split('\'')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect();
+            if syllables.len() == len {
+                return Some((len, syllables));
+            }
+        }
+        None
+    }
 }
 
 impl Default for PinyinGenerator {
@@ -91,10 +162,7 @@ impl CodeGenerator for PinyinGenerator {
     }
 
     fn generate_code_for_string(&self, s: &str) -> Result<Code> {
-        if let Some(pinyin) = self.get_word_pinyin(s) {
-            // Remove apostrophes and join - pinyin is already in format like "ni'hao"
-            // We want to store it as separate codes per character
-            let codes: Vec<String> = pinyin.split('\'').map(|s| s.to_string()).collect();
+        if let Some(codes) = self.get_word_pinyin(s) {
             Ok(Code::from_char_list(codes))
         } else {
             Err(Error::CharacterNotFound(s.chars().next().unwrap_or('?')))
@@ -153,6 +221,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_longest_match_segmentation() {
+        let generator = PinyinGenerator::new().unwrap();
+
+        let code = generator.generate_code_for_string("银行行长").unwrap();
+        assert_eq!(code.to_string_with_separator("'"), "yin'hang'hang'zhang");
+
+        // Whole-word entries still apply
+        let code = generator.generate_code_for_string("校改").unwrap();
+        assert_eq!(code.to_string_with_separator("'"), "jiao'gai");
+    }
+
+    #[test]
+    fn test_selection_strategy() {
+        let generator = PinyinGenerator::new().unwrap();
+        assert_eq!(generator.get_default_pinyin('率').unwrap(), "lv4");
+        assert_eq!(generator.get_default_pinyin('行').unwrap(), "xing2");
+        // No weights: first reading
+        assert_eq!(generator.get_default_pinyin('着').unwrap(), "zhe5");
+
+        let first = PinyinGenerator::new()
+            .unwrap()
+            .with_selection(PinyinSelection::First);
+        assert_eq!(first.get_default_pinyin('率').unwrap(), "shuai4");
+    }
+
     #[test]
     fn test_ascii_handling() {
         let generator = PinyinGenerator::new().unwrap();
@@ -0,0 +1,254 @@
+//! Romaji code generator for Japanese kana
+//!
+//! Mixed dictionaries sometimes contain kana words, which have no pinyin and
+//! would otherwise be dropped. They are romanized with Hepburn spelling into
+//! one code per word, since small kana such as ゃ and っ change the reading of
+//! their neighbours (きゃっと -> `kyatto`, コーヒー -> `koohii`).
+
+use crate::generate::CodeGenerator;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+
+/// Romaji generator
+#[derive(Debug, Clone, Default)]
+pub struct RomajiGenerator;
+
+impl RomajiGenerator {
+    pub fn new() -> Self {
+        RomajiGenerator
+    }
+
+    /// Romanize a word of hiragana and katakana
+    pub fn romanize(&self, text: &str) -> Result<String> {
+        let kana: Vec<char> = text.chars().map(to_hiragana).collect();
+        let mut result = String::new();
+        let mut geminate = false;
+        let mut i = 0;
+        while i < kana.len() {
+            let c = kana[i];
+            i += 1;
+            match c {
+                'っ' => {
+                    geminate = true;
+                    continue;
+                }
+                // The long vowel mark repeats the previous vowel
+                'ー' => {
+                    match result.chars().last() {
+                        Some(v @ ('a' | 'i' | 'u' | 'e' | 'o')) => result.push(v),
+                        _ => return Err(Error::CharacterNotFound(c)),
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            let mut syllable = romaji_of(c).ok_or(Error::CharacterNotFound(c))?.to_string();
+            if let Some(combined) = kana.get(i).and_then(|&next| combine(&syllable, next)) {
+                syllable = combined;
+                i += 1;
+            }
+            if geminate {
+                // っち is spelled tchi in Hepburn
+                match syllable.chars().next() {
+                    Some('c') => result.push('t'),
+                    Some(consonant) if !"aiueon".contains(consonant) => result.push(consonant),
+                    _ => {}
+                }
+                geminate = false;
+            }
+            result.push_str(&syllable);
+        }
+        Ok(result)
+    }
+}
+
+impl CodeGenerator for RomajiGenerator {
+    fn generate_code(&self, word: &mut WordLibrary) -> Result<()> {
+        // If already has romaji, skip
+        if word.code_type == CodeType::Kana && !word.codes.is_empty() {
+            return Ok(());
+        }
+
+        let code = self.generate_code_for_string(&word.word)?;
+        word.code_type = CodeType::Kana;
+        word.codes = code;
+        Ok(())
+    }
+
+    fn generate_code_for_string(&self, text: &str) -> Result<Code> {
+        Ok(Code::from_single(self.romanize(text)?))
+    }
+
+    fn get_codes_for_char(&self, c: char) -> Result<Vec<String>> {
+        Ok(vec![self.romanize(&c.to_string())?])
+    }
+
+    fn is_multi_code_per_char(&self) -> bool {
+        false
+    }
+
+    fn is_one_code_per_char(&self) -> bool {
+        false // Small kana change the reading of the previous one
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Kana
+    }
+}
+
+/// Whether a character is hiragana, katakana or the long vowel mark
+pub fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | '\u{30A1}'..='\u{30FA}' | 'ー')
+}
+
+/// Map katakana to the hiragana with the same reading
+fn to_hiragana(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Spelling of a syllable followed by a small kana (きゃ, ファ, ティ)
+fn combine(syllable: &str, next: char) -> Option<String> {
+    let (stem, vowel) = syllable.split_at(syllable.len() - 1);
+    match next {
+        'ゃ' | 'ゅ' | 'ょ' if vowel == "i" && !stem.is_empty() => {
+            let y = romaji_of(next)?;
+            Some(
+                if stem.ends_with("sh") || stem.ends_with("ch") || stem == "j" {
+                    format!("{}{}", stem, &y[1..])
+                } else {
+                    format!("{}{}", stem, y)
+                },
+            )
+        }
+        'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' => {
+            let stem = match syllable {
+                "u" => "w",
+                "fu" => "f",
+                "vu" => "v",
+                "te" | "de" => stem,
+                _ if stem.is_empty() || !matches!(vowel, "i" | "u") => return None,
+                _ => stem,
+            };
+            Some(format!("{}{}", stem, romaji_of(next)?))
+        }
+        _ => None,
+    }
+}
+
+fn romaji_of(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_romanize() {
+        let generator = RomajiGenerator::new();
+        let cases = [
+            ("ありがとう", "arigatou"),
+            ("きょうと", "kyouto"),
+            ("しゃしん", "shashin"),
+            ("きって", "kitte"),
+            ("まっちゃ", "matcha"),
+            ("コーヒー", "koohii"),
+            ("パーティー", "paatii"),
+            ("ファイル", "fairu"),
+            ("ウィキ", "wiki"),
+            ("ヴァイオリン", "vaiorin"),
+        ];
+        for (kana, romaji) in cases {
+            assert_eq!(generator.romanize(kana).unwrap(), romaji, "{}", kana);
+        }
+        assert!(matches!(
+            generator.romanize("ーあ"),
+            Err(Error::CharacterNotFound('ー'))
+        ));
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let mut word = WordLibrary::new("すし".to_string());
+        RomajiGenerator::new().generate_code(&mut word).unwrap();
+        assert_eq!(word.code_type, CodeType::Kana);
+        assert_eq!(word.get_word_code().as_deref(), Some("sushi"));
+
+        assert!(is_kana('ア') && is_kana('ー') && !is_kana('日'));
+        assert!(RomajiGenerator::new().romanize("日本").is_err());
+    }
+}
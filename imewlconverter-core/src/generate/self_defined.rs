@@ -0,0 +1,340 @@
+//! Self-defined code generator
+//!
+//! Generates codes from a user-provided `char\tcode` mapping file, using word
+//! formation rules in the format described in 自定义编码规则.md:
+//!
+//! ```text
+//! code_e2=p11+p12+p21+p22
+//! code_e3=p11+p21+p31+p32
+//! code_a4=p11+p21+p31+n11
+//! ```
+//!
+//! `code_e{n}` applies to words of exactly n characters, `code_a{n}` to words
+//! of n or more characters. `p{x}{y}` is the y-th code of the x-th character,
+//! `n{x}{y}` counts characters from the end, and `e` as y means the last code.
+
+use crate::generate::{code_combinations, CodeGenerator};
+use crate::import::read_file_with_encoding_str;
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::collections::HashMap;
+
+/// Most code variants generated for a word; characters past the limit only
+/// contribute their first code
+const MAX_COMBINATIONS: usize = 256;
+
+/// Which character a rule part refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharRef {
+    /// 1-based index from the start
+    FromStart(usize),
+    /// 1-based index from the end
+    FromEnd(usize),
+}
+
+/// Which code of a character a rule part refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeRef {
+    /// 1-based index
+    Index(usize),
+    /// The last code
+    Last,
+}
+
+/// A word formation rule such as `code_e2=p11+p12+p21+p22`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CodingRule {
+    /// Number of characters the rule applies to
+    length: usize,
+    /// Whether the rule also applies to longer words (`code_a`)
+    or_longer: bool,
+    parts: Vec<(CharRef, CodeRef)>,
+}
+
+impl CodingRule {
+    fn parse(line: &str) -> Result<Self> {
+        let invalid = || Error::Parse(format!("Invalid coding rule: {}", line));
+
+        let (head, body) = line.split_once('=').ok_or_else(invalid)?;
+        let head = head.trim();
+        let (or_longer, length) = if let Some(n) = head.strip_prefix("code_e") {
+            (false, n)
+        } else if let Some(n) = head.strip_prefix("code_a") {
+            (true, n)
+        } else {
+            return Err(invalid());
+        };
+        let length: usize = length.parse().map_err(|_| invalid())?;
+
+        let mut parts = Vec::new();
+        for part in body.split('+') {
+            let bytes = part.trim().as_bytes();
+            if bytes.len() != 3 {
+                return Err(invalid());
+            }
+            let index = (bytes[1] as char).to_digit(10).ok_or_else(invalid)? as usize;
+            let char_ref = match bytes[0] {
+                b'p' => CharRef::FromStart(index),
+                b'n' => CharRef::FromEnd(index),
+                _ => return Err(invalid()),
+            };
+            let code_ref = match bytes[2] {
+                b'e' => CodeRef::Last,
+                b => CodeRef::Index((b as char).to_digit(10).ok_or_else(invalid)? as usize),
+            };
+            parts.push((char_ref, code_ref));
+        }
+
+        Ok(CodingRule {
+            length,
+            or_longer,
+            parts,
+        })
+    }
+
+    fn matches(&self, len: usize) -> bool {
+        len == self.length || (self.or_longer && len > self.length)
+    }
+
+    /// Index of the character a part refers to in a word of `len` characters
+    fn resolve(char_ref: CharRef, len: usize) -> Option<usize> {
+        match char_ref {
+            CharRef::FromStart(i) => i.checked_sub(1).filter(|&i| i < len),
+            CharRef::FromEnd(i) => len.checked_sub(i),
+        }
+    }
+
+    /// Whether the rule reads character `index` of a word of `len` characters
+    fn uses_char(&self, index: usize, len: usize) -> bool {
+        self.parts
+            .iter()
+            .any(|&(char_ref, _)| Self::resolve(char_ref, len) == Some(index))
+    }
+
+    fn apply(&self, char_codes: &[&str]) -> String {
+        let mut result = String::new();
+        for &(char_ref, code_ref) in &self.parts {
+            let index = Self::resolve(char_ref, char_codes.len());
+            let Some(code) = index.and_then(|i| char_codes.get(i)) else {
+                continue;
+            };
+            let letter = match code_ref {
+                CodeRef::Index(i) => i.checked_sub(1).and_then(|i| code.chars().nth(i)),
+                CodeRef::Last => code.chars().last(),
+            };
+            if let Some(letter) = letter {
+                result.push(letter);
+            }
+        }
+        result
+    }
+}
+
+/// Generator driven by a user mapping table
+pub struct SelfDefinedGenerator {
+    mapping: HashMap<char, Vec<String>>,
+    rules: Vec<CodingRule>,
+    /// Letters taken from each character when no rule matches (None = all)
+    letters_per_char: Option<usize>,
+    /// Maximum length of a word code (None = unlimited)
+    max_code_length: Option<usize>,
+}
+
+impl SelfDefinedGenerator {
+    /// Create from mapping file content (`char\tcode` per line)
+    pub fn from_mapping(content: &str) -> Result<Self> {
+        Ok(SelfDefinedGenerator {
+            mapping: ResourceManager::load_simple_dict(content)?,
+            rules: Vec::new(),
+            letters_per_char: None,
+            max_code_length: None,
+        })
+    }
+
+    /// Load a mapping file with the given text encoding
+    pub fn from_file(path: &str, encoding: &str) -> Result<Self> {
+        let content = read_file_with_encoding_str(path, encoding)?;
+        Self::from_mapping(&content)
+    }
+
+    /// Set word formation rules, one `code_e{n}=...` / `code_a{n}=...` per line
+    pub fn with_rules(mut self, rules: &str) -> Result<Self> {
+        self.rules = rules
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(CodingRule::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self)
+    }
+
+    /// Take only the first `n` letters of each character when no rule matches
+    pub fn with_letters_per_char(mut self, n: usize) -> Self {
+        self.letters_per_char = Some(n);
+        self
+    }
+
+    /// Cap the total length of a word code
+    pub fn with_max_code_length(mut self, n: usize) -> Self {
+        self.max_code_length = Some(n);
+        self
+    }
+
+    /// Compose a word code from the codes of its characters
+    fn compose(&self, char_codes: &[&str]) -> String {
+        let mut code = if char_codes.len() == 1 {
+            char_codes[0].to_string()
+        } else if let Some(rule) = self.rules.iter().find(|r| r.matches(char_codes.len())) {
+            rule.apply(char_codes)
+        } else {
+            char_codes
+                .iter()
+                .map(|c| match self.letters_per_char {
+                    Some(n) => c.chars().take(n).collect(),
+                    None => c.to_string(),
+                })
+                .collect()
+        };
+
+        if let Some(max) = self.max_code_length {
+            if let Some((pos, _)) = code.char_indices().nth(max) {
+                code.truncate(pos);
+            }
+        }
+        code
+    }
+}
+
+impl CodeGenerator for SelfDefinedGenerator {
+    fn generate_code(&self, word: &mut WordLibrary) -> Result<()> {
+        let code = self.generate_code_for_string(&word.word)?;
+        word.code_type = CodeType::UserDefine;
+        word.codes = code;
+        Ok(())
+    }
+
+    fn generate_code_for_string(&self, text: &str) -> Result<Code> {
+        let chars: Vec<char> = text.chars().collect();
+        let rule = match chars.len() {
+            1 => None,
+            n => self.rules.iter().find(|r| r.matches(n)),
+        };
+
+        // Only the characters the rule reads are looked up, the others stand
+        // in as empty codes
+        let mut per_char = Vec::with_capacity(chars.len());
+        for (index, &c) in chars.iter().enumerate() {
+            match rule {
+                Some(rule) if !rule.uses_char(index, chars.len()) => {
+                    per_char.push(vec![String::new()])
+                }
+                _ => per_char.push(self.get_codes_for_char(c)?),
+            }
+        }
+        let mut combinations = 1usize;
+        for codes in &mut per_char {
+            if combinations.saturating_mul(codes.len()) > MAX_COMBINATIONS {
+                codes.truncate(1);
+            }
+            combinations *= codes.len();
+        }
+
+        let mut result: Vec<String> = Vec::new();
+        for combo in code_combinations(&per_char) {
+            let code = self.compose(&combo);
+            if !code.is_empty() && !result.contains(&code) {
+                result.push(code);
+            }
+        }
+
+        if result.is_empty() {
+            return Err(Error::CharacterNotFound(text.chars().next().unwrap_or('?')));
+        }
+        Ok(Code::from_multiple(result))
+    }
+
+    fn get_codes_for_char(&self, c: char) -> Result<Vec<String>> {
+        self.mapping
+            .get(&c)
+            .filter(|codes| !codes.is_empty())
+            .cloned()
+            .ok_or(Error::CharacterNotFound(c))
+    }
+
+    fn is_multi_code_per_char(&self) -> bool {
+        false
+    }
+
+    fn is_one_code_per_char(&self) -> bool {
+        false // One code per word
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::UserDefine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPING: &str = "中\tzhong\n华\thua\n人\tren\n民\tmin\n国\tguo\n";
+
+    #[test]
+    fn test_parse_rule() {
+        let rule = CodingRule::parse("code_a4=p11+p21+p31+n1e").unwrap();
+        assert!(rule.or_longer);
+        assert_eq!(rule.length, 4);
+        assert_eq!(rule.parts[3], (CharRef::FromEnd(1), CodeRef::Last));
+
+        assert!(CodingRule::parse("code_x2=p11").is_err());
+        assert!(CodingRule::parse("code_e2=q11").is_err());
+    }
+
+    #[test]
+    fn test_rules() {
+        let generator = SelfDefinedGenerator::from_mapping(MAPPING)
+            .unwrap()
+            .with_rules("code_e2=p11+p12+p21+p22\ncode_a3=p11+p21+p31+n1e")
+            .unwrap();
+
+        let two = generator.generate_code_for_string("中国").unwrap();
+        assert_eq!(two.get_single_code(), Some("zhgu"));
+
+        let five = generator.generate_code_for_string("中华人民国").unwrap();
+        assert_eq!(five.get_single_code(), Some("zhro"));
+
+        // The unmapped 4th character is not read by the rule
+        let skipped = generator.generate_code_for_string("中华人你国").unwrap();
+        assert_eq!(skipped.get_single_code(), Some("zhro"));
+    }
+
+    #[test]
+    fn test_combination_limit() {
+        let generator = SelfDefinedGenerator::from_mapping("中\ta\n中\tb\n").unwrap();
+        let code = generator
+            .generate_code_for_string(&"中".repeat(40))
+            .unwrap();
+        assert_eq!(code.0[0].len(), MAX_COMBINATIONS);
+    }
+
+    #[test]
+    fn test_letters_and_max_length() {
+        let generator = SelfDefinedGenerator::from_mapping(MAPPING)
+            .unwrap()
+            .with_letters_per_char(1)
+            .with_max_code_length(3);
+
+        let code = generator.generate_code_for_string("中华人民").unwrap();
+        assert_eq!(code.get_single_code(), Some("zhr"));
+
+        let single = generator.generate_code_for_string("中").unwrap();
+        assert_eq!(single.get_single_code(), Some("zho"));
+    }
+
+    #[test]
+    fn test_missing_char() {
+        let generator = SelfDefinedGenerator::from_mapping(MAPPING).unwrap();
+        assert!(generator.generate_code_for_string("你").is_err());
+    }
+}
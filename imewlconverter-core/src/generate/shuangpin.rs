@@ -0,0 +1,212 @@
+//! Shuangpin (double pinyin) code generator
+//!
+//! Converts full pinyin into two-key shuangpin codes using the embedded
+//! Shuangpin.txt mapping table
+
+use crate::generate::{CodeGenerator, PinyinGenerator};
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::sync::Arc;
+
+/// Shuangpin keyboard scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ShuangpinScheme {
+    /// 小鹤双拼
+    #[default]
+    Xiaohe,
+    /// 自然码
+    Ziranma,
+    /// 微软双拼
+    Microsoft,
+    /// 智能ABC
+    ZhinengAbc,
+    /// 紫光拼音
+    Ziguang,
+    /// 拼音加加
+    PinyinJiajia,
+    /// 星空键道
+    Xingkong,
+    /// 大牛双拼
+    Daniu,
+    /// 小浪双拼
+    Xiaolang,
+}
+
+impl ShuangpinScheme {
+    /// Column index of this scheme in Shuangpin.txt (excluding the full pinyin column)
+    fn column(&self) -> usize {
+        match self {
+            ShuangpinScheme::Xiaohe => 0,
+            ShuangpinScheme::Ziranma => 1,
+            ShuangpinScheme::Microsoft => 2,
+            ShuangpinScheme::ZhinengAbc => 3,
+            ShuangpinScheme::Ziguang => 4,
+            ShuangpinScheme::PinyinJiajia => 5,
+            ShuangpinScheme::Xingkong => 6,
+            ShuangpinScheme::Daniu => 7,
+            ShuangpinScheme::Xiaolang => 8,
+        }
+    }
+}
+
+/// Shuangpin generator
+pub struct ShuangpinGenerator {
+    /// Resource manager with all dictionaries
+    resources: Arc<ResourceManager>,
+    /// Generator used to obtain full pinyin first
+    pinyin: PinyinGenerator,
+    /// Keyboard scheme
+    scheme: ShuangpinScheme,
+}
+
+impl ShuangpinGenerator {
+    pub fn new(scheme: ShuangpinScheme) -> Result<Self> {
+        Ok(Self::with_resources(ResourceManager::shared(), scheme))
+    }
+
+    /// Initialize with existing resource manager (for sharing)
+    pub fn with_resources(resources: Arc<ResourceManager>, scheme: ShuangpinScheme) -> Self {
+        Self {
+            pinyin: PinyinGenerator::with_resources(resources.clone()),
+            resources,
+            scheme,
+        }
+    }
+
+    /// Get the keyboard scheme
+    pub fn scheme(&self) -> ShuangpinScheme {
+        self.scheme
+    }
+
+    /// Convert a single full pinyin syllable (tone digits allowed) to shuangpin
+    pub fn convert_syllable(&self, pinyin: &str) -> Result<String> {
+        let syllable = pinyin
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_lowercase()
+            .replace('ü', "v");
+
+        if let Some(code) = self
+            .resources
+            .get_shuangpin(&syllable)
+            .and_then(|codes| codes.get(self.scheme.column()))
+        {
+            return Ok(code.clone());
+        }
+
+        // Single ASCII letters (e.g. from English words) are kept as-is
+        if syllable.len() == 1 && syllable.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Ok(syllable);
+        }
+
+        Err(Error::Parse(format!("Unknown pinyin syllable: {}", pinyin)))
+    }
+
+    /// Convert a pinyin code to shuangpin, keeping its per-character structure
+    fn convert_code(&self, code: &Code) -> Result<Code> {
+        let mut result = Vec::with_capacity(code.len());
+        for codes in &code.0 {
+            let mut converted: Vec<String> = Vec::with_capacity(codes.len());
+            for py in codes {
+                let sp = self.convert_syllable(py)?;
+                if !converted.contains(&sp) {
+                    converted.push(sp);
+                }
+            }
+            result.push(converted);
+        }
+        Ok(Code::from_chars(result))
+    }
+}
+
+impl CodeGenerator for ShuangpinGenerator {
+    fn generate_code(&self, word: &mut WordLibrary) -> Result<()> {
+        let code = if word.code_type == CodeType::Pinyin && !word.codes.is_empty() {
+            // Reuse the imported pinyin instead of regenerating it
+            self.convert_code(&word.codes)?
+        } else {
+            self.generate_code_for_string(&word.word)?
+        };
+
+        word.code_type = CodeType::Shuangpin;
+        word.codes = code;
+        Ok(())
+    }
+
+    fn generate_code_for_string(&self, text: &str) -> Result<Code> {
+        let pinyin = self.pinyin.generate_code_for_string(text)?;
+        self.convert_code(&pinyin)
+    }
+
+    fn get_codes_for_char(&self, c: char) -> Result<Vec<String>> {
+        let mut result: Vec<String> = Vec::new();
+        for py in self.pinyin.get_codes_for_char(c)? {
+            let sp = self.convert_syllable(&py)?;
+            if !result.contains(&sp) {
+                result.push(sp);
+            }
+        }
+        Ok(result)
+    }
+
+    fn is_multi_code_per_char(&self) -> bool {
+        true // Polyphonic characters yield multiple shuangpin codes
+    }
+
+    fn is_one_code_per_char(&self) -> bool {
+        true
+    }
+
+    fn code_type(&self) -> CodeType {
+        CodeType::Shuangpin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_syllable() {
+        let generator = ShuangpinGenerator::new(ShuangpinScheme::Xiaohe).unwrap();
+        assert_eq!(generator.convert_syllable("hao3").unwrap(), "hc");
+        assert_eq!(generator.convert_syllable("zhuang").unwrap(), "vl");
+        assert_eq!(generator.convert_syllable("ang").unwrap(), "ah");
+        assert!(generator.convert_syllable("xyz").is_err());
+    }
+
+    #[test]
+    fn test_schemes() {
+        let resources = ResourceManager::shared();
+        let ziranma =
+            ShuangpinGenerator::with_resources(resources.clone(), ShuangpinScheme::Ziranma);
+        let ms = ShuangpinGenerator::with_resources(resources, ShuangpinScheme::Microsoft);
+
+        assert_eq!(ziranma.convert_syllable("shuang").unwrap(), "ud");
+        assert_eq!(ms.convert_syllable("a").unwrap(), "oa");
+    }
+
+    #[test]
+    fn test_generate_from_pinyin_code() {
+        let generator = ShuangpinGenerator::new(ShuangpinScheme::Xiaohe).unwrap();
+
+        let mut word = WordLibrary::new("你好".to_string());
+        word.set_code(
+            CodeType::Pinyin,
+            Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]),
+        );
+        generator.generate_code(&mut word).unwrap();
+
+        assert_eq!(word.code_type, CodeType::Shuangpin);
+        assert_eq!(word.codes.to_string_with_separator(" "), "ni hc");
+    }
+
+    #[test]
+    fn test_generate_from_word() {
+        let generator = ShuangpinGenerator::new(ShuangpinScheme::Xiaohe).unwrap();
+
+        let mut word = WordLibrary::new("中国".to_string());
+        generator.generate_code(&mut word).unwrap();
+
+        assert_eq!(word.codes.to_string_with_separator(""), "vsgo");
+    }
+}
@@ -0,0 +1,230 @@
+//! Wubi code generator
+//!
+//! Generates Wubi 86/98/New Age codes from the per-character codes in ChineseCode.txt,
+//! using the standard Wubi word-coding rules:
+//! - 1 char: the full character code
+//! - 2 chars: first 2 codes of each character
+//! - 3 chars: 1st code of the 1st and 2nd characters, first 2 codes of the 3rd
+//! - 4+ chars: 1st code of the 1st, 2nd, 3rd and last characters
+
+use crate::generate::{code_combinations, CodeGenerator};
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::sync::Arc;
+
+/// Wubi generator
+pub struct WubiGenerator {
+    /// Resource manager with all dictionaries
+    resources: Arc<ResourceManager>,
+    /// Wubi, Wubi98 or WubiNewAge
+    code_type: CodeType,
+}
+
+impl WubiGenerator {
+    /// Create a Wubi 86 generator
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_resources(ResourceManager::shared()))
+    }
+
+    /// Initialize with existing resource manager (for sharing)
+    pub fn with_resources(resources: Arc<ResourceManager>) -> Self {
+        Self {
+            resources,
+            code_type: CodeType::Wubi,
+        }
+    }
+
+    /// Select the Wubi version (Wubi, Wubi98 or WubiNewAge)
+    pub fn with_code_type(mut self, code_type: CodeType) -> Result<Self> {
+        match code_type {
+            CodeType::Wubi | CodeType::Wubi98 | CodeType::WubiNewAge => {
+                self.code_type = code_type;
+                Ok(self)
+            }
+            _ => Err(Error::InvalidCodeType),
+        }
+    }
+
+    /// Parts of the full character codes making up the word code
+    ///
+    /// Words of four or more characters only use the first three and the
+    /// last character.
+    fn rule_parts<'a>(char_codes: &[&'a str]) -> Vec<&'a str> {
+        fn take(code: &str, n: usize) -> &str {
+            code.get(..n).unwrap_or(code)
+        }
+
+        match char_codes.len() {
+            0 => vec![],
+            1 => vec![char_codes[0]],
+            2 => vec![take(char_codes[0], 2), take(char_codes[1], 2)],
+            3 => vec![
+                take(char_codes[0], 1),
+                take(char_codes[1], 1),
+                take(char_codes[2], 2),
+            ],
+            n => vec![
+                take(char_codes[0], 1),
+                take(char_codes[1], 1),
+                take(char_codes[2], 1),
+                take(char_codes[n - 1], 1),
+            ],
+        }
+    }
+
+    /// Compose a word code from the full codes of its characters
+    fn compose(char_codes: &[&str]) -> String {
+        Self::rule_parts(char_codes).concat()
+    }
+
+    /// Full codes of the characters taking part in the word rule
+    fn rule_char_codes(&self, text: &str) -> Result<Vec<Vec<String>>> {
+        let chars: Vec<char> = text.chars().collect();
+        let used: Vec<char> = if chars.len() > 4 {
+            vec![chars[0], chars[1], chars[2], chars[chars.len() - 1]]
+        } else {
+            chars
+        };
+        used.into_iter()
+            .map(|c| self.get_codes_for_char(c))
+            .collect()
+    }
+
+    /// Split a continuous word code like `wqvb` into per-character parts
+    /// (`wq`, `vb`) following the word rule
+    ///
+    /// Returns None when the code cannot be produced from the characters'
+    /// codes. For words of four or more characters the parts belong to the
+    /// first three and the last character.
+    pub fn split_code(&self, word: &str, code: &str) -> Option<Code> {
+        let per_char = self.rule_char_codes(word).ok()?;
+        code_combinations(&per_char)
+            .iter()
+            .find(|combo| Self::compose(combo) == code)
+            .map(|combo| {
+                let parts = Self::rule_parts(combo);
+                Code::from_char_list(parts.into_iter().map(String::from).collect())
+            })
+    }
+}
+
+impl Default for WubiGenerator {
+    fn default() -> Self {
+        Self::new().expect("Failed to load wubi resources")
+    }
+}
+
+impl CodeGenerator for WubiGenerator {
+    fn generate_code(&self, word: &mut WordLibrary) -> Result<()> {
+        // If already has codes of this type, skip
+        if word.code_type == self.code_type && !word.codes.is_empty() {
+            return Ok(());
+        }
+
+        let code = self.generate_code_for_string(&word.word)?;
+        word.code_type = self.code_type;
+        word.codes = code;
+        Ok(())
+    }
+
+    fn generate_code_for_string(&self, text: &str) -> Result<Code> {
+        // Only the characters taking part in the word rule need to be looked up
+        let per_char = self.rule_char_codes(text)?;
+
+        // Combine every code variant of multi-code characters
+        let mut result: Vec<String> = Vec::new();
+        for combo in code_combinations(&per_char) {
+            let code = Self::compose(&combo);
+            if !code.is_empty() && !result.contains(&code) {
+                result.push(code);
+            }
+        }
+
+        if result.is_empty() {
+            return Err(Error::CharacterNotFound(text.chars().next().unwrap_or('?')));
+        }
+        Ok(Code::from_multiple(result))
+    }
+
+    fn get_codes_for_char(&self, c: char) -> Result<Vec<String>> {
+        self.resources
+            .get_char_codes(c, &self.code_type)
+            .filter(|codes| !codes.is_empty())
+            .ok_or(Error::CharacterNotFound(c))
+    }
+
+    fn is_multi_code_per_char(&self) -> bool {
+        false
+    }
+
+    fn is_one_code_per_char(&self) -> bool {
+        false // One code per word
+    }
+
+    fn code_type(&self) -> CodeType {
+        self.code_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_char() {
+        let generator = WubiGenerator::new().unwrap();
+        let code = generator.generate_code_for_string("你").unwrap();
+        assert_eq!(code.get_single_code(), Some("wqiy"));
+    }
+
+    #[test]
+    fn test_word_rules() {
+        let generator = WubiGenerator::new().unwrap();
+
+        let two = generator.generate_code_for_string("你好").unwrap();
+        assert_eq!(two.get_single_code(), Some("wqvb"));
+
+        let three = generator.generate_code_for_string("中国人").unwrap();
+        assert_eq!(three.get_single_code(), Some("klww"));
+
+        let four = generator
+            .generate_code_for_string("中华人民共和国")
+            .unwrap();
+        assert_eq!(four.get_single_code(), Some("kwwl"));
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let generator = WubiGenerator::new()
+            .unwrap()
+            .with_code_type(CodeType::Wubi98)
+            .unwrap();
+
+        let mut word = WordLibrary::new("中国人民".to_string());
+        generator.generate_code(&mut word).unwrap();
+
+        assert_eq!(word.code_type, CodeType::Wubi98);
+        assert_eq!(word.get_single_code(), Some("klwn"));
+    }
+
+    #[test]
+    fn test_split_code() {
+        let generator = WubiGenerator::new().unwrap();
+        let split = |word: &str, code: &str| {
+            generator
+                .split_code(word, code)
+                .map(|c| c.to_string_with_separator(" "))
+        };
+
+        assert_eq!(split("你好", "wqvb").as_deref(), Some("wq vb"));
+        assert_eq!(split("中国人", "klww").as_deref(), Some("k l ww"));
+        assert_eq!(split("中华人民共和国", "kwwl").as_deref(), Some("k w w l"));
+        assert_eq!(split("你好", "wqvv"), None);
+    }
+
+    #[test]
+    fn test_invalid_code_type() {
+        let generator = WubiGenerator::new().unwrap();
+        assert!(generator.with_code_type(CodeType::Pinyin).is_err());
+    }
+}
@@ -3,29 +3,129 @@
 pub mod pinyin;
 
 use crate::Result;
+use encoding_rs::Encoding;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
-/// Write string to file with encoding
-pub fn write_file(path: &Path, content: &str, encoding: &str) -> Result<()> {
-    use encoding_rs::Encoding;
-
-    let encoding = if encoding == "utf-8" {
-        encoding_rs::UTF_8
-    } else if encoding == "gbk" {
-        encoding_rs::GBK
-    } else if encoding == "utf-16le" {
-        encoding_rs::UTF_16LE
-    } else if encoding == "big5" {
-        encoding_rs::BIG5
+/// Look up a text encoding by name (e.g. "utf-8", "gbk", "utf-16le"), defaulting to UTF-8
+pub fn encoding_for_label(name: &str) -> &'static Encoding {
+    Encoding::for_label(name.as_bytes()).unwrap_or(encoding_rs::UTF_8)
+}
+
+/// Label selecting automatic encoding detection
+pub const AUTO_ENCODING: &str = "auto";
+
+/// Check whether a name is `"auto"` or a known encoding label
+pub fn is_encoding_label(name: &str) -> bool {
+    name.eq_ignore_ascii_case(AUTO_ENCODING) || Encoding::for_label(name.as_bytes()).is_some()
+}
+
+/// Number of leading bytes inspected by `detect_encoding`
+const DETECT_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Detect the encoding of text data
+///
+/// A byte order mark wins. Otherwise UTF-16 is recognized by the share of
+/// code units that are ASCII or CJK ideographs, valid UTF-8 is taken as
+/// UTF-8 and anything else is assumed to be GBK.
+pub fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+
+    let sample = &bytes[..bytes.len().min(DETECT_SAMPLE_SIZE)];
+    if let Some(encoding) = detect_utf16(sample) {
+        return encoding;
+    }
+
+    match std::str::from_utf8(sample) {
+        Ok(_) => encoding_rs::UTF_8,
+        // A multi-byte sequence cut off at the end of the sample is still UTF-8
+        Err(e) if e.error_len().is_none() && sample.len() < bytes.len() => encoding_rs::UTF_8,
+        Err(_) => encoding_rs::GBK,
+    }
+}
+
+/// Guess UTF-16 without a BOM, returning the more plausible byte order
+fn detect_utf16(sample: &[u8]) -> Option<&'static Encoding> {
+    let units = sample.len() / 2;
+    if units < 2 {
+        return None;
+    }
+
+    let plausible = |unit: u16| matches!(unit, 0x09 | 0x0A | 0x0D | 0x20..=0x7E | 0x4E00..=0x9FFF);
+    let score = |to_unit: fn([u8; 2]) -> u16| {
+        sample
+            .chunks_exact(2)
+            .filter(|pair| plausible(to_unit([pair[0], pair[1]])))
+            .count()
+    };
+
+    let le = score(u16::from_le_bytes);
+    let be = score(u16::from_be_bytes);
+    let (best, encoding) = if le >= be {
+        (le, encoding_rs::UTF_16LE)
+    } else {
+        (be, encoding_rs::UTF_16BE)
+    };
+
+    // Single-byte and UTF-8 text rarely forms valid units this consistently
+    (best * 10 >= units * 9).then_some(encoding)
+}
+
+/// Decode text with the named encoding, or detect it for `"auto"`
+///
+/// A byte order mark overrides the named encoding. If the named encoding
+/// does not fit the data but the detected one does, the detected one is used
+/// instead of returning mojibake.
+pub fn decode_text(bytes: &[u8], encoding_name: &str) -> String {
+    let encoding = if encoding_name.eq_ignore_ascii_case(AUTO_ENCODING) {
+        detect_encoding(bytes)
     } else {
-        Encoding::for_label(encoding.as_bytes()).unwrap_or(encoding_rs::UTF_8)
+        encoding_for_label(encoding_name)
     };
 
-    let (encoded, _, _) = encoding.encode(content);
+    let (result, _, had_errors) = encoding.decode(bytes);
+    if !had_errors {
+        return result.into_owned();
+    }
+
+    let detected = detect_encoding(bytes);
+    if detected != encoding {
+        let (fallback, _, fallback_errors) = detected.decode(bytes);
+        if !fallback_errors {
+            tracing::warn!(
+                "file is not valid {}, decoding as {}",
+                encoding.name(),
+                detected.name()
+            );
+            return fallback.into_owned();
+        }
+    }
+
+    tracing::warn!("encoding errors detected when reading file");
+    result.into_owned()
+}
+
+/// Encode text with the named encoding
+///
+/// encoding_rs can only decode UTF-16, so UTF-16 output is produced here.
+pub fn encode_text(content: &str, encoding: &str) -> Vec<u8> {
+    let encoding = encoding_for_label(encoding);
+    if encoding == encoding_rs::UTF_16LE {
+        content.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    } else if encoding == encoding_rs::UTF_16BE {
+        content.encode_utf16().flat_map(u16::to_be_bytes).collect()
+    } else {
+        encoding.encode(content).0.into_owned()
+    }
+}
+
+/// Write string to file with encoding
+pub fn write_file(path: &Path, content: &str, encoding: &str) -> Result<()> {
     let mut file = File::create(path)?;
-    file.write_all(&encoded)?;
+    file.write_all(&encode_text(content, encoding))?;
 
     Ok(())
 }
@@ -36,4 +136,46 @@ mod tests {
 
     #[test]
     fn test_helper_module_exists() {}
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(encode_text("你", "utf-8"), vec![0xE4, 0xBD, 0xA0]);
+        assert_eq!(encode_text("你", "gbk"), vec![0xC4, 0xE3]);
+        assert_eq!(encode_text("你a", "utf-16le"), vec![0x60, 0x4F, 0x61, 0x00]);
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        let text = "你好\tni hao\t1\n中国\tzhong guo\t2\n";
+
+        assert_eq!(
+            detect_encoding(&encode_text(text, "utf-8")),
+            encoding_rs::UTF_8
+        );
+        assert_eq!(detect_encoding(&encode_text(text, "gbk")), encoding_rs::GBK);
+        assert_eq!(
+            detect_encoding(&encode_text(text, "utf-16le")),
+            encoding_rs::UTF_16LE
+        );
+        assert_eq!(
+            detect_encoding(&encode_text(text, "utf-16be")),
+            encoding_rs::UTF_16BE
+        );
+
+        let mut with_bom = vec![0xEF, 0xBB, 0xBF];
+        with_bom.extend(encode_text(text, "utf-8"));
+        assert_eq!(detect_encoding(&with_bom), encoding_rs::UTF_8);
+    }
+
+    #[test]
+    fn test_decode_text() {
+        let gbk = encode_text("你好\tni hao", "gbk");
+        assert_eq!(decode_text(&gbk, "auto"), "你好\tni hao");
+        // A GBK file read as UTF-8 falls back to the detected encoding
+        assert_eq!(decode_text(&gbk, "utf-8"), "你好\tni hao");
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend(encode_text("你好", "utf-16le"));
+        assert_eq!(decode_text(&utf16, "gbk"), "你好");
+    }
 }
@@ -2,6 +2,203 @@
 
 use std::collections::HashMap;
 
+/// How tone-marked pinyin (nǐ hǎo) is converted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneStyle {
+    /// Drop the tones: `ni hao`
+    Plain,
+    /// Append the tone number: `ni3 hao3`
+    Numbered,
+}
+
+/// Base letter and tone of a tone-marked letter; ü becomes `v`
+fn tone_mark(c: char) -> Option<(char, u8)> {
+    const MARKED: [(&str, char); 6] = [
+        ("āáǎà", 'a'),
+        ("ēéěè", 'e'),
+        ("īíǐì", 'i'),
+        ("ōóǒò", 'o'),
+        ("ūúǔù", 'u'),
+        ("ǖǘǚǜ", 'v'),
+    ];
+    // Syllabic n and m, as in ń (嗯) and ḿ (呣)
+    match c {
+        'ü' => return Some(('v', 0)),
+        'ń' => return Some(('n', 2)),
+        'ň' => return Some(('n', 3)),
+        'ǹ' => return Some(('n', 4)),
+        'ḿ' => return Some(('m', 2)),
+        _ => {}
+    }
+    MARKED.iter().find_map(|(marked, base)| {
+        marked
+            .chars()
+            .position(|m| m == c)
+            .map(|i| (*base, i as u8 + 1))
+    })
+}
+
+/// Convert a tone-marked syllable (`hǎo`) to plain (`hao`) or numbered
+/// (`hao3`) pinyin
+///
+/// Syllables without tone marks are returned unchanged.
+pub fn convert_tone_marks(syllable: &str, style: ToneStyle) -> String {
+    let mut result = String::with_capacity(syllable.len() + 1);
+    let mut tone = 0;
+    for c in syllable.chars() {
+        match tone_mark(c) {
+            Some((base, t)) => {
+                result.push(base);
+                tone = tone.max(t);
+            }
+            None => result.push(c),
+        }
+    }
+    if style == ToneStyle::Numbered && tone > 0 {
+        result.push(char::from(b'0' + tone));
+    }
+    result
+}
+
+/// Legal pinyin syllables without tones; ü is written `v`
+const SYLLABLES: &[&str] = &[
+    "a", "ai", "an", "ang", "ao", "ba", "bai", "ban", "bang", "bao", "bei", "ben", "beng", "bi",
+    "bian", "biao", "bie", "bin", "bing", "bo", "bu", "ca", "cai", "can", "cang", "cao", "ce",
+    "cen", "ceng", "cha", "chai", "chan", "chang", "chao", "che", "chen", "cheng", "chi", "chong",
+    "chou", "chu", "chua", "chuai", "chuan", "chuang", "chui", "chun", "chuo", "ci", "cong", "cou",
+    "cu", "cuan", "cui", "cun", "cuo", "da", "dai", "dan", "dang", "dao", "de", "dei", "den",
+    "deng", "di", "dia", "dian", "diao", "die", "ding", "diu", "dong", "dou", "du", "duan", "dui",
+    "dun", "duo", "e", "ei", "en", "eng", "er", "fa", "fan", "fang", "fei", "fen", "feng", "fo",
+    "fou", "fu", "ga", "gai", "gan", "gang", "gao", "ge", "gei", "gen", "geng", "gong", "gou",
+    "gu", "gua", "guai", "guan", "guang", "gui", "gun", "guo", "ha", "hai", "han", "hang", "hao",
+    "he", "hei", "hen", "heng", "hong", "hou", "hu", "hua", "huai", "huan", "huang", "hui", "hun",
+    "huo", "ji", "jia", "jian", "jiang", "jiao", "jie", "jin", "jing", "jiong", "jiu", "ju",
+    "juan", "jue", "jun", "ka", "kai", "kan", "kang", "kao", "ke", "kei", "ken", "keng", "kong",
+    "kou", "ku", "kua", "kuai", "kuan", "kuang", "kui", "kun", "kuo", "la", "lai", "lan", "lang",
+    "lao", "le", "lei", "leng", "li", "lia", "lian", "liang", "liao", "lie", "lin", "ling", "liu",
+    "lo", "long", "lou", "lu", "luan", "lue", "lun", "luo", "lv", "lve", "ma", "mai", "man",
+    "mang", "mao", "me", "mei", "men", "meng", "mi", "mian", "miao", "mie", "min", "ming", "miu",
+    "mo", "mou", "mu", "na", "nai", "nan", "nang", "nao", "ne", "nei", "nen", "neng", "ni", "nian",
+    "niang", "niao", "nie", "nin", "ning", "niu", "nong", "nou", "nu", "nuan", "nue", "nun", "nuo",
+    "nv", "nve", "o", "ou", "pa", "pai", "pan", "pang", "pao", "pei", "pen", "peng", "pi", "pian",
+    "piao", "pie", "pin", "ping", "po", "pou", "pu", "qi", "qia", "qian", "qiang", "qiao", "qie",
+    "qin", "qing", "qiong", "qiu", "qu", "quan", "que", "qun", "ran", "rang", "rao", "re", "ren",
+    "reng", "ri", "rong", "rou", "ru", "rua", "ruan", "rui", "run", "ruo", "sa", "sai", "san",
+    "sang", "sao", "se", "sen", "seng", "sha", "shai", "shan", "shang", "shao", "she", "shei",
+    "shen", "sheng", "shi", "shou", "shu", "shua", "shuai", "shuan", "shuang", "shui", "shun",
+    "shuo", "si", "song", "sou", "su", "suan", "sui", "sun", "suo", "ta", "tai", "tan", "tang",
+    "tao", "te", "tei", "teng", "ti", "tian", "tiao", "tie", "ting", "tong", "tou", "tu", "tuan",
+    "tui", "tun", "tuo", "wa", "wai", "wan", "wang", "wei", "wen", "weng", "wo", "wu", "xi", "xia",
+    "xian", "xiang", "xiao", "xie", "xin", "xing", "xiong", "xiu", "xu", "xuan", "xue", "xun",
+    "ya", "yan", "yang", "yao", "ye", "yi", "yin", "ying", "yo", "yong", "you", "yu", "yuan",
+    "yue", "yun", "za", "zai", "zan", "zang", "zao", "ze", "zei", "zen", "zeng", "zha", "zhai",
+    "zhan", "zhang", "zhao", "zhe", "zhei", "zhen", "zheng", "zhi", "zhong", "zhou", "zhu", "zhua",
+    "zhuai", "zhuan", "zhuang", "zhui", "zhun", "zhuo", "zi", "zong", "zou", "zu", "zuan", "zui",
+    "zun", "zuo",
+];
+
+/// Longest legal syllable (`chuang`)
+const MAX_SYLLABLE_LEN: usize = 6;
+
+/// Whether `s` is a legal pinyin syllable, ignoring case and a trailing tone
+/// number
+pub fn is_pinyin_syllable(s: &str) -> bool {
+    let s = s
+        .trim_end_matches(|c: char| c.is_ascii_digit())
+        .to_ascii_lowercase();
+    SYLLABLES.binary_search(&s.as_str()).is_ok()
+}
+
+/// Split unseparated pinyin (`nihao`) into syllables (`ni hao`)
+///
+/// `'` and spaces are kept as syllable boundaries. Ambiguous strings prefer
+/// the fewest syllables, then the fewest syllables starting with a vowel, so
+/// `xian` stays one syllable and `fangan` splits as `fan gan`; write `xi'an`
+/// to get two. Returns None when the input is not all pinyin.
+pub fn split_pinyin(code: &str) -> Option<Vec<String>> {
+    segment(code, None)
+}
+
+/// Split unseparated pinyin into exactly `count` syllables, as when the
+/// number of characters is known (`xian` for 西安 gives `xi an`)
+pub fn split_pinyin_into(code: &str, count: usize) -> Option<Vec<String>> {
+    segment(code, Some(count))
+}
+
+fn segment(code: &str, count: Option<usize>) -> Option<Vec<String>> {
+    let code = code.to_ascii_lowercase();
+    if !code.is_ascii() {
+        return None;
+    }
+    // Letters, and the positions explicit separators put a boundary at
+    let mut letters = String::with_capacity(code.len());
+    let mut boundaries = vec![true];
+    for c in code.chars() {
+        if c == '\'' || c.is_whitespace() {
+            *boundaries.last_mut()? = true;
+        } else {
+            letters.push(c);
+            boundaries.push(false);
+        }
+    }
+    *boundaries.last_mut()? = true;
+    let n = letters.len();
+    if n == 0 {
+        return None;
+    }
+
+    // best[i][k]: fewest vowel-initial syllables and the start of the last
+    // syllable, splitting letters[..i] into k syllables
+    let mut best: Vec<Vec<Option<(usize, usize)>>> = vec![vec![None; n + 1]; n + 1];
+    best[0][0] = Some((0, 0));
+    for end in 1..=n {
+        for start in end.saturating_sub(MAX_SYLLABLE_LEN)..end {
+            if boundaries[start + 1..end].contains(&true) {
+                continue;
+            }
+            let syllable = &letters[start..end];
+            if SYLLABLES.binary_search(&syllable).is_err() {
+                continue;
+            }
+            let vowel = usize::from(start > 0 && syllable.starts_with(['a', 'e', 'o']));
+            for k in 0..end {
+                let Some((vowels, _)) = best[start][k] else {
+                    continue;
+                };
+                let cost = vowels + vowel;
+                if best[end][k + 1].map_or(true, |(v, _)| cost < v) {
+                    best[end][k + 1] = Some((cost, start));
+                }
+            }
+        }
+    }
+
+    let mut k = match count {
+        Some(count) => count,
+        None => (1..=n).find(|&k| best[n][k].is_some())?,
+    };
+    let mut syllables = Vec::with_capacity(k);
+    let mut end = n;
+    while end > 0 {
+        let (_, start) = (*best[end].get(k)?)?;
+        syllables.push(letters[start..end].to_string());
+        end = start;
+        k -= 1;
+    }
+    syllables.reverse();
+    Some(syllables)
+}
+
+/// Split a word's single unseparated pinyin code into one syllable per
+/// character; other codes are returned unchanged
+pub fn split_unseparated(word: &str, codes: Vec<String>) -> Vec<String> {
+    let chars = word.chars().count();
+    if codes.len() != 1 || chars < 2 {
+        return codes;
+    }
+    split_pinyin_into(&codes[0], chars).unwrap_or(codes)
+}
+
 /// Pinyin helper for character lookups
 pub struct PinyinHelper {
     pinyin_dict: HashMap<char, Vec<String>>,
@@ -68,9 +265,33 @@ impl Default for PinyinHelper {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_convert_tone_marks() {
+        assert_eq!(convert_tone_marks("nǐ", ToneStyle::Plain), "ni");
+        assert_eq!(convert_tone_marks("hǎo", ToneStyle::Numbered), "hao3");
+        assert_eq!(convert_tone_marks("lǜ", ToneStyle::Numbered), "lv4");
+        assert_eq!(convert_tone_marks("lüe", ToneStyle::Numbered), "lve");
+        assert_eq!(convert_tone_marks("ma", ToneStyle::Numbered), "ma");
+    }
+
+    #[test]
+    fn test_split_pinyin() {
+        let split = |s| split_pinyin(s).map(|v| v.join(" "));
+        assert_eq!(split("nihao").as_deref(), Some("ni hao"));
+        assert_eq!(split("xian").as_deref(), Some("xian"));
+        assert_eq!(split("xi'an").as_deref(), Some("xi an"));
+        assert_eq!(split("fangan").as_deref(), Some("fan gan"));
+        assert_eq!(split("ZhongGuoRen").as_deref(), Some("zhong guo ren"));
+        assert_eq!(split("nihaoq"), None);
+        assert_eq!(split_pinyin_into("xian", 2).unwrap(), vec!["xi", "an"]);
+        assert_eq!(split_pinyin_into("xi'an", 1), None);
+        assert!(is_pinyin_syllable("Lve4"));
+        assert!(SYLLABLES.windows(2).all(|w| w[0] < w[1]));
+    }
+
     #[test]
     fn test_pinyin_helper_creation() {
-        let helper = PinyinHelper::new();
+        let _helper = PinyinHelper::new();
         // This is a placeholder test since we haven't loaded the dictionary yet
     }
 }
@@ -1,109 +1,629 @@
 //! Import traits and implementations for various IME formats
 
-use crate::{Result, WordLibrary};
+use crate::helpers::pinyin::{convert_tone_marks, ToneStyle};
+use crate::helpers::{decode_text, detect_encoding, encoding_for_label, AUTO_ENCODING};
+use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+use encoding_rs::{CoderResult, Decoder};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::{Read, Write};
 
 // Import implementations
+pub mod apple_replacement;
+pub mod archive;
+#[cfg(feature = "tokio")]
+pub mod async_import;
 pub mod baidu_pinyin;
 pub mod chinese_pyim;
+pub mod corpus;
+pub mod english_phrase;
+pub mod english_wordlist;
 pub mod fit_input;
 pub mod google_pinyin;
+pub mod html;
+pub mod ibus_table;
+pub mod jidian;
 pub mod libpinyin;
 pub mod ms_pinyin;
+#[cfg(windows)]
+pub mod ms_pinyin_auto;
+pub mod ms_pinyin_lex;
+pub mod ms_wubi;
+pub mod ndjson;
 pub mod pinyin_jiajia;
 pub mod qq_pinyin;
 pub mod qq_wubi;
 pub mod rime;
+pub mod rime_userdb;
+pub mod salvage;
+pub mod shouxin;
 pub mod sina_pinyin;
+pub mod sogou_phrase;
 pub mod sogou_pinyin;
 pub mod sogou_scel;
+pub mod sogou_wubi;
 pub mod wubi;
+pub mod yong;
+pub mod zhangyu;
 pub mod ziguang_pinyin;
+pub mod ziguang_uwl;
 
 // Re-exports
+pub use apple_replacement::AppleReplacementImport;
+#[cfg(feature = "tokio")]
+pub use async_import::import_from_reader_async;
 pub use baidu_pinyin::BaiduPinyinImport;
 pub use chinese_pyim::ChinesePyimImport;
+pub use corpus::CorpusImport;
+pub use english_phrase::EnglishPhraseImport;
+pub use english_wordlist::EnglishWordListImport;
 pub use fit_input::FitInputImport;
 pub use google_pinyin::GooglePinyinImport;
+pub use html::HtmlImport;
+pub use ibus_table::IbusTableImport;
+pub use jidian::JidianImport;
 pub use libpinyin::LibpinyinImport;
 pub use ms_pinyin::MsPinyinImport;
+pub use ms_pinyin_lex::MsPinyinLexImport;
+pub use ms_wubi::MsWubiImport;
+pub use ndjson::NdjsonImport;
 pub use pinyin_jiajia::PinyinJiajiaImport;
 pub use qq_pinyin::QQPinyinImport;
 pub use qq_wubi::QQWubiImport;
 pub use rime::RimeImport;
+pub use rime_userdb::RimeUserdbImport;
+pub use shouxin::ShouxinImport;
 pub use sina_pinyin::SinaPinyinImport;
+pub use sogou_phrase::SogouPhraseImport;
 pub use sogou_pinyin::SogouPinyinImport;
 pub use sogou_scel::SogouScelImport;
+pub use sogou_wubi::SogouWubiImport;
 pub use wubi::{Wubi86Import, Wubi98Import, WubiNewAgeImport};
+pub use yong::YongImport;
+pub use zhangyu::ZhangyuImport;
 pub use ziguang_pinyin::ZiguangPinyinImport;
+pub use ziguang_uwl::ZiguangUwlImport;
+
+/// How importers handle lines that do not match their format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorPolicy {
+    /// Fail on the first malformed line
+    Strict,
+    /// Log a warning for each malformed line and skip it
+    Warn,
+    /// Skip malformed lines silently
+    #[default]
+    Skip,
+}
+
+impl ErrorPolicy {
+    /// Handle the result of parsing a line of a file
+    ///
+    /// `line_number` is 1-based; errors are reported with the file location.
+    pub fn apply<T>(
+        self,
+        result: Result<Option<T>>,
+        path: &str,
+        line_number: usize,
+        line: &str,
+    ) -> Result<Option<T>> {
+        match (result, self) {
+            (Ok(entry), _) => Ok(entry),
+            (Err(e), ErrorPolicy::Strict) => Err(e.at_line(path, line_number, line)),
+            (Err(e), ErrorPolicy::Warn) => {
+                tracing::warn!("skipping {}", e.at_line(path, line_number, line));
+                Ok(None)
+            }
+            (Err(_), ErrorPolicy::Skip) => Ok(None),
+        }
+    }
+}
+
+/// Options controlling how a file is imported
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Text encoding overriding the format's default ("auto" detects it)
+    pub encoding: Option<String>,
+    pub error_policy: ErrorPolicy,
+    /// Convert tone-marked pinyin codes (nǐ hǎo)
+    pub pinyin_tones: Option<ToneStyle>,
+    /// Rank of entries without a frequency column, instead of the format's
+    /// default
+    pub default_rank: Option<i32>,
+    /// Skip corrupt blocks of binary files instead of failing
+    pub salvage: bool,
+}
+
+impl ImportOptions {
+    pub fn new() -> Self {
+        ImportOptions {
+            encoding: None,
+            error_policy: ErrorPolicy::default(),
+            pinyin_tones: None,
+            default_rank: None,
+            salvage: false,
+        }
+    }
+
+    pub fn with_encoding(mut self, encoding: &str) -> Self {
+        self.encoding = Some(encoding.to_string());
+        self
+    }
+
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    pub fn with_pinyin_tones(mut self, style: ToneStyle) -> Self {
+        self.pinyin_tones = Some(style);
+        self
+    }
+
+    pub fn with_default_rank(mut self, rank: i32) -> Self {
+        self.default_rank = Some(rank);
+        self
+    }
+
+    /// Recover what can be read from corrupt binary files, see `salvage`
+    pub fn with_salvage(mut self) -> Self {
+        self.salvage = true;
+        self
+    }
+
+    /// Import a line with `importer`, applying the default rank
+    pub(crate) fn parse_line<I: WordLibraryTextImport + ?Sized>(
+        &self,
+        importer: &I,
+        line: &str,
+    ) -> Result<Option<WordLibrary>> {
+        match self.default_rank {
+            Some(rank) => importer.import_line_with_rank(line, rank),
+            None => importer.import_line(line),
+        }
+    }
+
+    /// Convert the tone marks of imported pinyin codes, if enabled
+    pub fn normalize_pinyin(&self, words: &mut [WordLibrary]) {
+        let Some(style) = self.pinyin_tones else {
+            return;
+        };
+        for word in words {
+            if word.code_type != CodeType::Pinyin {
+                continue;
+            }
+            for code in word.codes.0.iter_mut().flatten() {
+                *code = convert_tone_marks(code, style);
+            }
+        }
+    }
+}
+
+/// Error for a line that does not match the format of an importer
+pub(crate) fn malformed_line(expected: &str) -> Error {
+    Error::Parse(format!("malformed line, expected `{}`", expected))
+}
+
+/// Path standing for stdin (as input) or stdout (as output)
+pub const STDIO_PATH: &str = "-";
 
 /// Trait for importing word libraries from files
 pub trait WordLibraryImport {
     /// Import from a file path, returns a vector of WordLibrary entries
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>>;
+
+    /// Import from a file path with an encoding override and error policy
+    ///
+    /// Binary formats ignore the options.
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        let _ = options;
+        self.import_from_file(path)
+    }
+
+    /// Import an in-memory buffer, e.g. a download or an archive entry
+    ///
+    /// Text formats use their default encoding.
+    fn import_from_bytes(&self, data: &[u8]) -> Result<Vec<WordLibrary>> {
+        self.import_from_reader(&mut &data[..], &ImportOptions::new())
+    }
+
+    /// Import a file together with the metadata it carries
+    ///
+    /// Formats without metadata return the entries of `import_with_options`.
+    fn import_library(&self, path: &str, options: &ImportOptions) -> Result<WordLibraryList> {
+        Ok(self.import_with_options(path, options)?.into())
+    }
+
+    /// Import from a reader together with the metadata of the library
+    fn import_library_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<WordLibraryList> {
+        Ok(self.import_from_reader(reader, options)?.into())
+    }
+
+    /// This importer as a streaming one, for line-based formats
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        None
+    }
+
+    /// Import from a reader, e.g. stdin
+    ///
+    /// By default the data is copied into a temporary file, for importers
+    /// that can only read files.
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let path =
+            std::env::temp_dir().join(format!("imewlconverter-stdin-{}", std::process::id()));
+        let mut file = File::create(&path)?;
+        std::io::copy(reader, &mut file)?;
+        file.flush()?;
+        drop(file);
+
+        let result = path
+            .to_str()
+            .ok_or_else(|| Error::Parse(format!("Invalid file path: {}", path.display())))
+            .and_then(|p| self.import_with_options(p, options));
+        std::fs::remove_file(&path).ok();
+        result
+    }
 }
 
 /// Trait for text-based import formats that can process line-by-line
 pub trait WordLibraryTextImport {
-    /// Import a single line, returns Some(WordLibrary) if valid, None if should skip
+    /// Import a single line
+    ///
+    /// Returns None for lines without an entry (blank lines, comments) and an
+    /// error for lines that do not match the format.
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>>;
 
+    /// Import a single line, giving entries without a frequency column
+    /// `default_rank`
+    ///
+    /// Formats without an optional frequency column ignore the rank.
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        let _ = default_rank;
+        self.import_line(line)
+    }
+
     /// Get the default text encoding (e.g., "utf-8", "gbk", "utf-16le")
     fn default_encoding(&self) -> &'static str {
         "utf-8"
     }
 
-    /// Read and parse entire file with encoding
+    /// Read and parse entire file with encoding, skipping malformed lines
     fn read_file_with_encoding(&self, path: &str, encoding_name: &str) -> Result<Vec<WordLibrary>> {
-        let content = read_file_with_encoding_str(path, encoding_name)?;
+        self.read_file_with_options(path, &ImportOptions::new().with_encoding(encoding_name))
+    }
+
+    /// Read and parse entire file according to the import options
+    fn read_file_with_options(
+        &self,
+        path: &str,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(&mut File::open(path)?, path, options)
+    }
+
+    /// Read and parse all of a reader according to the import options
+    ///
+    /// `name` identifies the input in error messages.
+    fn read_with_options(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options
+            .encoding
+            .as_deref()
+            .unwrap_or(self.default_encoding());
+        let content = read_with_encoding_str(reader, encoding)?;
         let mut result = Vec::new();
 
-        for line in content.lines() {
-            if let Some(wl) = self.import_line(line)? {
+        for (i, line) in content.lines().enumerate() {
+            let entry =
+                options
+                    .error_policy
+                    .apply(options.parse_line(self, line), name, i + 1, line)?;
+            if let Some(wl) = entry {
                 result.push(wl);
             }
         }
 
+        options.normalize_pinyin(&mut result);
         Ok(result)
     }
 }
 
-/// Helper function to read file with encoding detection
-pub fn read_file_with_encoding_str(path: &str, encoding_name: &str) -> Result<String> {
-    use encoding_rs::Encoding;
-    use std::fs;
+/// Trait for importers that yield entries one at a time
+///
+/// Unlike `WordLibraryImport`, the file is decoded incrementally, so memory use
+/// stays bounded regardless of the file size.
+pub trait WordLibraryStreamImport {
+    /// Stream entries from a file according to the import options
+    fn import_stream(
+        &self,
+        path: &str,
+        options: &ImportOptions,
+    ) -> Result<Box<dyn Iterator<Item = Result<WordLibrary>> + '_>> {
+        self.import_stream_from_reader(Box::new(File::open(path)?), path, options)
+    }
 
-    let bytes = fs::read(path)?;
+    /// Stream entries from a reader, e.g. stdin
+    ///
+    /// `name` identifies the input in error messages.
+    fn import_stream_from_reader<'a>(
+        &'a self,
+        reader: Box<dyn Read + 'a>,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Box<dyn Iterator<Item = Result<WordLibrary>> + 'a>>;
+}
+
+impl<T: WordLibraryTextImport> WordLibraryStreamImport for T {
+    fn import_stream_from_reader<'a>(
+        &'a self,
+        reader: Box<dyn Read + 'a>,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Box<dyn Iterator<Item = Result<WordLibrary>> + 'a>> {
+        let encoding = options
+            .encoding
+            .as_deref()
+            .unwrap_or(self.default_encoding());
+        let lines = DecodedLines::new(reader, encoding);
+        let name = name.to_string();
+        let options = options.clone();
+        Ok(Box::new(lines.enumerate().filter_map(move |(i, line)| {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            let entry = options
+                .error_policy
+                .apply(options.parse_line(self, &line), &name, i + 1, &line)
+                .transpose()?;
+            Some(entry.map(|mut word| {
+                options.normalize_pinyin(std::slice::from_mut(&mut word));
+                word
+            }))
+        })))
+    }
+}
+
+/// Size of the chunks read by `DecodedLines`
+pub(crate) const STREAM_CHUNK_SIZE: usize = 8192;
+
+/// Decoder for an encoding name, or None for `"auto"` to detect it from the
+/// first chunk
+pub(crate) fn decoder_for(encoding_name: &str) -> Option<Decoder> {
+    (!encoding_name.eq_ignore_ascii_case(AUTO_ENCODING))
+        .then(|| encoding_for_label(encoding_name).new_decoder())
+}
+
+/// Decode a chunk of bytes into `buffer`, detecting the encoding if needed
+pub(crate) fn decode_chunk(
+    decoder: &mut Option<Decoder>,
+    chunk: &[u8],
+    last: bool,
+    buffer: &mut String,
+) {
+    let decoder = decoder.get_or_insert_with(|| detect_encoding(chunk).new_decoder());
+
+    let mut start = 0;
+    loop {
+        if let Some(needed) = decoder.max_utf8_buffer_length(chunk.len() - start) {
+            buffer.reserve(needed);
+        }
+        let (result, read, _) = decoder.decode_to_string(&chunk[start..], buffer, last);
+        start += read;
+        if let CoderResult::InputEmpty = result {
+            break;
+        }
+    }
+}
+
+/// Remove the first complete line from `buffer`, without its line ending
+pub(crate) fn take_line(buffer: &mut String) -> Option<String> {
+    let pos = buffer.find('\n')?;
+    let mut line: String = buffer.drain(..=pos).collect();
+    line.pop();
+    if line.ends_with('\r') {
+        line.pop();
+    }
+    Some(line)
+}
+
+/// Iterator over the lines of a reader, decoded incrementally
+pub struct DecodedLines<R: Read> {
+    reader: R,
+    /// Created from the first chunk when the encoding is detected
+    decoder: Option<Decoder>,
+    /// Decoded text not yet returned as lines
+    buffer: String,
+    chunk: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: Read> DecodedLines<R> {
+    /// Create the iterator; `"auto"` detects the encoding from the first chunk
+    pub fn new(reader: R, encoding_name: &str) -> Self {
+        DecodedLines {
+            reader,
+            decoder: decoder_for(encoding_name),
+            buffer: String::new(),
+            chunk: vec![0; STREAM_CHUNK_SIZE],
+            eof: false,
+        }
+    }
 
-    // Get encoding
-    let encoding = if encoding_name == "utf-8" {
-        encoding_rs::UTF_8
-    } else if encoding_name == "gbk" {
-        encoding_rs::GBK
-    } else if encoding_name == "big5" {
-        encoding_rs::BIG5
-    } else if encoding_name == "utf-16le" {
-        encoding_rs::UTF_16LE
-    } else if encoding_name == "utf-16be" {
-        encoding_rs::UTF_16BE
-    } else {
-        Encoding::for_label(encoding_name.as_bytes()).unwrap_or(encoding_rs::UTF_8)
-    };
+    /// Read and decode the next chunk into the buffer
+    fn fill(&mut self) -> Result<()> {
+        let n = self.reader.read(&mut self.chunk)?;
+        let last = n == 0;
+        decode_chunk(&mut self.decoder, &self.chunk[..n], last, &mut self.buffer);
+        self.eof = last;
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for DecodedLines<R> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(line) = take_line(&mut self.buffer) {
+                return Some(Ok(line));
+            }
+
+            if self.eof {
+                if self.buffer.is_empty() {
+                    return None;
+                }
+                return Some(Ok(std::mem::take(&mut self.buffer)));
+            }
 
-    let (result, _, had_errors) = encoding.decode(&bytes);
-    if had_errors {
-        eprintln!("Warning: encoding errors detected when reading file");
+            if let Err(e) = self.fill() {
+                self.eof = true;
+                return Some(Err(e));
+            }
+        }
     }
+}
+
+/// Helper function to read file with encoding detection
+///
+/// `encoding_name` may be `"auto"`; see `helpers::decode_text`.
+pub fn read_file_with_encoding_str(path: &str, encoding_name: &str) -> Result<String> {
+    read_with_encoding_str(&mut File::open(path)?, encoding_name)
+}
 
-    Ok(result.into_owned())
+/// Read all of a reader and decode it like `read_file_with_encoding_str`
+pub fn read_with_encoding_str(reader: &mut dyn Read, encoding_name: &str) -> Result<String> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    Ok(decode_text(&bytes, encoding_name))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Cursor;
 
     #[test]
     fn test_read_file_utf8() {
         // This would require actual test files
         // Just test that the function exists and compiles
     }
+
+    #[test]
+    fn test_decoded_lines() {
+        // Enough lines to cross several chunk boundaries
+        let text = "你好\tni hao\t1\r\n".repeat(2000) + "世界\tshi jie\t2";
+        let bytes: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+
+        let lines: Vec<String> = DecodedLines::new(Cursor::new(bytes), "utf-16le")
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(lines.len(), 2001);
+        assert_eq!(lines[0], "你好\tni hao\t1");
+        assert_eq!(lines[2000], "世界\tshi jie\t2");
+    }
+
+    #[test]
+    fn test_import_from_reader() {
+        let data = "你好\tni hao\t10\nbad line\n";
+        let importer = RimeImport::new();
+        let words = importer
+            .import_from_reader(&mut Cursor::new(data), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.len(), 1);
+        assert_eq!(importer.import_from_bytes(data.as_bytes()).unwrap(), words);
+
+        let strict = ImportOptions::new().with_error_policy(ErrorPolicy::Strict);
+        let err = importer
+            .import_from_reader(&mut Cursor::new(data), &strict)
+            .unwrap_err();
+        assert!(err.to_string().contains("-:2"));
+    }
+
+    #[test]
+    fn test_import_stream() {
+        let path = std::env::temp_dir().join(format!(
+            "imewlconverter-stream-import-{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&path, "# comment\n你好\tni hao\t10\n世界\tshi jie\t5\n").unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let importer = RimeImport::new();
+        let streamed: Vec<WordLibrary> = importer
+            .import_stream(&path, &ImportOptions::new())
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        let whole = importer.import_from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(streamed, whole);
+
+        // The encoding and error policy of the options apply
+        let data: Vec<u8> = "你好\tni hao\t10\nbroken\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let options = ImportOptions::new()
+            .with_encoding("utf-16le")
+            .with_error_policy(ErrorPolicy::Strict);
+        let mut entries = importer
+            .import_stream_from_reader(Box::new(Cursor::new(data)), "input", &options)
+            .unwrap();
+        assert_eq!(entries.next().unwrap().unwrap().word, "你好");
+        assert!(entries.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_error_policy() {
+        let path = std::env::temp_dir().join(format!(
+            "imewlconverter-error-policy-{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&path, "你好\tni hao\t10\nbroken\n世界\tshi jie\t5\n").unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let importer = RimeImport::new();
+        let skipped = importer.import_from_file(&path).unwrap();
+        let warned = importer.import_with_options(
+            &path,
+            &ImportOptions::new().with_error_policy(ErrorPolicy::Warn),
+        );
+        let strict = importer.import_with_options(
+            &path,
+            &ImportOptions::new().with_error_policy(ErrorPolicy::Strict),
+        );
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(warned.unwrap().len(), 2);
+        match strict {
+            Err(Error::LineParse {
+                path: error_path,
+                line,
+                content,
+                ..
+            }) => {
+                assert_eq!(error_path, path);
+                assert_eq!(line, 2);
+                assert_eq!(content, "broken");
+            }
+            other => panic!("expected parse error, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,222 @@
+//! Apple text replacement (文本替换) import
+//!
+//! macOS exports the text replacements of System Settings as a property list
+//! when they are dragged to Finder, and iCloud syncs the same entries to iOS:
+//!
+//! ```text
+//! <dict>
+//!     <key>phrase</key>
+//!     <string>北京市海淀区</string>
+//!     <key>shortcut</key>
+//!     <string>bjhd</string>
+//! </dict>
+//! ```
+//!
+//! The CSV variant used by spreadsheet round trips has a `shortcut,phrase`
+//! header and quotes fields with commas, quotes or line breaks.
+
+use crate::import::html::html_to_text;
+use crate::import::{read_with_encoding_str, ImportOptions, WordLibraryImport};
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+/// Header line of the CSV variant
+pub const CSV_HEADER: &str = "shortcut,phrase";
+
+/// File layout of text replacements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplacementFormat {
+    /// `Text Substitutions.plist` dragged out of System Settings
+    Plist,
+    /// `shortcut,phrase` rows
+    Csv,
+}
+
+/// Apple text replacement importer
+///
+/// The phrase becomes the word and the shortcut its `UserDefinePhrase` code.
+pub struct AppleReplacementImport {
+    format: ReplacementFormat,
+}
+
+impl AppleReplacementImport {
+    pub fn new(format: ReplacementFormat) -> Self {
+        AppleReplacementImport { format }
+    }
+}
+
+impl WordLibraryImport for AppleReplacementImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_from_reader(&mut File::open(path)?, &ImportOptions::new())
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let content =
+            read_with_encoding_str(reader, options.encoding.as_deref().unwrap_or("utf-8"))?;
+        let entries = match self.format {
+            ReplacementFormat::Plist => parse_plist(&content)?,
+            ReplacementFormat::Csv => parse_csv(&content)?,
+        };
+        Ok(entries
+            .into_iter()
+            .filter(|(shortcut, phrase)| !shortcut.is_empty() && !phrase.is_empty())
+            .map(|(shortcut, phrase)| {
+                let mut wl = WordLibrary::new(phrase);
+                wl.code_type = CodeType::UserDefinePhrase;
+                wl.codes = Code::from_single(shortcut);
+                wl
+            })
+            .collect())
+    }
+}
+
+/// Text between `open` and `close` after `start`, with the end offset
+fn element<'a>(xml: &'a str, start: usize, open: &str, close: &str) -> Option<(&'a str, usize)> {
+    let begin = start + xml[start..].find(open)? + open.len();
+    let end = begin + xml[begin..].find(close)?;
+    Some((&xml[begin..end], end + close.len()))
+}
+
+/// (shortcut, phrase) pairs of a text replacement property list
+pub fn parse_plist(xml: &str) -> Result<Vec<(String, String)>> {
+    if !xml.contains("<plist") {
+        return Err(Error::InvalidFormat("Not a property list".into()));
+    }
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while let Some((dict, end)) = element(xml, offset, "<dict>", "</dict>") {
+        offset = end;
+        let (mut shortcut, mut phrase) = (None, None);
+        let mut pos = 0;
+        while let Some((key, key_end)) = element(dict, pos, "<key>", "</key>") {
+            // An empty string is written as <string/>
+            let rest = dict[key_end..].trim_start();
+            let (value, value_end) = if rest.starts_with("<string/>") {
+                (String::new(), dict.len() - rest.len() + "<string/>".len())
+            } else {
+                let (value, end) = element(dict, key_end, "<string>", "</string>")
+                    .ok_or_else(|| Error::InvalidFormat(format!("No string for key {}", key)))?;
+                (html_to_text(value), end)
+            };
+            match key {
+                "shortcut" => shortcut = Some(value),
+                "phrase" => phrase = Some(value),
+                _ => {}
+            }
+            pos = value_end;
+        }
+        match (shortcut, phrase) {
+            (Some(shortcut), Some(phrase)) => entries.push((shortcut, phrase)),
+            _ => {
+                return Err(Error::InvalidFormat(
+                    "Entry without shortcut or phrase".into(),
+                ))
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// (shortcut, phrase) pairs of the CSV variant, the header being optional
+pub fn parse_csv(text: &str) -> Result<Vec<(String, String)>> {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(Error::InvalidFormat("Unterminated quoted field".into()));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.into_iter()
+        .filter(|row| !(row.len() == 1 && row[0].trim().is_empty()))
+        .enumerate()
+        .filter(|(i, row)| !(*i == 0 && row.join(",").eq_ignore_ascii_case(CSV_HEADER)))
+        .map(|(i, row)| match <[String; 2]>::try_from(row) {
+            Ok([shortcut, phrase]) => Ok((shortcut, phrase)),
+            Err(_) => Err(Error::InvalidFormat(format!(
+                "Row {} does not have two fields",
+                i + 1
+            ))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_plist() {
+        let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<array>
+	<dict>
+		<key>phrase</key>
+		<string>北京市海淀区</string>
+		<key>shortcut</key>
+		<string>bjhd</string>
+	</dict>
+	<dict>
+		<key>phrase</key>
+		<string>R&amp;D</string>
+		<key>shortcut</key>
+		<string>rd</string>
+	</dict>
+</array>
+</plist>"#;
+        let words = AppleReplacementImport::new(ReplacementFormat::Plist)
+            .import_from_reader(&mut plist.as_bytes(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "北京市海淀区");
+        assert_eq!(words[0].get_word_code().as_deref(), Some("bjhd"));
+        assert_eq!(words[1].word, "R&D");
+        assert!(parse_plist("bjhd,北京").is_err());
+    }
+
+    #[test]
+    fn test_import_csv() {
+        let csv =
+            "shortcut,phrase\r\nbjhd,北京市海淀区\r\nomw,\"On my way, \"\"soon\"\"\nsee you\"\r\n";
+        let entries = parse_csv(csv).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("bjhd".to_string(), "北京市海淀区".to_string()),
+                (
+                    "omw".to_string(),
+                    "On my way, \"soon\"\nsee you".to_string()
+                ),
+            ]
+        );
+        assert!(parse_csv("a,b,c").is_err());
+        assert!(parse_csv("a,\"b").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Dictionaries packed in zip archives
+//!
+//! Dictionary downloads are often zipped, sometimes with several dictionaries
+//! of different formats in one archive. Each file of an archive is imported
+//! with the format its extension points to; extensions shared by several
+//! formats, such as `.txt`, are left to the caller's importer.
+
+use crate::import::{ImportOptions, WordLibraryImport};
+use crate::registry::{FormatDescriptor, FormatRegistry};
+use crate::{Error, Result, WordLibraryList};
+use std::fs::File;
+use std::io::{Read, Seek};
+
+/// Refuse archive files larger than this, dictionaries are a few MB at most
+const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+/// Refuse archives whose files add up to more than this
+const MAX_TOTAL_SIZE: u64 = 256 * 1024 * 1024;
+
+/// A file read from an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// Path of the file inside the archive
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl ArchiveEntry {
+    /// Import the entry with the format of its extension, or with `fallback`
+    /// when the extension is unknown or ambiguous
+    pub fn import(
+        &self,
+        registry: &FormatRegistry,
+        fallback: &dyn WordLibraryImport,
+        options: &ImportOptions,
+    ) -> Result<WordLibraryList> {
+        let mut reader = self.data.as_slice();
+        match detect_format(registry, &self.name) {
+            Some(format) => registry
+                .importer(format.name())?
+                .import_library_from_reader(&mut reader, options),
+            None => fallback.import_library_from_reader(&mut reader, options),
+        }
+    }
+}
+
+/// Whether a path names an archive
+pub fn is_archive(path: &str) -> bool {
+    path.to_lowercase().ends_with(".zip")
+}
+
+/// The only importable format matching the extension of an entry
+pub fn detect_format<'a>(registry: &'a FormatRegistry, name: &str) -> Option<&'a FormatDescriptor> {
+    let mut formats = registry
+        .find_by_path(name)
+        .into_iter()
+        .filter(|format| format.can_import());
+    match (formats.next(), formats.next()) {
+        (Some(format), None) => Some(format),
+        _ => None,
+    }
+}
+
+/// Read the files of a zip archive, skipping directories
+///
+/// Fails on files larger than 64 MB or archives larger than 256 MB once
+/// decompressed, whatever sizes the archive declares.
+pub fn read_zip<R: Read + Seek>(reader: R) -> Result<Vec<ArchiveEntry>> {
+    read_zip_limited(reader, MAX_ENTRY_SIZE, MAX_TOTAL_SIZE)
+}
+
+fn read_zip_limited<R: Read + Seek>(
+    reader: R,
+    entry_limit: u64,
+    total_limit: u64,
+) -> Result<Vec<ArchiveEntry>> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(zip_error)?;
+    let mut entries = Vec::new();
+    let mut total = 0u64;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(zip_error)?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut data = Vec::new();
+        let read = (&mut file).take(entry_limit + 1).read_to_end(&mut data)? as u64;
+        if read > entry_limit {
+            return Err(Error::InvalidFormat(format!(
+                "Zip archive: {} is larger than {} bytes",
+                file.name(),
+                entry_limit
+            )));
+        }
+        total += read;
+        if total > total_limit {
+            return Err(Error::InvalidFormat(format!(
+                "Zip archive: files are larger than {} bytes",
+                total_limit
+            )));
+        }
+        entries.push(ArchiveEntry {
+            name: file.name().to_string(),
+            data,
+        });
+    }
+    Ok(entries)
+}
+
+/// Read the files of an archive on disk
+pub fn read_archive(path: &str) -> Result<Vec<ArchiveEntry>> {
+    read_zip(File::open(path)?)
+}
+
+fn zip_error(e: zip::result::ZipError) -> Error {
+    match e {
+        zip::result::ZipError::Io(e) => Error::Io(e),
+        e => Error::InvalidFormat(format!("Zip archive: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::RimeImport;
+    use std::io::{Cursor, Write};
+
+    #[test]
+    fn test_import_zip_entries() {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+        writer.add_directory("dicts/", options).unwrap();
+        writer.start_file("dicts/a.dict.yaml", options).unwrap();
+        writer.write_all("你好\tni hao\t1\n".as_bytes()).unwrap();
+        writer.start_file("b.txt", options).unwrap();
+        writer.write_all("世界\tshi jie\t2\n".as_bytes()).unwrap();
+        let data = writer.finish().unwrap().into_inner();
+
+        let entries = read_zip(Cursor::new(data.as_slice())).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["dicts/a.dict.yaml", "b.txt"]);
+
+        let registry = FormatRegistry::with_builtin_formats();
+        assert_eq!(
+            detect_format(&registry, &entries[0].name).map(|f| f.name()),
+            Some("rime")
+        );
+        assert!(detect_format(&registry, &entries[1].name).is_none());
+        for entry in &entries {
+            let words = entry
+                .import(&registry, &RimeImport::new(), &ImportOptions::new())
+                .unwrap();
+            assert_eq!(words.len(), 1);
+        }
+        assert!(is_archive("Dicts.ZIP"));
+
+        let limited = |entry_limit, total_limit| {
+            read_zip_limited(Cursor::new(data.as_slice()), entry_limit, total_limit)
+        };
+        assert!(limited(64, 64).is_ok());
+        assert!(limited(16, 64).is_err());
+        assert!(limited(64, 32).is_err());
+    }
+}
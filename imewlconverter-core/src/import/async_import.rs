@@ -0,0 +1,82 @@
+//! Asynchronous import for text formats (feature `tokio`)
+//!
+//! The input is read and parsed chunk by chunk between awaits, so parsing a
+//! large dictionary does not hold a runtime thread for the whole file.
+
+use crate::import::{
+    decode_chunk, decoder_for, take_line, ImportOptions, WordLibraryTextImport, STDIO_PATH,
+    STREAM_CHUNK_SIZE,
+};
+use crate::{Result, WordLibrary};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Import all entries of an async reader with a text importer
+///
+/// Behaves like `WordLibraryImport::import_from_reader`: the encoding and
+/// error policy come from `options`, errors name the input `-`.
+pub async fn import_from_reader_async<I, R>(
+    importer: &I,
+    mut reader: R,
+    options: &ImportOptions,
+) -> Result<Vec<WordLibrary>>
+where
+    I: WordLibraryTextImport + ?Sized,
+    R: AsyncRead + Unpin,
+{
+    let encoding = options
+        .encoding
+        .as_deref()
+        .unwrap_or(importer.default_encoding());
+    let mut decoder = decoder_for(encoding);
+    let mut buffer = String::new();
+    let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+    let mut line_number = 0;
+    let mut result = Vec::new();
+
+    let mut parse = |line: &str, line_number: usize| -> Result<()> {
+        let entry = options.error_policy.apply(
+            options.parse_line(importer, line),
+            STDIO_PATH,
+            line_number,
+            line,
+        )?;
+        result.extend(entry);
+        Ok(())
+    };
+
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        let last = n == 0;
+        decode_chunk(&mut decoder, &chunk[..n], last, &mut buffer);
+
+        while let Some(line) = take_line(&mut buffer) {
+            line_number += 1;
+            parse(&line, line_number)?;
+        }
+        if last {
+            if !buffer.is_empty() {
+                parse(&buffer, line_number + 1)?;
+            }
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::RimeImport;
+
+    #[tokio::test]
+    async fn test_import_from_reader_async() {
+        let data = "你好\tni hao\t10\r\n".repeat(1000) + "世界\tshi jie\t5";
+        let words =
+            import_from_reader_async(&RimeImport::new(), data.as_bytes(), &ImportOptions::new())
+                .await
+                .unwrap();
+        assert_eq!(words.len(), 1001);
+        assert_eq!(words[1000].word, "世界");
+    }
+}
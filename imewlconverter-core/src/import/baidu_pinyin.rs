@@ -1,8 +1,12 @@
 /// Baidu Pinyin text format (.txt)
 /// Format: word\tpinyin'\trank (for Chinese) or word\trank (for English)
 /// Example: 你好\tni'hao'\t1000
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{CodeType, Result, WordLibrary};
+use std::io::Read;
 
 pub struct BaiduPinyinImport;
 
@@ -15,7 +19,7 @@ impl WordLibraryTextImport for BaiduPinyinImport {
 
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() < 2 {
-            return Ok(None);
+            return Err(malformed_line("word\tpinyin\trank"));
         }
 
         let word = parts[0];
@@ -25,6 +29,7 @@ impl WordLibraryTextImport for BaiduPinyinImport {
             // English word: word\trank
             wl.rank = parts[1].parse().unwrap_or(0);
             wl.code_type = CodeType::English;
+            wl.is_english = true;
         } else if parts.len() >= 3 {
             // Chinese word: word\tpinyin'\trank
             let pinyin_str = parts[1];
@@ -51,6 +56,22 @@ impl WordLibraryImport for BaiduPinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
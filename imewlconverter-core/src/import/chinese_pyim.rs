@@ -3,8 +3,13 @@
 //! Format: `code word1 word2 word3`
 //! Example: `ni'hao 你好 尼好`
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, read_with_encoding_str, ImportOptions, WordLibraryImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{Code, CodeType, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
 
 /// Chinese Pyim format importer
 pub struct ChinesePyimImport;
@@ -13,6 +18,32 @@ impl ChinesePyimImport {
     pub fn new() -> Self {
         ChinesePyimImport
     }
+
+    /// Parse all lines of a reader; `name` identifies it in error messages
+    fn read(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options
+            .encoding
+            .as_deref()
+            .unwrap_or(self.default_encoding());
+        let content = read_with_encoding_str(reader, encoding)?;
+        let mut result = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            let words =
+                options
+                    .error_policy
+                    .apply(self.parse_line(line).map(Some), name, i + 1, line)?;
+            result.extend(words.into_iter().flatten());
+        }
+
+        options.normalize_pinyin(&mut result);
+        Ok(result)
+    }
 }
 
 impl Default for ChinesePyimImport {
@@ -31,7 +62,7 @@ impl WordLibraryTextImport for ChinesePyimImport {
         let parts: Vec<&str> = line.split_whitespace().collect();
 
         if parts.len() < 2 {
-            return Ok(None);
+            return Err(malformed_line("code word1 word2 ..."));
         }
 
         let code_str = parts[0];
@@ -58,41 +89,53 @@ impl WordLibraryTextImport for ChinesePyimImport {
     }
 }
 
-impl WordLibraryImport for ChinesePyimImport {
-    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
-        let content = read_file_with_encoding_str(path, self.default_encoding())?;
-        let mut result = Vec::new();
-
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
-                continue;
-            }
+impl ChinesePyimImport {
+    /// Parse all words of a line, which share the same code
+    fn parse_line(&self, line: &str) -> Result<Vec<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            return Ok(Vec::new());
+        }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 2 {
-                continue;
-            }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 {
+            return Err(malformed_line("code word1 word2 ..."));
+        }
 
-            let code_str = parts[0];
-            let codes: Vec<String> = code_str.split('\'').map(|s| s.to_string()).collect();
+        let code_str = parts[0];
+        let codes: Vec<String> = code_str.split('\'').map(|s| s.to_string()).collect();
 
-            // Process all words in this line
-            for (i, word) in parts[1..].iter().enumerate() {
+        Ok(parts[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
                 let mut wl = WordLibrary::new(word.to_string());
                 wl.rank = (parts.len() - i) as i32; // Higher rank for earlier words
                 wl.code_type = CodeType::Pinyin;
                 wl.codes = Code::from_char_list(codes.clone());
-                result.push(wl);
-            }
-        }
-
-        Ok(result)
+                wl
+            })
+            .collect())
     }
 }
 
-// Helper function from import.rs
-use crate::import::read_file_with_encoding_str;
+impl WordLibraryImport for ChinesePyimImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read(&mut File::open(path)?, path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read(reader, STDIO_PATH, options)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -108,4 +151,15 @@ mod tests {
         assert_eq!(word.word, "你好");
         assert_eq!(word.get_pinyin_string("'"), "ni'hao");
     }
+
+    #[test]
+    fn test_parse_line_multiple_words() {
+        let importer = ChinesePyimImport::new();
+        let words = importer.parse_line("ni'hao 你好 尼好").unwrap();
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].word, "尼好");
+        assert!(words[0].rank > words[1].rank);
+        assert!(importer.parse_line("ni'hao").is_err());
+    }
 }
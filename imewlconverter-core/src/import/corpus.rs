@@ -0,0 +1,341 @@
+//! Raw text corpus import
+//!
+//! Turns plain Chinese prose into a word library: the text is segmented by
+//! forward maximum matching against a dictionary, and every dictionary word
+//! found becomes an entry ranked by its number of occurrences, with the
+//! toneless pinyin of the embedded resources as codes.
+//!
+//! With `NgramExtraction` enabled, frequent character sequences missing from
+//! the dictionary are added as new word candidates.
+
+use crate::generate::{CodeGenerator, PinyinGenerator};
+use crate::helpers::AUTO_ENCODING;
+use crate::import::{read_with_encoding_str, ImportOptions, WordLibraryImport};
+use crate::resource::ResourceManager;
+use crate::{Code, Result, WordLibrary};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+
+/// Check whether a character is a CJK ideograph
+fn is_chinese_char(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+/// Settings for discovering new words among the n-grams of a corpus
+///
+/// A candidate must occur at least `min_count` times, and its mutual
+/// information (the log2 ratio of its probability to that of its least
+/// cohesive split into two parts) must reach `min_mutual_information`.
+/// Candidates only ever occurring inside a longer candidate are dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NgramExtraction {
+    pub min_len: usize,
+    pub max_len: usize,
+    pub min_count: usize,
+    pub min_mutual_information: f64,
+}
+
+impl NgramExtraction {
+    pub fn new() -> Self {
+        NgramExtraction {
+            min_len: 2,
+            max_len: 8,
+            min_count: 5,
+            min_mutual_information: 3.0,
+        }
+    }
+
+    /// Set the range of candidate lengths in characters
+    pub fn with_length(mut self, min_len: usize, max_len: usize) -> Self {
+        self.min_len = min_len.max(2);
+        self.max_len = max_len.max(self.min_len);
+        self
+    }
+
+    pub fn with_min_count(mut self, min_count: usize) -> Self {
+        self.min_count = min_count;
+        self
+    }
+
+    pub fn with_min_mutual_information(mut self, min_mutual_information: f64) -> Self {
+        self.min_mutual_information = min_mutual_information;
+        self
+    }
+
+    /// Find candidate words in text, highest count first
+    pub fn extract(&self, text: &str) -> Vec<(String, usize)> {
+        let runs: Vec<Vec<char>> = text
+            .split(|c: char| !is_chinese_char(c))
+            .filter(|run| !run.is_empty())
+            .map(|run| run.chars().collect())
+            .collect();
+
+        // Counts of every sequence up to max_len, for the candidates and the
+        // parts of their splits
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut total = 0usize;
+        for run in &runs {
+            total += run.len();
+            for start in 0..run.len() {
+                for len in 1..=self.max_len.min(run.len() - start) {
+                    let gram: String = run[start..start + len].iter().collect();
+                    *counts.entry(gram).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let probability = |gram: &str| counts[gram] as f64 / total as f64;
+        let mut candidates: Vec<(String, usize, f64)> = counts
+            .iter()
+            .filter(|(gram, &count)| {
+                let len = gram.chars().count();
+                count >= self.min_count && (self.min_len..=self.max_len).contains(&len)
+            })
+            .filter_map(|(gram, &count)| {
+                let bounds: Vec<usize> = gram.char_indices().map(|(i, _)| i).skip(1).collect();
+                let information = bounds
+                    .iter()
+                    .map(|&i| {
+                        let split = probability(&gram[..i]) * probability(&gram[i..]);
+                        (probability(gram) / split).log2()
+                    })
+                    .fold(f64::INFINITY, f64::min);
+                (information >= self.min_mutual_information)
+                    .then(|| (gram.clone(), count, information))
+            })
+            .collect();
+
+        let fragments: HashSet<String> = candidates
+            .iter()
+            .filter(|(gram, count, _)| {
+                candidates.iter().any(|(longer, other, _)| {
+                    other == count && longer.len() > gram.len() && longer.contains(gram.as_str())
+                })
+            })
+            .map(|(gram, _, _)| gram.clone())
+            .collect();
+        candidates.retain(|(gram, _, _)| !fragments.contains(gram));
+
+        candidates.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then(b.2.total_cmp(&a.2))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        candidates
+            .into_iter()
+            .map(|(gram, count, _)| (gram, count))
+            .collect()
+    }
+}
+
+impl Default for NgramExtraction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Corpus importer segmenting text with a word dictionary
+pub struct CorpusImport {
+    dictionary: HashSet<String>,
+    /// Length in characters of the longest dictionary word
+    max_len: usize,
+    min_count: usize,
+    ngrams: Option<NgramExtraction>,
+    pinyin: PinyinGenerator,
+}
+
+impl CorpusImport {
+    /// Create with the embedded word list as dictionary
+    pub fn new() -> Self {
+        Self::with_dictionary(ResourceManager::global().words().map(str::to_string))
+    }
+
+    /// Create with a custom dictionary
+    pub fn with_dictionary(words: impl IntoIterator<Item = String>) -> Self {
+        let dictionary: HashSet<String> = words.into_iter().collect();
+        let max_len = dictionary
+            .iter()
+            .map(|w| w.chars().count())
+            .max()
+            .unwrap_or(0);
+        CorpusImport {
+            dictionary,
+            max_len,
+            min_count: 1,
+            ngrams: None,
+            pinyin: PinyinGenerator::with_resources(ResourceManager::shared()),
+        }
+    }
+
+    /// Drop words found fewer than `min_count` times
+    pub fn with_min_count(mut self, min_count: usize) -> Self {
+        self.min_count = min_count;
+        self
+    }
+
+    /// Also add new word candidates found among the n-grams of the text
+    pub fn with_ngrams(mut self, extraction: NgramExtraction) -> Self {
+        self.ngrams = Some(extraction);
+        self
+    }
+
+    /// Segment text by forward maximum matching
+    ///
+    /// Text is split at non-Chinese characters first. Characters not starting
+    /// a dictionary word become single-character segments.
+    pub fn segment<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let mut segments = Vec::new();
+        for run in text.split(|c: char| !is_chinese_char(c)) {
+            // Byte offsets of the characters, plus the end of the run
+            let bounds: Vec<usize> = run
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain(std::iter::once(run.len()))
+                .collect();
+            let chars = bounds.len() - 1;
+
+            let mut start = 0;
+            while start < chars {
+                let longest = self.max_len.min(chars - start);
+                let len = (2..=longest)
+                    .rev()
+                    .find(|&len| {
+                        self.dictionary
+                            .contains(&run[bounds[start]..bounds[start + len]])
+                    })
+                    .unwrap_or(1);
+                segments.push(&run[bounds[start]..bounds[start + len]]);
+                start += len;
+            }
+        }
+        segments
+    }
+
+    /// Entry for a word found `count` times, with toneless pinyin codes
+    fn entry(&self, word: &str, count: usize) -> WordLibrary {
+        let rank = i32::try_from(count).unwrap_or(i32::MAX);
+        let mut entry = WordLibrary::with_rank(word.to_string(), rank);
+        // Words without pinyin are kept for other code generators
+        if let Ok(code) = self.pinyin.generate_code_for_string(word) {
+            let syllables = code.0.into_iter().flatten().map(|syllable| {
+                syllable
+                    .trim_end_matches(|c: char| c.is_ascii_digit())
+                    .to_string()
+            });
+            entry.codes = Code::from_char_list(syllables.collect());
+        }
+        entry
+    }
+
+    /// Count the dictionary words of a text, in order of first occurrence,
+    /// followed by the new word candidates
+    pub fn import_text(&self, text: &str) -> Vec<WordLibrary> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        let mut order = Vec::new();
+        for segment in self.segment(text) {
+            if !self.dictionary.contains(segment) {
+                continue;
+            }
+            let count = counts.entry(segment).or_insert(0);
+            if *count == 0 {
+                order.push(segment);
+            }
+            *count += 1;
+        }
+
+        let mut words: Vec<WordLibrary> = order
+            .into_iter()
+            .filter(|word| counts[word] >= self.min_count)
+            .map(|word| self.entry(word, counts[word]))
+            .collect();
+
+        if let Some(extraction) = &self.ngrams {
+            words.extend(
+                extraction
+                    .extract(text)
+                    .into_iter()
+                    .filter(|(gram, _)| !self.dictionary.contains(gram))
+                    .map(|(gram, count)| self.entry(&gram, count)),
+            );
+        }
+
+        words
+    }
+}
+
+impl Default for CorpusImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryImport for CorpusImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.import_from_reader(&mut File::open(path)?, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options.encoding.as_deref().unwrap_or(AUTO_ENCODING);
+        let text = read_with_encoding_str(reader, encoding)?;
+        Ok(self.import_text(&text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn importer() -> CorpusImport {
+        CorpusImport::with_dictionary(
+            ["中国", "中国人", "人民", "北京"]
+                .iter()
+                .map(|w| w.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_segment() {
+        assert_eq!(
+            importer().segment("中国人民爱北京, 北京ABC中国"),
+            vec!["中国人", "民", "爱", "北京", "北京", "中国"]
+        );
+    }
+
+    #[test]
+    fn test_import_text() {
+        let words = importer().import_text("北京是中国的首都。北京很大。");
+        let ranks: Vec<(&str, i32)> = words.iter().map(|w| (w.word.as_str(), w.rank)).collect();
+        assert_eq!(ranks, vec![("北京", 2), ("中国", 1)]);
+        assert_eq!(words[0].get_pinyin_string(" "), "bei jing");
+
+        let words = importer()
+            .with_min_count(2)
+            .import_text("北京是中国的首都。北京很大。");
+        assert_eq!(words.len(), 1);
+    }
+
+    #[test]
+    fn test_ngram_extraction() {
+        let text = "区块链技术。区块链很新。学习区块链！".repeat(3);
+        let extraction = NgramExtraction::new()
+            .with_length(2, 4)
+            .with_min_count(6)
+            .with_min_mutual_information(1.0);
+
+        let candidates = extraction.extract(&text);
+        assert_eq!(candidates, vec![("区块链".to_string(), 9)]);
+
+        let words = importer().with_ngrams(extraction).import_text(&text);
+        assert_eq!(words[0].word, "区块链");
+        assert_eq!(words[0].get_pinyin_string(" "), "qu kuai lian");
+    }
+}
@@ -0,0 +1,146 @@
+//! English custom phrase (英文自定义短语) import for QQ and Sogou Pinyin
+//!
+//! Text expansion snippets, one per line:
+//! - Sogou: `shortcut,position=expansion`, GBK
+//! - QQ: `shortcut=position,expansion`, UTF-16LE
+//!
+//! Example: `omw,1=On my way!`
+
+use crate::import::sogou_phrase::MAX_POSITION;
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
+
+/// Input method whose English custom phrase layout is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhraseDialect {
+    Sogou,
+    QQ,
+}
+
+impl PhraseDialect {
+    /// Split a line into its shortcut, position and expansion
+    pub fn parse(self, line: &str) -> Option<(&str, i32, &str)> {
+        let (shortcut, position, expansion) = match self {
+            PhraseDialect::Sogou => {
+                let (key, expansion) = line.split_once('=')?;
+                let (shortcut, position) = key.split_once(',')?;
+                (shortcut, position, expansion)
+            }
+            PhraseDialect::QQ => {
+                let (shortcut, value) = line.split_once('=')?;
+                let (position, expansion) = value.split_once(',')?;
+                (shortcut, position, expansion)
+            }
+        };
+        if shortcut.is_empty() || !shortcut.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return None;
+        }
+        Some((shortcut, position.trim().parse().ok()?, expansion))
+    }
+
+    /// Format an entry line
+    pub fn format(self, shortcut: &str, position: i32, expansion: &str) -> String {
+        match self {
+            PhraseDialect::Sogou => format!("{},{}={}", shortcut, position, expansion),
+            PhraseDialect::QQ => format!("{}={},{}", shortcut, position, expansion),
+        }
+    }
+
+    pub fn encoding(self) -> &'static str {
+        match self {
+            PhraseDialect::Sogou => "gbk",
+            PhraseDialect::QQ => "utf-16le",
+        }
+    }
+}
+
+/// English custom phrase importer
+///
+/// The expansion becomes the word and the shortcut its `UserDefinePhrase`
+/// code; the position (1 to 9) is kept and becomes a rank, position 1 ranking
+/// highest.
+pub struct EnglishPhraseImport {
+    dialect: PhraseDialect,
+}
+
+impl EnglishPhraseImport {
+    pub fn new(dialect: PhraseDialect) -> Self {
+        EnglishPhraseImport { dialect }
+    }
+}
+
+impl WordLibraryTextImport for EnglishPhraseImport {
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        if line.trim().is_empty() || line.starts_with(';') {
+            return Ok(None);
+        }
+
+        let Some((shortcut, position, expansion)) = self.dialect.parse(line) else {
+            return Err(malformed_line(&self.dialect.format(
+                "shortcut",
+                1,
+                "expansion",
+            )));
+        };
+        let position = position.clamp(1, MAX_POSITION);
+        let mut wl = WordLibrary::with_rank(
+            expansion.trim_end().to_string(),
+            MAX_POSITION + 1 - position,
+        );
+        wl.code_type = CodeType::UserDefinePhrase;
+        wl.codes = Code::from_single(shortcut.to_lowercase());
+        wl.is_english = wl.word.is_ascii();
+        wl.position = Some(position as u8);
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        self.dialect.encoding()
+    }
+}
+
+impl WordLibraryImport for EnglishPhraseImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_encoding(path, self.default_encoding())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_line() {
+        let sogou = EnglishPhraseImport::new(PhraseDialect::Sogou);
+        let word = sogou.import_line("omw,2=On my way!").unwrap().unwrap();
+        assert_eq!(word.word, "On my way!");
+        assert_eq!(word.rank, 8);
+        assert_eq!(word.get_single_code(), Some("omw"));
+        assert_eq!(word.code_type, CodeType::UserDefinePhrase);
+
+        let qq = EnglishPhraseImport::new(PhraseDialect::QQ);
+        let word = qq.import_line("brb=1,Be right back").unwrap().unwrap();
+        assert_eq!((word.word.as_str(), word.rank), ("Be right back", 9));
+        assert!(qq.import_line("omw,2=On my way!").is_err());
+    }
+}
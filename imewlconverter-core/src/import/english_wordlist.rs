@@ -0,0 +1,111 @@
+//! English word frequency list import
+//!
+//! Format: `word count` separated by spaces or tabs, as in common corpus
+//! frequency lists; `count word` is accepted too and the count is optional
+//! Example: `hello 12345`
+
+use crate::import::{
+    ImportOptions, WordLibraryImport, WordLibraryStreamImport, WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
+
+/// English word frequency list importer
+///
+/// Entries are marked as English with the lowercase word as code, so
+/// exporters and filters handle them through the English word policy.
+pub struct EnglishWordListImport;
+
+impl EnglishWordListImport {
+    pub fn new() -> Self {
+        EnglishWordListImport
+    }
+}
+
+impl Default for EnglishWordListImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Split a line into the word and its count, if any
+fn split_count(line: &str, default_count: i32) -> (&str, i32) {
+    if let Some((word, count)) = line.rsplit_once(char::is_whitespace) {
+        if let Ok(count) = count.parse::<i32>() {
+            return (word.trim_end(), count);
+        }
+    }
+    if let Some((count, word)) = line.split_once(char::is_whitespace) {
+        if let Ok(count) = count.parse::<i32>() {
+            return (word.trim_start(), count);
+        }
+    }
+    (line, default_count)
+}
+
+impl WordLibraryTextImport for EnglishWordListImport {
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let (word, count) = split_count(line, default_rank);
+        let mut wl = WordLibrary::with_rank(word.to_string(), count);
+        wl.is_english = true;
+        wl.code_type = CodeType::English;
+        wl.codes = Code::from_single(word.to_lowercase());
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "utf-8"
+    }
+}
+
+impl WordLibraryImport for EnglishWordListImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_encoding(path, self.default_encoding())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_line() {
+        let importer = EnglishWordListImport::new();
+        let word = importer.import_line("Hello\t1234").unwrap().unwrap();
+        assert_eq!(word.word, "Hello");
+        assert_eq!(word.rank, 1234);
+        assert_eq!(word.get_single_code(), Some("hello"));
+        assert!(word.is_english_word());
+
+        let word = importer.import_line("42 ice cream").unwrap().unwrap();
+        assert_eq!((word.word.as_str(), word.rank), ("ice cream", 42));
+        let word = importer.import_line("world").unwrap().unwrap();
+        assert_eq!((word.word.as_str(), word.rank), ("world", 0));
+        assert!(importer.import_line("# comment").unwrap().is_none());
+    }
+}
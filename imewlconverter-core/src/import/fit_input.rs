@@ -3,8 +3,12 @@
 //! Format: `word,code,rank`
 //! Example: `你好,ni'hao,1000`
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
 
 /// FitInput format importer
 pub struct FitInputImport;
@@ -23,6 +27,10 @@ impl Default for FitInputImport {
 
 impl WordLibraryTextImport for FitInputImport {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             return Ok(None);
@@ -31,7 +39,7 @@ impl WordLibraryTextImport for FitInputImport {
         let parts: Vec<&str> = line.split(',').collect();
 
         if parts.len() < 2 {
-            return Ok(None);
+            return Err(malformed_line("word,code,rank"));
         }
 
         let word = parts[0];
@@ -39,7 +47,7 @@ impl WordLibraryTextImport for FitInputImport {
         let rank = if parts.len() >= 3 {
             parts[2].parse::<i32>().unwrap_or(0)
         } else {
-            0
+            default_rank
         };
 
         let mut wl = WordLibrary::new(word.to_string());
@@ -62,6 +70,22 @@ impl WordLibraryImport for FitInputImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,12 @@
 /// Google Pinyin text format (.txt)
 /// Format: word\trank\tpinyin1 pinyin2 pinyin3
 /// Example: 你好\t1000\tni hao
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{CodeType, Result, WordLibrary};
+use std::io::Read;
 
 pub struct GooglePinyinImport;
 
@@ -15,7 +19,7 @@ impl WordLibraryTextImport for GooglePinyinImport {
 
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() < 3 {
-            return Ok(None);
+            return Err(malformed_line("word\trank\tpinyin"));
         }
 
         let word = parts[0];
@@ -45,6 +49,22 @@ impl WordLibraryImport for GooglePinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,195 @@
+//! HTML page import
+//!
+//! Strips the markup of a saved web page and imports its text as a corpus,
+//! see `CorpusImport`.
+
+use crate::helpers::AUTO_ENCODING;
+use crate::import::{read_with_encoding_str, CorpusImport, ImportOptions, WordLibraryImport};
+use crate::{Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+/// Elements whose content is not page text
+const SKIPPED_ELEMENTS: [&str; 4] = ["script", "style", "noscript", "template"];
+
+/// Elements separating text, replaced by a line break
+const BLOCK_ELEMENTS: [&str; 24] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "nav",
+    "p",
+    "section",
+    "td",
+    "tr",
+];
+
+/// Name of the element of a tag, lowercased, e.g. `div` for `</DIV>`
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Decode a character reference without the surrounding `&` and `;`
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let number = entity.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Extract the text of an HTML document
+///
+/// Comments and the content of scripts and styles are dropped, block elements
+/// become line breaks and character references are decoded.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+
+    while let Some(pos) = rest.find(['<', '&']) {
+        text.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if rest.starts_with('&') {
+            let entity = rest[1..]
+                .find(';')
+                .filter(|&end| end <= 10)
+                .and_then(|end| Some((decode_entity(&rest[1..=end])?, end)));
+            match entity {
+                Some((c, end)) => {
+                    text.push(c);
+                    rest = &rest[end + 2..];
+                }
+                None => {
+                    text.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        } else if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else {
+            let Some(end) = rest.find('>') else {
+                break;
+            };
+            let name = tag_name(&rest[1..end]);
+            let closing = rest[1..].starts_with('/');
+            rest = &rest[end + 1..];
+
+            if !closing && SKIPPED_ELEMENTS.contains(&name.as_str()) {
+                let close = format!("</{}", name);
+                let lower = rest.to_ascii_lowercase();
+                rest = lower
+                    .find(&close)
+                    .and_then(|start| rest[start..].find('>').map(|end| &rest[start + end + 1..]))
+                    .unwrap_or("");
+            } else if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                text.push('\n');
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// HTML importer running the page text through a corpus importer
+pub struct HtmlImport {
+    corpus: CorpusImport,
+}
+
+impl HtmlImport {
+    pub fn new() -> Self {
+        HtmlImport {
+            corpus: CorpusImport::new(),
+        }
+    }
+
+    /// Use a configured corpus importer, e.g. with n-gram extraction
+    pub fn with_corpus(corpus: CorpusImport) -> Self {
+        HtmlImport { corpus }
+    }
+
+    /// Import the words of an HTML document
+    pub fn import_html(&self, html: &str) -> Vec<WordLibrary> {
+        self.corpus.import_text(&html_to_text(html))
+    }
+}
+
+impl Default for HtmlImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryImport for HtmlImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.import_from_reader(&mut File::open(path)?, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options.encoding.as_deref().unwrap_or(AUTO_ENCODING);
+        Ok(self.import_html(&read_with_encoding_str(reader, encoding)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><style>p { color: red }</style>\
+            <SCRIPT>var 北京 = 1;</SCRIPT></head>\
+            <body><!-- 注释 --><p>你好&amp;<b>世界</b></p><div>中&#22269;&#x4EBA;</div></body></html>";
+        assert_eq!(html_to_text(html), "\n你好&世界\n\n中国人\n");
+        assert_eq!(html_to_text("a &unknown; b < c"), "a &unknown; b < c");
+    }
+
+    #[test]
+    fn test_import_html() {
+        let importer = HtmlImport::with_corpus(CorpusImport::with_dictionary(
+            ["北京", "上海"].iter().map(|w| w.to_string()),
+        ));
+        let words = importer.import_html("<td>北京</td><td>上海</td><script>北京</script>");
+        let ranks: Vec<(&str, i32)> = words.iter().map(|w| (w.word.as_str(), w.rank)).collect();
+        assert_eq!(ranks, vec![("北京", 1), ("上海", 1)]);
+    }
+}
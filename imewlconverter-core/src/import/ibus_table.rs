@@ -0,0 +1,174 @@
+//! ibus-table source file import
+//!
+//! Format: `code\tword\tfreq` between `BEGIN_TABLE` and `END_TABLE`, after a
+//! `BEGIN_DEFINITION` section of `KEY = value` attributes
+//! Example: `vqkb\t你好\t1000`
+
+use crate::import::{
+    malformed_line, read_with_encoding_str, ImportOptions, WordLibraryImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+/// ibus-table source file importer
+///
+/// Only the `BEGIN_TABLE` section holds words; character prompts and the
+/// phrase construction (`GOUCI`) sections are skipped.
+pub struct IbusTableImport {
+    code_type: CodeType,
+}
+
+impl IbusTableImport {
+    pub fn new() -> Self {
+        IbusTableImport {
+            code_type: CodeType::Wubi,
+        }
+    }
+
+    pub fn with_code_type(code_type: CodeType) -> Self {
+        IbusTableImport { code_type }
+    }
+
+    /// Parse the table section of a reader; `name` identifies it in error
+    /// messages
+    fn read(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options
+            .encoding
+            .as_deref()
+            .unwrap_or(self.default_encoding());
+        let content = read_with_encoding_str(reader, encoding)?;
+        let mut result = Vec::new();
+        let mut in_table = false;
+
+        for (i, line) in content.lines().enumerate() {
+            match line.trim() {
+                "BEGIN_TABLE" => in_table = true,
+                "END_TABLE" => in_table = false,
+                _ if in_table => {
+                    let word = options.error_policy.apply(
+                        options.parse_line(self, line),
+                        name,
+                        i + 1,
+                        line,
+                    )?;
+                    result.extend(word);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl Default for IbusTableImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse the `KEY = value` attributes of the definition section
+pub fn parse_definition(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != "BEGIN_DEFINITION")
+        .take_while(|line| *line != "END_DEFINITION")
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+impl WordLibraryTextImport for IbusTableImport {
+    /// Parse a line of the table section
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() < 2 {
+            return Err(malformed_line("code\tword\tfreq"));
+        }
+
+        let rank = parts
+            .get(2)
+            .map_or(default_rank, |f| f.parse().unwrap_or(0));
+        let mut wl = WordLibrary::with_rank(parts[1].to_string(), rank);
+        wl.code_type = self.code_type;
+        wl.codes = Code::from_single(parts[0].to_string());
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "utf-8"
+    }
+}
+
+impl WordLibraryImport for IbusTableImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read(&mut File::open(path)?, path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read(reader, STDIO_PATH, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "### ibus-table source\n\
+        SCHEMA_START\n\
+        BEGIN_DEFINITION\n\
+        NAME = Wubi\n\
+        MAX_KEY_LENGTH = 4\n\
+        END_DEFINITION\n\
+        BEGIN_CHAR_PROMPTS_DEFINITION\n\
+        a\t工\n\
+        END_CHAR_PROMPTS_DEFINITION\n\
+        BEGIN_TABLE\n\
+        vqkb\t你好\t1000\n\
+        wqvb\t你们\t800\n\
+        END_TABLE\n\
+        BEGIN_GOUCI\n\
+        你好\n\
+        END_GOUCI\n";
+
+    #[test]
+    fn test_import_table() {
+        let words = IbusTableImport::new()
+            .import_from_reader(&mut TABLE.as_bytes(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "你好");
+        assert_eq!(words[0].rank, 1000);
+        assert_eq!(words[0].get_single_code(), Some("vqkb"));
+
+        let definition = parse_definition(TABLE);
+        assert_eq!(definition[0], ("NAME".to_string(), "Wubi".to_string()));
+        assert_eq!(definition.len(), 2);
+    }
+}
@@ -0,0 +1,123 @@
+//! Jidian Wubi (极点五笔) mb text import
+//!
+//! Format: `code\tword1 word2 ...`
+//! Example: `vqkb\t你好 你们好`
+
+use crate::import::{
+    malformed_line, read_with_encoding_str, ImportOptions, WordLibraryImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+/// Jidian Wubi mb text importer
+///
+/// Each word of a line becomes an entry; words sharing a code get decreasing
+/// ranks in their order on the line.
+pub struct JidianImport;
+
+impl JidianImport {
+    pub fn new() -> Self {
+        JidianImport
+    }
+
+    /// Parse all words of a line, which share the same code
+    fn parse_line(&self, line: &str) -> Result<Vec<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(Vec::new());
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 {
+            return Err(malformed_line("code\tword1 word2 ..."));
+        }
+
+        Ok(parts[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let rank = (parts.len() - 1 - i) as i32;
+                let mut wl = WordLibrary::with_rank(word.to_string(), rank);
+                wl.code_type = CodeType::Wubi;
+                wl.codes = Code::from_single(parts[0].to_string());
+                wl
+            })
+            .collect())
+    }
+
+    /// Parse all lines of a reader; `name` identifies it in error messages
+    fn read(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options
+            .encoding
+            .as_deref()
+            .unwrap_or(self.default_encoding());
+        let content = read_with_encoding_str(reader, encoding)?;
+        let mut result = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            let words =
+                options
+                    .error_policy
+                    .apply(self.parse_line(line).map(Some), name, i + 1, line)?;
+            result.extend(words.into_iter().flatten());
+        }
+
+        Ok(result)
+    }
+}
+
+impl Default for JidianImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryTextImport for JidianImport {
+    /// Parse the first word of a line
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        Ok(self.parse_line(line)?.into_iter().next())
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "gbk"
+    }
+}
+
+impl WordLibraryImport for JidianImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read(&mut File::open(path)?, path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read(reader, STDIO_PATH, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let words = JidianImport::new().parse_line("vqkb\t你好 你们好").unwrap();
+        let entries: Vec<(&str, i32)> = words.iter().map(|w| (w.word.as_str(), w.rank)).collect();
+        assert_eq!(entries, vec![("你好", 2), ("你们好", 1)]);
+        assert_eq!(words[1].get_single_code(), Some("vqkb"));
+        assert!(JidianImport::new().parse_line("vqkb").is_err());
+    }
+}
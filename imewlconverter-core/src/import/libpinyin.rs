@@ -3,8 +3,12 @@
 //! Format: `word rank code1 code2...`
 //! Example: `你好 1000 ni hao` (space-separated pinyin)
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
 
 /// libpinyin format importer
 pub struct LibpinyinImport;
@@ -31,7 +35,7 @@ impl WordLibraryTextImport for LibpinyinImport {
         let parts: Vec<&str> = line.split_whitespace().collect();
 
         if parts.len() < 3 {
-            return Ok(None);
+            return Err(malformed_line("word rank code1 code2 ..."));
         }
 
         let word = parts[0];
@@ -55,6 +59,22 @@ impl WordLibraryImport for LibpinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -3,8 +3,12 @@
 //! Format: `code rank word` (space or tab separated)
 //! Example: `ni'hao 1000 你好`
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
 
 /// Microsoft Pinyin format importer
 pub struct MsPinyinImport;
@@ -31,7 +35,7 @@ impl WordLibraryTextImport for MsPinyinImport {
         let parts: Vec<&str> = line.split_whitespace().collect();
 
         if parts.len() < 3 {
-            return Ok(None);
+            return Err(malformed_line("code rank word"));
         }
 
         let code_str = parts[0];
@@ -58,6 +62,22 @@ impl WordLibraryImport for MsPinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,68 @@
+//! Microsoft Pinyin user lexicons of the current Windows user
+//!
+//! Microsoft Pinyin keeps the user phrases in `%AppData%`, a path most users
+//! never see. `discover()` finds them so they can be imported directly.
+
+use crate::import::ms_pinyin_lex::MsPinyinLexImport;
+use crate::import::{ImportOptions, WordLibraryImport};
+use crate::{Error, Result, WordLibraryList};
+use std::path::PathBuf;
+
+/// Directory of the lexicons, relative to `%AppData%`
+pub const LEXICON_DIR: &str = r"Microsoft\InputMethod\Chs";
+
+/// User phrase lexicons of Microsoft Pinyin
+pub const LEXICON_FILES: [&str; 1] = ["ChsPinyinEUDPv1.lex"];
+
+/// Paths of the existing user lexicons
+pub fn discover() -> Result<Vec<PathBuf>> {
+    let app_data = std::env::var_os("APPDATA")
+        .ok_or_else(|| Error::Unsupported("%AppData% is not set".into()))?;
+    let dir = PathBuf::from(app_data).join(LEXICON_DIR);
+    Ok(LEXICON_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.is_file())
+        .collect())
+}
+
+/// Import all user lexicons found by `discover()`
+pub fn import_discovered(options: &ImportOptions) -> Result<WordLibraryList> {
+    let importer = MsPinyinLexImport::new();
+    let mut words = WordLibraryList::new();
+    for path in discover()? {
+        let path = path
+            .to_str()
+            .ok_or_else(|| Error::Parse(format!("Invalid file path: {}", path.display())))?;
+        words.append(&mut importer.import_library(path, options)?);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover() {
+        let app_data =
+            std::env::temp_dir().join(format!("imewlconverter-appdata-{}", std::process::id()));
+        let dir = app_data.join(LEXICON_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        let original = std::env::var_os("APPDATA");
+        std::env::set_var("APPDATA", &app_data);
+
+        let before = discover().unwrap();
+        std::fs::write(dir.join(LEXICON_FILES[0]), b"").unwrap();
+        let after = discover().unwrap();
+
+        match original {
+            Some(value) => std::env::set_var("APPDATA", value),
+            None => std::env::remove_var("APPDATA"),
+        }
+        std::fs::remove_dir_all(&app_data).ok();
+
+        assert!(before.is_empty());
+        assert_eq!(after, vec![dir.join(LEXICON_FILES[0])]);
+    }
+}
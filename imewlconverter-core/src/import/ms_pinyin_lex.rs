@@ -0,0 +1,161 @@
+//! Microsoft Pinyin user phrase lexicon import (ChsPinyinEUDPv1.lex)
+//!
+//! Layout (little-endian):
+//! - 0x00: magic `mschxudp`
+//! - 0x10: u32 offset of the entry offset table
+//! - 0x14: u32 offset of the entries
+//! - 0x1C: u32 number of entries
+//!
+//! The offset table holds a u32 per entry, relative to the entries. Each entry
+//! has a 16-byte header (u32 tag, u16 offset of the phrase within the entry,
+//! u8 candidate position, ...) followed by the null-terminated UTF-16LE code
+//! and phrase.
+
+use crate::import::sogou_phrase::MAX_POSITION;
+use crate::import::{ImportOptions, WordLibraryImport};
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+const MAGIC: &[u8; 8] = b"mschxudp";
+const ENTRY_HEADER_SIZE: usize = 16;
+
+/// Microsoft Pinyin .lex user phrase importer
+pub struct MsPinyinLexImport;
+
+impl MsPinyinLexImport {
+    pub fn new() -> Self {
+        MsPinyinLexImport
+    }
+}
+
+impl Default for MsPinyinLexImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryImport for MsPinyinLexImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_from_reader(&mut File::open(path)?, &ImportOptions::new())
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        _options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        parse_lex(&data)
+    }
+}
+
+fn invalid_at(offset: usize, what: &str) -> Error {
+    Error::Parse(format!("Invalid lex {} at 0x{:x}", what, offset))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<usize> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+        .ok_or_else(|| invalid_at(offset, "offset"))
+}
+
+/// Read a null-terminated UTF-16LE string starting at `offset`
+fn read_utf16z(data: &[u8], offset: usize) -> Result<String> {
+    let units: Vec<u16> = data
+        .get(offset..)
+        .ok_or_else(|| invalid_at(offset, "string"))?
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    String::from_utf16(&units).map_err(|_| invalid_at(offset, "UTF-16 string"))
+}
+
+/// Parse all user phrases of a .lex file
+pub fn parse_lex(data: &[u8]) -> Result<Vec<WordLibrary>> {
+    if !data.starts_with(MAGIC) {
+        return Err(Error::Parse("Invalid MS Pinyin lex magic number".into()));
+    }
+    let table = read_u32(data, 0x10)?;
+    let entries = read_u32(data, 0x14)?;
+    let count = read_u32(data, 0x1C)?;
+
+    let mut words = Vec::with_capacity(count.min(data.len() / ENTRY_HEADER_SIZE));
+    for i in 0..count {
+        let start = entries + read_u32(data, table + i * 4)?;
+        let header = data
+            .get(start..start + ENTRY_HEADER_SIZE)
+            .ok_or_else(|| invalid_at(start, "entry"))?;
+        let phrase_offset = u16::from_le_bytes([header[4], header[5]]) as usize;
+        let position = (header[6] as i32).clamp(1, MAX_POSITION);
+
+        let code = read_utf16z(data, start + ENTRY_HEADER_SIZE)?;
+        let phrase = read_utf16z(data, start + phrase_offset)?;
+        if code.is_empty() || phrase.is_empty() {
+            return Err(invalid_at(start, "entry"));
+        }
+
+        let mut wl = WordLibrary::with_rank(phrase, MAX_POSITION + 1 - position);
+        wl.code_type = CodeType::UserDefinePhrase;
+        wl.codes = Code::from_single(code.to_lowercase());
+        wl.position = Some(position as u8);
+        words.push(wl);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16z(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    fn sample_lex() -> Vec<u8> {
+        let mut entries = Vec::new();
+        let mut offsets = Vec::new();
+        for (code, phrase, position) in [("bj", "北京市", 1u8), ("dz", "海淀区", 3)] {
+            offsets.extend((entries.len() as u32).to_le_bytes());
+            let code = utf16z(code);
+            let phrase_offset = (ENTRY_HEADER_SIZE + code.len()) as u16;
+            entries.extend([0x10, 0x00, 0x10, 0x00]);
+            entries.extend(phrase_offset.to_le_bytes());
+            entries.extend([position, 0x06]);
+            entries.extend([0; 8]);
+            entries.extend(code);
+            entries.extend(utf16z(phrase));
+        }
+
+        let mut data = vec![0u8; 0x40];
+        data[..8].copy_from_slice(MAGIC);
+        data[0x10..0x14].copy_from_slice(&0x40u32.to_le_bytes());
+        data[0x14..0x18].copy_from_slice(&(0x40 + offsets.len() as u32).to_le_bytes());
+        data[0x1C..0x20].copy_from_slice(&2u32.to_le_bytes());
+        data.extend(offsets);
+        data.extend(entries);
+        data
+    }
+
+    #[test]
+    fn test_import_lex() {
+        let words = MsPinyinLexImport::new()
+            .import_from_reader(&mut sample_lex().as_slice(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "北京市");
+        assert_eq!(words[0].get_word_code().as_deref(), Some("bj"));
+        assert_eq!(words[1].position, Some(3));
+        assert_eq!(words[1].rank, 7);
+
+        assert!(parse_lex(b"not a lexicon").is_err());
+        let mut truncated = sample_lex();
+        truncated.truncate(0x50);
+        assert!(parse_lex(&truncated).is_err());
+    }
+}
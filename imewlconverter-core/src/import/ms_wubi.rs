@@ -0,0 +1,101 @@
+//! Windows 10 Microsoft Wubi custom phrase import
+//!
+//! Format: `code\tword\tposition`, UTF-16LE with a `;` comment header line
+//! Example: `vqkb\t你好\t1`
+
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
+
+/// Highest candidate position of a custom phrase
+pub const MAX_POSITION: i32 = 9;
+
+/// Microsoft Wubi custom phrase importer
+///
+/// The candidate position (1 to 9) becomes a rank, position 1 ranking
+/// highest.
+pub struct MsWubiImport;
+
+impl MsWubiImport {
+    pub fn new() -> Self {
+        MsWubiImport
+    }
+}
+
+impl Default for MsWubiImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryTextImport for MsWubiImport {
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            return Ok(None);
+        }
+
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() < 2 {
+            return Err(malformed_line("code\tword\tposition"));
+        }
+
+        let position = parts
+            .get(2)
+            .and_then(|p| p.trim().parse::<i32>().ok())
+            .unwrap_or(1)
+            .clamp(1, MAX_POSITION);
+        let mut wl = WordLibrary::with_rank(parts[1].to_string(), MAX_POSITION + 1 - position);
+        wl.code_type = CodeType::Wubi;
+        wl.codes = Code::from_single(parts[0].to_string());
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "utf-16le"
+    }
+}
+
+impl WordLibraryImport for MsWubiImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_encoding(path, self.default_encoding())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_line() {
+        let importer = MsWubiImport::new();
+        let word = importer.import_line("vqkb\t你好\t2").unwrap().unwrap();
+        assert_eq!(word.word, "你好");
+        assert_eq!(word.rank, 8);
+        assert_eq!(word.get_single_code(), Some("vqkb"));
+        assert!(importer
+            .import_line(";微软五笔自定义短语")
+            .unwrap()
+            .is_none());
+        assert!(importer.import_line("vqkb").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Canonical intermediate format import (versioned NDJSON)
+//!
+//! The format keeps everything the converter knows about a library, so a
+//! library can be extracted and cleaned once and exported to many formats
+//! later, or shared independently of any IME:
+//!
+//! ```text
+//! {"format":"imewlconverter","version":1,"source_format":"scel","metadata":{"name":"计算机词汇"}}
+//! {"word":"你好","rank":1000,"code_type":"Pinyin","codes":[["ni"],["hao"]],"is_english":false}
+//! ```
+//!
+//! The first line is the header, every following line one `WordLibrary`
+//! with the fields of its serde representation. Readers reject headers of a
+//! newer version; fields added within a version are optional.
+
+use crate::import::{read_with_encoding_str, ImportOptions, WordLibraryImport, STDIO_PATH};
+use crate::{Error, LibraryMetadata, Result, WordLibrary, WordLibraryList};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+
+/// Value of the `format` field of the header
+pub const NDJSON_FORMAT: &str = "imewlconverter";
+
+/// Version of the format written by this crate
+pub const NDJSON_VERSION: u32 = 1;
+
+/// First line of an intermediate file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NdjsonHeader {
+    pub format: String,
+    pub version: u32,
+    /// Format the library was originally imported from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "LibraryMetadata::is_empty")]
+    pub metadata: LibraryMetadata,
+}
+
+impl NdjsonHeader {
+    /// Header describing a word list
+    pub fn for_list(words: &WordLibraryList) -> Self {
+        NdjsonHeader {
+            format: NDJSON_FORMAT.to_string(),
+            version: NDJSON_VERSION,
+            source_format: words.source_format.clone(),
+            name: words.name.clone(),
+            metadata: words.metadata.clone(),
+        }
+    }
+}
+
+/// Canonical intermediate format importer
+pub struct NdjsonImport;
+
+impl NdjsonImport {
+    pub fn new() -> Self {
+        NdjsonImport
+    }
+
+    fn read_library(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<WordLibraryList> {
+        let content =
+            read_with_encoding_str(reader, options.encoding.as_deref().unwrap_or("utf-8"))?;
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, first) = lines
+            .next()
+            .ok_or_else(|| Error::InvalidFormat("Missing intermediate format header".into()))?;
+        let header: NdjsonHeader = serde_json::from_str(first)
+            .map_err(|e| Error::InvalidFormat(format!("Invalid header: {}", e)))?;
+        if header.format != NDJSON_FORMAT {
+            return Err(Error::InvalidFormat(format!(
+                "Not an intermediate file: format '{}'",
+                header.format
+            )));
+        }
+        if header.version > NDJSON_VERSION {
+            return Err(Error::Unsupported(format!(
+                "Intermediate format version {} is newer than {}",
+                header.version, NDJSON_VERSION
+            )));
+        }
+
+        let mut words = WordLibraryList::new();
+        for (i, line) in lines {
+            let entry = serde_json::from_str::<WordLibrary>(line)
+                .map(Some)
+                .map_err(|e| Error::Parse(e.to_string()));
+            if let Some(word) = options.error_policy.apply(entry, name, i + 1, line)? {
+                words.push(word);
+            }
+        }
+        words.source_format = header.source_format;
+        words.name = header.name;
+        words.metadata = header.metadata;
+        Ok(words)
+    }
+}
+
+impl Default for NdjsonImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryImport for NdjsonImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        Ok(self.import_library(path, &ImportOptions::new())?.into_vec())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        Ok(self.import_library(path, options)?.into_vec())
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        Ok(self.read_library(reader, STDIO_PATH, options)?.into_vec())
+    }
+
+    fn import_library(&self, path: &str, options: &ImportOptions) -> Result<WordLibraryList> {
+        self.read_library(&mut File::open(path)?, path, options)
+    }
+
+    fn import_library_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<WordLibraryList> {
+        self.read_library(reader, STDIO_PATH, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import() {
+        let text = r#"{"format":"imewlconverter","version":1,"source_format":"scel","metadata":{"name":"计算机词汇"}}
+{"word":"你好","rank":1000,"code_type":"Pinyin","codes":[["ni"],["hao"]],"is_english":false}
+"#;
+        let words = NdjsonImport::new()
+            .import_library_from_reader(&mut text.as_bytes(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].get_pinyin_string(" "), "ni hao");
+        assert_eq!(words.source_format.as_deref(), Some("scel"));
+        assert_eq!(words.metadata.name.as_deref(), Some("计算机词汇"));
+
+        let newer = r#"{"format":"imewlconverter","version":2}"#;
+        assert!(matches!(
+            NdjsonImport::new().import_from_bytes(newer.as_bytes()),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(NdjsonImport::new()
+            .import_from_bytes(b"{\"word\":\"x\"}")
+            .is_err());
+    }
+}
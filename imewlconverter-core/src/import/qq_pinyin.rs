@@ -3,8 +3,13 @@
 //! Format: `pinyin word rank`
 //! Example: `ni'hao 你好 1000`
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::helpers::pinyin::split_unseparated;
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
 
 /// QQ Pinyin text format importer
 pub struct QQPinyinImport;
@@ -23,6 +28,10 @@ impl Default for QQPinyinImport {
 
 impl WordLibraryTextImport for QQPinyinImport {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
         let line = line.trim();
         if line.is_empty() {
             return Ok(None);
@@ -38,7 +47,7 @@ impl WordLibraryTextImport for QQPinyinImport {
         let parts: Vec<&str> = line.split_whitespace().collect();
 
         if parts.len() < 2 {
-            return Ok(None);
+            return Err(malformed_line("pinyin word rank"));
         }
 
         let pinyin = parts[0];
@@ -46,7 +55,7 @@ impl WordLibraryTextImport for QQPinyinImport {
         let rank = if parts.len() >= 3 {
             parts[2].parse::<i32>().unwrap_or(0)
         } else {
-            0
+            default_rank
         };
 
         // Parse pinyin - split by apostrophe
@@ -59,7 +68,7 @@ impl WordLibraryTextImport for QQPinyinImport {
         let mut wl = WordLibrary::new(word.to_string());
         wl.rank = rank;
         wl.code_type = CodeType::Pinyin;
-        wl.codes = Code::from_char_list(pinyin_parts);
+        wl.codes = Code::from_char_list(split_unseparated(word, pinyin_parts));
 
         Ok(Some(wl))
     }
@@ -73,6 +82,22 @@ impl WordLibraryImport for QQPinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -3,8 +3,14 @@
 //! Format: `word\tcode\trank`
 //! Example: `你好\tni hao\t1000`
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
-use crate::{Code, CodeType, Result, WordLibrary};
+use crate::helpers::pinyin::split_unseparated;
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, LibraryMetadata, Result, WordLibrary, WordLibraryList};
+use std::fs::File;
+use std::io::Read;
 
 /// Rime format importer
 pub struct RimeImport {
@@ -31,15 +37,22 @@ impl Default for RimeImport {
 
 impl WordLibraryTextImport for RimeImport {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             return Ok(None);
         }
+        if !line.contains('\t') && is_yaml_header(line) {
+            return Ok(None);
+        }
 
         let parts: Vec<&str> = line.split('\t').collect();
 
         if parts.len() < 2 {
-            return Ok(None);
+            return Err(malformed_line("word\tcode\trank"));
         }
 
         let word = parts[0];
@@ -47,7 +60,7 @@ impl WordLibraryTextImport for RimeImport {
         let rank = if parts.len() >= 3 {
             parts[2].parse::<i32>().unwrap_or(0)
         } else {
-            0
+            default_rank
         };
 
         let mut wl = WordLibrary::new(word.to_string());
@@ -58,7 +71,7 @@ impl WordLibraryTextImport for RimeImport {
         if self.code_type == CodeType::Pinyin {
             // Split by space for pinyin
             let codes: Vec<String> = code.split_whitespace().map(|s| s.to_string()).collect();
-            wl.codes = Code::from_char_list(codes);
+            wl.codes = Code::from_char_list(split_unseparated(word, codes));
         } else {
             // For other code types, treat as single code
             wl.codes = Code::from_single(code.to_string());
@@ -72,10 +85,91 @@ impl WordLibraryTextImport for RimeImport {
     }
 }
 
+/// Check whether a line belongs to the YAML header of a .dict.yaml file
+fn is_yaml_header(line: &str) -> bool {
+    line == "---"
+        || line == "..."
+        || line.starts_with("- ")
+        || line.contains(": ")
+        || line.ends_with(':')
+}
+
+/// Read the name and version from the YAML header of a .dict.yaml file
+fn parse_header(text: &str) -> LibraryMetadata {
+    let mut metadata = LibraryMetadata::default();
+    for line in text.lines() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line == "..." || line.contains('\t') {
+            break;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(": ") else {
+            continue;
+        };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        let field = match key {
+            "name" => &mut metadata.name,
+            "version" => &mut metadata.version,
+            _ => continue,
+        };
+        if !value.is_empty() {
+            *field = Some(value.to_string());
+        }
+    }
+    metadata
+}
+
+impl RimeImport {
+    /// Read the entries and the header of a .dict.yaml file
+    fn read_library(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<WordLibraryList> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let mut words =
+            WordLibraryList::from(self.read_with_options(&mut &data[..], name, options)?);
+        words.metadata = parse_header(&String::from_utf8_lossy(&data));
+        Ok(words)
+    }
+}
+
 impl WordLibraryImport for RimeImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn import_library(&self, path: &str, options: &ImportOptions) -> Result<WordLibraryList> {
+        self.read_library(&mut File::open(path)?, path, options)
+    }
+
+    fn import_library_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<WordLibraryList> {
+        self.read_library(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -92,6 +186,9 @@ mod tests {
         assert_eq!(word.word, "你好");
         assert_eq!(word.rank, 1000);
         assert_eq!(word.get_pinyin_string(" "), "ni hao");
+
+        let word = importer.import_line("西安	xian	10").unwrap().unwrap();
+        assert_eq!(word.get_pinyin_string(" "), "xi an");
     }
 
     #[test]
@@ -114,5 +211,16 @@ mod tests {
         assert!(result.is_some());
         let word = result.unwrap();
         assert_eq!(word.rank, 0);
+
+        let options = ImportOptions::new().with_default_rank(100);
+        let words = importer
+            .read_with_options(
+                &mut "你好\tni hao\n世界\tshi jie\t5\n".as_bytes(),
+                "-",
+                &options,
+            )
+            .unwrap();
+        let ranks: Vec<i32> = words.iter().map(|w| w.rank).collect();
+        assert_eq!(ranks, vec![100, 5]);
     }
 }
@@ -0,0 +1,137 @@
+//! Rime user dictionary snapshot import
+//!
+//! Format: `code \tword\tc=commits d=weight t=tick`, after `#@/key\tvalue`
+//! metadata lines
+//! Example: `ni hao \t你好\tc=3 d=2.7 t=693`
+
+use crate::data::attributes;
+use crate::helpers::pinyin::split_unseparated;
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
+
+/// Rime `.userdb.txt` snapshot importer
+///
+/// The commit count becomes the rank; the weight and tick are kept as
+/// attributes. Entries deleted by the user have a negative count and are
+/// skipped.
+pub struct RimeUserdbImport;
+
+impl RimeUserdbImport {
+    pub fn new() -> Self {
+        RimeUserdbImport
+    }
+}
+
+impl Default for RimeUserdbImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse the `#@/key\tvalue` metadata lines of a snapshot
+pub fn parse_metadata(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("#@/"))
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(key, value)| (key.to_string(), value.trim_end().to_string()))
+        .collect()
+}
+
+impl WordLibraryTextImport for RimeUserdbImport {
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        if line.trim().is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let mut parts = line.splitn(3, '\t');
+        let (Some(code), Some(word)) = (parts.next(), parts.next()) else {
+            return Err(malformed_line("code \tword\tc=commits d=weight t=tick"));
+        };
+        let fields = parts.next().unwrap_or_default();
+        let field = |prefix: &str| {
+            fields
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix(prefix))
+        };
+        let commits = field("c=").and_then(|c| c.parse::<i32>().ok()).unwrap_or(0);
+        if commits < 0 {
+            return Ok(None);
+        }
+
+        let mut wl = WordLibrary::with_rank(word.to_string(), commits);
+        if let Some(weight) = field("d=") {
+            wl.set_attribute(attributes::USERDB_WEIGHT, weight);
+        }
+        if let Some(tick) = field("t=") {
+            wl.set_attribute(attributes::USERDB_TICK, tick);
+        }
+        wl.code_type = CodeType::Pinyin;
+        let codes = code.split_whitespace().map(str::to_string).collect();
+        wl.codes = Code::from_char_list(split_unseparated(word, codes));
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "utf-8"
+    }
+}
+
+impl WordLibraryImport for RimeUserdbImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_encoding(path, self.default_encoding())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_line() {
+        let importer = RimeUserdbImport::new();
+        let word = importer
+            .import_line("ni hao \t你好\tc=3 d=2.7 t=693")
+            .unwrap()
+            .unwrap();
+        assert_eq!(word.word, "你好");
+        assert_eq!(word.rank, 3);
+        assert_eq!(word.get_pinyin_string(" "), "ni hao");
+
+        assert!(importer
+            .import_line("#@/db_type\tuserdb")
+            .unwrap()
+            .is_none());
+        assert!(importer
+            .import_line("shi jie \t世界\tc=-1 d=0.5 t=12")
+            .unwrap()
+            .is_none());
+        assert!(importer.import_line("broken").is_err());
+
+        let metadata = parse_metadata("# Rime user dictionary\n#@/db_name\tluna_pinyin.userdb\n");
+        assert_eq!(
+            metadata,
+            vec![("db_name".to_string(), "luna_pinyin.userdb".to_string())]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Recovery of entries from partially corrupt binary dictionaries
+//!
+//! In salvage mode, binary importers skip the blocks they cannot parse and
+//! resume at the next block that parses, instead of failing the import.
+//! The skipped byte ranges are recorded in a `SalvageReport`.
+
+use crate::import::{ErrorPolicy, ImportOptions};
+use serde::Serialize;
+use std::fmt;
+
+/// A byte range left out of a salvaged import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedRange {
+    /// Offset of the first skipped byte
+    pub start: usize,
+    /// Offset after the last skipped byte
+    pub end: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for SkippedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "skipped bytes 0x{:x}..0x{:x}: {}",
+            self.start, self.end, self.reason
+        )
+    }
+}
+
+/// Corruption found while salvaging a file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SalvageReport {
+    pub skipped: Vec<SkippedRange>,
+}
+
+impl SalvageReport {
+    /// Whether the file was read without skipping anything
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty()
+    }
+
+    /// Total number of skipped bytes
+    pub fn skipped_bytes(&self) -> usize {
+        self.skipped.iter().map(|r| r.end - r.start).sum()
+    }
+
+    pub(crate) fn skip(&mut self, start: usize, end: usize, reason: &str) {
+        self.skipped.push(SkippedRange {
+            start,
+            end,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Log the skipped ranges of `name` as warnings, unless the error policy
+    /// of `options` skips problems silently
+    pub(crate) fn warn(&self, name: &str, options: &ImportOptions) {
+        if options.error_policy == ErrorPolicy::Skip {
+            return;
+        }
+        for range in &self.skipped {
+            tracing::warn!("{}: {}", name, range);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut report = SalvageReport::default();
+        assert!(report.is_clean());
+        report.skip(0x10, 0x30, "invalid entry");
+        report.skip(0x40, 0x48, "truncated");
+        assert_eq!(report.skipped_bytes(), 0x28);
+        assert_eq!(
+            report.skipped[0].to_string(),
+            "skipped bytes 0x10..0x30: invalid entry"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Baidu Shouxin (手心输入法) text import
+//!
+//! Format: `word\tpinyin` with an optional rank column, UTF-16LE
+//! Example: `你好\tni'hao\t1000`
+
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
+
+/// Baidu Shouxin format importer
+pub struct ShouxinImport;
+
+impl ShouxinImport {
+    pub fn new() -> Self {
+        ShouxinImport
+    }
+}
+
+impl Default for ShouxinImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryTextImport for ShouxinImport {
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() < 2 || parts[0].is_empty() || parts[1].is_empty() {
+            return Err(malformed_line("word\\tpinyin[\\trank]"));
+        }
+        let rank = match parts.get(2) {
+            Some(rank) => rank.trim().parse::<i32>().unwrap_or(0),
+            None => default_rank,
+        };
+
+        let mut wl = WordLibrary::with_rank(parts[0].to_string(), rank);
+        wl.code_type = CodeType::Pinyin;
+        let syllables: Vec<String> = parts[1]
+            .split('\'')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_lowercase())
+            .collect();
+        wl.codes = Code::from_char_list(syllables);
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "utf-16le"
+    }
+}
+
+impl WordLibraryImport for ShouxinImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_encoding(path, self.default_encoding())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_sample() {
+        let sample = "\u{feff}你好\tni'hao\t1000\r\n中国\tzhong'guo\r\n";
+        let bytes: Vec<u8> = sample.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let words = ShouxinImport::new()
+            .import_from_reader(
+                &mut bytes.as_slice(),
+                &ImportOptions::new().with_default_rank(5),
+            )
+            .unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "你好");
+        assert_eq!(words[0].rank, 1000);
+        assert_eq!(words[0].get_pinyin_string("'"), "ni'hao");
+        assert_eq!(words[1].word, "中国");
+        assert_eq!(words[1].rank, 5);
+        assert!(ShouxinImport::new().import_line("你好 ni'hao").is_err());
+    }
+}
@@ -1,10 +1,14 @@
 //! Sina Pinyin format import
 //!
-//! Format: `word	code	rank`
-//! Example: `你好	ni'hao	1000`
+//! Format: `word\tcode\trank`
+//! Example: `你好\tni'hao\t1000`
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
 
 /// Sina Pinyin format importer
 pub struct SinaPinyinImport;
@@ -23,6 +27,10 @@ impl Default for SinaPinyinImport {
 
 impl WordLibraryTextImport for SinaPinyinImport {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             return Ok(None);
@@ -31,7 +39,7 @@ impl WordLibraryTextImport for SinaPinyinImport {
         let parts: Vec<&str> = line.split('\t').collect();
 
         if parts.len() < 2 {
-            return Ok(None);
+            return Err(malformed_line("word\tcode\trank"));
         }
 
         let word = parts[0];
@@ -39,7 +47,7 @@ impl WordLibraryTextImport for SinaPinyinImport {
         let rank = if parts.len() >= 3 {
             parts[2].parse::<i32>().unwrap_or(0)
         } else {
-            0
+            default_rank
         };
 
         let mut wl = WordLibrary::new(word.to_string());
@@ -62,6 +70,22 @@ impl WordLibraryImport for SinaPinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,168 @@
+//! Sogou custom phrase (搜狗自定义短语, PhraseEdit.txt) import
+//!
+//! Format: `code,position=phrase`, GBK, with `;` comment lines
+//! Example: `dz,1=北京市海淀区`
+//!
+//! Lines that are neither a comment nor an entry continue the phrase of the
+//! entry above, so phrases can span several lines. Phrases starting with `#`
+//! are Sogou's function phrases (e.g. `#$year年$month月`) and kept as is.
+
+use crate::import::{
+    malformed_line, read_with_encoding_str, ImportOptions, WordLibraryImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+/// Highest candidate position of a custom phrase
+pub const MAX_POSITION: i32 = 9;
+
+/// Split an entry line into its code, position and first phrase line
+///
+/// Codes consist of ASCII letters only; anything else is not an entry.
+pub fn parse_entry(line: &str) -> Option<(&str, i32, &str)> {
+    let (key, phrase) = line.split_once('=')?;
+    let (code, position) = key.split_once(',')?;
+    if code.is_empty() || !code.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+    let position = position.trim().parse::<i32>().ok()?;
+    Some((code, position, phrase))
+}
+
+/// Sogou custom phrase importer
+///
+/// The candidate position (1 to 9) is kept and becomes a rank, position 1
+/// ranking highest.
+pub struct SogouPhraseImport;
+
+impl SogouPhraseImport {
+    pub fn new() -> Self {
+        SogouPhraseImport
+    }
+
+    fn entry(code: &str, position: i32, phrase: &str) -> WordLibrary {
+        let position = position.clamp(1, MAX_POSITION);
+        let mut wl = WordLibrary::with_rank(phrase.to_string(), MAX_POSITION + 1 - position);
+        wl.code_type = CodeType::UserDefinePhrase;
+        wl.codes = Code::from_single(code.to_lowercase());
+        wl.position = Some(position as u8);
+        wl
+    }
+
+    /// Parse all lines of a reader; `name` identifies it in error messages
+    fn read(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options
+            .encoding
+            .as_deref()
+            .unwrap_or(self.default_encoding());
+        let content = read_with_encoding_str(reader, encoding)?;
+        let mut result = Vec::new();
+        let mut current: Option<WordLibrary> = None;
+
+        for (i, line) in content.lines().enumerate() {
+            if line.starts_with(';') {
+                continue;
+            }
+            if let Some((code, position, phrase)) = parse_entry(line) {
+                result.extend(current.take());
+                current = Some(Self::entry(code, position, phrase));
+                continue;
+            }
+            match &mut current {
+                Some(entry) => {
+                    entry.word.push('\n');
+                    entry.word.push_str(line);
+                }
+                None if line.trim().is_empty() => {}
+                None => {
+                    options.error_policy.apply(
+                        Err::<Option<()>, _>(malformed_line("code,position=phrase")),
+                        name,
+                        i + 1,
+                        line,
+                    )?;
+                }
+            }
+        }
+        result.extend(current);
+
+        // Blank lines before the next entry do not belong to the phrase
+        for entry in &mut result {
+            entry.word.truncate(entry.word.trim_end().len());
+        }
+        result.retain(|entry| !entry.word.is_empty());
+        Ok(result)
+    }
+}
+
+impl Default for SogouPhraseImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryTextImport for SogouPhraseImport {
+    /// Parse a single-line entry
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        if line.trim().is_empty() || line.starts_with(';') {
+            return Ok(None);
+        }
+        match parse_entry(line) {
+            Some((code, position, phrase)) => {
+                Ok(Some(Self::entry(code, position, phrase.trim_end())))
+            }
+            None => Err(malformed_line("code,position=phrase")),
+        }
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "gbk"
+    }
+}
+
+impl WordLibraryImport for SogouPhraseImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read(&mut File::open(path)?, path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read(reader, STDIO_PATH, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_line_phrase() {
+        let content = "; 自定义短语\r\ndz,1=北京市\r\n海淀区\r\n\r\nwx,2=😊\r\n";
+        let words = SogouPhraseImport::new()
+            .import_from_reader(&mut content.as_bytes(), &ImportOptions::new())
+            .unwrap();
+        let entries: Vec<(&str, Option<&str>, i32)> = words
+            .iter()
+            .map(|w| (w.word.as_str(), w.get_single_code(), w.rank))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![("北京市\n海淀区", Some("dz"), 9), ("😊", Some("wx"), 8)]
+        );
+        assert!(SogouPhraseImport::new().import_line("北京市").is_err());
+    }
+}
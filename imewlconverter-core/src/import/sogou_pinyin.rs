@@ -1,24 +1,37 @@
+use crate::helpers::pinyin::split_unseparated;
 /// Sogou Pinyin text format (.txt)
 /// Format: 'pinyin word
 /// Example: 'ni'hao 你好
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{CodeType, Result, WordLibrary};
+use std::io::Read;
 
 pub struct SogouPinyinImport;
 
 impl WordLibraryTextImport for SogouPinyinImport {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 1)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
         let line = line.trim();
 
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
         // Lines starting with ' are dictionary entries
         if !line.starts_with('\'') {
-            return Ok(None);
+            return Err(malformed_line("'pinyin word"));
         }
 
         // Parse: 'pinyin word
         let parts: Vec<&str> = line.split_whitespace().collect();
         if parts.len() < 2 {
-            return Ok(None);
+            return Err(malformed_line("'pinyin word"));
         }
 
         let pinyin_str = parts[0];
@@ -33,8 +46,8 @@ impl WordLibraryTextImport for SogouPinyinImport {
 
         let mut wl = WordLibrary::new(word.to_string());
         wl.code_type = CodeType::Pinyin;
-        wl.rank = 1;
-        wl.codes = crate::Code::from_char_list(pinyin);
+        wl.rank = default_rank;
+        wl.codes = crate::Code::from_char_list(split_unseparated(word, pinyin));
 
         Ok(Some(wl))
     }
@@ -48,6 +61,22 @@ impl WordLibraryImport for SogouPinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -1,33 +1,113 @@
-/// Sogou SCEL binary format parser
-/// This is the most popular binary dictionary format in China
-use crate::import::WordLibraryImport;
-use crate::{CodeType, Error, Result, WordLibrary};
-use nom::{bytes::complete::take, number::complete::le_u16, IResult};
-use std::collections::HashMap;
+//! Sogou SCEL binary format parser
+//!
+//! This is the most popular binary dictionary format in China. Layout:
+//! - header up to 0x1540: magic, group count (0x120), word count (0x124),
+//!   then name, category, description and examples as UTF-16LE
+//! - pinyin table at 0x1540: entry count, then index, byte length, pinyin
+//! - dictionary: the groups of words sharing a pinyin, one after another
+//! - optional deleted-word table: `DELTBL`, entry count, then each word's
+//!   length in characters and UTF-16LE text
+//!
+//! With `ImportOptions::with_salvage`, corrupt groups of the dictionary are
+//! skipped and reading resumes at the next group that parses.
+
+use crate::import::salvage::SalvageReport;
+use crate::import::{ImportOptions, WordLibraryImport, STDIO_PATH};
+use crate::{CodeType, Error, LibraryMetadata, Result, WordLibrary, WordLibraryList};
+use nom::bytes::complete::take;
+use nom::combinator::{map_opt, verify};
+use nom::multi::{length_count, length_data};
+use nom::number::complete::{le_u16, le_u32};
+use nom::IResult;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Read;
 
+/// Offset of the pinyin table, right after the header
+const PINYIN_TABLE_OFFSET: usize = 0x1540;
+/// Smallest pinyin table entry: index, length and one UTF-16 unit
+const MIN_PINYIN_ENTRY_SIZE: usize = 6;
+/// Smallest word of a dictionary group: length, one UTF-16 unit and the
+/// extension length
+const MIN_WORD_SIZE: usize = 6;
+
+/// Marker of the deleted-word table
+const DELETED_TABLE_MARKER: &[u8] = b"DELTBL";
+
 pub struct SogouScelImport;
 
 impl SogouScelImport {
-    /// Read SCEL file information without parsing dictionary
+    /// Read SCEL file information, including the deleted words
     pub fn read_info(path: &str) -> Result<ScelInfo> {
         let mut file = File::open(path)?;
         let mut buffer = Vec::new();
         file.read_to_end(&mut buffer)?;
 
-        let info = parse_scel_info(&buffer)?;
+        let (info, _) = parse_scel_file(&buffer)?;
         Ok(info)
     }
+
+    /// Read the entries that can be recovered from a partially corrupt SCEL
+    /// file, with the byte ranges that were skipped
+    ///
+    /// The header and the pinyin table must be intact.
+    pub fn salvage(data: &[u8]) -> Result<(ScelInfo, Vec<WordLibrary>, SalvageReport)> {
+        let mut report = SalvageReport::default();
+        let (info, words) = parse_scel(data, Some(&mut report))?;
+        Ok((info, words, report))
+    }
+
+    /// Read a whole file, salvaging it if the options ask for it
+    fn read(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<(ScelInfo, Vec<WordLibrary>)> {
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer)?;
+        if !options.salvage {
+            return parse_scel_file(&buffer);
+        }
+        let (info, words, report) = Self::salvage(&buffer)?;
+        report.warn(name, options);
+        Ok((info, words))
+    }
 }
 
 impl WordLibraryImport for SogouScelImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
-        let mut file = File::open(path)?;
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
+        self.import_from_reader(&mut File::open(path)?, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        Ok(self.read(&mut File::open(path)?, path, options)?.1)
+    }
 
-        parse_scel_file(&buffer)
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        Ok(self.read(reader, STDIO_PATH, options)?.1)
+    }
+
+    fn import_library(&self, path: &str, options: &ImportOptions) -> Result<WordLibraryList> {
+        let (info, words) = self.read(&mut File::open(path)?, path, options)?;
+        let mut words = WordLibraryList::from(words);
+        words.metadata = info.into();
+        Ok(words)
+    }
+
+    fn import_library_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<WordLibraryList> {
+        let (info, words) = self.read(reader, STDIO_PATH, options)?;
+        let mut words = WordLibraryList::from(words);
+        words.metadata = info.into();
+        Ok(words)
     }
 }
 
@@ -37,12 +117,28 @@ pub struct ScelInfo {
     pub category: String,
     pub description: String,
     pub example: String,
+    /// Number of groups of words sharing a pinyin
+    pub group_count: u32,
     pub word_count: u32,
+    /// Words of the deleted-word table, left out of imports
+    pub deleted_words: Vec<String>,
+}
+
+impl From<ScelInfo> for LibraryMetadata {
+    fn from(info: ScelInfo) -> Self {
+        let non_empty = |s: String| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        LibraryMetadata {
+            name: non_empty(info.name),
+            version: None,
+            category: non_empty(info.category),
+            description: non_empty(info.description),
+        }
+    }
 }
 
 /// Parse SCEL file information
 fn parse_scel_info(data: &[u8]) -> Result<ScelInfo> {
-    if data.len() < 0x1540 {
+    if data.len() < PINYIN_TABLE_OFFSET {
         return Err(Error::Parse("File too small to be valid SCEL".into()));
     }
 
@@ -51,8 +147,14 @@ fn parse_scel_info(data: &[u8]) -> Result<ScelInfo> {
         return Err(Error::Parse("Invalid SCEL magic number".into()));
     }
 
-    // Word count at 0x124
-    let word_count = u32::from_le_bytes([data[0x124], data[0x125], data[0x126], data[0x127]]);
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ])
+    };
 
     // Read UTF-16LE strings
     let name = read_utf16le_string(&data[0x130..0x338])?;
@@ -65,152 +167,232 @@ fn parse_scel_info(data: &[u8]) -> Result<ScelInfo> {
         category,
         description,
         example,
-        word_count,
+        group_count: read_u32(0x120),
+        word_count: read_u32(0x124),
+        deleted_words: Vec::new(),
     })
 }
 
-/// Parse the entire SCEL file and extract dictionary entries
-fn parse_scel_file(data: &[u8]) -> Result<Vec<WordLibrary>> {
-    if data.len() < 0x1540 {
-        return Err(Error::Parse("File too small to be valid SCEL".into()));
-    }
+/// Parse the entire SCEL file and extract dictionary entries, leaving out
+/// the deleted words
+fn parse_scel_file(data: &[u8]) -> Result<(ScelInfo, Vec<WordLibrary>)> {
+    parse_scel(data, None)
+}
 
-    // Parse pinyin table (starts around 0x1540)
-    let pinyin_table = parse_pinyin_table(data)?;
+/// Parse a SCEL file, skipping corrupt parts into `salvage` if given
+fn parse_scel(
+    data: &[u8],
+    mut salvage: Option<&mut SalvageReport>,
+) -> Result<(ScelInfo, Vec<WordLibrary>)> {
+    let mut info = parse_scel_info(data)?;
+    let (pinyin_table, dict_start) = parse_pinyin_table(data)?;
+    let (mut words, dict_end) = match salvage.as_deref_mut() {
+        Some(report) => {
+            salvage_dictionary(data, dict_start, info.group_count, &pinyin_table, report)
+        }
+        None => parse_dictionary(data, dict_start, info.group_count, &pinyin_table)?,
+    };
+
+    info.deleted_words = match (parse_deleted_words(data, dict_end), salvage) {
+        (Ok(deleted), _) => deleted,
+        (Err(_), Some(report)) => {
+            report.skip(dict_end, data.len(), "invalid deleted-word table");
+            Vec::new()
+        }
+        (Err(e), None) => return Err(e),
+    };
+    if !info.deleted_words.is_empty() {
+        let deleted: HashSet<&str> = info.deleted_words.iter().map(String::as_str).collect();
+        words.retain(|w| !deleted.contains(w.word.as_str()));
+    }
+    Ok((info, words))
+}
 
-    // Parse dictionary entries (starts after pinyin table)
-    let dict_start = find_dict_start(data)?;
-    parse_dictionary(&data[dict_start..], &pinyin_table)
+fn invalid_at(offset: usize, what: &str) -> Error {
+    Error::Parse(format!("Invalid SCEL {} at 0x{:x}", what, offset))
 }
 
-/// Parse the pinyin index table
-fn parse_pinyin_table(data: &[u8]) -> Result<HashMap<u16, String>> {
-    let mut table = HashMap::new();
-    let mut offset = 0x1540;
+/// Parse the pinyin index table, returning it with the offset of the
+/// dictionary that follows it
+fn parse_pinyin_table(data: &[u8]) -> Result<(HashMap<u16, String>, usize)> {
+    let mut offset = PINYIN_TABLE_OFFSET;
+    let (_, count) = le_u32::<_, nom::error::Error<&[u8]>>(&data[offset..])
+        .map_err(|_| invalid_at(offset, "pinyin table"))?;
+    offset += 4;
+    if count as usize > (data.len() - offset) / MIN_PINYIN_ENTRY_SIZE {
+        return Err(Error::Parse(format!(
+            "SCEL pinyin table count {} exceeds the file size",
+            count
+        )));
+    }
 
-    while offset < data.len() - 4 {
-        if let Ok((_, (index, pinyin))) = parse_pinyin_entry(&data[offset..]) {
-            if index == 0 {
-                break; // End of pinyin table
-            }
-            let pinyin_len = pinyin.encode_utf16().count();
-            table.insert(index, pinyin.clone());
-            offset += 2 + 2 + pinyin_len * 2; // index + length + utf16 chars
-        } else {
-            break;
-        }
+    let mut table = HashMap::with_capacity(count as usize);
+    for _ in 0..count {
+        let (remaining, (index, pinyin)) =
+            parse_pinyin_entry(&data[offset..]).map_err(|_| invalid_at(offset, "pinyin"))?;
+        table.insert(index, pinyin);
+        offset = data.len() - remaining.len();
     }
 
-    Ok(table)
+    Ok((table, offset))
 }
 
 /// Parse a single pinyin table entry
 fn parse_pinyin_entry(data: &[u8]) -> IResult<&[u8], (u16, String)> {
     let (data, index) = le_u16(data)?;
-    let (data, length) = le_u16(data)?;
-
-    let byte_len = length as usize * 2;
-    let (data, pinyin_bytes) = take(byte_len)(data)?;
-
-    let pinyin = String::from_utf16_lossy(
-        &pinyin_bytes
-            .chunks(2)
-            .map(|c| u16::from_le_bytes([c[0], c[1]]))
-            .collect::<Vec<_>>(),
-    );
-
+    let (data, pinyin) = map_opt(length_data(even_length), |bytes: &[u8]| {
+        read_utf16(bytes).filter(|py| !py.is_empty() && py.chars().all(|c| c.is_ascii_lowercase()))
+    })(data)?;
     Ok((data, (index, pinyin)))
 }
 
-/// Find where dictionary entries start
-fn find_dict_start(data: &[u8]) -> Result<usize> {
-    // Dictionary typically starts after pinyin table
-    // Look for pattern or use heuristic
-    for i in 0x1540..data.len() - 10 {
-        // Dictionary entries have a specific pattern
-        if data[i] == 0 && data[i + 1] == 0 && data[i + 2] > 0 && data[i + 3] == 0 {
-            return Ok(i);
-        }
+/// Parse the dictionary: `group_count` groups of words sharing a pinyin
+///
+/// Returns the words with the offset following the last group.
+fn parse_dictionary(
+    data: &[u8],
+    start: usize,
+    group_count: u32,
+    pinyin_table: &HashMap<u16, String>,
+) -> Result<(Vec<WordLibrary>, usize)> {
+    let mut entries = Vec::new();
+    let mut offset = start;
+
+    for _ in 0..group_count {
+        let (remaining, words) = parse_dict_group(&data[offset..], pinyin_table).map_err(|_| {
+            if offset == data.len() {
+                Error::Parse("SCEL dictionary is truncated".into())
+            } else {
+                invalid_at(offset, "entry")
+            }
+        })?;
+        entries.extend(words);
+        offset = data.len() - remaining.len();
     }
-    Err(Error::Parse("Could not find dictionary start".into()))
+
+    Ok((entries, offset))
 }
 
-/// Parse dictionary entries
-fn parse_dictionary(data: &[u8], pinyin_table: &HashMap<u16, String>) -> Result<Vec<WordLibrary>> {
+/// Parse the dictionary like `parse_dictionary`, skipping corrupt groups
+///
+/// After a group fails to parse, reading resumes at the next offset where a
+/// plausible group starts. The deleted-word table, if found, bounds the
+/// dictionary.
+fn salvage_dictionary(
+    data: &[u8],
+    start: usize,
+    group_count: u32,
+    pinyin_table: &HashMap<u16, String>,
+    report: &mut SalvageReport,
+) -> (Vec<WordLibrary>, usize) {
+    let end = data[start..]
+        .windows(DELETED_TABLE_MARKER.len())
+        .position(|w| w == DELETED_TABLE_MARKER)
+        .map_or(data.len(), |pos| start + pos);
     let mut entries = Vec::new();
-    let mut offset = 0;
-
-    while offset < data.len() - 14 {
-        match parse_dict_entry(&data[offset..], pinyin_table) {
-            Ok((remaining, entry)) => {
-                if let Some(wl) = entry {
-                    entries.push(wl);
-                }
-                let consumed = data[offset..].len() - remaining.len();
-                offset += consumed;
+    let mut offset = start;
+    let mut groups = 0;
+    let skipped = report.skipped.len();
+
+    while groups < group_count && offset < end {
+        match parse_dict_group(&data[offset..end], pinyin_table) {
+            Ok((remaining, words)) => {
+                entries.extend(words);
+                offset = end - remaining.len();
+                groups += 1;
             }
             Err(_) => {
-                offset += 1; // Skip bad byte
+                let resume = (offset + 1..end)
+                    .find(|&o| is_plausible_group(&data[o..end], pinyin_table))
+                    .unwrap_or(end);
+                report.skip(offset, resume, "invalid entry");
+                offset = resume;
             }
         }
-
-        if entries.len() >= 100000 {
-            break; // Safety limit
-        }
+    }
+    // Skipped ranges hide how many groups they held
+    if groups < group_count && report.skipped.len() == skipped {
+        report.skip(offset, end, "dictionary is truncated");
     }
 
-    Ok(entries)
+    (entries, offset)
 }
 
-/// Parse a single dictionary entry
-fn parse_dict_entry<'a>(
+/// Whether a group parses at the start of `data` with every word as long as
+/// its pinyin, so that resuming does not start inside a word
+fn is_plausible_group(data: &[u8], pinyin_table: &HashMap<u16, String>) -> bool {
+    matches!(
+        parse_dict_group(data, pinyin_table),
+        Ok((_, words)) if words.iter().all(|w| w.word.chars().count() == w.codes.len())
+    )
+}
+
+/// Parse the deleted-word table at `offset`, if there is one
+fn parse_deleted_words(data: &[u8], offset: usize) -> Result<Vec<String>> {
+    let Some(table) = data[offset..].strip_prefix(DELETED_TABLE_MARKER) else {
+        return Ok(Vec::new());
+    };
+    let offset = offset + DELETED_TABLE_MARKER.len();
+    let (_, words) = length_count(le_u16, deleted_word)(table)
+        .map_err(|_| invalid_at(offset, "deleted-word table"))?;
+    Ok(words)
+}
+
+/// A deleted word: its length in characters, then its UTF-16LE text
+fn deleted_word(data: &[u8]) -> IResult<&[u8], String> {
+    let (data, len) = verify(le_u16, |&n| n > 0)(data)?;
+    map_opt(take(len as usize * 2), read_utf16)(data)
+}
+
+/// Parse a group of words sharing a pinyin
+///
+/// Layout: word count, byte length of the pinyin indices, the indices, then
+/// for each word its byte length, UTF-16LE text, extension byte length and
+/// extension data. The extension starts with the word's frequency.
+fn parse_dict_group<'a>(
     data: &'a [u8],
     pinyin_table: &HashMap<u16, String>,
-) -> IResult<&'a [u8], Option<WordLibrary>> {
-    let (data, same_pinyin_count) = le_u16(data)?;
-    let (data, pinyin_len) = le_u16(data)?;
-
-    // Read pinyin indices
-    let mut pinyin_parts = Vec::new();
+) -> IResult<&'a [u8], Vec<WordLibrary>> {
+    let (data, word_count) = verify(le_u16, |&n| n > 0)(data)?;
+    let (data, pinyin) = map_opt(length_data(even_length), |bytes: &[u8]| {
+        bytes
+            .chunks_exact(2)
+            .map(|c| pinyin_table.get(&u16::from_le_bytes([c[0], c[1]])).cloned())
+            .collect::<Option<Vec<_>>>()
+    })(data)?;
+
+    let mut words = Vec::with_capacity((word_count as usize).min(data.len() / MIN_WORD_SIZE));
     let mut remaining = data;
-    for _ in 0..pinyin_len {
-        let (r, index) = le_u16(remaining)?;
-        if let Some(py) = pinyin_table.get(&index) {
-            pinyin_parts.push(py.clone());
-        }
+    for _ in 0..word_count {
+        let (r, word) = map_opt(length_data(even_length), read_utf16)(remaining)?;
+        let (r, ext) = length_data(le_u16)(r)?;
+        let frequency = match ext {
+            [low, high, ..] => u16::from_le_bytes([*low, *high]),
+            _ => 0,
+        };
+
+        let mut wl = WordLibrary::with_rank(word, i32::from(frequency));
+        wl.code_type = CodeType::Pinyin;
+        wl.codes = crate::Code::from_char_list(pinyin.clone());
+        words.push(wl);
         remaining = r;
     }
 
-    // Read words with same pinyin
-    let mut words = Vec::new();
-    for _ in 0..same_pinyin_count {
-        let (r, word_len) = le_u16(remaining)?;
-        let byte_len = word_len as usize * 2;
-        let (r, word_bytes) = take(byte_len)(r)?;
-
-        let word = String::from_utf16_lossy(
-            &word_bytes
-                .chunks(2)
-                .map(|c| u16::from_le_bytes([c[0], c[1]]))
-                .collect::<Vec<_>>(),
-        );
-
-        let (r, ext_len) = le_u16(r)?;
-        let (r, _ext) = take(ext_len as usize * 2)(r)?; // Skip extension
+    Ok((remaining, words))
+}
 
-        words.push(word);
-        remaining = r;
-    }
+/// A non-zero, even byte length of UTF-16 data
+fn even_length(data: &[u8]) -> IResult<&[u8], u16> {
+    verify(le_u16, |&n| n > 0 && n % 2 == 0)(data)
+}
 
-    // Return first word (or could return all)
-    if let Some(word) = words.first() {
-        let mut wl = WordLibrary::new(word.clone());
-        wl.code_type = CodeType::Pinyin;
-        wl.codes = crate::Code::from_char_list(pinyin_parts);
-        Ok((remaining, Some(wl)))
-    } else {
-        Ok((remaining, None))
-    }
+/// Decode UTF-16LE bytes, None on unpaired surrogates
+fn read_utf16(bytes: &[u8]) -> Option<String> {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
 }
 
 /// Read a null-terminated UTF-16LE string
@@ -241,17 +423,115 @@ mod tests {
         assert_eq!(result, "Test");
     }
 
-    #[test]
-    fn test_scel_info_parse() {
-        // This would require a real SCEL file to test properly
-        // For now, just ensure the struct exists
-        let info = ScelInfo {
-            name: "Test".to_string(),
-            category: "Test".to_string(),
-            description: "Test description".to_string(),
-            example: "Test example".to_string(),
-            word_count: 1000,
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    fn push_string(data: &mut Vec<u8>, s: &str) {
+        let bytes = utf16(s);
+        data.extend((bytes.len() as u16).to_le_bytes());
+        data.extend(bytes);
+    }
+
+    /// A SCEL file with the groups ni'hao (你好, 拟好) and shi (是)
+    fn sample_scel(pinyin_index: u16) -> Vec<u8> {
+        let mut data = vec![0u8; PINYIN_TABLE_OFFSET];
+        data[..12].copy_from_slice(b"\x40\x15\x00\x00\x44\x43\x53\x01\x01\x00\x00\x00");
+        data[0x120..0x124].copy_from_slice(&2u32.to_le_bytes());
+        data[0x124..0x128].copy_from_slice(&3u32.to_le_bytes());
+        let name = utf16("测试");
+        data[0x130..0x130 + name.len()].copy_from_slice(&name);
+
+        data.extend(3u32.to_le_bytes());
+        for (index, pinyin) in [(0u16, "ni"), (1, "hao"), (2, "shi")] {
+            data.extend(index.to_le_bytes());
+            push_string(&mut data, pinyin);
+        }
+
+        let ext = |frequency: u16| {
+            let mut ext = [0u8; 10];
+            ext[..2].copy_from_slice(&frequency.to_le_bytes());
+            ext
         };
-        assert_eq!(info.name, "Test");
+        data.extend(2u16.to_le_bytes());
+        data.extend(4u16.to_le_bytes());
+        data.extend(0u16.to_le_bytes());
+        data.extend(pinyin_index.to_le_bytes());
+        for (word, frequency) in [("你好", 300), ("拟好", 20)] {
+            push_string(&mut data, word);
+            data.extend(10u16.to_le_bytes());
+            data.extend(ext(frequency));
+        }
+        data.extend(1u16.to_le_bytes());
+        data.extend(2u16.to_le_bytes());
+        data.extend(2u16.to_le_bytes());
+        push_string(&mut data, "是");
+        data.extend(10u16.to_le_bytes());
+        data.extend(ext(500));
+        data
+    }
+
+    #[test]
+    fn test_parse_scel() {
+        let data = sample_scel(1);
+        let info = parse_scel_info(&data).unwrap();
+        assert_eq!((info.name.as_str(), info.group_count), ("测试", 2));
+
+        let (_, words) = parse_scel_file(&data).unwrap();
+        let words: Vec<String> = words
+            .iter()
+            .map(|w| format!("{} {} {}", w.word, w.get_pinyin_string("'"), w.rank))
+            .collect();
+        assert_eq!(
+            words,
+            vec!["你好 ni'hao 300", "拟好 ni'hao 20", "是 shi 500"]
+        );
+
+        // Unknown pinyin indices and truncated files are rejected
+        assert!(parse_scel_file(&sample_scel(7)).is_err());
+        assert!(parse_scel_file(&data[..data.len() - 4]).is_err());
+
+        // A pinyin count larger than the file is rejected before allocating
+        let mut huge = data.clone();
+        huge[PINYIN_TABLE_OFFSET..PINYIN_TABLE_OFFSET + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_pinyin_table(&huge).is_err());
+    }
+
+    #[test]
+    fn test_salvage() {
+        let mut data = sample_scel(1);
+        // Corrupt the pinyin index of the first group (ni'hao)
+        let first_group = data.len() - 66;
+        data[first_group + 4..first_group + 6].copy_from_slice(&9u16.to_le_bytes());
+        assert!(parse_scel_file(&data).is_err());
+
+        let (_, words, report) = SogouScelImport::salvage(&data).unwrap();
+        let words: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["是"]);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].start, first_group);
+        assert_eq!(report.skipped[0].end, data.len() - 22);
+
+        data.truncate(data.len() - 4);
+        let (_, words, report) = SogouScelImport::salvage(&data).unwrap();
+        assert!(words.is_empty());
+        assert_eq!(report.skipped.last().unwrap().end, data.len());
+    }
+
+    #[test]
+    fn test_deleted_words() {
+        let mut data = sample_scel(1);
+        data.extend(DELETED_TABLE_MARKER);
+        data.extend(1u16.to_le_bytes());
+        data.extend(2u16.to_le_bytes());
+        data.extend(utf16("拟好"));
+
+        let (info, words) = parse_scel_file(&data).unwrap();
+        assert_eq!(info.deleted_words, vec!["拟好"]);
+        let words: Vec<&str> = words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["你好", "是"]);
+
+        data.truncate(data.len() - 2);
+        assert!(parse_scel_file(&data).is_err());
     }
 }
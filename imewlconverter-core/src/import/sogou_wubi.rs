@@ -0,0 +1,128 @@
+//! Sogou Wubi (搜狗五笔) user dictionary import
+//!
+//! Format: `code=word`, GBK, with `;` comment lines
+//! Example: `vqkb=你好`
+//!
+//! Lines sharing a code list its candidates in order, first candidate first.
+
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Sogou Wubi user dictionary importer
+///
+/// The candidate order becomes the rank: the first word of a code ranks
+/// highest, so exporters grouping by code keep the order.
+pub struct SogouWubiImport;
+
+impl SogouWubiImport {
+    pub fn new() -> Self {
+        SogouWubiImport
+    }
+}
+
+impl Default for SogouWubiImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rank the words of each code by their order
+fn rank_by_order(words: &mut [WordLibrary]) {
+    let mut counts: HashMap<String, i32> = HashMap::new();
+    for word in words.iter() {
+        *counts
+            .entry(word.get_word_code().unwrap_or_default())
+            .or_default() += 1;
+    }
+    for word in words.iter_mut() {
+        let remaining = counts
+            .get_mut(&word.get_word_code().unwrap_or_default())
+            .expect("counted above");
+        word.rank = *remaining;
+        *remaining -= 1;
+    }
+}
+
+impl WordLibraryTextImport for SogouWubiImport {
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            return Ok(None);
+        }
+        let Some((code, word)) = line.split_once('=') else {
+            return Err(malformed_line("code=word"));
+        };
+        let (code, word) = (code.trim(), word.trim());
+        if code.is_empty() || !code.bytes().all(|b| b.is_ascii_alphabetic()) || word.is_empty() {
+            return Err(malformed_line("code=word"));
+        }
+
+        let mut wl = WordLibrary::with_rank(word.to_string(), default_rank);
+        wl.code_type = CodeType::Wubi;
+        wl.codes = Code::from_single(code.to_lowercase());
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "gbk"
+    }
+}
+
+impl WordLibraryImport for SogouWubiImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        let mut words = self.read_file_with_options(path, options)?;
+        rank_by_order(&mut words);
+        Ok(words)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let mut words = self.read_with_options(reader, STDIO_PATH, options)?;
+        rank_by_order(&mut words);
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_order() {
+        let input = ";搜狗五笔用户词库\r\nvqkb=你好\r\na=工\r\nvqkb=你们好\r\n";
+        let words = SogouWubiImport::new()
+            .import_from_reader(
+                &mut input.as_bytes(),
+                &ImportOptions::new().with_encoding("utf-8"),
+            )
+            .unwrap();
+        let entries: Vec<(&str, Option<String>, i32)> = words
+            .iter()
+            .map(|w| (w.word.as_str(), w.get_word_code(), w.rank))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("你好", Some("vqkb".to_string()), 2),
+                ("工", Some("a".to_string()), 1),
+                ("你们好", Some("vqkb".to_string()), 1),
+            ]
+        );
+        assert!(SogouWubiImport::new().import_line("vqkb 你好").is_err());
+    }
+}
@@ -1,15 +1,25 @@
+use crate::generate::wubi::WubiGenerator;
 /// Wubi input method formats (86/98/NewAge)
 /// Format: word\tcode1 code2 code3\trank
 /// Example: 你好\tni hao\t1000
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
-use crate::{CodeType, Result, WordLibrary};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
 
 /// Wubi 86 format importer
 pub struct Wubi86Import;
 
 impl WordLibraryTextImport for Wubi86Import {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
-        parse_wubi_line(line, CodeType::Wubi)
+        parse_wubi_line(line, CodeType::Wubi, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        parse_wubi_line(line, CodeType::Wubi, default_rank)
     }
 
     fn default_encoding(&self) -> &'static str {
@@ -21,6 +31,22 @@ impl WordLibraryImport for Wubi86Import {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 /// Wubi 98 format importer
@@ -28,7 +54,11 @@ pub struct Wubi98Import;
 
 impl WordLibraryTextImport for Wubi98Import {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
-        parse_wubi_line(line, CodeType::Wubi98)
+        parse_wubi_line(line, CodeType::Wubi98, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        parse_wubi_line(line, CodeType::Wubi98, default_rank)
     }
 
     fn default_encoding(&self) -> &'static str {
@@ -40,6 +70,22 @@ impl WordLibraryImport for Wubi98Import {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 /// Wubi New Age format importer
@@ -47,7 +93,11 @@ pub struct WubiNewAgeImport;
 
 impl WordLibraryTextImport for WubiNewAgeImport {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
-        parse_wubi_line(line, CodeType::WubiNewAge)
+        parse_wubi_line(line, CodeType::WubiNewAge, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        parse_wubi_line(line, CodeType::WubiNewAge, default_rank)
     }
 
     fn default_encoding(&self) -> &'static str {
@@ -59,11 +109,31 @@ impl WordLibraryImport for WubiNewAgeImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 /// Common parsing logic for Wubi formats
 /// Format can be: word\tcode or word code
-fn parse_wubi_line(line: &str, code_type: CodeType) -> Result<Option<WordLibrary>> {
+fn parse_wubi_line(
+    line: &str,
+    code_type: CodeType,
+    default_rank: i32,
+) -> Result<Option<WordLibrary>> {
     let line = line.trim();
     if line.is_empty() || line.starts_with('#') {
         return Ok(None);
@@ -77,34 +147,46 @@ fn parse_wubi_line(line: &str, code_type: CodeType) -> Result<Option<WordLibrary
     };
 
     if parts.len() < 2 {
-        return Ok(None);
+        return Err(malformed_line("word\tcode\trank"));
     }
 
     let word = parts[0];
     let code_str = parts[1];
 
     // Wubi codes can be space-separated or continuous
-    let codes: Vec<String> = if code_str.contains(' ') {
-        code_str.split_whitespace().map(|s| s.to_string()).collect()
+    let codes = if code_str.contains(' ') {
+        Code::from_char_list(code_str.split_whitespace().map(|s| s.to_string()).collect())
     } else {
-        // For single character, it's one code; for words, split by character length
-        vec![code_str.to_string()]
+        split_continuous_code(word, code_str, code_type)
+            .unwrap_or_else(|| Code::from_single(code_str.to_string()))
     };
 
     let rank = if parts.len() >= 3 {
         parts[2].parse().unwrap_or(0)
     } else {
-        0
+        default_rank
     };
 
     let mut wl = WordLibrary::new(word.to_string());
     wl.code_type = code_type;
     wl.rank = rank;
-    wl.codes = crate::Code::from_char_list(codes);
+    wl.codes = codes;
 
     Ok(Some(wl))
 }
 
+/// Split the continuous code of a word into per-character codes, using the
+/// Wubi tables; None for single characters or codes not following the rules
+fn split_continuous_code(word: &str, code: &str, code_type: CodeType) -> Option<Code> {
+    if word.chars().count() < 2 {
+        return None;
+    }
+    WubiGenerator::with_resources(ResourceManager::shared())
+        .with_code_type(code_type)
+        .ok()?
+        .split_code(word, code)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,5 +225,11 @@ mod tests {
         let wl = result.unwrap();
         assert_eq!(wl.word, "你好");
         assert_eq!(wl.rank, 1000);
+        assert_eq!(wl.codes.to_string_with_separator(" "), "wq vb");
+        assert_eq!(wl.get_word_code().as_deref(), Some("wqvb"));
+
+        // Codes not following the word rule are kept whole
+        let wl = importer.import_line("你好\tabcd").unwrap().unwrap();
+        assert_eq!(wl.get_single_code(), Some("abcd"));
     }
 }
@@ -0,0 +1,141 @@
+//! Yong (小小输入法) code table import
+//!
+//! Format: `code word1 word2 ...` after a `[DATA]` line, preceded by
+//! `key=value` settings
+//! Example: `vqkb 你好 你们好`
+
+use crate::helpers::AUTO_ENCODING;
+use crate::import::{
+    malformed_line, read_with_encoding_str, ImportOptions, WordLibraryImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+/// Yong code table importer
+///
+/// Words sharing a code get decreasing ranks in their order on the line.
+pub struct YongImport;
+
+impl YongImport {
+    pub fn new() -> Self {
+        YongImport
+    }
+
+    /// Parse all words of a line, which share the same code
+    fn parse_line(&self, line: &str) -> Result<Vec<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(Vec::new());
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 {
+            return Err(malformed_line("code word1 word2 ..."));
+        }
+
+        Ok(parts[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let rank = (parts.len() - 1 - i) as i32;
+                let mut wl = WordLibrary::with_rank(word.to_string(), rank);
+                wl.code_type = CodeType::Yong;
+                wl.codes = Code::from_single(parts[0].to_string());
+                wl
+            })
+            .collect())
+    }
+
+    /// Parse the data section of a reader; `name` identifies it in error
+    /// messages
+    fn read(
+        &self,
+        reader: &mut dyn Read,
+        name: &str,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let encoding = options
+            .encoding
+            .as_deref()
+            .unwrap_or(self.default_encoding());
+        let content = read_with_encoding_str(reader, encoding)?;
+        // Tables without settings have no [DATA] line
+        let mut in_data = !content.lines().any(|line| line.trim() == "[DATA]");
+        let mut result = Vec::new();
+
+        for (i, line) in content.lines().enumerate() {
+            if line.trim() == "[DATA]" {
+                in_data = true;
+            } else if in_data {
+                let words = options.error_policy.apply(
+                    self.parse_line(line).map(Some),
+                    name,
+                    i + 1,
+                    line,
+                )?;
+                result.extend(words.into_iter().flatten());
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl Default for YongImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryTextImport for YongImport {
+    /// Parse the first word of a line
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        Ok(self.parse_line(line)?.into_iter().next())
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        AUTO_ENCODING
+    }
+}
+
+impl WordLibraryImport for YongImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_with_options(path, &ImportOptions::new())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read(&mut File::open(path)?, path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read(reader, STDIO_PATH, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_table() {
+        let table = "name=五笔\nkey=abcdefghijklmnopqrstuvwxy\nlen=4\n[DATA]\na 工 式\nvqkb 你好\n";
+        let words = YongImport::new()
+            .import_from_reader(&mut table.as_bytes(), &ImportOptions::new())
+            .unwrap();
+        let entries: Vec<(&str, &str, i32)> = words
+            .iter()
+            .map(|w| (w.get_single_code().unwrap(), w.word.as_str(), w.rank))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![("a", "工", 2), ("a", "式", 1), ("vqkb", "你好", 1)]
+        );
+        assert_eq!(words[0].code_type, CodeType::Yong);
+    }
+}
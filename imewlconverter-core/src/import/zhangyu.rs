@@ -0,0 +1,106 @@
+//! Zhangyu (章鱼输入法) text import
+//!
+//! Format: `word,pinyin,rank` with space-separated syllables, GBK
+//! Example: `你好,ni hao,1000`
+
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
+use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
+
+/// Zhangyu format importer
+pub struct ZhangyuImport;
+
+impl ZhangyuImport {
+    pub fn new() -> Self {
+        ZhangyuImport
+    }
+}
+
+impl Default for ZhangyuImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryTextImport for ZhangyuImport {
+    fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            return Ok(None);
+        }
+
+        let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+        if !(2..=3).contains(&parts.len()) || parts[0].is_empty() || parts[1].is_empty() {
+            return Err(malformed_line("word,pinyin,rank"));
+        }
+        let rank = match parts.get(2) {
+            Some(rank) if !rank.is_empty() => rank.parse::<i32>().unwrap_or(0),
+            _ => default_rank,
+        };
+
+        let mut wl = WordLibrary::with_rank(parts[0].to_string(), rank);
+        wl.code_type = CodeType::Pinyin;
+        let syllables: Vec<String> = parts[1]
+            .split_whitespace()
+            .map(|s| s.to_lowercase())
+            .collect();
+        wl.codes = Code::from_char_list(syllables);
+        Ok(Some(wl))
+    }
+
+    fn default_encoding(&self) -> &'static str {
+        "gbk"
+    }
+}
+
+impl WordLibraryImport for ZhangyuImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_encoding(path, self.default_encoding())
+    }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::encode_text;
+
+    #[test]
+    fn test_import_sample() {
+        let sample = encode_text(
+            "; 章鱼输入法词库\r\n你好,ni hao,1000\r\n长城,chang cheng,\r\n",
+            "gbk",
+        );
+        let words = ZhangyuImport::new()
+            .import_from_reader(&mut sample.as_slice(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "你好");
+        assert_eq!(words[0].rank, 1000);
+        assert_eq!(words[1].get_pinyin_string("'"), "chang'cheng");
+        assert_eq!(words[1].rank, 0);
+        assert!(ZhangyuImport::new().import_line("你好 ni hao 1").is_err());
+    }
+}
@@ -3,8 +3,12 @@
 //! Format: `code=word rank` or `code word rank`
 //! Example: `ni'hao=你好 1000` or `ni'hao 你好 1000`
 
-use crate::import::{WordLibraryImport, WordLibraryTextImport};
+use crate::import::{
+    malformed_line, ImportOptions, WordLibraryImport, WordLibraryStreamImport,
+    WordLibraryTextImport, STDIO_PATH,
+};
 use crate::{Code, CodeType, Result, WordLibrary};
+use std::io::Read;
 
 /// ZiGuang Pinyin format importer
 pub struct ZiguangPinyinImport;
@@ -23,6 +27,10 @@ impl Default for ZiguangPinyinImport {
 
 impl WordLibraryTextImport for ZiguangPinyinImport {
     fn import_line(&self, line: &str) -> Result<Option<WordLibrary>> {
+        self.import_line_with_rank(line, 0)
+    }
+
+    fn import_line_with_rank(&self, line: &str, default_rank: i32) -> Result<Option<WordLibrary>> {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             return Ok(None);
@@ -33,18 +41,18 @@ impl WordLibraryTextImport for ZiguangPinyinImport {
             // Format: code=word rank
             let parts: Vec<&str> = line.split_whitespace().collect();
             if parts.is_empty() {
-                return Ok(None);
+                return Err(malformed_line("code=word rank or code word rank"));
             }
 
             let code_word: Vec<&str> = parts[0].split('=').collect();
             if code_word.len() != 2 {
-                return Ok(None);
+                return Err(malformed_line("code=word rank or code word rank"));
             }
 
             let rank = if parts.len() > 1 {
                 parts[1].parse::<i32>().unwrap_or(0)
             } else {
-                0
+                default_rank
             };
 
             (code_word[0], code_word[1], rank)
@@ -52,13 +60,13 @@ impl WordLibraryTextImport for ZiguangPinyinImport {
             // Format: code word rank
             let parts: Vec<&str> = line.split_whitespace().collect();
             if parts.len() < 2 {
-                return Ok(None);
+                return Err(malformed_line("code=word rank or code word rank"));
             }
 
             let rank = if parts.len() > 2 {
                 parts[2].parse::<i32>().unwrap_or(0)
             } else {
-                0
+                default_rank
             };
 
             (parts[0], parts[1], rank)
@@ -84,6 +92,22 @@ impl WordLibraryImport for ZiguangPinyinImport {
     fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
         self.read_file_with_encoding(path, self.default_encoding())
     }
+
+    fn import_with_options(&self, path: &str, options: &ImportOptions) -> Result<Vec<WordLibrary>> {
+        self.read_file_with_options(path, options)
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        self.read_with_options(reader, STDIO_PATH, options)
+    }
+
+    fn as_stream_import(&self) -> Option<&dyn WordLibraryStreamImport> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,206 @@
+//! ZiGuang Pinyin (紫光拼音) binary user dictionary import (.uwl)
+//!
+//! Layout (little-endian):
+//! - 0x02: text encoding of the words, 0x09 for UTF-16LE, GBK otherwise
+//! - 0x48: u32 number of segments
+//! - 0xC00: segments of 1024 bytes, each with a 16-byte header (u32 index,
+//!   u32 0xFFFFFFFF, u32 unused, u32 data length) followed by the entries
+//!
+//! Each entry is a u8 word length in bytes, a u8 syllable count, a u16 rank,
+//! one u16 per syllable (initial index in the low 5 bits, final index above)
+//! and the word.
+
+use crate::helpers::decode_text;
+use crate::import::{ImportOptions, WordLibraryImport};
+use crate::{Code, CodeType, Error, Result, WordLibrary};
+use std::fs::File;
+use std::io::Read;
+
+const ENCODING_OFFSET: usize = 0x02;
+const SEGMENT_COUNT_OFFSET: usize = 0x48;
+const SEGMENTS_OFFSET: usize = 0xC00;
+const SEGMENT_SIZE: usize = 1024;
+const SEGMENT_HEADER_SIZE: usize = 16;
+
+const INITIALS: [&str; 24] = [
+    "", "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "zh", "ch", "sh",
+    "r", "z", "c", "s", "y", "w",
+];
+
+const FINALS: [&str; 36] = [
+    "", "a", "o", "e", "i", "u", "v", "ai", "ei", "ui", "ao", "ou", "iu", "ie", "ve", "er", "an",
+    "en", "in", "un", "vn", "ang", "eng", "ing", "ong", "ia", "iao", "ian", "iang", "iong", "ua",
+    "uo", "uai", "uan", "uang", "ue",
+];
+
+/// ZiGuang Pinyin .uwl importer
+pub struct ZiguangUwlImport;
+
+impl ZiguangUwlImport {
+    pub fn new() -> Self {
+        ZiguangUwlImport
+    }
+}
+
+impl Default for ZiguangUwlImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WordLibraryImport for ZiguangUwlImport {
+    fn import_from_file(&self, path: &str) -> Result<Vec<WordLibrary>> {
+        self.import_from_reader(&mut File::open(path)?, &ImportOptions::new())
+    }
+
+    fn import_from_reader(
+        &self,
+        reader: &mut dyn Read,
+        _options: &ImportOptions,
+    ) -> Result<Vec<WordLibrary>> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        parse_uwl(&data)
+    }
+}
+
+fn invalid_at(offset: usize, what: &str) -> Error {
+    Error::Parse(format!("Invalid uwl {} at 0x{:x}", what, offset))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Parse all entries of a .uwl file
+pub fn parse_uwl(data: &[u8]) -> Result<Vec<WordLibrary>> {
+    if data.len() < SEGMENTS_OFFSET {
+        return Err(Error::Parse("File too small to be a valid uwl".into()));
+    }
+    let encoding = if data[ENCODING_OFFSET] == 0x09 {
+        "utf-16le"
+    } else {
+        "gbk"
+    };
+
+    let segments = read_u32(data, SEGMENT_COUNT_OFFSET).unwrap_or(0) as usize;
+    let mut words = Vec::new();
+    for i in 0..segments {
+        let start = SEGMENTS_OFFSET + SEGMENT_SIZE * i;
+        let length =
+            read_u32(data, start + 12).ok_or_else(|| invalid_at(start, "segment header"))? as usize;
+        if length > SEGMENT_SIZE - SEGMENT_HEADER_SIZE {
+            return Err(invalid_at(start, "segment length"));
+        }
+        let mut offset = start + SEGMENT_HEADER_SIZE;
+        let end = offset + length;
+        while offset < end {
+            let (word, next) = parse_entry(data, offset, end, encoding)?;
+            words.push(word);
+            offset = next;
+        }
+    }
+    Ok(words)
+}
+
+/// Parse the entry at `offset`, returning it with the offset of the next one
+fn parse_entry(
+    data: &[u8],
+    offset: usize,
+    end: usize,
+    encoding: &str,
+) -> Result<(WordLibrary, usize)> {
+    let header = data
+        .get(offset..offset + 4)
+        .filter(|_| offset + 4 <= end)
+        .ok_or_else(|| invalid_at(offset, "entry"))?;
+    let (word_len, syllables) = (header[0] as usize, header[1] as usize);
+    let rank = u16::from_le_bytes([header[2], header[3]]);
+
+    let mut pos = offset + 4;
+    let mut codes = Vec::with_capacity(syllables);
+    for _ in 0..syllables {
+        let index = read_u16(data, pos).ok_or_else(|| invalid_at(pos, "syllable"))?;
+        let initial = INITIALS.get((index & 0x1F) as usize);
+        let last = FINALS.get((index >> 5) as usize);
+        match (initial, last) {
+            (Some(initial), Some(last)) if !(initial.is_empty() && last.is_empty()) => {
+                codes.push(format!("{}{}", initial, last))
+            }
+            _ => return Err(invalid_at(pos, "syllable")),
+        }
+        pos += 2;
+    }
+
+    let text = data
+        .get(pos..pos + word_len)
+        .filter(|_| pos + word_len <= end && word_len > 0)
+        .ok_or_else(|| invalid_at(pos, "word"))?;
+    let mut word = WordLibrary::with_rank(decode_text(text, encoding), i32::from(rank));
+    word.code_type = CodeType::Pinyin;
+    word.codes = Code::from_char_list(codes);
+    Ok((word, pos + word_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syllable(initial: &str, last: &str) -> [u8; 2] {
+        let i = INITIALS.iter().position(|s| *s == initial).unwrap() as u16;
+        let f = FINALS.iter().position(|s| *s == last).unwrap() as u16;
+        (i | f << 5).to_le_bytes()
+    }
+
+    fn sample_uwl() -> Vec<u8> {
+        let mut data = vec![0u8; SEGMENTS_OFFSET];
+        data[ENCODING_OFFSET] = 0x09;
+        data[SEGMENT_COUNT_OFFSET] = 1;
+
+        let mut entries = Vec::new();
+        for (word, pinyin, rank) in [
+            ("你好", [("n", "i"), ("h", "ao")], 300u16),
+            ("中国", [("zh", "ong"), ("g", "uo")], 20),
+        ] {
+            let text: Vec<u8> = word.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            entries.extend([text.len() as u8, pinyin.len() as u8]);
+            entries.extend(rank.to_le_bytes());
+            for (initial, last) in pinyin {
+                entries.extend(syllable(initial, last));
+            }
+            entries.extend(text);
+        }
+
+        let mut segment = vec![0u8; SEGMENT_SIZE];
+        segment[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        segment[12..16].copy_from_slice(&(entries.len() as u32).to_le_bytes());
+        segment[16..16 + entries.len()].copy_from_slice(&entries);
+        data.extend(segment);
+        data
+    }
+
+    #[test]
+    fn test_import_uwl() {
+        let words = ZiguangUwlImport::new()
+            .import_from_reader(&mut sample_uwl().as_slice(), &ImportOptions::new())
+            .unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "你好");
+        assert_eq!(words[0].get_pinyin_string(" "), "ni hao");
+        assert_eq!(words[0].rank, 300);
+        assert_eq!(words[1].get_pinyin_string(" "), "zhong guo");
+
+        let mut truncated = sample_uwl();
+        truncated[SEGMENTS_OFFSET + 12] = 40;
+        assert!(parse_uwl(&truncated).is_err());
+        assert!(parse_uwl(&[0; 16]).is_err());
+    }
+}
@@ -21,19 +21,38 @@
 //! word.rank = 1000;
 //! ```
 
+pub mod batch;
 pub mod data;
+pub mod diff;
 pub mod error;
 pub mod export;
+#[cfg(feature = "network")]
+pub mod fetch;
 pub mod filter;
 pub mod generate;
 pub mod helpers;
 pub mod import;
+pub mod merge;
+pub mod normalize;
+pub mod pipeline;
 pub mod rank;
+pub mod registry;
+pub mod report;
 pub mod resource;
+pub mod service;
+pub mod sort;
+pub mod stats;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transform;
 pub mod translate;
+pub mod validate;
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 // Re-export commonly used types
-pub use data::{Code, CodeType, WordLibrary, WordLibraryList};
+pub use data::{Code, CodeType, LibraryMetadata, WordLibrary, WordLibraryBuilder, WordLibraryList};
 pub use error::{Error, Result};
 
 /// Version of the converter
@@ -0,0 +1,137 @@
+//! Merging word libraries imported from several sources
+//!
+//! Entries of different sources conflict when they have the same word and the
+//! same codes. Ranks are scaled by the weight of their source before the
+//! conflicts are resolved according to a `MergeStrategy`.
+
+use crate::{Code, WordLibraryList};
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// How conflicting entries of different sources are resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    /// Keep every entry, conflicts included
+    #[default]
+    Concat,
+    /// The entry of the earliest source wins
+    KeepFirst,
+    /// Entries of later sources override earlier ones
+    Override,
+    /// Keep one entry with the ranks of all conflicting entries added up
+    #[serde(rename = "sum")]
+    SumRanks,
+    /// Keep one entry with the highest rank
+    #[serde(rename = "max")]
+    MaxRank,
+}
+
+/// Words of one source with the multiplier applied to their ranks
+#[derive(Debug, Clone)]
+pub struct MergeSource {
+    pub words: WordLibraryList,
+    pub weight: f64,
+}
+
+impl MergeSource {
+    pub fn new(words: WordLibraryList) -> Self {
+        MergeSource { words, weight: 1.0 }
+    }
+
+    /// Set the multiplier applied to the ranks of this source
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Merge the sources in order
+///
+/// Merged entries stay at the position of their first occurrence. The
+/// merged list takes over the source and metadata of the first source.
+pub fn merge_libraries(sources: Vec<MergeSource>, strategy: MergeStrategy) -> WordLibraryList {
+    let mut merged = WordLibraryList::new();
+    let mut index: HashMap<(String, Code), usize> = HashMap::new();
+
+    for (i, source) in sources.into_iter().enumerate() {
+        if i == 0 {
+            merged.copy_metadata(&source.words);
+        }
+        for mut word in source.words {
+            if source.weight != 1.0 {
+                word.rank = (f64::from(word.rank) * source.weight).round() as i32;
+            }
+
+            if strategy == MergeStrategy::Concat {
+                merged.push(word);
+                continue;
+            }
+
+            match index.entry((word.word.clone(), word.codes.clone())) {
+                Entry::Vacant(entry) => {
+                    entry.insert(merged.len());
+                    merged.push(word);
+                }
+                Entry::Occupied(entry) => {
+                    let existing = &mut merged[*entry.get()];
+                    match strategy {
+                        MergeStrategy::Concat | MergeStrategy::KeepFirst => {}
+                        MergeStrategy::Override => *existing = word,
+                        MergeStrategy::SumRanks => {
+                            existing.rank = existing.rank.saturating_add(word.rank)
+                        }
+                        MergeStrategy::MaxRank => existing.rank = existing.rank.max(word.rank),
+                    }
+                }
+            }
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WordLibrary;
+
+    fn word(text: &str, rank: i32) -> WordLibrary {
+        let mut word = WordLibrary::new(text.to_string());
+        word.rank = rank;
+        word
+    }
+
+    fn sources() -> Vec<MergeSource> {
+        vec![
+            MergeSource::new(vec![word("你好", 10), word("世界", 4)].into()),
+            MergeSource::new(vec![word("你好", 3), word("中国", 7)].into()).with_weight(2.0),
+        ]
+    }
+
+    fn ranks(words: &WordLibraryList) -> Vec<(&str, i32)> {
+        words.iter().map(|w| (w.word.as_str(), w.rank)).collect()
+    }
+
+    #[test]
+    fn test_merge_strategies() {
+        let concat = merge_libraries(sources(), MergeStrategy::Concat);
+        assert_eq!(
+            ranks(&concat),
+            vec![("你好", 10), ("世界", 4), ("你好", 6), ("中国", 14)]
+        );
+
+        let first = merge_libraries(sources(), MergeStrategy::KeepFirst);
+        assert_eq!(ranks(&first), vec![("你好", 10), ("世界", 4), ("中国", 14)]);
+
+        let last = merge_libraries(sources(), MergeStrategy::Override);
+        assert_eq!(ranks(&last)[0], ("你好", 6));
+
+        let sum = merge_libraries(sources(), MergeStrategy::SumRanks);
+        assert_eq!(ranks(&sum)[0], ("你好", 16));
+
+        let max = merge_libraries(sources(), MergeStrategy::MaxRank);
+        assert_eq!(ranks(&max)[0], ("你好", 10));
+    }
+}
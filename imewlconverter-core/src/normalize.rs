@@ -0,0 +1,124 @@
+//! Unicode normalization of imported words
+//!
+//! The same character may be stored under several codepoints: CJK
+//! compatibility ideographs (U+F900 豈 for U+8C48 豈), Kangxi radicals
+//! (U+2F00 ⼀ for 一) or regional variants (﨑 for 崎). Words are brought to
+//! NFC and their variants mapped to the canonical form, so that lookups into
+//! the embedded dictionaries and duplicate detection see one character.
+
+use crate::WordLibrary;
+use std::collections::HashMap;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// Variant characters with the codepoint commonly used for them
+const VARIANTS: [(char, char); 6] = [
+    ('﨑', '崎'),
+    ('髙', '高'),
+    ('𠮷', '吉'),
+    ('⺠', '民'),
+    ('⻌', '辶'),
+    ('⻍', '辶'),
+];
+
+/// NFC normalization with a map of variant characters
+#[derive(Debug, Clone)]
+pub struct UnicodeNormalizer {
+    variants: HashMap<char, char>,
+}
+
+impl UnicodeNormalizer {
+    /// Normalizer with the built-in variant map
+    pub fn new() -> Self {
+        UnicodeNormalizer {
+            variants: VARIANTS.into_iter().collect(),
+        }
+    }
+
+    /// Map `from` to `to` in addition to the built-in variants
+    pub fn with_variant(mut self, from: char, to: char) -> Self {
+        self.variants.insert(from, to);
+        self
+    }
+
+    /// Normalize a text, returning None when it is already canonical
+    pub fn normalize(&self, text: &str) -> Option<String> {
+        let canonical = |c: char| !is_radical(c) && !self.variants.contains_key(&c);
+        if is_nfc_quick(text.chars()) == IsNormalized::Yes && text.chars().all(canonical) {
+            return None;
+        }
+        let mut result = String::with_capacity(text.len());
+        for c in text.nfc() {
+            match self.variants.get(&c) {
+                Some(&variant) => result.push(variant),
+                // Radicals decompose to the ideograph they look like
+                None if is_radical(c) => result.extend(c.to_string().nfkc()),
+                None => result.push(c),
+            }
+        }
+        (result != text).then_some(result)
+    }
+
+    /// Normalize the word and codes of an entry, returning whether it changed
+    pub fn normalize_word(&self, word: &mut WordLibrary) -> bool {
+        let mut changed = false;
+        if let Some(normalized) = self.normalize(&word.word) {
+            word.word = normalized;
+            changed = true;
+        }
+        // Tone-marked pinyin may come decomposed (a + U+0301)
+        for code in word.codes.0.iter_mut().flatten() {
+            if code.is_ascii() {
+                continue;
+            }
+            if let Some(normalized) = self.normalize(code) {
+                *code = normalized;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+impl Default for UnicodeNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Kangxi radicals and CJK radicals supplement
+fn is_radical(c: char) -> bool {
+    matches!(c, '\u{2E80}'..='\u{2EFF}' | '\u{2F00}'..='\u{2FDF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    #[test]
+    fn test_normalize() {
+        let normalizer = UnicodeNormalizer::new();
+        assert_eq!(normalizer.normalize("中国"), None);
+        assert_eq!(
+            normalizer.normalize("\u{F900}").as_deref(),
+            Some("\u{8C48}")
+        );
+        assert_eq!(normalizer.normalize("⼀⼆").as_deref(), Some("一二"));
+        assert_eq!(normalizer.normalize("人⺠").as_deref(), Some("人民"));
+        assert_eq!(normalizer.normalize("長﨑").as_deref(), Some("長崎"));
+
+        let custom = UnicodeNormalizer::new().with_variant('裏', '里');
+        assert_eq!(custom.normalize("裏面").as_deref(), Some("里面"));
+    }
+
+    #[test]
+    fn test_normalize_word() {
+        let normalizer = UnicodeNormalizer::new();
+        let mut word = WordLibrary::new("⼤家".to_string());
+        word.codes = Code::from_char_list(vec!["da\u{300}".to_string(), "jia".to_string()]);
+        assert!(normalizer.normalize_word(&mut word));
+        assert_eq!(word.word, "大家");
+        assert_eq!(word.codes.to_string_with_separator(" "), "dà jia");
+        assert!(!normalizer.normalize_word(&mut word));
+    }
+}
@@ -0,0 +1,1153 @@
+//! Conversion pipeline orchestrating import, processing and export
+//!
+//! The pipeline runs the steps of a conversion in a fixed order:
+//! 1. Import each input file and apply the single-entry filters
+//! 2. Apply the batch filters to the merged list
+//! 3. Translate between Simplified and Traditional Chinese
+//! 4. Generate codes, transcode Zhuyin/pinyin, generate ranks, tag idioms,
+//!    boost ranks by word length and normalize the case and width of codes
+//! 5. Apply fuzzy pinyin, add pinyin abbreviations, expand polyphonic words,
+//!    resolve code collisions and export
+//!
+//! # Example
+//!
+//! ```no_run
+//! use imewlconverter_core::export::rime::RimeExport;
+//! use imewlconverter_core::filter::length::LengthFilter;
+//! use imewlconverter_core::import::SogouPinyinImport;
+//! use imewlconverter_core::pipeline::ConversionPipeline;
+//!
+//! let pipeline = ConversionPipeline::new(Box::new(SogouPinyinImport), Box::new(RimeExport::new()))
+//!     .with_filter(Box::new(LengthFilter::new(2, 10)));
+//! let output = pipeline.convert(&["words.txt"]).unwrap();
+//! ```
+
+pub mod job;
+
+pub use job::{run_job, JobSpec};
+
+use crate::export::{
+    english_entry, expand_polyphonic, export_with_english_policy, split_words, write_outputs,
+    CollisionResolver, EnglishPolicy, ExportSplit, WordLibraryExport,
+};
+use crate::filter::{BatchFilter, SingleFilter};
+use crate::generate::{CodeGenerator, RomajiGenerator};
+use crate::helpers::pinyin::ToneStyle;
+use crate::import::{archive, ErrorPolicy, ImportOptions, WordLibraryImport, STDIO_PATH};
+use crate::normalize::UnicodeNormalizer;
+use crate::rank::{LengthBoost, RankGenerator};
+use crate::registry::FormatRegistry;
+use crate::report::ConversionReport;
+use crate::sort::{SortOrder, WordLibrarySorter};
+use crate::stats::{CountingReader, ImportStats};
+use crate::transform::abbreviation::PinyinAbbreviation;
+use crate::transform::code_normalize::CodeNormalizer;
+use crate::transform::fuzzy_pinyin::FuzzyPinyin;
+use crate::transform::idiom::IdiomTagger;
+use crate::transform::zhuyin::Transcode;
+use crate::translate::{regenerate_codes, translate_word_list, ChineseConverter, TranslationType};
+use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Progress notification emitted while the pipeline runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent<'a> {
+    /// A file has been imported
+    Imported { path: &'a str, count: usize },
+    /// The single-entry filters have been applied to an imported file
+    Filtered { path: &'a str, count: usize },
+    /// Translation, code and rank generation are done
+    Processed { count: usize },
+    /// The word list has been exported
+    Exported { count: usize },
+}
+
+type ProgressCallback = Box<dyn Fn(&ProgressEvent)>;
+
+/// Stage of the pipeline reported to a `ProgressSink`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStage {
+    /// Items are input files
+    Import,
+    /// Items are imported words
+    Filter,
+    Translate,
+    Generate,
+    Rank,
+    Export,
+}
+
+impl ProgressStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgressStage::Import => "Importing",
+            ProgressStage::Filter => "Filtering",
+            ProgressStage::Translate => "Translating",
+            ProgressStage::Generate => "Generating codes",
+            ProgressStage::Rank => "Generating ranks",
+            ProgressStage::Export => "Exporting",
+        }
+    }
+}
+
+/// Receiver of item-level progress, e.g. a progress bar
+///
+/// Each stage is reported as `start`, any number of `advance` calls with the
+/// number of items processed so far, and `finish`.
+pub trait ProgressSink {
+    /// A stage starts processing `total` items
+    fn start(&self, stage: ProgressStage, total: usize);
+
+    /// `processed` of the stage's items are done
+    fn advance(&self, stage: ProgressStage, processed: usize);
+
+    /// The stage is done
+    fn finish(&self, stage: ProgressStage) {
+        let _ = stage;
+    }
+}
+
+/// Builder-style conversion pipeline shared by the CLI and library users
+pub struct ConversionPipeline {
+    importer: Box<dyn WordLibraryImport>,
+    exporter: Box<dyn WordLibraryExport>,
+    import_options: ImportOptions,
+    /// Name of the input format, recorded on the imported list
+    source_format: Option<String>,
+    /// Normalizes imported words before filtering (None = disabled)
+    normalizer: Option<UnicodeNormalizer>,
+    filters: Vec<Box<dyn SingleFilter>>,
+    batch_filters: Vec<Box<dyn BatchFilter>>,
+    translator: Option<(Box<dyn ChineseConverter>, TranslationType)>,
+    /// Regenerate glyph-based codes after translation
+    retranslate_codes: bool,
+    generator: Option<Box<dyn CodeGenerator>>,
+    /// Keep the codes of words already in the generator's code type
+    keep_output_codes: bool,
+    rank_generator: Option<Box<dyn RankGenerator>>,
+    idiom_tagger: Option<IdiomTagger>,
+    length_boost: Option<LengthBoost>,
+    transcode: Option<Transcode>,
+    code_normalizer: Option<CodeNormalizer>,
+    fuzzy_pinyin: Option<FuzzyPinyin>,
+    abbreviations: Option<PinyinAbbreviation>,
+    /// Cap on exported combinations per polyphonic word (None = no expansion)
+    polyphonic_limit: Option<usize>,
+    english_policy: EnglishPolicy,
+    sorter: Option<WordLibrarySorter>,
+    collisions: Option<CollisionResolver>,
+    split: ExportSplit,
+    progress: Option<ProgressCallback>,
+    progress_sink: Option<Box<dyn ProgressSink>>,
+    /// Merge entries with the same word and codes
+    merge_duplicates: bool,
+    report: RefCell<ConversionReport>,
+}
+
+impl ConversionPipeline {
+    pub fn new(importer: Box<dyn WordLibraryImport>, exporter: Box<dyn WordLibraryExport>) -> Self {
+        ConversionPipeline {
+            importer,
+            exporter,
+            import_options: ImportOptions::new(),
+            source_format: None,
+            normalizer: Some(UnicodeNormalizer::new()),
+            filters: Vec::new(),
+            batch_filters: Vec::new(),
+            translator: None,
+            retranslate_codes: false,
+            generator: None,
+            keep_output_codes: false,
+            rank_generator: None,
+            idiom_tagger: None,
+            length_boost: None,
+            transcode: None,
+            code_normalizer: None,
+            fuzzy_pinyin: None,
+            abbreviations: None,
+            polyphonic_limit: None,
+            english_policy: EnglishPolicy::default(),
+            sorter: None,
+            collisions: None,
+            split: ExportSplit::default(),
+            progress: None,
+            progress_sink: None,
+            merge_duplicates: false,
+            report: RefCell::new(ConversionReport::default()),
+        }
+    }
+
+    /// Record the name of the input format on the imported word list
+    pub fn with_source_format(mut self, format: &str) -> Self {
+        self.source_format = Some(format.to_string());
+        self
+    }
+
+    /// Read text input files with the given encoding, or `"auto"` to detect it
+    pub fn with_input_encoding(mut self, encoding: &str) -> Self {
+        self.import_options = self.import_options.with_encoding(encoding);
+        self
+    }
+
+    /// Convert tone-marked pinyin codes of text inputs
+    pub fn with_pinyin_tones(mut self, style: ToneStyle) -> Self {
+        self.import_options = self.import_options.with_pinyin_tones(style);
+        self
+    }
+
+    /// Rank of text input entries without a frequency column
+    pub fn with_default_rank(mut self, rank: i32) -> Self {
+        self.import_options = self.import_options.with_default_rank(rank);
+        self
+    }
+
+    /// Recover what can be read from corrupt binary inputs instead of
+    /// failing; the skipped byte ranges are reported as warnings
+    pub fn salvage(mut self, enabled: bool) -> Self {
+        self.import_options.salvage = enabled;
+        self
+    }
+
+    /// Set how malformed input lines are handled
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.import_options = self.import_options.with_error_policy(policy);
+        self
+    }
+
+    /// Normalize imported words with `normalizer` instead of the default one
+    pub fn with_normalizer(mut self, normalizer: UnicodeNormalizer) -> Self {
+        self.normalizer = Some(normalizer);
+        self
+    }
+
+    /// Bring imported words to NFC and map variant characters (on by default)
+    pub fn normalize_unicode(mut self, enabled: bool) -> Self {
+        self.normalizer = enabled.then(|| self.normalizer.take().unwrap_or_default());
+        self
+    }
+
+    /// Add a filter applied to each entry after import
+    pub fn with_filter(mut self, filter: Box<dyn SingleFilter>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Add a filter applied to the merged word list
+    pub fn with_batch_filter(mut self, filter: Box<dyn BatchFilter>) -> Self {
+        self.batch_filters.push(filter);
+        self
+    }
+
+    /// Translate words between Simplified and Traditional Chinese
+    pub fn with_translator(
+        mut self,
+        converter: Box<dyn ChineseConverter>,
+        translation: TranslationType,
+    ) -> Self {
+        self.translator = Some((converter, translation));
+        self
+    }
+
+    /// Regenerate glyph-based codes (Wubi, Cangjie, ...) after translation
+    pub fn retranslate_codes(mut self, enabled: bool) -> Self {
+        self.retranslate_codes = enabled;
+        self
+    }
+
+    /// Generate codes for the output encoding
+    ///
+    /// Words whose codes cannot be generated are dropped.
+    pub fn with_generator(mut self, generator: Box<dyn CodeGenerator>) -> Self {
+        self.generator = Some(generator);
+        self.keep_output_codes = false;
+        self
+    }
+
+    /// Generate codes for the words not yet in the output encoding
+    ///
+    /// Unlike `with_generator`, words already coded in the generator's code
+    /// type keep their codes, and so do English words and custom phrases,
+    /// which the exporters handle themselves.
+    pub fn with_output_generator(mut self, generator: Box<dyn CodeGenerator>) -> Self {
+        self.generator = Some(generator);
+        self.keep_output_codes = true;
+        self
+    }
+
+    /// Generate ranks for the words
+    pub fn with_rank_generator(mut self, generator: Box<dyn RankGenerator>) -> Self {
+        self.rank_generator = Some(generator);
+        self
+    }
+
+    /// Tag idioms (成语) with the `is_idiom` attribute after rank generation
+    pub fn with_idiom_tagger(mut self, tagger: IdiomTagger) -> Self {
+        self.idiom_tagger = Some(tagger);
+        self
+    }
+
+    /// Scale ranks by word length after rank generation
+    pub fn with_length_boost(mut self, boost: LengthBoost) -> Self {
+        self.length_boost = Some(boost);
+        self
+    }
+
+    /// Transcode between Zhuyin and pinyin codes after code generation
+    pub fn with_transcode(mut self, transcode: Transcode) -> Self {
+        self.transcode = Some(transcode);
+        self
+    }
+
+    /// Normalize the case and width of codes before export
+    pub fn with_code_normalizer(mut self, normalizer: CodeNormalizer) -> Self {
+        self.code_normalizer = Some(normalizer);
+        self
+    }
+
+    /// Apply fuzzy pinyin rules to the pinyin codes on export
+    pub fn with_fuzzy_pinyin(mut self, fuzzy: FuzzyPinyin) -> Self {
+        self.fuzzy_pinyin = Some(fuzzy);
+        self
+    }
+
+    /// Add an abbreviation entry (`nh` for 你好) for each pinyin word on export
+    pub fn with_abbreviations(mut self, abbreviations: PinyinAbbreviation) -> Self {
+        self.abbreviations = Some(abbreviations);
+        self
+    }
+
+    /// Export every code combination of polyphonic words, at most `limit` each
+    pub fn expand_polyphonic(mut self, limit: usize) -> Self {
+        self.polyphonic_limit = Some(limit);
+        self
+    }
+
+    /// Merge entries with the same word and codes, keeping the highest rank
+    pub fn merge_duplicates(mut self, enabled: bool) -> Self {
+        self.merge_duplicates = enabled;
+        self
+    }
+
+    /// Set how English words are exported
+    pub fn english_policy(mut self, policy: EnglishPolicy) -> Self {
+        self.english_policy = policy;
+        self
+    }
+
+    /// Sort the words before export
+    pub fn with_sorter(mut self, sorter: WordLibrarySorter) -> Self {
+        self.sorter = Some(sorter);
+        self
+    }
+
+    /// Group words sharing a code before export, after sorting
+    pub fn with_collision_resolver(mut self, resolver: CollisionResolver) -> Self {
+        self.collisions = Some(resolver);
+        self
+    }
+
+    /// Split the exported words into several outputs
+    pub fn with_split(mut self, split: ExportSplit) -> Self {
+        self.split = split;
+        self
+    }
+
+    /// Set a callback receiving progress notifications
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ProgressEvent) + 'static,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a sink receiving item-level progress of every stage
+    pub fn with_progress_sink(mut self, sink: Box<dyn ProgressSink>) -> Self {
+        self.progress_sink = Some(sink);
+        self
+    }
+
+    /// Take the statistics collected since the last call
+    pub fn take_report(&self) -> ConversionReport {
+        self.report.take()
+    }
+
+    /// Get the exporter (e.g. for its output encoding)
+    pub fn exporter(&self) -> &dyn WordLibraryExport {
+        self.exporter.as_ref()
+    }
+
+    /// Whether the code generation leaves `word` as it is
+    fn keeps_codes(&self, word: &WordLibrary, code_type: CodeType) -> bool {
+        self.keep_output_codes
+            && (word.is_english_word()
+                || word.has_code()
+                    && (word.code_type == code_type
+                        || word.code_type == CodeType::UserDefinePhrase))
+    }
+
+    fn notify(&self, event: ProgressEvent) {
+        if let Some(callback) = &self.progress {
+            callback(&event);
+        }
+    }
+
+    fn start(&self, stage: ProgressStage, total: usize) {
+        if let Some(sink) = &self.progress_sink {
+            sink.start(stage, total);
+        }
+    }
+
+    fn advance(&self, stage: ProgressStage, processed: usize) {
+        if let Some(sink) = &self.progress_sink {
+            sink.advance(stage, processed);
+        }
+    }
+
+    fn finish(&self, stage: ProgressStage) {
+        if let Some(sink) = &self.progress_sink {
+            sink.finish(stage);
+        }
+    }
+
+    /// Import files and apply all filters
+    pub fn import(&self, paths: &[&str]) -> Result<WordLibraryList> {
+        let mut imported = Vec::with_capacity(paths.len());
+
+        self.start(ProgressStage::Import, paths.len());
+        for (i, &path) in paths.iter().enumerate() {
+            let _span = tracing::info_span!("import", path).entered();
+            if archive::is_archive(path) {
+                imported.extend(self.import_archive(path)?);
+                self.advance(ProgressStage::Import, i + 1);
+                continue;
+            }
+            let started = Instant::now();
+            let (words, bytes) = if path == STDIO_PATH {
+                let mut stdin = CountingReader::new(std::io::stdin().lock());
+                let words = self
+                    .importer
+                    .import_library_from_reader(&mut stdin, &self.import_options)?;
+                (words, stdin.bytes_read())
+            } else {
+                let words = self.importer.import_library(path, &self.import_options)?;
+                (words, std::fs::metadata(path).map_or(0, |m| m.len()))
+            };
+            self.notify(ProgressEvent::Imported {
+                path,
+                count: words.len(),
+            });
+            let stats = ImportStats::new(bytes, words.len(), started.elapsed());
+            tracing::debug!(words = words.len(), bytes, "imported");
+            self.report.borrow_mut().record_import(path, stats);
+            imported.push((path.to_string(), words));
+            self.advance(ProgressStage::Import, i + 1);
+        }
+        self.finish(ProgressStage::Import);
+
+        self.filter_imported(imported)
+    }
+
+    /// Import every file of an archive, each named `archive/entry`
+    ///
+    /// Files with an unknown or ambiguous extension are read with the
+    /// pipeline's importer.
+    fn import_archive(&self, path: &str) -> Result<Vec<(String, WordLibraryList)>> {
+        let registry = FormatRegistry::with_builtin_formats();
+        let mut imported = Vec::new();
+        for entry in archive::read_archive(path)? {
+            let name = format!("{}/{}", path, entry.name);
+            let started = Instant::now();
+            let words = entry.import(&registry, self.importer.as_ref(), &self.import_options)?;
+            self.notify(ProgressEvent::Imported {
+                path: &name,
+                count: words.len(),
+            });
+            let stats = ImportStats::new(entry.data.len() as u64, words.len(), started.elapsed());
+            self.report.borrow_mut().record_import(&name, stats);
+            imported.push((name, words));
+        }
+        Ok(imported)
+    }
+
+    /// Import all of a reader and apply all filters
+    ///
+    /// `name` identifies the input in progress events and reports.
+    pub fn import_reader(&self, name: &str, reader: &mut dyn Read) -> Result<WordLibraryList> {
+        self.start(ProgressStage::Import, 1);
+        let started = Instant::now();
+        let mut reader = CountingReader::new(reader);
+        let words = self
+            .importer
+            .import_library_from_reader(&mut reader, &self.import_options)?;
+        self.notify(ProgressEvent::Imported {
+            path: name,
+            count: words.len(),
+        });
+        let stats = ImportStats::new(reader.bytes_read(), words.len(), started.elapsed());
+        self.report.borrow_mut().record_import(name, stats);
+        self.advance(ProgressStage::Import, 1);
+        self.finish(ProgressStage::Import);
+
+        self.filter_imported(vec![(name.to_string(), words)])
+    }
+
+    /// Apply the filters to the words imported from each input
+    fn filter_imported(&self, imported: Vec<(String, WordLibraryList)>) -> Result<WordLibraryList> {
+        let total = imported.iter().map(|(_, words)| words.len()).sum();
+        let mut all_words = WordLibraryList::with_capacity(total);
+        // The metadata of a single input describes the result
+        let (name, metadata) = match imported.as_slice() {
+            [(path, words)] => (library_name(path), words.metadata.clone()),
+            _ => Default::default(),
+        };
+        let mut processed = 0;
+        // Entries are counted against the first filter rejecting them
+        let mut dropped = vec![0; self.filters.len()];
+
+        self.start(ProgressStage::Filter, total);
+        for (path, mut words) in imported {
+            words.filter_in_place(|w| {
+                processed += 1;
+                self.advance(ProgressStage::Filter, processed);
+                if let Some(normalizer) = &self.normalizer {
+                    normalizer.normalize_word(w);
+                }
+                match self.filters.iter().position(|f| !f.is_keep(w)) {
+                    Some(i) => {
+                        dropped[i] += 1;
+                        false
+                    }
+                    None => true,
+                }
+            });
+            self.notify(ProgressEvent::Filtered {
+                path: &path,
+                count: words.len(),
+            });
+
+            all_words.append(&mut words);
+        }
+
+        let mut report = self.report.borrow_mut();
+        for (filter, dropped) in self.filters.iter().zip(dropped) {
+            tracing::debug!(filter = filter.name(), dropped, "filtered");
+            report.record_dropped(filter.name(), dropped);
+        }
+        for filter in &self.batch_filters {
+            let before = all_words.len();
+            all_words = filter.filter(all_words)?;
+            report.record_dropped(filter.name(), before.saturating_sub(all_words.len()));
+        }
+        self.finish(ProgressStage::Filter);
+
+        all_words.source_format = self.source_format.clone();
+        all_words.source_encoding = self.import_options.encoding.clone();
+        all_words.name = name;
+        all_words.metadata = metadata;
+        Ok(all_words)
+    }
+
+    /// Translate words and generate codes and ranks
+    pub fn process(&self, mut words: WordLibraryList) -> Result<WordLibraryList> {
+        let _span = tracing::info_span!("process", words = words.len()).entered();
+        if let Some((converter, translation)) = &self.translator {
+            self.start(ProgressStage::Translate, words.len());
+            let changed = translate_word_list(converter.as_ref(), &mut words, *translation)?;
+            if self.retranslate_codes {
+                let mut report = self.report.borrow_mut();
+                for (word, e) in regenerate_codes(&mut words, &changed) {
+                    report.record_generation_failure(&word, &e);
+                }
+            }
+            self.advance(ProgressStage::Translate, words.len());
+            self.finish(ProgressStage::Translate);
+        }
+
+        if let Some(generator) = &self.generator {
+            let mut processed = 0;
+            self.start(ProgressStage::Generate, words.len());
+            let mut report = self.report.borrow_mut();
+            words.filter_in_place(|w| {
+                processed += 1;
+                self.advance(ProgressStage::Generate, processed);
+                if self.keeps_codes(w, generator.code_type()) {
+                    return true;
+                }
+                match generate_word(generator.as_ref(), w) {
+                    Ok(keep) => keep,
+                    Err(e) => {
+                        tracing::debug!(word = %w.word, error = %e, "code generation failed");
+                        report.record_generation_failure(&w.word, &e);
+                        false
+                    }
+                }
+            });
+            self.finish(ProgressStage::Generate);
+        }
+
+        if let Some(transcode) = self.transcode {
+            let mut report = self.report.borrow_mut();
+            for (word, e) in transcode.apply_all(&mut words) {
+                report.record_generation_failure(&word, &e);
+            }
+        }
+
+        if let Some(generator) = &self.rank_generator {
+            self.start(ProgressStage::Rank, words.len());
+            for (i, word) in words.iter_mut().enumerate() {
+                generator.generate_rank(word)?;
+                self.advance(ProgressStage::Rank, i + 1);
+            }
+            self.finish(ProgressStage::Rank);
+        }
+
+        if let Some(tagger) = &self.idiom_tagger {
+            let tagged = tagger.apply(&mut words);
+            tracing::debug!(tagged, "tagged idioms");
+        }
+
+        if let Some(boost) = &self.length_boost {
+            boost.apply(&mut words);
+        }
+
+        if let Some(normalizer) = &self.code_normalizer {
+            normalizer.apply(&mut words);
+        }
+
+        if self.merge_duplicates {
+            let before = words.len();
+            words.dedup();
+            self.report.borrow_mut().duplicates_merged += before - words.len();
+        }
+
+        self.notify(ProgressEvent::Processed { count: words.len() });
+        Ok(words)
+    }
+
+    /// Export processed words
+    pub fn export(&self, words: &WordLibraryList) -> Result<Vec<String>> {
+        let exporter = self.exporter.as_ref();
+        let _span = tracing::info_span!("export", format = exporter.format_name()).entered();
+        self.start(ProgressStage::Export, words.len());
+        let mut exported = match &self.fuzzy_pinyin {
+            Some(fuzzy) => Cow::Owned(fuzzy.apply(words)),
+            None => Cow::Borrowed(words),
+        };
+        if let Some(abbreviations) = &self.abbreviations {
+            exported = Cow::Owned(abbreviations.apply(&exported));
+        }
+        if let Some(limit) = self.polyphonic_limit {
+            exported = Cow::Owned(expand_polyphonic(&exported, limit));
+        }
+        if let Some(sorter) = &self.sorter {
+            sorter.sort(exported.to_mut());
+        }
+        if let Some(resolver) = &self.collisions {
+            exported = Cow::Owned(resolver.resolve(&exported));
+        }
+        if let Cow::Owned(list) = &mut exported {
+            list.copy_metadata(words);
+        }
+        let output = match self.split {
+            ExportSplit::None => {
+                export_with_english_policy(exporter, &exported, self.english_policy)?
+            }
+            split => {
+                let mut output = Vec::new();
+                for chunk in split_words(exporter, &exported, split) {
+                    output.extend(export_with_english_policy(
+                        exporter,
+                        &chunk,
+                        self.english_policy,
+                    )?);
+                }
+                output
+            }
+        };
+        // Count the entries reaching the exporter, after the transforms
+        let count = match self.english_policy {
+            EnglishPolicy::Drop => exported.iter().filter(|w| !w.is_english_word()).count(),
+            _ => exported.len(),
+        };
+        tracing::debug!(words = count, files = output.len(), "exported");
+        self.report.borrow_mut().exported += count;
+        self.advance(ProgressStage::Export, words.len());
+        self.finish(ProgressStage::Export);
+        self.notify(ProgressEvent::Exported { count });
+        Ok(output)
+    }
+
+    /// Export processed words to a writer, e.g. stdout
+    pub fn export_to_writer(&self, words: &WordLibraryList, writer: &mut dyn Write) -> Result<()> {
+        let output = self.export(words)?;
+        write_outputs(&output, self.exporter.encoding(), writer)
+    }
+
+    /// Run the whole pipeline, returning the exported content
+    pub fn convert(&self, paths: &[&str]) -> Result<Vec<String>> {
+        let words = self.import(paths)?;
+        let words = self.process(words)?;
+        self.export(&words)
+    }
+
+    /// Whether `convert_stream` supports this pipeline
+    pub fn can_stream(&self) -> bool {
+        self.importer.as_stream_import().is_some()
+            && self.exporter.as_stream_export().is_some()
+            && self.unstreamable_step().is_none()
+    }
+
+    /// The first configured step needing the whole word list
+    fn unstreamable_step(&self) -> Option<&'static str> {
+        let steps = [
+            (!self.batch_filters.is_empty(), "batch filters"),
+            (self.translator.is_some(), "translation"),
+            (self.idiom_tagger.is_some(), "idiom tagging"),
+            (self.length_boost.is_some(), "length boost"),
+            (self.transcode.is_some(), "transcoding"),
+            (self.code_normalizer.is_some(), "code normalization"),
+            (self.fuzzy_pinyin.is_some(), "fuzzy pinyin"),
+            (self.abbreviations.is_some(), "abbreviations"),
+            (self.polyphonic_limit.is_some(), "polyphonic expansion"),
+            (
+                self.sorter
+                    .as_ref()
+                    .is_some_and(|s| s.order() != SortOrder::Input),
+                "sorting",
+            ),
+            (self.collisions.is_some(), "collision resolution"),
+            (self.split != ExportSplit::None, "split export"),
+            (self.merge_duplicates, "duplicate merging"),
+            (
+                self.english_policy == EnglishPolicy::Separate,
+                "separate English export",
+            ),
+        ];
+        steps
+            .into_iter()
+            .find_map(|(enabled, step)| enabled.then_some(step))
+    }
+
+    /// Run the whole pipeline one entry at a time, writing to `writer`
+    ///
+    /// Memory use stays bounded regardless of the input size. Only the
+    /// normalizer, single filters, code and rank generators and the English
+    /// policy apply; steps needing the whole list fail with
+    /// `Error::Unsupported`. No header is written and duplicates are kept.
+    /// Returns the number of exported entries.
+    pub fn convert_stream(&self, path: &str, writer: &mut dyn Write) -> Result<usize> {
+        let importer = self
+            .importer
+            .as_stream_import()
+            .ok_or_else(|| Error::Unsupported("Streaming import of this format".into()))?;
+        let exporter = self.exporter.as_stream_export().ok_or_else(|| {
+            Error::Unsupported(format!(
+                "Streaming export to {}",
+                self.exporter.format_name()
+            ))
+        })?;
+        if let Some(step) = self.unstreamable_step() {
+            return Err(Error::Unsupported(format!("Streaming with {}", step)));
+        }
+        if archive::is_archive(path) {
+            return Err(Error::Unsupported("Streaming archives".into()));
+        }
+
+        let _span = tracing::info_span!("convert_stream", path).entered();
+        let started = Instant::now();
+        let input: Box<dyn Read> = if path == STDIO_PATH {
+            Box::new(std::io::stdin().lock())
+        } else {
+            Box::new(File::open(path)?)
+        };
+        let mut reader = CountingReader::new(input);
+        let code_type = exporter.code_type();
+        let mut imported = 0;
+        // Entries are counted against the first filter rejecting them
+        let mut dropped = vec![0; self.filters.len()];
+        let mut failures = Vec::new();
+
+        let exported = {
+            let words = importer.import_stream_from_reader(
+                Box::new(&mut reader),
+                path,
+                &self.import_options,
+            )?;
+            let mut words = words.filter_map(|word| {
+                let mut word = match word {
+                    Ok(word) => word,
+                    Err(e) => return Some(Err(e)),
+                };
+                imported += 1;
+                if let Some(normalizer) = &self.normalizer {
+                    normalizer.normalize_word(&mut word);
+                }
+                if let Some(i) = self.filters.iter().position(|f| !f.is_keep(&word)) {
+                    dropped[i] += 1;
+                    return None;
+                }
+                if let Some(generator) = self
+                    .generator
+                    .as_ref()
+                    .filter(|g| !self.keeps_codes(&word, g.code_type()))
+                {
+                    match generate_word(generator.as_ref(), &mut word) {
+                        Ok(true) => {}
+                        Ok(false) => return None,
+                        Err(e) => {
+                            failures.push((word.word, e));
+                            return None;
+                        }
+                    }
+                }
+                if let Some(generator) = &self.rank_generator {
+                    if let Err(e) = generator.generate_rank(&mut word) {
+                        return Some(Err(e));
+                    }
+                }
+                if word.is_english_word() {
+                    if self.english_policy == EnglishPolicy::Drop {
+                        return None;
+                    }
+                    word = english_entry(&word, code_type);
+                }
+                Some(Ok(word))
+            });
+            exporter.export_stream(&mut words, writer)?
+        };
+        writer.flush()?;
+
+        self.notify(ProgressEvent::Imported {
+            path,
+            count: imported,
+        });
+        let mut report = self.report.borrow_mut();
+        let stats = ImportStats::new(reader.bytes_read(), imported, started.elapsed());
+        report.record_import(path, stats);
+        for (filter, dropped) in self.filters.iter().zip(dropped) {
+            report.record_dropped(filter.name(), dropped);
+        }
+        for (word, e) in failures {
+            report.record_generation_failure(&word, &e);
+        }
+        report.exported += exported;
+        drop(report);
+        self.notify(ProgressEvent::Exported { count: exported });
+        Ok(exported)
+    }
+
+    /// Run the whole pipeline in memory, without touching the file system
+    ///
+    /// Returns the encoded output; split outputs are separated by line breaks.
+    pub fn convert_bytes(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let words = self.import_reader("input", &mut &input[..])?;
+        let words = self.process(words)?;
+        let mut output = Vec::new();
+        self.export_to_writer(&words, &mut output)?;
+        Ok(output)
+    }
+}
+
+/// Generate the codes of one word, returning whether to keep it
+fn generate_word(generator: &dyn CodeGenerator, word: &mut WordLibrary) -> Result<bool> {
+    // Codes of symbol phrases cannot be derived from the symbols
+    if word.is_symbol_phrase() && word.has_code() {
+        return Ok(true);
+    }
+    match generator.generate_code(word) {
+        Ok(()) => Ok(true),
+        // Kana words pass through with romaji codes
+        Err(Error::CharacterNotFound(_)) if word.is_kana_word() => {
+            Ok(RomajiGenerator::new().generate_code(word).is_ok())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Convert a word library in memory between two built-in formats
+pub fn convert_bytes(input: &[u8], input_format: &str, output_format: &str) -> Result<Vec<u8>> {
+    let registry = FormatRegistry::with_builtin_formats();
+    ConversionPipeline::new(
+        registry.importer(input_format)?,
+        registry.exporter(output_format)?,
+    )
+    .with_source_format(input_format)
+    .convert_bytes(input)
+}
+
+/// Name of a word library read from `path`: the file name without extensions
+fn library_name(path: &str) -> Option<String> {
+    if path == STDIO_PATH {
+        return None;
+    }
+    let name = Path::new(path).file_name()?.to_str()?;
+    let stem = name.split('.').next().unwrap_or(name);
+    (!stem.is_empty()).then(|| stem.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::jidian::JidianExport;
+    use crate::export::rime::RimeExport;
+    use crate::filter::length::LengthFilter;
+    use crate::generate::{PinyinGenerator, WubiGenerator};
+    use crate::import::RimeImport;
+    use crate::rank::DefaultRankGenerator;
+    use crate::translate::OpenCCConverter;
+    use crate::Code;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn pipeline() -> ConversionPipeline {
+        ConversionPipeline::new(Box::new(RimeImport::new()), Box::new(RimeExport::new()))
+    }
+
+    #[test]
+    fn test_process() {
+        let pipeline = pipeline()
+            .with_translator(
+                Box::new(OpenCCConverter::new().unwrap()),
+                TranslationType::ToTraditional,
+            )
+            .with_generator(Box::new(PinyinGenerator::new().unwrap()))
+            .with_rank_generator(Box::new(DefaultRankGenerator::new(7)));
+
+        let words: WordLibraryList = vec![WordLibrary::new("中国".to_string())].into();
+        let words = pipeline.process(words).unwrap();
+
+        assert_eq!(words[0].word, "中國");
+        assert_eq!(words[0].code_type, CodeType::Pinyin);
+        assert_eq!(words[0].rank, 7);
+    }
+
+    #[test]
+    fn test_convert_file() {
+        let path = std::env::temp_dir().join(format!(
+            "imewlconverter-pipeline-test-{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&path, "你好\tni hao\t10\n中华人民共和国\tzhong hua\t5\n").unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        let pipeline = pipeline()
+            .with_filter(Box::new(LengthFilter::new(1, 4)))
+            .on_progress(move |event| recorded.borrow_mut().push(format!("{:?}", event)));
+
+        let output = pipeline.convert(&[&path]).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(output, vec!["你好\tni hao\t10".to_string()]);
+        let events = events.borrow();
+        assert_eq!(events.len(), 4);
+        assert!(events[1].contains("count: 1"));
+
+        let report = pipeline.take_report();
+        assert_eq!(report.imported(), 2);
+        assert_eq!(report.filters[0].filter, "LengthFilter");
+        assert_eq!(report.filters[0].dropped, 1);
+    }
+
+    #[test]
+    fn test_convert_stream() {
+        let path = std::env::temp_dir().join(format!(
+            "imewlconverter-pipeline-stream-{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "你好\tni hao\t10\n中华人民共和国\tzhong hua\t5\nhello\thello\t1\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let filtered = pipeline().with_filter(Box::new(LengthFilter::new(1, 5)));
+        assert!(filtered.can_stream());
+        let mut output = Vec::new();
+        let count = filtered.convert_stream(&path, &mut output).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "你好\tni hao\t10\nhello\thello\t1"
+        );
+        let report = filtered.take_report();
+        assert_eq!(report.imported(), 3);
+        assert_eq!(report.filters[0].dropped, 1);
+        assert_eq!(report.exported, 2);
+
+        let dropping = pipeline().english_policy(EnglishPolicy::Drop);
+        let mut output = Vec::new();
+        assert_eq!(dropping.convert_stream(&path, &mut output).unwrap(), 2);
+
+        let merging = pipeline().merge_duplicates(true);
+        assert!(!merging.can_stream());
+        assert!(matches!(
+            merging.convert_stream(&path, &mut Vec::new()),
+            Err(Error::Unsupported(_))
+        ));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_report() {
+        let mut unknown = WordLibrary::new("㐀".to_string());
+        unknown.rank = 1;
+        let mut first = WordLibrary::new("你好".to_string());
+        first.rank = 3;
+        let mut second = first.clone();
+        second.rank = 8;
+
+        let pipeline = pipeline()
+            .with_generator(Box::new(PinyinGenerator::new().unwrap()))
+            .merge_duplicates(true);
+        let words = pipeline
+            .process(vec![unknown, first, second].into())
+            .unwrap();
+        pipeline.export(&words).unwrap();
+
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].rank, 8);
+
+        let report = pipeline.take_report();
+        assert_eq!(report.duplicates_merged, 1);
+        assert_eq!(report.exported, 1);
+        assert_eq!(report.generation_failures.len(), 1);
+        assert_eq!(report.generation_failures[0].character, Some('㐀'));
+        assert_eq!(pipeline.take_report(), ConversionReport::default());
+    }
+
+    #[test]
+    fn test_output_generator() {
+        let mut wubi = WordLibrary::new("中国".to_string());
+        wubi.code_type = CodeType::Wubi;
+        wubi.codes = Code::from_single("zzzz".to_string());
+        let mut pinyin = WordLibrary::new("你好".to_string());
+        pinyin.codes = Code::from_char_list(vec!["ni".to_string(), "hao".to_string()]);
+        let words: WordLibraryList =
+            vec![wubi, pinyin, WordLibrary::new("hello".to_string())].into();
+
+        let pipeline =
+            ConversionPipeline::new(Box::new(RimeImport::new()), Box::new(JidianExport::new()))
+                .with_output_generator(Box::new(WubiGenerator::new().unwrap()));
+        let words = pipeline.process(words).unwrap();
+
+        assert_eq!(words[0].get_word_code().unwrap(), "zzzz");
+        assert_eq!(words[1].code_type, CodeType::Wubi);
+        assert_eq!(words[1].get_word_code().unwrap(), "wqvb");
+        assert_eq!(words[2].word, "hello");
+    }
+
+    #[test]
+    fn test_report_exported_after_english_policy() {
+        let words: WordLibraryList = vec![
+            WordLibrary::new("你好".to_string()),
+            WordLibrary::new("hello".to_string()),
+        ]
+        .into();
+        let pipeline = pipeline().english_policy(EnglishPolicy::Drop);
+        pipeline.export(&words).unwrap();
+
+        assert_eq!(pipeline.take_report().exported, 1);
+    }
+
+    #[test]
+    fn test_normalize_imported() {
+        let read = |pipeline: ConversionPipeline| {
+            let input = "\u{F900}\tgeng\t1\n⼤家\tda jia\t1\n";
+            let words = pipeline.import_reader("-", &mut input.as_bytes()).unwrap();
+            words.iter().map(|w| w.word.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(read(pipeline()), vec!["\u{8C48}", "大家"]);
+        assert_eq!(
+            read(pipeline().normalize_unicode(false)),
+            vec!["\u{F900}", "⼤家"]
+        );
+    }
+
+    #[test]
+    fn test_kana_passthrough() {
+        let pipeline = pipeline().with_generator(Box::new(PinyinGenerator::new().unwrap()));
+        let words = vec![
+            WordLibrary::new("你好".to_string()),
+            WordLibrary::new("ありがとう".to_string()),
+        ];
+        let words = pipeline.process(words.into()).unwrap();
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[1].code_type, CodeType::Kana);
+        let output = pipeline.export(&words).unwrap();
+        assert!(output[0].ends_with("ありがとう\tarigatou\t0"));
+    }
+
+    struct RecordingSink(Rc<RefCell<Vec<(ProgressStage, usize)>>>);
+
+    impl ProgressSink for RecordingSink {
+        fn start(&self, stage: ProgressStage, total: usize) {
+            self.0.borrow_mut().push((stage, total));
+        }
+
+        fn advance(&self, stage: ProgressStage, processed: usize) {
+            self.0.borrow_mut().push((stage, processed));
+        }
+    }
+
+    #[test]
+    fn test_progress_sink() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let pipeline = pipeline()
+            .with_rank_generator(Box::new(DefaultRankGenerator::new(1)))
+            .with_progress_sink(Box::new(RecordingSink(calls.clone())));
+
+        let words: WordLibraryList = vec![
+            WordLibrary::new("你好".to_string()),
+            WordLibrary::new("世界".to_string()),
+        ]
+        .into();
+        pipeline.process(words).unwrap();
+
+        assert_eq!(
+            *calls.borrow(),
+            vec![
+                (ProgressStage::Rank, 2),
+                (ProgressStage::Rank, 1),
+                (ProgressStage::Rank, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_encoding() {
+        let path = std::env::temp_dir().join(format!(
+            "imewlconverter-pipeline-encoding-{}.txt",
+            std::process::id()
+        ));
+        let content = "你好\tni hao\t10\n";
+        let bytes: Vec<u8> = content.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        std::fs::write(&path, bytes).unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let words = pipeline().with_input_encoding("auto").import(&[&path]);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(words.unwrap()[0].word, "你好");
+    }
+
+    #[test]
+    fn test_convert_bytes() {
+        let input = "你好\tni hao\t10\n中华人民共和国\tzhong hua\t5\n";
+        let output = convert_bytes(input.as_bytes(), "rime", "rime").unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "你好\tni hao\t10\n中华人民共和国\tzhong hua\t5"
+        );
+        assert!(convert_bytes(b"", "nope", "rime").is_err());
+    }
+}
@@ -0,0 +1,555 @@
+//! Job files describing a whole conversion
+//!
+//! A `JobSpec` declares the inputs, the processing steps and the outputs of a
+//! conversion, so it can be checked in and rerun, e.g. in CI. It is plain
+//! serde data; the CLI reads it from TOML or YAML:
+//!
+//! ```toml
+//! merge_strategy = "sum"
+//! sort = "pinyin"
+//!
+//! [[inputs]]
+//! path = "sogou.scel"
+//! format = "scel"
+//!
+//! [[inputs]]
+//! path = "user.txt"
+//! format = "rime"
+//! weight = 2.0
+//!
+//! [filters]
+//! min_length = 2
+//! charset = "gbk"
+//! idioms = "exclude"
+//! wordlist = { path = "blocked.txt" }
+//!
+//! [rank]
+//! strategy = "combine"
+//! existing = 0.3
+//!
+//! [[rank.generators]]
+//! strategy = "corpus"
+//! path = "news.txt"
+//! weight = 0.7
+//!
+//! [[length_boost]]
+//! min_length = 4
+//! max_length = 4
+//! factor = 1.2
+//!
+//! [[outputs]]
+//! path = "out.dict.yaml"
+//! format = "rime"
+//! ```
+
+use crate::batch::numbered_path;
+use crate::filter::charset::{Charset, CharsetFilter};
+use crate::filter::idiom::IdiomFilter;
+use crate::filter::length::LengthFilter;
+use crate::filter::rank::RankFilter;
+use crate::filter::reference::ReferenceFilter;
+use crate::filter::sensitive::SensitiveWordFilter;
+use crate::filter::wordlist::{WordlistFilter, WordlistMode};
+use crate::filter::SingleFilter;
+use crate::generate::generator_for;
+use crate::helpers::{write_file, AUTO_ENCODING};
+use crate::import::{ErrorPolicy, ImportOptions};
+use crate::merge::{merge_libraries, MergeSource, MergeStrategy};
+use crate::pipeline::ConversionPipeline;
+use crate::rank::{
+    CombineMode, CompositeRankGenerator, CorpusRankGenerator, DefaultRankGenerator,
+    FrequencyRankGenerator, LengthBoost, LengthBoostRule, PositionRankGenerator, RankFallback,
+    RankGenerator,
+};
+use crate::registry::FormatRegistry;
+use crate::resource::ResourceManager;
+use crate::sort::{SortOrder, WordLibrarySorter};
+use crate::translate::{OpenCCConverter, TranslationType};
+use crate::{CodeType, Error, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A conversion declared in a job file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobSpec {
+    pub inputs: Vec<JobInput>,
+    pub outputs: Vec<JobOutput>,
+    #[serde(default)]
+    pub filters: JobFilters,
+    /// Regenerate codes of this type, e.g. `"Wubi"`
+    #[serde(default)]
+    pub generator: Option<CodeType>,
+    #[serde(default)]
+    pub rank: Option<RankStrategy>,
+    /// Rank factors by word length, applied after `rank`
+    #[serde(default)]
+    pub length_boost: Vec<LengthBoostRule>,
+    #[serde(default)]
+    pub translate: Option<TranslationType>,
+    /// Regenerate glyph-based codes after translation
+    #[serde(default)]
+    pub retranslate_codes: bool,
+    #[serde(default)]
+    pub merge_strategy: MergeStrategy,
+    /// Merge entries with the same word and codes
+    #[serde(default)]
+    pub dedup: bool,
+    #[serde(default)]
+    pub sort: SortOrder,
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+}
+
+/// An input file of a job
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobInput {
+    pub path: PathBuf,
+    pub format: String,
+    /// Text encoding, defaults to the format's encoding
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Multiplier applied to the ranks of this input
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// An output file of a job; split outputs get a number appended
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobOutput {
+    pub path: PathBuf,
+    pub format: String,
+}
+
+/// Filters applied to each imported entry
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobFilters {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub min_rank: i32,
+    pub max_rank: i32,
+    /// Drop words found in this reference dictionary
+    pub exclude: Option<JobInput>,
+    /// Only drop excluded words whose codes match too
+    pub exclude_match_codes: bool,
+    /// Drop words with characters outside this repertoire
+    pub charset: Option<Charset>,
+    /// Remove or keep the words listed in a file
+    pub wordlist: Option<JobWordlist>,
+    /// Drop words containing a term of the bundled sensitive word list
+    pub sensitive: bool,
+    /// Drop words containing a term of these files, one per line
+    pub sensitive_words: Vec<PathBuf>,
+    /// Keep only idioms, or leave them out
+    pub idioms: Option<IdiomSelection>,
+}
+
+impl Default for JobFilters {
+    fn default() -> Self {
+        JobFilters {
+            min_length: 1,
+            max_length: 100,
+            min_rank: 0,
+            max_rank: i32::MAX,
+            exclude: None,
+            exclude_match_codes: false,
+            charset: None,
+            wordlist: None,
+            sensitive: false,
+            sensitive_words: Vec::new(),
+            idioms: None,
+        }
+    }
+}
+
+impl JobFilters {
+    /// Filters of the imported entries besides length, rank and exclusion
+    fn entry_filters(&self) -> Result<Vec<Box<dyn SingleFilter>>> {
+        let mut filters: Vec<Box<dyn SingleFilter>> = Vec::new();
+        if let Some(charset) = self.charset {
+            filters.push(Box::new(CharsetFilter::new(charset)));
+        }
+        if let Some(wordlist) = &self.wordlist {
+            let encoding = wordlist.encoding.as_deref().unwrap_or(AUTO_ENCODING);
+            filters.push(Box::new(WordlistFilter::from_file(
+                path_str(&wordlist.path)?,
+                encoding,
+                wordlist.mode,
+            )?));
+        }
+        if self.sensitive || !self.sensitive_words.is_empty() {
+            let mut filter = if self.sensitive {
+                SensitiveWordFilter::with_embedded()
+            } else {
+                SensitiveWordFilter::new()
+            };
+            for path in &self.sensitive_words {
+                filter = filter.with_term_file(path_str(path)?, AUTO_ENCODING)?;
+            }
+            filters.push(Box::new(filter));
+        }
+        if let Some(idioms) = self.idioms {
+            filters.push(Box::new(match idioms {
+                IdiomSelection::Only => IdiomFilter::idioms_only(),
+                IdiomSelection::Exclude => IdiomFilter::new(),
+            }));
+        }
+        Ok(filters)
+    }
+}
+
+/// A word list filtering the entries of a job
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobWordlist {
+    pub path: PathBuf,
+    /// Remove the listed words (default) or keep only them
+    #[serde(default)]
+    pub mode: WordlistMode,
+    /// Text encoding of the file (detected by default)
+    #[serde(default)]
+    pub encoding: Option<String>,
+}
+
+/// Which entries a job keeps with respect to idioms (成语)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdiomSelection {
+    /// Keep only idioms
+    Only,
+    /// Leave idioms out
+    Exclude,
+}
+
+/// How the ranks of the entries are generated
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum RankStrategy {
+    /// The same rank for every entry
+    Fixed { rank: i32 },
+    /// Ranks from the embedded word frequencies
+    Frequency {
+        #[serde(default)]
+        force: bool,
+    },
+    /// Ranks decreasing with the position in the list
+    Position { start: i32, step: i32 },
+    /// Ranks looked up in a plain text corpus or a word frequency table
+    Corpus {
+        path: PathBuf,
+        /// The file is a `word<TAB>frequency` table rather than plain text
+        #[serde(default)]
+        frequencies: bool,
+        #[serde(default)]
+        fallback: RankFallback,
+    },
+    /// Weighted combination of other strategies
+    Combine {
+        #[serde(default)]
+        mode: CombineMode,
+        /// Weight of the rank the entries already have
+        #[serde(default)]
+        existing: f64,
+        generators: Vec<WeightedRankStrategy>,
+    },
+}
+
+/// A strategy of a `combine` rank strategy with its weight
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WeightedRankStrategy {
+    #[serde(flatten)]
+    pub strategy: RankStrategy,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+impl RankStrategy {
+    fn paths_mut(&mut self) -> Vec<&mut PathBuf> {
+        match self {
+            RankStrategy::Corpus { path, .. } => vec![path],
+            RankStrategy::Combine { generators, .. } => generators
+                .iter_mut()
+                .flat_map(|g| g.strategy.paths_mut())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Generator applying the strategy
+    pub fn generator(&self) -> Result<Box<dyn RankGenerator>> {
+        Ok(match self {
+            RankStrategy::Fixed { rank } => Box::new(DefaultRankGenerator::new(*rank)),
+            RankStrategy::Frequency { force } => {
+                Box::new(FrequencyRankGenerator::new().with_force(*force))
+            }
+            RankStrategy::Position { start, step } => {
+                Box::new(PositionRankGenerator::new(*start, *step))
+            }
+            RankStrategy::Corpus {
+                path,
+                frequencies,
+                fallback,
+            } => {
+                let path = path_str(path)?;
+                let generator = if *frequencies {
+                    CorpusRankGenerator::from_frequency_file(path, AUTO_ENCODING)?
+                } else {
+                    CorpusRankGenerator::from_corpus_file(path, AUTO_ENCODING)?
+                };
+                Box::new(generator.with_fallback(*fallback))
+            }
+            RankStrategy::Combine {
+                mode,
+                existing,
+                generators,
+            } => {
+                let mut combined = CompositeRankGenerator::new(*mode).with_existing_rank(*existing);
+                for generator in generators {
+                    combined =
+                        combined.with_generator(generator.strategy.generator()?, generator.weight);
+                }
+                Box::new(combined)
+            }
+        })
+    }
+}
+
+impl JobSpec {
+    /// Resolve relative paths against a directory, usually that of the job file
+    pub fn resolve_paths(&mut self, base: &Path) {
+        let inputs = self.inputs.iter_mut().chain(&mut self.filters.exclude);
+        let paths = inputs
+            .map(|input| &mut input.path)
+            .chain(self.outputs.iter_mut().map(|output| &mut output.path))
+            .chain(self.filters.wordlist.iter_mut().map(|list| &mut list.path))
+            .chain(self.filters.sensitive_words.iter_mut())
+            .chain(self.rank.iter_mut().flat_map(|rank| rank.paths_mut()));
+        for path in paths {
+            if path.is_relative() {
+                *path = base.join(&*path);
+            }
+        }
+    }
+
+    /// Pipeline for one input and one output with the job's settings
+    fn pipeline(
+        &self,
+        registry: &FormatRegistry,
+        input: &JobInput,
+        output: &JobOutput,
+    ) -> Result<ConversionPipeline> {
+        let filters = &self.filters;
+        let mut pipeline = ConversionPipeline::new(
+            registry.importer(&input.format)?,
+            registry.exporter(&output.format)?,
+        )
+        .with_source_format(&input.format)
+        .with_filter(Box::new(LengthFilter::new(
+            filters.min_length,
+            filters.max_length,
+        )))
+        .with_filter(Box::new(RankFilter::new(
+            filters.min_rank,
+            filters.max_rank,
+        )))
+        .with_error_policy(self.on_error)
+        .merge_duplicates(self.dedup)
+        .with_sorter(WordLibrarySorter::new(self.sort));
+
+        if let Some(encoding) = &input.encoding {
+            pipeline = pipeline.with_input_encoding(encoding);
+        }
+        if let Some(translation) = self.translate {
+            pipeline = pipeline
+                .with_translator(Box::new(OpenCCConverter::new()?), translation)
+                .retranslate_codes(self.retranslate_codes);
+        }
+        if let Some(code_type) = self.generator {
+            pipeline =
+                pipeline.with_generator(generator_for(code_type, ResourceManager::shared())?);
+        }
+        if let Some(rank) = &self.rank {
+            pipeline = pipeline.with_rank_generator(rank.generator()?);
+        }
+        if !self.length_boost.is_empty() {
+            pipeline =
+                pipeline.with_length_boost(LengthBoost::with_rules(self.length_boost.clone()));
+        }
+        Ok(pipeline)
+    }
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| Error::InvalidFormat(format!("Invalid file path: {}", path.display())))
+}
+
+/// Run a job, returning the number of words written to each output
+pub fn run_job(spec: &JobSpec) -> Result<Vec<usize>> {
+    let Some(first_output) = spec.outputs.first() else {
+        return Err(Error::InvalidFormat("Job has no outputs".to_string()));
+    };
+    if spec.inputs.is_empty() {
+        return Err(Error::InvalidFormat("Job has no inputs".to_string()));
+    }
+    let registry = FormatRegistry::with_builtin_formats();
+    // Fail on unknown formats before writing anything
+    for output in &spec.outputs {
+        registry.exporter(&output.format)?;
+    }
+
+    let reference = match &spec.filters.exclude {
+        Some(exclude) => {
+            // Unlike the inputs, the reference dictionary is not filtered
+            let mut options = ImportOptions::new().with_error_policy(spec.on_error);
+            if let Some(encoding) = &exclude.encoding {
+                options = options.with_encoding(encoding);
+            }
+            let words = registry
+                .importer(&exclude.format)?
+                .import_with_options(path_str(&exclude.path)?, &options)?
+                .into();
+            Some(ReferenceFilter::new(&words).with_match_codes(spec.filters.exclude_match_codes))
+        }
+        None => None,
+    };
+
+    let mut sources = Vec::with_capacity(spec.inputs.len());
+    for input in &spec.inputs {
+        let mut pipeline = spec.pipeline(&registry, input, first_output)?;
+        if let Some(reference) = &reference {
+            pipeline = pipeline.with_filter(Box::new(reference.clone()));
+        }
+        for filter in spec.filters.entry_filters()? {
+            pipeline = pipeline.with_filter(filter);
+        }
+        let words = pipeline.import(&[path_str(&input.path)?])?;
+        sources.push(MergeSource::new(words).with_weight(input.weight));
+    }
+    let words = merge_libraries(sources, spec.merge_strategy);
+
+    let mut counts = Vec::with_capacity(spec.outputs.len());
+    for output in &spec.outputs {
+        let pipeline = spec.pipeline(&registry, &spec.inputs[0], output)?;
+        let words = pipeline.process(words.clone())?;
+        for (i, content) in pipeline.export(&words)?.iter().enumerate() {
+            write_file(
+                &numbered_path(&output.path, i),
+                content,
+                pipeline.exporter().encoding(),
+            )?;
+        }
+        counts.push(words.len());
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_job() {
+        let dir = std::env::temp_dir().join(format!("imewlconverter-job-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "你好\tni hao\t10\n中国\tzhong guo\t5\n").unwrap();
+        std::fs::write(dir.join("b.txt"), "你好\tni hao\t3\n").unwrap();
+
+        let mut spec = JobSpec {
+            inputs: ["a.txt", "b.txt"]
+                .iter()
+                .map(|path| JobInput {
+                    path: PathBuf::from(path),
+                    format: "rime".to_string(),
+                    encoding: None,
+                    weight: 1.0,
+                })
+                .collect(),
+            outputs: vec![JobOutput {
+                path: PathBuf::from("out.txt"),
+                format: "rime".to_string(),
+            }],
+            filters: JobFilters {
+                min_rank: 4,
+                ..JobFilters::default()
+            },
+            generator: None,
+            rank: None,
+            length_boost: Vec::new(),
+            translate: None,
+            retranslate_codes: false,
+            merge_strategy: MergeStrategy::SumRanks,
+            dedup: false,
+            sort: SortOrder::RankDescending,
+            on_error: ErrorPolicy::Skip,
+        };
+        spec.resolve_paths(&dir);
+
+        let counts = run_job(&spec);
+        let output = std::fs::read_to_string(dir.join("out.txt"));
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(counts.unwrap(), vec![2]);
+        assert_eq!(output.unwrap(), "你好\tni hao\t10\n中国\tzhong guo\t5");
+    }
+
+    #[test]
+    fn test_job_filters() {
+        let dir =
+            std::env::temp_dir().join(format!("imewlconverter-job-filters-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("in.txt"),
+            "你好\tni hao\t10\n中国\tzhong guo\t5\n一心一意\tyi xin yi yi\t3\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("blocked.txt"), "中国\n").unwrap();
+
+        let mut spec = JobSpec {
+            inputs: vec![JobInput {
+                path: PathBuf::from("in.txt"),
+                format: "rime".to_string(),
+                encoding: None,
+                weight: 1.0,
+            }],
+            outputs: vec![JobOutput {
+                path: PathBuf::from("out.txt"),
+                format: "rime".to_string(),
+            }],
+            filters: JobFilters {
+                wordlist: Some(JobWordlist {
+                    path: PathBuf::from("blocked.txt"),
+                    mode: WordlistMode::Remove,
+                    encoding: None,
+                }),
+                idioms: Some(IdiomSelection::Exclude),
+                charset: Some(Charset::Gb2312),
+                ..JobFilters::default()
+            },
+            generator: None,
+            rank: None,
+            length_boost: Vec::new(),
+            translate: None,
+            retranslate_codes: false,
+            merge_strategy: MergeStrategy::default(),
+            dedup: false,
+            sort: SortOrder::default(),
+            on_error: ErrorPolicy::Skip,
+        };
+        spec.resolve_paths(&dir);
+
+        let counts = run_job(&spec);
+        let output = std::fs::read_to_string(dir.join("out.txt"));
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(counts.unwrap(), vec![1]);
+        assert_eq!(output.unwrap(), "你好\tni hao\t10");
+    }
+}
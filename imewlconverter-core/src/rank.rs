@@ -1,6 +1,13 @@
 //! Word rank generation strategies
 
-use crate::{Result, WordLibrary};
+use crate::import::corpus::CorpusImport;
+use crate::import::read_file_with_encoding_str;
+use crate::resource::ResourceManager;
+use crate::{Result, WordLibrary, WordLibraryList};
+use serde::Deserialize;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Trait for word rank generators
 pub trait RankGenerator {
@@ -44,6 +51,433 @@ impl RankGenerator for DefaultRankGenerator {
     }
 }
 
+/// Parse a `word\tfrequency` table (or separated by spaces), skipping empty
+/// and `#` comment lines; frequencies are rounded
+fn parse_word_frequencies(content: &str) -> impl Iterator<Item = (&str, i32)> {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let (word, frequency) = (parts.next()?, parts.next()?);
+            let frequency = frequency.parse::<f64>().ok()?;
+            Some((word, frequency.round() as i32))
+        })
+}
+
+/// Rank generator based on word and character frequencies
+///
+/// Words found in the user-supplied frequency table use that frequency.
+/// Other words are ranked by their rarest character, using the character
+/// frequencies embedded in ChineseCode.txt, since a word can be no more
+/// common than its least common character.
+pub struct FrequencyRankGenerator {
+    resources: Arc<ResourceManager>,
+    /// User-supplied word frequencies
+    word_frequencies: HashMap<String, i32>,
+    /// Rank for words without any known character
+    default_rank: i32,
+    force: bool,
+}
+
+impl FrequencyRankGenerator {
+    pub fn new() -> Self {
+        Self::with_resources(ResourceManager::shared())
+    }
+
+    /// Initialize with existing resource manager (for sharing)
+    pub fn with_resources(resources: Arc<ResourceManager>) -> Self {
+        FrequencyRankGenerator {
+            resources,
+            word_frequencies: HashMap::new(),
+            default_rank: 1,
+            force: false,
+        }
+    }
+
+    /// Add word frequencies from text content
+    /// Format: `word\tfrequency` (or separated by spaces), `#` comments
+    pub fn with_word_frequencies(mut self, content: &str) -> Self {
+        for (word, frequency) in parse_word_frequencies(content) {
+            self.word_frequencies.insert(word.to_string(), frequency);
+        }
+        self
+    }
+
+    /// Load word frequencies from a file with the given text encoding
+    pub fn with_word_frequency_file(self, path: &str, encoding: &str) -> Result<Self> {
+        let content = read_file_with_encoding_str(path, encoding)?;
+        Ok(self.with_word_frequencies(&content))
+    }
+
+    /// Set the rank of words without any known character
+    pub fn with_default_rank(mut self, rank: i32) -> Self {
+        self.default_rank = rank;
+        self
+    }
+
+    /// Replace existing ranks instead of only filling in missing ones
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
+impl Default for FrequencyRankGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RankGenerator for FrequencyRankGenerator {
+    fn get_rank(&self, word: &str) -> Result<i32> {
+        if let Some(&frequency) = self.word_frequencies.get(word) {
+            return Ok(frequency);
+        }
+
+        let rarest = word
+            .chars()
+            .filter_map(|c| self.resources.get_frequency(c))
+            .reduce(f64::min);
+
+        Ok(match rarest {
+            Some(frequency) => (frequency.round() as i32).max(1),
+            None => self.default_rank,
+        })
+    }
+
+    fn force_use(&self) -> bool {
+        self.force
+    }
+}
+
+/// Rank generator assigning descending ranks by source position
+///
+/// The first word gets `start_rank`, and each following word `step` less,
+/// down to `min_rank`. Useful for sources without frequencies (e.g. Sogou
+/// text exports) that list words from most to least common. Existing ranks
+/// are always replaced, since such sources carry a placeholder rank.
+pub struct PositionRankGenerator {
+    start_rank: i32,
+    step: i32,
+    min_rank: i32,
+    /// Rank of the next word
+    next: Cell<i32>,
+}
+
+impl PositionRankGenerator {
+    pub fn new(start_rank: i32, step: i32) -> Self {
+        PositionRankGenerator {
+            start_rank,
+            step,
+            min_rank: 1,
+            next: Cell::new(start_rank),
+        }
+    }
+
+    /// Set the lowest rank assigned
+    pub fn with_min_rank(mut self, min_rank: i32) -> Self {
+        self.min_rank = min_rank;
+        self
+    }
+
+    /// Start again from the first position (e.g. for the next source file)
+    pub fn reset(&self) {
+        self.next.set(self.start_rank);
+    }
+}
+
+impl Default for PositionRankGenerator {
+    fn default() -> Self {
+        Self::new(100_000, 1)
+    }
+}
+
+impl RankGenerator for PositionRankGenerator {
+    fn get_rank(&self, _word: &str) -> Result<i32> {
+        let rank = self.next.get().max(self.min_rank);
+        self.next.set(rank.saturating_sub(self.step));
+        Ok(rank)
+    }
+
+    fn force_use(&self) -> bool {
+        true
+    }
+}
+
+/// Rank of words missing from the reference data of `CorpusRankGenerator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RankFallback {
+    /// Keep the rank the word already has
+    #[default]
+    Keep,
+    /// Use a fixed rank
+    Fixed(i32),
+    /// Use the count of the rarest character, as words are no more common
+    /// than their least common character; words with an unknown character
+    /// keep their rank
+    Characters,
+}
+
+/// Rank generator looking words up in user-supplied reference data
+///
+/// The data is either a word frequency table or a plain text corpus, whose
+/// words are counted after segmenting it with the embedded word list. Found
+/// words always get their count as rank; the others get the fallback.
+pub struct CorpusRankGenerator {
+    words: HashMap<String, i32>,
+    chars: HashMap<char, i32>,
+    fallback: RankFallback,
+}
+
+impl CorpusRankGenerator {
+    /// Load a word frequency table
+    /// Format: `word\tfrequency` (or separated by spaces), `#` comments
+    pub fn from_frequencies(content: &str) -> Self {
+        let mut generator = Self::empty();
+        for (word, frequency) in parse_word_frequencies(content) {
+            generator.add(word, frequency);
+        }
+        generator
+    }
+
+    /// Count the words of a plain text corpus
+    pub fn from_corpus(text: &str) -> Self {
+        Self::from_segments(CorpusImport::new().segment(text))
+    }
+
+    /// Count the segments of a corpus split by a custom segmenter
+    pub fn from_segments<'a>(segments: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut generator = Self::empty();
+        for segment in segments {
+            generator.add(segment, 1);
+        }
+        generator
+    }
+
+    pub fn from_frequency_file(path: &str, encoding: &str) -> Result<Self> {
+        Ok(Self::from_frequencies(&read_file_with_encoding_str(
+            path, encoding,
+        )?))
+    }
+
+    pub fn from_corpus_file(path: &str, encoding: &str) -> Result<Self> {
+        Ok(Self::from_corpus(&read_file_with_encoding_str(
+            path, encoding,
+        )?))
+    }
+
+    pub fn with_fallback(mut self, fallback: RankFallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    fn empty() -> Self {
+        CorpusRankGenerator {
+            words: HashMap::new(),
+            chars: HashMap::new(),
+            fallback: RankFallback::default(),
+        }
+    }
+
+    fn add(&mut self, word: &str, count: i32) {
+        let total = self.words.entry(word.to_string()).or_insert(0);
+        *total = total.saturating_add(count);
+        for c in word.chars() {
+            let total = self.chars.entry(c).or_insert(0);
+            *total = total.saturating_add(count);
+        }
+    }
+
+    /// Count of a word, or its fallback rank if there is one
+    fn lookup(&self, word: &str) -> Option<i32> {
+        if let Some(&count) = self.words.get(word) {
+            return Some(count);
+        }
+        match self.fallback {
+            RankFallback::Keep => None,
+            RankFallback::Fixed(rank) => Some(rank),
+            RankFallback::Characters => word
+                .chars()
+                .map(|c| self.chars.get(&c).copied())
+                .collect::<Option<Vec<i32>>>()
+                .and_then(|counts| counts.into_iter().min()),
+        }
+    }
+}
+
+impl RankGenerator for CorpusRankGenerator {
+    /// Count of a word, 0 when neither it nor its fallback is known
+    fn get_rank(&self, word: &str) -> Result<i32> {
+        Ok(self.lookup(word).unwrap_or(0))
+    }
+
+    fn force_use(&self) -> bool {
+        true
+    }
+
+    fn generate_rank(&self, word: &mut WordLibrary) -> Result<()> {
+        if let Some(rank) = self.lookup(&word.word) {
+            word.rank = rank;
+        }
+        Ok(())
+    }
+}
+
+/// How `CompositeRankGenerator` combines the ranks of its generators
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CombineMode {
+    /// Sum of the weighted ranks
+    #[default]
+    WeightedSum,
+    /// Highest weighted rank
+    Max,
+}
+
+/// Rank generator combining several weighted generators
+///
+/// Each generator ranks its own copy of the entry, so generators that keep
+/// existing ranks contribute those. The rank the entry already has can be
+/// included with its own weight, e.g. 0.7 × corpus + 0.3 × existing rank.
+pub struct CompositeRankGenerator {
+    generators: Vec<(Box<dyn RankGenerator>, f64)>,
+    existing_weight: f64,
+    mode: CombineMode,
+}
+
+impl CompositeRankGenerator {
+    pub fn new(mode: CombineMode) -> Self {
+        CompositeRankGenerator {
+            generators: Vec::new(),
+            existing_weight: 0.0,
+            mode,
+        }
+    }
+
+    pub fn with_generator(mut self, generator: Box<dyn RankGenerator>, weight: f64) -> Self {
+        self.generators.push((generator, weight));
+        self
+    }
+
+    /// Include the rank the entry already has with `weight`
+    pub fn with_existing_rank(mut self, weight: f64) -> Self {
+        self.existing_weight = weight;
+        self
+    }
+}
+
+impl Default for CompositeRankGenerator {
+    fn default() -> Self {
+        Self::new(CombineMode::default())
+    }
+}
+
+impl RankGenerator for CompositeRankGenerator {
+    fn get_rank(&self, word: &str) -> Result<i32> {
+        let mut entry = WordLibrary::new(word.to_string());
+        self.generate_rank(&mut entry)?;
+        Ok(entry.rank)
+    }
+
+    fn force_use(&self) -> bool {
+        true
+    }
+
+    fn generate_rank(&self, word: &mut WordLibrary) -> Result<()> {
+        let mut ranks = Vec::with_capacity(self.generators.len() + 1);
+        if self.existing_weight != 0.0 {
+            ranks.push(f64::from(word.rank) * self.existing_weight);
+        }
+        for (generator, weight) in &self.generators {
+            let mut entry = word.clone();
+            generator.generate_rank(&mut entry)?;
+            ranks.push(f64::from(entry.rank) * weight);
+        }
+
+        let combined = match self.mode {
+            CombineMode::WeightedSum => ranks.iter().copied().reduce(|a, b| a + b),
+            CombineMode::Max => ranks.iter().copied().reduce(f64::max),
+        };
+        if let Some(rank) = combined {
+            word.rank = rank.round().clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+        }
+        Ok(())
+    }
+}
+
+/// Rank factor for words of a length range, see `LengthBoost`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LengthBoostRule {
+    #[serde(default = "default_min_length")]
+    pub min_length: usize,
+    /// Longest length matched, unbounded if not set
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    pub factor: f64,
+}
+
+fn default_min_length() -> usize {
+    1
+}
+
+impl LengthBoostRule {
+    pub fn new(min_length: usize, max_length: Option<usize>, factor: f64) -> Self {
+        LengthBoostRule {
+            min_length,
+            max_length,
+            factor,
+        }
+    }
+
+    fn matches(&self, length: usize) -> bool {
+        length >= self.min_length && self.max_length.map_or(true, |max| length <= max)
+    }
+}
+
+/// Scales ranks by word length, for IMEs favoring longer phrases
+///
+/// The rank of a word is multiplied by the factor of the first rule matching
+/// its length in characters, e.g. 1.2 for four-character idioms and 0.8 for
+/// single characters. Words matching no rule keep their rank.
+#[derive(Debug, Clone, Default)]
+pub struct LengthBoost {
+    rules: Vec<LengthBoostRule>,
+}
+
+impl LengthBoost {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rules(rules: Vec<LengthBoostRule>) -> Self {
+        LengthBoost { rules }
+    }
+
+    /// Scale the rank of words of `min_length` to `max_length` characters
+    pub fn with_rule(mut self, min_length: usize, max_length: Option<usize>, factor: f64) -> Self {
+        self.rules
+            .push(LengthBoostRule::new(min_length, max_length, factor));
+        self
+    }
+
+    pub fn boost(&self, word: &mut WordLibrary) {
+        let length = word.word.chars().count();
+        if let Some(rule) = self.rules.iter().find(|rule| rule.matches(length)) {
+            let rank = (f64::from(word.rank) * rule.factor).round();
+            word.rank = rank.clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+        }
+    }
+
+    pub fn apply(&self, words: &mut WordLibraryList) {
+        for word in words.iter_mut() {
+            self.boost(word);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,4 +496,109 @@ mod tests {
         generator.generate_rank(&mut word).unwrap();
         assert_eq!(word.rank, 100);
     }
+
+    #[test]
+    fn test_frequency_rank_generator() {
+        let generator =
+            FrequencyRankGenerator::new().with_word_frequencies("# 1000\n你好\t5000\n坏行\n");
+
+        // User-supplied word frequency
+        assert_eq!(generator.get_rank("你好").unwrap(), 5000);
+
+        // Rarest character: 的 is far more common than 曦
+        let common = generator.get_rank("的").unwrap();
+        let rare = generator.get_rank("的曦").unwrap();
+        assert!(rare < common);
+        assert_eq!(rare, generator.get_rank("曦").unwrap());
+
+        // No known character; comment lines are not words
+        assert_eq!(generator.get_rank("abc").unwrap(), 1);
+        assert_eq!(generator.get_rank("#").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_frequency_rank_keeps_existing() {
+        let generator = FrequencyRankGenerator::new();
+        let mut word = WordLibrary::with_rank("的".to_string(), 3);
+
+        generator.generate_rank(&mut word).unwrap();
+        assert_eq!(word.rank, 3);
+
+        generator.with_force(true).generate_rank(&mut word).unwrap();
+        assert!(word.rank > 3);
+    }
+
+    #[test]
+    fn test_position_rank_generator() {
+        let generator = PositionRankGenerator::new(10, 4).with_min_rank(1);
+        let mut words: Vec<WordLibrary> = ["一", "二", "三", "四"]
+            .iter()
+            .map(|w| WordLibrary::with_rank(w.to_string(), 1))
+            .collect();
+
+        for word in words.iter_mut() {
+            generator.generate_rank(word).unwrap();
+        }
+        let ranks: Vec<i32> = words.iter().map(|w| w.rank).collect();
+        assert_eq!(ranks, vec![10, 6, 2, 1]);
+
+        generator.reset();
+        assert_eq!(generator.get_rank("五").unwrap(), 10);
+    }
+
+    #[test]
+    fn test_corpus_rank_generator() {
+        let generator = CorpusRankGenerator::from_frequencies("# 词频\n你好\t30\n好人 5\n");
+        let mut word = WordLibrary::with_rank("你好".to_string(), 1);
+        generator.generate_rank(&mut word).unwrap();
+        assert_eq!(word.rank, 30);
+        let mut unknown = WordLibrary::with_rank("人好".to_string(), 7);
+        generator.generate_rank(&mut unknown).unwrap();
+        assert_eq!(unknown.rank, 7);
+
+        // 人 only occurs in 好人, 好 in both words
+        let generator = generator.with_fallback(RankFallback::Characters);
+        assert_eq!(generator.get_rank("人好").unwrap(), 5);
+        assert_eq!(generator.get_rank("人们").unwrap(), 0);
+        let generator = generator.with_fallback(RankFallback::Fixed(2));
+        assert_eq!(generator.get_rank("人们").unwrap(), 2);
+
+        let segmenter = CorpusImport::with_dictionary(["中国".to_string(), "人民".to_string()]);
+        let generator = CorpusRankGenerator::from_segments(segmenter.segment("中国人民。中国！"));
+        assert_eq!(generator.get_rank("中国").unwrap(), 2);
+        assert_eq!(generator.get_rank("人民").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_composite_rank_generator() {
+        let corpus = CorpusRankGenerator::from_frequencies("你好\t1000\n");
+        let generator = CompositeRankGenerator::new(CombineMode::WeightedSum)
+            .with_generator(Box::new(corpus), 0.7)
+            .with_existing_rank(0.3);
+        let mut word = WordLibrary::with_rank("你好".to_string(), 100);
+        generator.generate_rank(&mut word).unwrap();
+        assert_eq!(word.rank, 730);
+
+        let generator = CompositeRankGenerator::new(CombineMode::Max)
+            .with_generator(Box::new(DefaultRankGenerator::new(50)), 1.0)
+            .with_generator(Box::new(DefaultRankGenerator::new(80)), 0.5);
+        assert_eq!(generator.get_rank("你好").unwrap(), 50);
+    }
+
+    #[test]
+    fn test_length_boost() {
+        let boost = LengthBoost::new()
+            .with_rule(4, Some(4), 1.2)
+            .with_rule(1, Some(1), 0.5)
+            .with_rule(3, None, 2.0);
+        let ranks: Vec<i32> = ["一", "你好", "马马虎虎", "中华人民共和国"]
+            .iter()
+            .map(|word| {
+                let mut word = WordLibrary::with_rank(word.to_string(), 100);
+                boost.boost(&mut word);
+                word.rank
+            })
+            .collect();
+        assert_eq!(ranks, vec![50, 100, 120, 200]);
+    }
 }
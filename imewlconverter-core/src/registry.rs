@@ -0,0 +1,506 @@
+//! Registry of the supported dictionary formats
+//!
+//! Formats are registered by name together with aliases, file extensions and
+//! factories for their importer and exporter. The built-in formats are
+//! available through `FormatRegistry::with_builtin_formats()`; downstream
+//! crates can register their own formats on top of them.
+//!
+//! # Example
+//!
+//! ```
+//! use imewlconverter_core::import::RimeImport;
+//! use imewlconverter_core::registry::{FormatDescriptor, FormatRegistry};
+//!
+//! let mut registry = FormatRegistry::with_builtin_formats();
+//! registry.register(
+//!     FormatDescriptor::new("my-rime", "Rime with a custom name")
+//!         .with_alias("mr")
+//!         .with_importer(|| Box::new(RimeImport::new())),
+//! );
+//!
+//! assert!(registry.importer("mr").is_ok());
+//! ```
+
+use crate::export::apple_replacement::AppleReplacementExport;
+use crate::export::english_phrase::EnglishPhraseExport;
+use crate::export::ibus_table::IbusTableExport;
+use crate::export::jidian::JidianExport;
+use crate::export::libime::LibimeExport;
+use crate::export::ms_wubi::MsWubiExport;
+use crate::export::ndjson::NdjsonExport;
+use crate::export::qq_pinyin::QQPinyinExport;
+use crate::export::rime::RimeExport;
+use crate::export::rime_essay::RimeEssayExport;
+use crate::export::rime_userdb::RimeUserdbExport;
+use crate::export::sogou_english::SogouEnglishExport;
+use crate::export::sogou_phrase::SogouPhraseExport;
+use crate::export::sogou_wubi::SogouWubiExport;
+use crate::export::yong::YongExport;
+use crate::export::WordLibraryExport;
+use crate::import::apple_replacement::ReplacementFormat;
+use crate::import::english_phrase::PhraseDialect;
+use crate::import::*;
+use crate::{CodeType, Error, Result};
+
+type ImporterFactory = Box<dyn Fn() -> Box<dyn WordLibraryImport> + Send + Sync>;
+type ExporterFactory = Box<dyn Fn() -> Box<dyn WordLibraryExport> + Send + Sync>;
+
+/// Description of a format and how to construct its importer and exporter
+pub struct FormatDescriptor {
+    name: String,
+    description: String,
+    aliases: Vec<String>,
+    extensions: Vec<String>,
+    /// Default text encoding (None for binary formats)
+    encoding: Option<String>,
+    importer: Option<ImporterFactory>,
+    exporter: Option<ExporterFactory>,
+}
+
+impl FormatDescriptor {
+    pub fn new(name: &str, description: &str) -> Self {
+        FormatDescriptor {
+            name: normalize(name),
+            description: description.to_string(),
+            aliases: Vec::new(),
+            extensions: Vec::new(),
+            encoding: None,
+            importer: None,
+            exporter: None,
+        }
+    }
+
+    /// Add an alternative name
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        self.aliases.push(normalize(alias));
+        self
+    }
+
+    /// Add a file extension, without the leading dot (e.g. "dict.yaml")
+    pub fn with_extension(mut self, extension: &str) -> Self {
+        self.extensions
+            .push(extension.trim_start_matches('.').to_lowercase());
+        self
+    }
+
+    /// Set the default text encoding
+    pub fn with_encoding(mut self, encoding: &str) -> Self {
+        self.encoding = Some(encoding.to_string());
+        self
+    }
+
+    /// Set the factory creating the importer
+    pub fn with_importer<F>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> Box<dyn WordLibraryImport> + Send + Sync + 'static,
+    {
+        self.importer = Some(Box::new(factory));
+        self
+    }
+
+    /// Set the factory creating the exporter
+    pub fn with_exporter<F>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> Box<dyn WordLibraryExport> + Send + Sync + 'static,
+    {
+        self.exporter = Some(Box::new(factory));
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn encoding(&self) -> Option<&str> {
+        self.encoding.as_deref()
+    }
+
+    pub fn can_import(&self) -> bool {
+        self.importer.is_some()
+    }
+
+    pub fn can_export(&self) -> bool {
+        self.exporter.is_some()
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.iter().any(|alias| alias == name)
+    }
+}
+
+/// Normalize a format name: lowercase with '-' as separator
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('_', "-")
+}
+
+/// Collection of formats looked up by name, alias or file extension
+#[derive(Default)]
+pub struct FormatRegistry {
+    formats: Vec<FormatDescriptor>,
+}
+
+impl FormatRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        FormatRegistry {
+            formats: Vec::new(),
+        }
+    }
+
+    /// Create a registry with all built-in formats
+    pub fn with_builtin_formats() -> Self {
+        let mut registry = Self::new();
+        register_builtin_formats(&mut registry);
+        registry
+    }
+
+    /// Register a format
+    ///
+    /// A format with the same name is replaced, so built-in formats can be
+    /// overridden.
+    pub fn register(&mut self, format: FormatDescriptor) {
+        match self.formats.iter_mut().find(|f| f.name == format.name) {
+            Some(existing) => *existing = format,
+            None => self.formats.push(format),
+        }
+    }
+
+    /// All registered formats in registration order
+    pub fn formats(&self) -> impl Iterator<Item = &FormatDescriptor> {
+        self.formats.iter()
+    }
+
+    /// Find a format by name or alias (case-insensitive, '_' and '-' are equivalent)
+    pub fn find(&self, name: &str) -> Option<&FormatDescriptor> {
+        let name = normalize(name);
+        self.formats.iter().find(|f| f.matches(&name))
+    }
+
+    /// Find the formats using the extension of a file name
+    ///
+    /// Compound extensions such as "dict.yaml" are matched too.
+    pub fn find_by_path(&self, path: &str) -> Vec<&FormatDescriptor> {
+        let path = path.to_lowercase();
+        self.formats
+            .iter()
+            .filter(|f| {
+                f.extensions
+                    .iter()
+                    .any(|ext| path.ends_with(&format!(".{}", ext)))
+            })
+            .collect()
+    }
+
+    /// Create the importer of a format
+    pub fn importer(&self, name: &str) -> Result<Box<dyn WordLibraryImport>> {
+        let format = self.lookup(name)?;
+        let factory = format
+            .importer
+            .as_ref()
+            .ok_or_else(|| Error::Unsupported(format!("Import of format '{}'", format.name)))?;
+        Ok(factory())
+    }
+
+    /// Create the exporter of a format
+    pub fn exporter(&self, name: &str) -> Result<Box<dyn WordLibraryExport>> {
+        let format = self.lookup(name)?;
+        let factory = format
+            .exporter
+            .as_ref()
+            .ok_or_else(|| Error::Unsupported(format!("Export of format '{}'", format.name)))?;
+        Ok(factory())
+    }
+
+    fn lookup(&self, name: &str) -> Result<&FormatDescriptor> {
+        self.find(name)
+            .ok_or_else(|| Error::InvalidFormat(format!("Unknown format '{}'", name)))
+    }
+}
+
+fn register_builtin_formats(registry: &mut FormatRegistry) {
+    let formats = [
+        FormatDescriptor::new("apple-csv", "Apple text replacements (CSV)")
+            .with_extension("csv")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(AppleReplacementImport::new(ReplacementFormat::Csv)))
+            .with_exporter(|| Box::new(AppleReplacementExport::new(ReplacementFormat::Csv))),
+        FormatDescriptor::new("apple-plist", "Apple text replacements (macOS/iOS plist)")
+            .with_alias("plist")
+            .with_extension("plist")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(AppleReplacementImport::new(ReplacementFormat::Plist)))
+            .with_exporter(|| Box::new(AppleReplacementExport::new(ReplacementFormat::Plist))),
+        FormatDescriptor::new("baidu-pinyin", "Baidu Pinyin")
+            .with_alias("bdpy")
+            .with_extension("txt")
+            .with_encoding("utf-16le")
+            .with_importer(|| Box::new(BaiduPinyinImport)),
+        FormatDescriptor::new("chinese-pyim", "Chinese Pyim")
+            .with_alias("pyim")
+            .with_extension("pyim")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(ChinesePyimImport::new())),
+        FormatDescriptor::new("corpus", "Plain Chinese text, segmented into words")
+            .with_alias("text")
+            .with_extension("txt")
+            .with_encoding("auto")
+            .with_importer(|| Box::new(CorpusImport::new())),
+        FormatDescriptor::new("english-wordlist", "English word frequency list")
+            .with_alias("enwl")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(EnglishWordListImport::new())),
+        FormatDescriptor::new("fit-input", "FIT input")
+            .with_alias("fit")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(FitInputImport::new())),
+        FormatDescriptor::new("google-pinyin", "Google Pinyin")
+            .with_alias("ggpy")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(GooglePinyinImport)),
+        FormatDescriptor::new("html", "Web page, text segmented into words")
+            .with_alias("htm")
+            .with_extension("html")
+            .with_extension("htm")
+            .with_encoding("auto")
+            .with_importer(|| Box::new(HtmlImport::new())),
+        FormatDescriptor::new("ibus-table", "ibus-table source (Wubi)")
+            .with_alias("ibus")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(IbusTableImport::new()))
+            .with_exporter(|| Box::new(IbusTableExport::new())),
+        FormatDescriptor::new("imewl", "Canonical intermediate format (versioned NDJSON)")
+            .with_alias("ndjson")
+            .with_extension("ndjson")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(NdjsonImport::new()))
+            .with_exporter(|| Box::new(NdjsonExport::new())),
+        FormatDescriptor::new("jidian", "Jidian Wubi mb text")
+            .with_alias("jd")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(JidianImport::new()))
+            .with_exporter(|| Box::new(JidianExport::new())),
+        FormatDescriptor::new("libime", "Fcitx5 pinyin dictionary (libime text)")
+            .with_alias("fcitx5")
+            .with_extension("txt")
+            .with_extension("dict")
+            .with_encoding("utf-8")
+            .with_exporter(|| Box::new(LibimeExport::new())),
+        FormatDescriptor::new("libpinyin", "Libpinyin")
+            .with_alias("libpy")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(LibpinyinImport::new())),
+        FormatDescriptor::new("ms-pinyin", "Microsoft Pinyin")
+            .with_alias("mspy")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(MsPinyinImport::new())),
+        FormatDescriptor::new("ms-pinyin-lex", "Microsoft Pinyin user phrases (.lex)")
+            .with_alias("mspylex")
+            .with_extension("lex")
+            .with_importer(|| Box::new(MsPinyinLexImport::new())),
+        FormatDescriptor::new("ms-wubi", "Microsoft Wubi custom phrases (Windows 10)")
+            .with_alias("mswb")
+            .with_extension("txt")
+            .with_encoding("utf-16le")
+            .with_importer(|| Box::new(MsWubiImport::new()))
+            .with_exporter(|| Box::new(MsWubiExport::new())),
+        FormatDescriptor::new("pinyin-jiajia", "Pinyin Jiajia")
+            .with_alias("pyjj")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(PinyinJiajiaImport::new())),
+        FormatDescriptor::new("qq-english-phrase", "QQ Pinyin English custom phrases")
+            .with_alias("qqenphrase")
+            .with_extension("txt")
+            .with_encoding("utf-16le")
+            .with_importer(|| Box::new(EnglishPhraseImport::new(PhraseDialect::QQ)))
+            .with_exporter(|| Box::new(EnglishPhraseExport::new(PhraseDialect::QQ))),
+        FormatDescriptor::new("qq-pinyin", "QQ Pinyin text")
+            .with_alias("qqpy")
+            .with_extension("txt")
+            .with_encoding("utf-16le")
+            .with_importer(|| Box::new(QQPinyinImport::new()))
+            .with_exporter(|| Box::new(QQPinyinExport::new())),
+        FormatDescriptor::new("qq-wubi", "QQ Wubi")
+            .with_alias("qqwb")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(QQWubiImport::new())),
+        FormatDescriptor::new("rime", "Rime")
+            .with_extension("dict.yaml")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(RimeImport::new()))
+            .with_exporter(|| Box::new(RimeExport::new())),
+        FormatDescriptor::new("rime-jyutping", "Rime with Cantonese Jyutping codes")
+            .with_alias("jyutping")
+            .with_extension("dict.yaml")
+            .with_encoding("utf-8")
+            .with_exporter(|| Box::new(RimeExport::with_code_type(CodeType::Jyutping))),
+        FormatDescriptor::new("rime-shuangpin", "Rime with shuangpin codes")
+            .with_alias("shuangpin")
+            .with_extension("dict.yaml")
+            .with_encoding("utf-8")
+            .with_exporter(|| Box::new(RimeExport::with_code_type(CodeType::Shuangpin))),
+        FormatDescriptor::new("rime-essay", "Rime essay.txt word frequencies")
+            .with_alias("essay")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_exporter(|| Box::new(RimeEssayExport::new())),
+        FormatDescriptor::new("rime-userdb", "Rime user dictionary snapshot")
+            .with_alias("userdb")
+            .with_extension("userdb.txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(RimeUserdbImport::new()))
+            .with_exporter(|| Box::new(RimeUserdbExport::new())),
+        FormatDescriptor::new("shouxin", "Baidu Shouxin text")
+            .with_alias("sxpy")
+            .with_extension("txt")
+            .with_encoding("utf-16le")
+            .with_importer(|| Box::new(ShouxinImport::new())),
+        FormatDescriptor::new("sina-pinyin", "Sina Pinyin")
+            .with_alias("sinapy")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(SinaPinyinImport::new())),
+        FormatDescriptor::new("sogou-english-phrase", "Sogou English custom phrases")
+            .with_alias("sgenphrase")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(EnglishPhraseImport::new(PhraseDialect::Sogou)))
+            .with_exporter(|| Box::new(EnglishPhraseExport::new(PhraseDialect::Sogou))),
+        FormatDescriptor::new("sogou-phrase", "Sogou custom phrases (PhraseEdit.txt)")
+            .with_alias("sgphrase")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(SogouPhraseImport::new()))
+            .with_exporter(|| Box::new(SogouPhraseExport::new())),
+        FormatDescriptor::new("sogou-pinyin", "Sogou Pinyin text")
+            .with_alias("sgpy")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(SogouPinyinImport)),
+        FormatDescriptor::new("sogou-english", "Sogou English thesaurus")
+            .with_alias("sgen")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_exporter(|| Box::new(SogouEnglishExport::new())),
+        FormatDescriptor::new("sogou-scel", "Sogou Scel binary")
+            .with_alias("scel")
+            .with_extension("scel")
+            .with_importer(|| Box::new(SogouScelImport)),
+        FormatDescriptor::new("sogou-wubi", "Sogou Wubi user dictionary")
+            .with_alias("sgwb")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(SogouWubiImport::new()))
+            .with_exporter(|| Box::new(SogouWubiExport::new())),
+        FormatDescriptor::new("wubi86", "Wubi 86")
+            .with_alias("wb86")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(Wubi86Import)),
+        FormatDescriptor::new("wubi98", "Wubi 98")
+            .with_alias("wb98")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(Wubi98Import)),
+        FormatDescriptor::new("wubi-new-age", "Wubi New Age")
+            .with_alias("wbnewage")
+            .with_extension("txt")
+            .with_encoding("utf-8")
+            .with_importer(|| Box::new(WubiNewAgeImport)),
+        FormatDescriptor::new("yong", "Yong (Xiaoxiao) code table")
+            .with_alias("xiaoxiao")
+            .with_extension("txt")
+            .with_encoding("auto")
+            .with_importer(|| Box::new(YongImport::new()))
+            .with_exporter(|| Box::new(YongExport::new())),
+        FormatDescriptor::new("zhangyu", "Zhangyu text")
+            .with_alias("zypy")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(ZhangyuImport::new())),
+        FormatDescriptor::new("ziguang-pinyin", "Ziguang Pinyin")
+            .with_alias("zgpy")
+            .with_extension("txt")
+            .with_encoding("gbk")
+            .with_importer(|| Box::new(ZiguangPinyinImport::new())),
+        FormatDescriptor::new("ziguang-uwl", "Ziguang Pinyin binary user dictionary")
+            .with_alias("uwl")
+            .with_extension("uwl")
+            .with_importer(|| Box::new(ZiguangUwlImport::new())),
+    ];
+
+    for format in formats {
+        registry.register(format);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let registry = FormatRegistry::with_builtin_formats();
+
+        assert_eq!(registry.find("qqpy").unwrap().name(), "qq-pinyin");
+        assert_eq!(registry.find("QQ_Pinyin").unwrap().name(), "qq-pinyin");
+        assert!(registry.find("unknown").is_none());
+
+        let scel = registry.find_by_path("/tmp/词库.SCEL");
+        assert_eq!(scel.len(), 1);
+        assert_eq!(scel[0].name(), "sogou-scel");
+        assert_eq!(registry.find_by_path("luna.dict.yaml")[0].name(), "rime");
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let registry = FormatRegistry::with_builtin_formats();
+
+        assert!(registry.importer("rime").is_ok());
+        assert!(registry.exporter("rime").is_ok());
+        assert!(matches!(
+            registry.exporter("scel"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            registry.importer("nope"),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_custom_format() {
+        let mut registry = FormatRegistry::with_builtin_formats();
+        let count = registry.formats().count();
+
+        registry.register(
+            FormatDescriptor::new("rime", "Rime (import only)")
+                .with_importer(|| Box::new(RimeImport::new())),
+        );
+        registry.register(FormatDescriptor::new("custom", "Custom").with_alias("c"));
+
+        assert_eq!(registry.formats().count(), count + 1);
+        assert!(!registry.find("rime").unwrap().can_export());
+        assert_eq!(registry.find("C").unwrap().name(), "custom");
+    }
+}
@@ -0,0 +1,98 @@
+//! Statistics collected while converting a word library
+
+use crate::stats::ImportStats;
+use crate::Error;
+use serde::Serialize;
+
+/// Number of entries imported from one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileReport {
+    pub path: String,
+    pub imported: usize,
+    /// Size of the file and the time its import took
+    pub stats: ImportStats,
+}
+
+/// Number of entries dropped by one filter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FilterReport {
+    pub filter: String,
+    pub dropped: usize,
+}
+
+/// A word dropped because its codes could not be generated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GenerationFailure {
+    pub word: String,
+    /// Character missing from the code table, if that was the cause
+    pub character: Option<char>,
+    pub error: String,
+}
+
+/// Summary of a conversion returned by `ConversionPipeline::take_report`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConversionReport {
+    pub files: Vec<FileReport>,
+    pub filters: Vec<FilterReport>,
+    pub generation_failures: Vec<GenerationFailure>,
+    /// Entries merged into an identical word with the same codes
+    pub duplicates_merged: usize,
+    pub exported: usize,
+}
+
+impl ConversionReport {
+    /// Total number of imported entries
+    pub fn imported(&self) -> usize {
+        self.files.iter().map(|f| f.imported).sum()
+    }
+
+    /// Import counters summed over all files
+    pub fn import_stats(&self) -> ImportStats {
+        let mut stats = ImportStats::default();
+        for file in &self.files {
+            stats.add(&file.stats);
+        }
+        stats
+    }
+
+    /// Total number of entries dropped by filters
+    pub fn filtered(&self) -> usize {
+        self.filters.iter().map(|f| f.dropped).sum()
+    }
+
+    pub(crate) fn record_import(&mut self, path: &str, stats: ImportStats) {
+        self.files.push(FileReport {
+            path: path.to_string(),
+            imported: stats.entries,
+            stats,
+        });
+    }
+
+    /// Add dropped entries to the count of a filter
+    pub(crate) fn record_dropped(&mut self, filter: &str, dropped: usize) {
+        match self.filters.iter_mut().find(|f| f.filter == filter) {
+            Some(report) => report.dropped += dropped,
+            None => self.filters.push(FilterReport {
+                filter: filter.to_string(),
+                dropped,
+            }),
+        }
+    }
+
+    pub(crate) fn record_generation_failure(&mut self, word: &str, error: &Error) {
+        let character = match error {
+            Error::CharacterNotFound(c) => Some(*c),
+            _ => None,
+        };
+        self.generation_failures.push(GenerationFailure {
+            word: word.to_string(),
+            character,
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Short name of a type, without its module path
+pub(crate) fn short_type_name(name: &'static str) -> &'static str {
+    name.rsplit("::").next().unwrap_or(name)
+}
@@ -2,6 +2,7 @@
 use crate::data::CodeType;
 use crate::error::Error;
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
 
 /// Character encoding information from ChineseCode.txt
 #[derive(Debug, Clone)]
@@ -12,7 +13,9 @@ pub struct ChineseCode {
     pub wubi98: Vec<String>,   // ggll
     pub wubi_new: Vec<String>, // ggll
     pub pinyin: Vec<String>,   // yi1
-    pub frequency: f64,        // 37283.98
+    /// Relative frequency of each reading in `pinyin` (0.0 when unknown)
+    pub pinyin_weights: Vec<f64>,
+    pub frequency: f64, // 37283.98
 }
 
 impl ChineseCode {
@@ -35,13 +38,23 @@ pub struct WordPinyin {
     pub pinyin: String, // with apostrophes like 'jiao'gai
 }
 
+/// Lazily loaded instance shared by all generators
+static GLOBAL: OnceLock<Arc<ResourceManager>> = OnceLock::new();
+
 /// Resource manager for all embedded dictionaries
+///
+/// Pinyin reading weights cover only a few common polyphonic characters; see
+/// `get_pinyin_weights`.
 pub struct ResourceManager {
     chinese_code: HashMap<char, ChineseCode>,
     word_pinyin: HashMap<String, WordPinyin>,
+    /// Length in characters of the longest entry in `word_pinyin`
+    max_word_pinyin_len: usize,
     zhengma: HashMap<char, Vec<String>>,
     cangjie: HashMap<char, Vec<String>>,
     zhuyin: HashMap<char, Vec<String>>,
+    jyutping: HashMap<char, Vec<String>>,
+    shuangpin: HashMap<String, Vec<String>>,
 }
 
 impl ResourceManager {
@@ -52,16 +65,43 @@ impl ResourceManager {
         let zhengma = Self::load_simple_dict(include_str!("../resources/Zhengma.txt"))?;
         let cangjie = Self::load_simple_dict(include_str!("../resources/Cangjie5.txt"))?;
         let zhuyin = Self::load_simple_dict(include_str!("../resources/Zhuyin.txt"))?;
+        let jyutping = Self::load_simple_dict(include_str!("../resources/Jyutping.txt"))?;
+        let shuangpin = Self::load_shuangpin()?;
+        let max_word_pinyin_len = word_pinyin
+            .keys()
+            .map(|word| word.chars().count())
+            .max()
+            .unwrap_or(0);
 
         Ok(Self {
             chinese_code,
             word_pinyin,
+            max_word_pinyin_len,
             zhengma,
             cangjie,
             zhuyin,
+            jyutping,
+            shuangpin,
         })
     }
 
+    /// Get the shared instance, loading the resources on first use
+    ///
+    /// Parsing the embedded dictionaries is expensive, so generators use this
+    /// instance by default instead of calling `new()`.
+    pub fn global() -> &'static ResourceManager {
+        GLOBAL.get_or_init(Self::load_global)
+    }
+
+    /// Get the shared instance as an `Arc` (for `with_resources` constructors)
+    pub fn shared() -> Arc<ResourceManager> {
+        GLOBAL.get_or_init(Self::load_global).clone()
+    }
+
+    fn load_global() -> Arc<ResourceManager> {
+        Arc::new(Self::new().expect("Failed to load resources"))
+    }
+
     /// Get character codes by type
     pub fn get_char_codes(&self, ch: char, code_type: &CodeType) -> Option<Vec<String>> {
         match code_type {
@@ -72,6 +112,7 @@ impl ResourceManager {
             CodeType::Zhengma => self.zhengma.get(&ch).cloned(),
             CodeType::Cangjie => self.cangjie.get(&ch).cloned(),
             CodeType::Zhuyin => self.zhuyin.get(&ch).cloned(),
+            CodeType::Jyutping => self.jyutping.get(&ch).cloned(),
             _ => None,
         }
     }
@@ -81,6 +122,36 @@ impl ResourceManager {
         self.word_pinyin.get(word).map(|wp| wp.pinyin.clone())
     }
 
+    /// Words of the embedded word list (WordPinyin.txt)
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.word_pinyin.keys().map(String::as_str)
+    }
+
+    /// Length in characters of the longest polyphonic word entry
+    pub fn max_word_pinyin_len(&self) -> usize {
+        self.max_word_pinyin_len
+    }
+
+    /// Get the shuangpin codes of a toneless pinyin syllable
+    ///
+    /// The returned codes are ordered by the scheme columns of Shuangpin.txt
+    /// (小鹤, 自然码, 微软, 智能ABC, 紫光, 拼音加加, 星空键道, 大牛, 小浪).
+    pub fn get_shuangpin(&self, syllable: &str) -> Option<&Vec<String>> {
+        self.shuangpin.get(syllable)
+    }
+
+    /// Get the relative frequency of each pinyin reading of a character
+    ///
+    /// The weights are in the same order as the readings returned by
+    /// `get_char_codes(ch, &CodeType::Pinyin)`; unknown weights are 0.0.
+    /// ChineseCode.txt only weights about twenty common polyphonic
+    /// characters, so most polyphones have all weights 0.0.
+    pub fn get_pinyin_weights(&self, ch: char) -> Option<&[f64]> {
+        self.chinese_code
+            .get(&ch)
+            .map(|code| code.pinyin_weights.as_slice())
+    }
+
     /// Get character frequency
     pub fn get_frequency(&self, ch: char) -> Option<f64> {
         self.chinese_code.get(&ch).map(|code| code.frequency)
@@ -88,13 +159,16 @@ impl ResourceManager {
 
     /// Load ChineseCode.txt
     /// Format: U+4E00\t一\tggll\tggll\tggll\tyi1\t37283.98
+    ///
+    /// Readings of polyphonic characters may carry a relative frequency,
+    /// e.g. `xing2:70,hang2:29,heng2:1`. Lines starting with `#` are comments.
     fn load_chinese_code() -> Result<HashMap<char, ChineseCode>, Error> {
         let content = include_str!("../resources/ChineseCode.txt");
         let mut map = HashMap::new();
 
         for line in content.lines() {
             let line = line.trim();
-            if line.is_empty() {
+            if line.is_empty() || line.starts_with('#') {
                 continue;
             }
 
@@ -108,13 +182,15 @@ impl ResourceManager {
                 .next()
                 .ok_or_else(|| Error::Parse("Empty character field".into()))?;
 
+            let (pinyin, pinyin_weights) = Self::split_weighted_codes(parts[5]);
             let code = ChineseCode {
                 unicode: parts[0].to_string(),
                 character,
                 wubi86: Self::split_codes(parts[2]),
                 wubi98: Self::split_codes(parts[3]),
                 wubi_new: Self::split_codes(parts[4]),
-                pinyin: Self::split_codes(parts[5]),
+                pinyin,
+                pinyin_weights,
                 frequency: parts[6].parse().unwrap_or(0.0),
             };
 
@@ -150,8 +226,32 @@ impl ResourceManager {
         Ok(map)
     }
 
+    /// Load Shuangpin.txt
+    /// Format: 全拼\t小鹤双拼\t自然码\t... (first line is the header)
+    fn load_shuangpin() -> Result<HashMap<String, Vec<String>>, Error> {
+        let content = include_str!("../resources/Shuangpin.txt");
+        let mut map = HashMap::new();
+
+        for line in content.lines().skip(1) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 2 {
+                continue;
+            }
+
+            let codes = parts[1..].iter().map(|s| s.trim().to_string()).collect();
+            map.insert(parts[0].to_string(), codes);
+        }
+
+        Ok(map)
+    }
+
     /// Load simple dictionary format (char\tcode1,code2,...)
-    fn load_simple_dict(content: &str) -> Result<HashMap<char, Vec<String>>, Error> {
+    pub(crate) fn load_simple_dict(content: &str) -> Result<HashMap<char, Vec<String>>, Error> {
         let mut map = HashMap::new();
 
         for line in content.lines() {
@@ -166,12 +266,18 @@ impl ResourceManager {
             }
 
             let character = parts[0]
+                .trim_start_matches('\u{feff}')
                 .chars()
                 .next()
                 .ok_or_else(|| Error::Parse("Empty character field".into()))?;
 
-            let codes = Self::split_codes(parts[1]);
-            map.insert(character, codes);
+            // A character may be listed on several lines, one per code
+            let entry: &mut Vec<String> = map.entry(character).or_default();
+            for code in Self::split_codes(parts[1]) {
+                if !entry.contains(&code) {
+                    entry.push(code);
+                }
+            }
         }
 
         Ok(map)
@@ -187,6 +293,17 @@ impl ResourceManager {
             .filter(|s| !s.is_empty())
             .collect()
     }
+
+    /// Split comma separated codes with optional `:weight` suffixes
+    fn split_weighted_codes(s: &str) -> (Vec<String>, Vec<f64>) {
+        Self::split_codes(s)
+            .into_iter()
+            .map(|code| match code.split_once(':') {
+                Some((code, weight)) => (code.to_string(), weight.parse().unwrap_or(0.0)),
+                None => (code, 0.0),
+            })
+            .unzip()
+    }
 }
 
 impl Default for ResourceManager {
@@ -221,6 +338,14 @@ mod tests {
         println!("一 wubi: {:?}", wubi.unwrap());
     }
 
+    #[test]
+    fn test_global() {
+        let global = ResourceManager::global();
+        assert!(std::ptr::eq(global, ResourceManager::global()));
+        assert!(std::ptr::eq(global, ResourceManager::shared().as_ref()));
+        assert!(global.get_char_codes('一', &CodeType::Pinyin).is_some());
+    }
+
     #[test]
     fn test_word_pinyin() {
         let manager = ResourceManager::new().unwrap();
@@ -233,6 +358,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pinyin_weights() {
+        let manager = ResourceManager::new().unwrap();
+
+        let pinyin = manager.get_char_codes('率', &CodeType::Pinyin).unwrap();
+        assert_eq!(pinyin, vec!["shuai4".to_string(), "lv4".to_string()]);
+        assert_eq!(manager.get_pinyin_weights('率').unwrap(), &[20.0, 80.0]);
+
+        // Characters without annotated weights
+        assert_eq!(manager.get_pinyin_weights('一').unwrap(), &[0.0]);
+    }
+
     #[test]
     fn test_other_encodings() {
         let manager = ResourceManager::new().unwrap();
@@ -244,5 +381,19 @@ mod tests {
         // Test Cangjie
         let cangjie = manager.get_char_codes('一', &CodeType::Cangjie);
         println!("一 cangjie: {:?}", cangjie);
+
+        // First line of Cangjie5.txt starts with a BOM
+        let ri = manager.get_char_codes('日', &CodeType::Cangjie).unwrap();
+        assert!(ri.contains(&"a".to_string()));
+    }
+
+    #[test]
+    fn test_shuangpin_table() {
+        let manager = ResourceManager::new().unwrap();
+
+        let hao = manager.get_shuangpin("hao").unwrap();
+        assert_eq!(hao[0], "hc"); // 小鹤
+        assert_eq!(hao[1], "hk"); // 自然码
+        assert!(manager.get_shuangpin("全拼").is_none());
     }
 }
@@ -0,0 +1,197 @@
+//! Thread-safe conversion service for embedding in servers
+//!
+//! A `Converter` loads the embedded resources and the format registry once
+//! and is shared between threads; every request builds its own pipeline,
+//! so concurrent conversions do not interfere.
+//!
+//! ```
+//! use imewlconverter_core::service::{ConversionRequest, Converter};
+//!
+//! let converter = Converter::new();
+//! let request = ConversionRequest::new("你好\tni hao\t1\n".as_bytes().to_vec(), "rime", "rime");
+//! let response = converter.convert(&request).unwrap();
+//! assert_eq!(response.words, 1);
+//! ```
+
+use crate::generate::{output_generator_for, ShuangpinScheme};
+use crate::import::ErrorPolicy;
+use crate::pipeline::ConversionPipeline;
+use crate::registry::FormatRegistry;
+use crate::report::ConversionReport;
+use crate::resource::ResourceManager;
+use crate::Result;
+use std::sync::Arc;
+
+/// A conversion of an in-memory word library
+#[derive(Debug, Clone)]
+pub struct ConversionRequest {
+    pub input: Vec<u8>,
+    pub input_format: String,
+    pub output_format: String,
+    /// Text encoding of the input, defaults to the input format's
+    pub input_encoding: Option<String>,
+    pub error_policy: ErrorPolicy,
+    /// Merge entries with the same word and codes
+    pub merge_duplicates: bool,
+    /// Keyboard scheme of shuangpin output
+    pub shuangpin_scheme: ShuangpinScheme,
+}
+
+impl ConversionRequest {
+    pub fn new(input: Vec<u8>, input_format: &str, output_format: &str) -> Self {
+        ConversionRequest {
+            input,
+            input_format: input_format.to_string(),
+            output_format: output_format.to_string(),
+            input_encoding: None,
+            error_policy: ErrorPolicy::default(),
+            merge_duplicates: false,
+            shuangpin_scheme: ShuangpinScheme::default(),
+        }
+    }
+
+    pub fn with_input_encoding(mut self, encoding: &str) -> Self {
+        self.input_encoding = Some(encoding.to_string());
+        self
+    }
+
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.error_policy = policy;
+        self
+    }
+
+    pub fn merge_duplicates(mut self, enabled: bool) -> Self {
+        self.merge_duplicates = enabled;
+        self
+    }
+
+    pub fn with_shuangpin_scheme(mut self, scheme: ShuangpinScheme) -> Self {
+        self.shuangpin_scheme = scheme;
+        self
+    }
+}
+
+/// Result of a conversion
+#[derive(Debug, Clone)]
+pub struct ConversionResponse {
+    /// Encoded output; split outputs are separated by line breaks
+    pub output: Vec<u8>,
+    /// Default file extension of the output format
+    pub extension: Option<String>,
+    /// Number of exported words
+    pub words: usize,
+    pub report: ConversionReport,
+}
+
+/// Converter shared between threads, see the module documentation
+pub struct Converter {
+    registry: FormatRegistry,
+    resources: Arc<ResourceManager>,
+}
+
+impl Converter {
+    /// Converter for the built-in formats
+    pub fn new() -> Self {
+        Self::with_registry(FormatRegistry::with_builtin_formats())
+    }
+
+    /// Converter for the formats of `registry`
+    pub fn with_registry(registry: FormatRegistry) -> Self {
+        Converter {
+            registry,
+            resources: ResourceManager::shared(),
+        }
+    }
+
+    pub fn registry(&self) -> &FormatRegistry {
+        &self.registry
+    }
+
+    /// Convert a word library between two formats of the registry
+    pub fn convert(&self, request: &ConversionRequest) -> Result<ConversionResponse> {
+        let exporter = self.registry.exporter(&request.output_format)?;
+        let generator = output_generator_for(
+            exporter.as_ref(),
+            self.resources.clone(),
+            request.shuangpin_scheme,
+        )?;
+        let mut pipeline =
+            ConversionPipeline::new(self.registry.importer(&request.input_format)?, exporter)
+                .with_source_format(&request.input_format)
+                .with_error_policy(request.error_policy)
+                .merge_duplicates(request.merge_duplicates);
+        if let Some(encoding) = &request.input_encoding {
+            pipeline = pipeline.with_input_encoding(encoding);
+        }
+        // Imported codes of another type are regenerated for the output
+        if let Some(generator) = generator {
+            pipeline = pipeline.with_output_generator(generator);
+        }
+
+        let output = pipeline.convert_bytes(&request.input)?;
+        let report = pipeline.take_report();
+        let extension = self
+            .registry
+            .find(&request.output_format)
+            .and_then(|f| f.extensions().first().cloned());
+        Ok(ConversionResponse {
+            output,
+            extension,
+            words: report.exported,
+            report,
+        })
+    }
+}
+
+impl Default for Converter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_concurrent_conversions() {
+        assert_send_sync::<Converter>();
+        let converter = Arc::new(Converter::new());
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let converter = converter.clone();
+                std::thread::spawn(move || {
+                    let input = format!("你好\tni hao\t{}\n", i);
+                    let request = ConversionRequest::new(input.into_bytes(), "rime", "libime");
+                    converter.convert(&request).unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            let response = handle.join().unwrap();
+            assert_eq!(response.output, "你好 ni'hao 0".as_bytes());
+            assert_eq!(response.extension.as_deref(), Some("txt"));
+            assert_eq!(response.words, 1);
+        }
+
+        let unknown = ConversionRequest::new(Vec::new(), "rime", "nope");
+        assert!(converter.convert(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_output_codes() {
+        let input = "你好\tni hao\t1\n".as_bytes().to_vec();
+        let request = ConversionRequest::new(input, "rime", "jidian");
+        let response = Converter::new().convert(&request).unwrap();
+        let (output, _, _) = encoding_rs::GBK.decode(&response.output);
+        assert_eq!(output, "wqvb\t你好");
+
+        let input = "你好\tni hao\t1\n".as_bytes().to_vec();
+        let request = ConversionRequest::new(input, "rime", "rime-shuangpin")
+            .with_shuangpin_scheme(ShuangpinScheme::Microsoft);
+        let response = Converter::new().convert(&request).unwrap();
+        assert_eq!(response.output, "你好\tni hk\t1".as_bytes());
+    }
+}
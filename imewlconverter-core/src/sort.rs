@@ -0,0 +1,122 @@
+//! Sorting word libraries before export
+//!
+//! Several IMEs (e.g. Rime) import sorted dictionaries faster or expect them
+//! sorted. All orders are stable, so entries with equal keys keep their
+//! input order.
+
+use crate::generate::{CodeGenerator, PinyinGenerator};
+use crate::{WordLibrary, WordLibraryList};
+use serde::Deserialize;
+use std::cmp::Reverse;
+
+/// Order of the exported entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// Keep the input order
+    #[default]
+    Input,
+    /// Highest rank first
+    #[serde(rename = "rank")]
+    RankDescending,
+    /// By code, then by word
+    Code,
+    /// By the Unicode code points of the word
+    Word,
+    /// By the pinyin of the word, then by word
+    #[serde(rename = "pinyin")]
+    WordPinyin,
+}
+
+/// Sorts word libraries in a `SortOrder`
+pub struct WordLibrarySorter {
+    order: SortOrder,
+    /// Only created for pinyin order
+    pinyin: Option<PinyinGenerator>,
+}
+
+impl WordLibrarySorter {
+    pub fn new(order: SortOrder) -> Self {
+        let pinyin = match order {
+            SortOrder::WordPinyin => PinyinGenerator::new().ok(),
+            _ => None,
+        };
+        WordLibrarySorter { order, pinyin }
+    }
+
+    pub fn order(&self) -> SortOrder {
+        self.order
+    }
+
+    /// Pinyin of a word, or the word itself if it has none
+    fn pinyin_key(&self, word: &WordLibrary) -> String {
+        self.pinyin
+            .as_ref()
+            .and_then(|generator| generator.generate_code_for_string(&word.word).ok())
+            .map(|code| code.to_string_with_separator(" "))
+            .unwrap_or_else(|| word.word.clone())
+    }
+
+    /// Sort a word library in place
+    pub fn sort(&self, words: &mut WordLibraryList) {
+        match self.order {
+            SortOrder::Input => {}
+            SortOrder::RankDescending => words.sort_by_key(|w| Reverse(w.rank)),
+            SortOrder::Code => words
+                .sort_by_cached_key(|w| (w.codes.to_string_with_separator(" "), w.word.clone())),
+            SortOrder::Word => words.sort_by(|a, b| a.word.cmp(&b.word)),
+            SortOrder::WordPinyin => {
+                words.sort_by_cached_key(|w| (self.pinyin_key(w), w.word.clone()))
+            }
+        }
+    }
+}
+
+impl Default for WordLibrarySorter {
+    fn default() -> Self {
+        Self::new(SortOrder::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    fn words() -> WordLibraryList {
+        [
+            ("中国", "zg", 5),
+            ("爱", "ai", 9),
+            ("北京", "bj", 1),
+            ("你好", "nh", 9),
+        ]
+        .iter()
+        .map(|&(word, code, rank)| {
+            let mut w = WordLibrary::with_rank(word.to_string(), rank);
+            w.codes = Code::from_single(code.to_string());
+            w
+        })
+        .collect()
+    }
+
+    fn sorted(order: SortOrder) -> Vec<String> {
+        let mut list = words();
+        WordLibrarySorter::new(order).sort(&mut list);
+        list.into_iter().map(|w| w.word).collect()
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        assert_eq!(sorted(SortOrder::Input), ["中国", "爱", "北京", "你好"]);
+        assert_eq!(
+            sorted(SortOrder::RankDescending),
+            ["爱", "你好", "中国", "北京"]
+        );
+        assert_eq!(sorted(SortOrder::Code), ["爱", "北京", "你好", "中国"]);
+        assert_eq!(sorted(SortOrder::Word), ["中国", "你好", "北京", "爱"]);
+        assert_eq!(
+            sorted(SortOrder::WordPinyin),
+            ["爱", "北京", "你好", "中国"]
+        );
+    }
+}
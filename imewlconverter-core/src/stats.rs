@@ -0,0 +1,92 @@
+//! Throughput counters for importers
+
+use serde::Serialize;
+use std::io::{self, Read};
+use std::time::Duration;
+
+/// Reader counting the bytes read through it
+pub struct CountingReader<R> {
+    inner: R,
+    bytes: u64,
+}
+
+impl<R: Read> CountingReader<R> {
+    pub fn new(inner: R) -> Self {
+        CountingReader { inner, bytes: 0 }
+    }
+
+    /// Bytes read so far
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes += n as u64;
+        Ok(n)
+    }
+}
+
+/// Amount of input parsed and the time it took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportStats {
+    pub bytes: u64,
+    pub entries: usize,
+    pub elapsed: Duration,
+}
+
+impl ImportStats {
+    pub fn new(bytes: u64, entries: usize, elapsed: Duration) -> Self {
+        ImportStats {
+            bytes,
+            entries,
+            elapsed,
+        }
+    }
+
+    /// Add the counters of another import
+    pub fn add(&mut self, other: &ImportStats) {
+        self.bytes += other.bytes;
+        self.entries += other.entries;
+        self.elapsed += other.elapsed;
+    }
+
+    pub fn bytes_per_sec(&self) -> f64 {
+        rate(self.bytes as f64, self.elapsed)
+    }
+
+    pub fn entries_per_sec(&self) -> f64 {
+        rate(self.entries as f64, self.elapsed)
+    }
+}
+
+/// Amount per second, 0 when no time was measured
+fn rate(amount: f64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        amount / secs
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters() {
+        let mut reader = CountingReader::new("你好\tni hao\n".as_bytes());
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(reader.bytes_read(), 14);
+
+        let mut stats = ImportStats::new(1000, 10, Duration::from_millis(500));
+        stats.add(&ImportStats::new(1000, 30, Duration::from_millis(500)));
+        assert_eq!(stats.bytes_per_sec(), 2000.0);
+        assert_eq!(stats.entries_per_sec(), 40.0);
+        assert_eq!(ImportStats::default().entries_per_sec(), 0.0);
+    }
+}
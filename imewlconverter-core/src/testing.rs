@@ -0,0 +1,131 @@
+//! Round-trip helpers for format implementations
+//!
+//! Enabled by the `testing` feature. A format pair round-trips when the words
+//! exported by one are imported back by the other with the same codes:
+//!
+//! ```ignore
+//! use imewlconverter_core::testing::{assert_roundtrip, sample_words};
+//!
+//! let sample = sample_words(CodeType::Pinyin);
+//! assert_roundtrip(&MyImport::new(), &MyExport::new(), &sample);
+//! ```
+
+use crate::export::WordLibraryExport;
+use crate::generate::generator_for;
+use crate::import::{ImportOptions, WordLibraryImport};
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Result, WordLibrary, WordLibraryList};
+use std::collections::BTreeMap;
+
+/// Words of the sample fixtures
+pub const SAMPLE_WORDS: [&str; 4] = ["你好", "世界", "中国人", "输入法"];
+
+/// Sample words with codes of `code_type`, ranked in descending order
+///
+/// Codes come from the built-in generator of the code type, pinyin without
+/// tone numbers. Code types without a generator, such as user-defined
+/// phrases, get the pinyin initials as a word code (`nh` for 你好).
+pub fn sample_words(code_type: CodeType) -> WordLibraryList {
+    let resources = ResourceManager::shared();
+    let (generator, initials_only) = match generator_for(code_type, resources.clone()) {
+        Ok(generator) => (generator, false),
+        Err(_) => (generator_for(CodeType::Pinyin, resources).unwrap(), true),
+    };
+    SAMPLE_WORDS
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let mut wl = WordLibrary::with_rank(word.to_string(), 1000 - i as i32 * 100);
+            wl.code_type = code_type;
+            if let Ok(mut codes) = generator.generate_code_for_string(word) {
+                for code in codes.0.iter_mut().flatten() {
+                    code.retain(|c| !c.is_ascii_digit());
+                }
+                wl.codes = if initials_only {
+                    let initials: String = codes
+                        .get_default_codes()
+                        .iter()
+                        .filter_map(|c| c.chars().next())
+                        .collect();
+                    Code::from_single(initials)
+                } else {
+                    codes
+                };
+            }
+            wl
+        })
+        .collect()
+}
+
+/// Export `sample` with `exporter` and import the output with `importer`
+pub fn roundtrip(
+    importer: &dyn WordLibraryImport,
+    exporter: &dyn WordLibraryExport,
+    sample: &WordLibraryList,
+) -> Result<WordLibraryList> {
+    let mut output = Vec::new();
+    exporter.export_to_writer(sample, &mut output)?;
+    let words = importer.import_from_reader(&mut output.as_slice(), &ImportOptions::new())?;
+    Ok(words.into())
+}
+
+/// Assert that `sample` round-trips through `exporter` and `importer`
+///
+/// The same words must come back. Their codes must match too when the
+/// exporter writes the sample's code type and the importer reads codes.
+pub fn assert_roundtrip(
+    importer: &dyn WordLibraryImport,
+    exporter: &dyn WordLibraryExport,
+    sample: &WordLibraryList,
+) {
+    let format = exporter.format_name();
+    let words = roundtrip(importer, exporter, sample)
+        .unwrap_or_else(|e| panic!("{}: round trip failed: {}", format, e));
+
+    let codes = |words: &WordLibraryList| -> BTreeMap<String, Option<String>> {
+        words
+            .iter()
+            .map(|w| (w.word.clone(), w.get_word_code()))
+            .collect()
+    };
+    let (expected, actual) = (codes(sample), codes(&words));
+    assert_eq!(
+        expected.keys().collect::<Vec<_>>(),
+        actual.keys().collect::<Vec<_>>(),
+        "{}: words differ after the round trip",
+        format
+    );
+
+    let compare_codes = words.iter().any(|w| w.has_code())
+        && sample.iter().all(|w| w.code_type == exporter.code_type());
+    if compare_codes {
+        assert_eq!(
+            expected, actual,
+            "{}: codes differ after the round trip",
+            format
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::FormatRegistry;
+
+    #[test]
+    fn test_builtin_roundtrips() {
+        let registry = FormatRegistry::with_builtin_formats();
+        let mut checked = 0;
+        for format in registry.formats() {
+            if !format.can_import() || !format.can_export() {
+                continue;
+            }
+            let importer = registry.importer(format.name()).unwrap();
+            let exporter = registry.exporter(format.name()).unwrap();
+            let sample = sample_words(exporter.code_type());
+            assert_roundtrip(importer.as_ref(), exporter.as_ref(), &sample);
+            checked += 1;
+        }
+        assert_ne!(checked, 0);
+    }
+}
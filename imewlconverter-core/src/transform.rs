@@ -0,0 +1,7 @@
+//! Transformations of the codes of word libraries before export
+
+pub mod abbreviation;
+pub mod code_normalize;
+pub mod fuzzy_pinyin;
+pub mod idiom;
+pub mod zhuyin;
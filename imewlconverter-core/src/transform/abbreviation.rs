@@ -0,0 +1,112 @@
+//! Pinyin abbreviations (首字母简拼)
+//!
+//! Custom phrase targets often match the initials a user types, e.g. `nh`
+//! for 你好, rather than the full spelling. The abbreviation is added as an
+//! extra entry next to each word, which exporters write as an extra line.
+
+use crate::{Code, CodeType, WordLibrary, WordLibraryList};
+
+/// Adds an abbreviation entry for the pinyin words of a list
+#[derive(Debug, Clone, Default)]
+pub struct PinyinAbbreviation {
+    compound_initials: bool,
+}
+
+impl PinyinAbbreviation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the initials zh, ch and sh whole (知识 → `zhsh` instead of `zs`)
+    pub fn compound_initials(mut self, enabled: bool) -> Self {
+        self.compound_initials = enabled;
+        self
+    }
+
+    /// Initial of a syllable
+    fn initial<'a>(&self, syllable: &'a str) -> &'a str {
+        let compound = ["zh", "ch", "sh"]
+            .iter()
+            .find(|initial| self.compound_initials && syllable.starts_with(*initial));
+        match compound {
+            Some(initial) => &syllable[..initial.len()],
+            None => &syllable[..syllable.chars().next().map_or(0, char::len_utf8)],
+        }
+    }
+
+    /// Abbreviation entry of a pinyin word of two or more syllables
+    pub fn abbreviate(&self, word: &WordLibrary) -> Option<WordLibrary> {
+        if word.code_type != CodeType::Pinyin || word.is_symbol_phrase() {
+            return None;
+        }
+        let syllables = word.codes.get_default_codes();
+        if syllables.len() < 2 || syllables.iter().any(|s| s.is_empty()) {
+            return None;
+        }
+        let initials: Vec<String> = syllables
+            .iter()
+            .map(|s| self.initial(&s.to_lowercase()).to_string())
+            .collect();
+        // Words of single-letter syllables are their own abbreviation
+        if initials == syllables {
+            return None;
+        }
+        let mut entry = word.clone();
+        entry.codes = Code::from_char_list(initials);
+        Some(entry)
+    }
+
+    /// Words of a list, each followed by its abbreviation entry
+    pub fn apply(&self, words: &WordLibraryList) -> WordLibraryList {
+        let mut result = WordLibraryList::with_capacity(words.len() * 2);
+        for word in words {
+            result.push(word.clone());
+            result.extend(self.abbreviate(word));
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pinyin_word(word: &str, pinyin: &[&str]) -> WordLibrary {
+        let mut wl = WordLibrary::with_rank(word.to_string(), 10);
+        wl.codes = Code::from_char_list(pinyin.iter().map(|s| s.to_string()).collect());
+        wl
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        let words: WordLibraryList = vec![
+            pinyin_word("你好", &["ni", "hao"]),
+            pinyin_word("知识", &["zhi", "shi"]),
+            pinyin_word("好", &["hao"]),
+        ]
+        .into();
+
+        let result = PinyinAbbreviation::new().apply(&words);
+        let entries: Vec<(&str, String)> = result
+            .iter()
+            .map(|w| (w.word.as_str(), w.get_pinyin_string("")))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("你好", "nihao".to_string()),
+                ("你好", "nh".to_string()),
+                ("知识", "zhishi".to_string()),
+                ("知识", "zs".to_string()),
+                ("好", "hao".to_string()),
+            ]
+        );
+
+        let compound = PinyinAbbreviation::new()
+            .compound_initials(true)
+            .abbreviate(&pinyin_word("知识", &["zhi", "shi"]))
+            .unwrap();
+        assert_eq!(compound.get_pinyin_string("'"), "zh'sh");
+        assert_eq!(compound.rank, 10);
+    }
+}
@@ -0,0 +1,95 @@
+//! Normalization of code case and width
+//!
+//! Some sources store codes in uppercase (`NIHAO`), with stray spaces
+//! (`wq vb`) or in full-width letters (`ｎｉ`), which target IMEs do not
+//! match against what the user types.
+
+use crate::{WordLibrary, WordLibraryList};
+
+/// Rewrites codes to lowercase, half-width, whitespace-free form
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeNormalizer {
+    lowercase: bool,
+    strip_whitespace: bool,
+    half_width: bool,
+}
+
+impl CodeNormalizer {
+    /// Normalizer changing nothing until options are enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lowercase the codes
+    pub fn with_lowercase(mut self) -> Self {
+        self.lowercase = true;
+        self
+    }
+
+    /// Remove whitespace inside the codes
+    pub fn with_strip_whitespace(mut self) -> Self {
+        self.strip_whitespace = true;
+        self
+    }
+
+    /// Convert full-width Latin letters, digits and punctuation to half-width
+    pub fn with_half_width(mut self) -> Self {
+        self.half_width = true;
+        self
+    }
+
+    /// Normalize a single code
+    pub fn normalize(&self, code: &str) -> String {
+        let mut result: String = code
+            .chars()
+            .map(|c| if self.half_width { to_half_width(c) } else { c })
+            .filter(|c| !(self.strip_whitespace && c.is_whitespace()))
+            .collect();
+        if self.lowercase {
+            result = result.to_lowercase();
+        }
+        result
+    }
+
+    /// Normalize every code of a word
+    pub fn normalize_word(&self, word: &mut WordLibrary) {
+        for code in word.codes.0.iter_mut().flatten() {
+            *code = self.normalize(code);
+        }
+    }
+
+    /// Normalize the codes of every word of a list
+    pub fn apply(&self, words: &mut WordLibraryList) {
+        for word in words.iter_mut() {
+            self.normalize_word(word);
+        }
+    }
+}
+
+/// Half-width form of a full-width ASCII character or the ideographic space
+fn to_half_width(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let all = CodeNormalizer::new()
+            .with_lowercase()
+            .with_strip_whitespace()
+            .with_half_width();
+        assert_eq!(all.normalize("ＮＩ\u{3000}Hao "), "nihao");
+        assert_eq!(all.normalize("wq vb"), "wqvb");
+
+        let width_only = CodeNormalizer::new().with_half_width();
+        assert_eq!(width_only.normalize("ＷＱ vb"), "WQ vb");
+        assert_eq!(CodeNormalizer::new().normalize("ＮＩ"), "ＮＩ");
+    }
+}
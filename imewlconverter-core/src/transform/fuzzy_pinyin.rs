@@ -0,0 +1,270 @@
+//! Fuzzy pinyin (模糊音)
+//!
+//! Many IMEs let users confuse similar initials and finals, e.g. type `zi`
+//! for 知 (`zhi`). For IMEs without that option, the fuzzy pairs can be
+//! baked into the word library: either by rewriting the codes to the form
+//! the user types, or by adding an entry for every fuzzy variant.
+
+use crate::{Code, CodeType, Error, Result, WordLibrary, WordLibraryList};
+use std::collections::HashSet;
+
+/// Pinyin initials, two-letter ones first
+const INITIALS: [&str; 23] = [
+    "zh", "ch", "sh", "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "r",
+    "z", "c", "s", "y", "w",
+];
+
+/// Default cap on the number of entries emitted per word when expanding
+pub const DEFAULT_FUZZY_LIMIT: usize = 16;
+
+/// Part of a syllable a fuzzy rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FuzzyPosition {
+    Initial,
+    Final,
+}
+
+/// A fuzzy pair, read as "`from` is typed as `to`"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyRule {
+    pub from: String,
+    pub to: String,
+    pub position: FuzzyPosition,
+}
+
+impl FuzzyRule {
+    pub fn initial(from: &str, to: &str) -> Self {
+        FuzzyRule {
+            from: from.to_string(),
+            to: to.to_string(),
+            position: FuzzyPosition::Initial,
+        }
+    }
+
+    pub fn final_(from: &str, to: &str) -> Self {
+        FuzzyRule {
+            from: from.to_string(),
+            to: to.to_string(),
+            position: FuzzyPosition::Final,
+        }
+    }
+
+    /// Parse a rule like `zh=z` or `ang=an`; pairs of initials are initial
+    /// rules, anything else final rules
+    pub fn parse(rule: &str) -> Result<Self> {
+        let (from, to) = rule
+            .split_once('=')
+            .map(|(from, to)| (from.trim(), to.trim()))
+            .filter(|(from, to)| !from.is_empty() && !to.is_empty() && from != to)
+            .ok_or_else(|| Error::InvalidFormat(format!("Invalid fuzzy pinyin rule '{}'", rule)))?;
+        if INITIALS.contains(&from) && INITIALS.contains(&to) {
+            Ok(Self::initial(from, to))
+        } else {
+            Ok(Self::final_(from, to))
+        }
+    }
+}
+
+/// The usual fuzzy pairs, mapped to the shorter form
+pub fn default_rules() -> Vec<FuzzyRule> {
+    let mut rules: Vec<FuzzyRule> = [("zh", "z"), ("ch", "c"), ("sh", "s"), ("n", "l")]
+        .iter()
+        .map(|&(from, to)| FuzzyRule::initial(from, to))
+        .collect();
+    rules.extend(
+        [
+            ("ang", "an"),
+            ("eng", "en"),
+            ("ing", "in"),
+            ("iang", "ian"),
+            ("uang", "uan"),
+        ]
+        .iter()
+        .map(|&(from, to)| FuzzyRule::final_(from, to)),
+    );
+    rules
+}
+
+/// How fuzzy rules are applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FuzzyMode {
+    /// Replace codes with their fuzzy form
+    #[default]
+    Rewrite,
+    /// Keep the original entry and add one per fuzzy variant
+    Expand,
+}
+
+/// Split a syllable into initial, final and tone number
+fn split_syllable(syllable: &str) -> (&str, &str, &str) {
+    let base = syllable.trim_end_matches(|c: char| c.is_ascii_digit());
+    let tone = &syllable[base.len()..];
+    let initial = INITIALS
+        .iter()
+        .find(|initial| base.starts_with(*initial) && base.len() > initial.len())
+        .copied()
+        .unwrap_or("");
+    (initial, &base[initial.len()..], tone)
+}
+
+/// Fuzzy pinyin transformation of pinyin codes
+pub struct FuzzyPinyin {
+    rules: Vec<FuzzyRule>,
+    mode: FuzzyMode,
+    limit: usize,
+}
+
+impl FuzzyPinyin {
+    /// Apply the default rules in the given mode
+    pub fn new(mode: FuzzyMode) -> Self {
+        FuzzyPinyin {
+            rules: default_rules(),
+            mode,
+            limit: DEFAULT_FUZZY_LIMIT,
+        }
+    }
+
+    pub fn with_rules(mut self, rules: Vec<FuzzyRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Cap the number of entries emitted per word when expanding
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Replacements of a syllable part at `position`, original first
+    fn replacements<'a>(&'a self, part: &'a str, position: FuzzyPosition) -> Vec<&'a str> {
+        let mut parts = vec![part];
+        for rule in &self.rules {
+            if rule.position == position && rule.from == part && !parts.contains(&&*rule.to) {
+                parts.push(&rule.to);
+            }
+        }
+        parts
+    }
+
+    /// Fuzzy variants of a syllable, the syllable itself first
+    pub fn variants(&self, syllable: &str) -> Vec<String> {
+        let (initial, final_, tone) = split_syllable(syllable);
+        let finals = self.replacements(final_, FuzzyPosition::Final);
+        let mut variants = Vec::new();
+        for initial in self.replacements(initial, FuzzyPosition::Initial) {
+            for final_ in &finals {
+                variants.push(format!("{}{}{}", initial, final_, tone));
+            }
+        }
+        variants
+    }
+
+    /// Fuzzy form of a syllable, using the first matching rules
+    pub fn rewrite(&self, syllable: &str) -> String {
+        let (initial, final_, tone) = split_syllable(syllable);
+        let initial = self.replacements(initial, FuzzyPosition::Initial);
+        let final_ = self.replacements(final_, FuzzyPosition::Final);
+        format!(
+            "{}{}{}",
+            initial.get(1).unwrap_or(&initial[0]),
+            final_.get(1).unwrap_or(&final_[0]),
+            tone
+        )
+    }
+
+    /// Transform the codes of the pinyin words of a list
+    pub fn apply(&self, words: &WordLibraryList) -> WordLibraryList {
+        let mut result = WordLibraryList::with_capacity(words.len());
+        for word in words {
+            if word.code_type != CodeType::Pinyin || word.is_symbol_phrase() {
+                result.push(word.clone());
+                continue;
+            }
+            match self.mode {
+                FuzzyMode::Rewrite => {
+                    let mut rewritten = word.clone();
+                    for codes in &mut rewritten.codes.0 {
+                        for code in codes.iter_mut() {
+                            *code = self.rewrite(code);
+                        }
+                    }
+                    result.push(rewritten);
+                }
+                FuzzyMode::Expand => result.extend(self.expand(word)),
+            }
+        }
+        result
+    }
+
+    /// Entries for the fuzzy variants of the default codes of a word
+    fn expand(&self, word: &WordLibrary) -> Vec<WordLibrary> {
+        let variants = Code::from_chars(
+            word.codes
+                .get_default_codes()
+                .iter()
+                .map(|syllable| self.variants(syllable))
+                .collect(),
+        );
+        if variants.combination_count() <= 1 {
+            return vec![word.clone()];
+        }
+
+        let mut seen = HashSet::new();
+        let mut result = vec![word.clone()];
+        seen.insert(word.codes.get_default_codes().join(" "));
+        for codes in variants.combinations(self.limit) {
+            if result.len() >= self.limit {
+                break;
+            }
+            if seen.insert(codes.to_string_with_separator(" ")) {
+                let mut entry = word.clone();
+                entry.codes = codes;
+                result.push(entry);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, pinyin: &str) -> WordLibrary {
+        let mut wl = WordLibrary::new(text.to_string());
+        wl.codes = Code::from_char_list(pinyin.split(' ').map(String::from).collect());
+        wl
+    }
+
+    #[test]
+    fn test_variants() {
+        let fuzzy = FuzzyPinyin::new(FuzzyMode::Expand);
+        assert_eq!(
+            fuzzy.variants("zhang1"),
+            vec!["zhang1", "zhan1", "zang1", "zan1"]
+        );
+        assert_eq!(fuzzy.variants("an"), vec!["an"]);
+        assert_eq!(fuzzy.rewrite("shang"), "san");
+        assert_eq!(fuzzy.rewrite("liang"), "lian");
+        assert!(FuzzyRule::parse("zh").is_err());
+        assert_eq!(
+            FuzzyRule::parse("l=n").unwrap(),
+            FuzzyRule::initial("l", "n")
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let words: WordLibraryList = vec![word("知道", "zhi dao"), word("你们", "ni men")].into();
+
+        let rewritten = FuzzyPinyin::new(FuzzyMode::Rewrite).apply(&words);
+        let codes: Vec<String> = rewritten.iter().map(|w| w.get_pinyin_string(" ")).collect();
+        assert_eq!(codes, vec!["zi dao", "li men"]);
+
+        let expanded = FuzzyPinyin::new(FuzzyMode::Expand)
+            .with_rules(vec![FuzzyRule::parse("zh=z").unwrap()])
+            .apply(&words);
+        let codes: Vec<String> = expanded.iter().map(|w| w.get_pinyin_string(" ")).collect();
+        assert_eq!(codes, vec!["zhi dao", "zi dao", "ni men"]);
+    }
+}
@@ -0,0 +1,94 @@
+//! Idiom (成语) detection and tagging
+//!
+//! Words found in the bundled idiom list get the `is_idiom` attribute, so
+//! later stages can filter or rank them apart from other phrases.
+
+use crate::data::attributes::IS_IDIOM;
+use crate::{WordLibrary, WordLibraryList};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+fn idioms() -> &'static HashSet<&'static str> {
+    static IDIOMS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    IDIOMS.get_or_init(|| {
+        include_str!("../../resources/Idioms.txt")
+            .lines()
+            .map(|line| line.trim_start_matches('\u{feff}').trim())
+            .filter(|line| !line.is_empty())
+            .collect()
+    })
+}
+
+/// Check if a word is in the bundled idiom list
+pub fn is_idiom(word: &str) -> bool {
+    idioms().contains(word)
+}
+
+/// Check if an entry is tagged as an idiom or in the bundled list
+pub fn is_idiom_entry(word: &WordLibrary) -> bool {
+    word.attribute(IS_IDIOM) == Some("true") || is_idiom(&word.word)
+}
+
+/// Tags the idioms of a word list
+#[derive(Debug, Clone, Default)]
+pub struct IdiomTagger {
+    rank_factor: Option<f64>,
+}
+
+impl IdiomTagger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also multiply the rank of idioms by `factor`
+    pub fn with_rank_factor(mut self, factor: f64) -> Self {
+        self.rank_factor = Some(factor);
+        self
+    }
+
+    /// Tag a word if it is an idiom, returning whether it is
+    pub fn tag(&self, word: &mut WordLibrary) -> bool {
+        if !is_idiom(&word.word) {
+            return false;
+        }
+        word.set_attribute(IS_IDIOM, "true");
+        if let Some(factor) = self.rank_factor {
+            let rank = (f64::from(word.rank) * factor).round();
+            word.rank = rank.clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+        }
+        true
+    }
+
+    /// Tag the idioms of a list, returning their number
+    pub fn apply(&self, words: &mut WordLibraryList) -> usize {
+        let mut tagged = 0;
+        for word in words.iter_mut() {
+            if self.tag(word) {
+                tagged += 1;
+            }
+        }
+        tagged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_idioms() {
+        let mut words: WordLibraryList = ["画蛇添足", "你好", "一帆风顺"]
+            .iter()
+            .map(|w| WordLibrary::with_rank(w.to_string(), 10))
+            .collect();
+        let tagged = IdiomTagger::new().with_rank_factor(1.5).apply(&mut words);
+        assert_eq!(tagged, 2);
+
+        let entries: Vec<(bool, i32)> = words.iter().map(|w| (is_idiom_entry(w), w.rank)).collect();
+        assert_eq!(entries, vec![(true, 15), (false, 10), (true, 15)]);
+        assert_eq!(
+            words.iter().next().unwrap().attribute(IS_IDIOM),
+            Some("true")
+        );
+    }
+}
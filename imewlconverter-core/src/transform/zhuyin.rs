@@ -0,0 +1,195 @@
+//! Zhuyin (注音, Bopomofo) ↔ pinyin transcoding
+//!
+//! Syllables are mapped through the bundled Zhuyin table. Tones are kept:
+//! Zhuyin marks `ˊ ˇ ˋ` and the neutral `˙` become pinyin tone numbers and
+//! back. The unmarked first tone of Zhuyin maps to a syllable without a
+//! number.
+
+use crate::helpers::pinyin::is_pinyin_syllable;
+use crate::{CodeType, Error, Result, WordLibrary, WordLibraryList};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Tone marks, from the first tone to the neutral one
+const TONE_MARKS: [char; 5] = ['ˉ', 'ˊ', 'ˇ', 'ˋ', '˙'];
+
+struct Table {
+    to_pinyin: HashMap<String, String>,
+    to_zhuyin: HashMap<String, String>,
+}
+
+fn table() -> &'static Table {
+    static TABLE: OnceLock<Table> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = Table {
+            to_pinyin: HashMap::new(),
+            to_zhuyin: HashMap::new(),
+        };
+        for line in include_str!("../../resources/Zhuyin.txt").lines() {
+            let line = line.trim_start_matches('\u{feff}');
+            let Some((zhuyin, pinyin)) = line.split_once('\t') else {
+                continue;
+            };
+            let (zhuyin, pinyin) = (zhuyin.trim(), pinyin.trim());
+            // The table also maps ㄧ and ㄨ to the bare y and w
+            if !is_pinyin_syllable(pinyin) {
+                continue;
+            }
+            table
+                .to_pinyin
+                .insert(zhuyin.to_string(), pinyin.to_string());
+            table
+                .to_zhuyin
+                .entry(pinyin.to_string())
+                .or_insert_with(|| zhuyin.to_string());
+        }
+        table
+    })
+}
+
+/// Whether a code is written in Zhuyin
+pub fn is_zhuyin(code: &str) -> bool {
+    code.chars()
+        .any(|c| matches!(c, '\u{3105}'..='\u{312F}' | '\u{31A0}'..='\u{31BF}'))
+}
+
+/// Convert a Zhuyin syllable (`ㄏㄠˇ`) to pinyin (`hao3`)
+pub fn zhuyin_to_pinyin(syllable: &str) -> Option<String> {
+    let mut tone = None;
+    let bare: String = syllable
+        .chars()
+        .filter(|c| match TONE_MARKS.iter().position(|m| m == c) {
+            Some(i) => {
+                tone = Some(i + 1);
+                false
+            }
+            None => !c.is_whitespace(),
+        })
+        .collect();
+    let pinyin = table().to_pinyin.get(&bare)?;
+    Some(match tone {
+        Some(tone) => format!("{}{}", pinyin, tone),
+        None => pinyin.clone(),
+    })
+}
+
+/// Convert a pinyin syllable (`hao3`) to Zhuyin (`ㄏㄠˇ`)
+pub fn pinyin_to_zhuyin(syllable: &str) -> Option<String> {
+    let bare = syllable.trim_end_matches(|c: char| c.is_ascii_digit());
+    let tone = syllable[bare.len()..].parse::<usize>().ok();
+    let mut bare = bare.to_ascii_lowercase().replace('ü', "v");
+    // The table spells 略 and 虐 as lue and nue
+    if bare == "lve" || bare == "nve" {
+        bare.replace_range(1..2, "u");
+    }
+    let zhuyin = table().to_zhuyin.get(&bare)?;
+    Some(match tone {
+        Some(5) | Some(0) => format!("˙{}", zhuyin),
+        Some(tone @ 2..=4) => format!("{}{}", zhuyin, TONE_MARKS[tone - 1]),
+        _ => zhuyin.clone(),
+    })
+}
+
+/// Direction of a transcoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transcode {
+    /// Zhuyin codes to pinyin
+    ToPinyin,
+    /// Pinyin codes to Zhuyin
+    ToZhuyin,
+}
+
+impl Transcode {
+    /// Code type of transcoded words
+    pub fn target(self) -> CodeType {
+        match self {
+            Transcode::ToPinyin => CodeType::Pinyin,
+            Transcode::ToZhuyin => CodeType::Zhuyin,
+        }
+    }
+
+    /// Whether the codes of a word are in the source system
+    fn applies_to(self, word: &WordLibrary) -> bool {
+        if !word.has_code() || word.is_english || word.is_symbol_phrase() {
+            return false;
+        }
+        match self {
+            Transcode::ToPinyin => {
+                word.code_type == CodeType::Zhuyin
+                    || word.codes.0.iter().flatten().any(|c| is_zhuyin(c))
+            }
+            Transcode::ToZhuyin => word.code_type == CodeType::Pinyin,
+        }
+    }
+
+    /// Transcode the codes of a word; other words are left unchanged
+    pub fn apply(self, word: &mut WordLibrary) -> Result<()> {
+        if !self.applies_to(word) {
+            return Ok(());
+        }
+        let convert = match self {
+            Transcode::ToPinyin => zhuyin_to_pinyin,
+            Transcode::ToZhuyin => pinyin_to_zhuyin,
+        };
+        let mut codes = word.codes.clone();
+        for code in codes.0.iter_mut().flatten() {
+            *code = convert(code).ok_or_else(|| {
+                Error::InvalidFormat(format!("No {} for syllable '{}'", self.target(), code))
+            })?;
+        }
+        word.codes = codes;
+        word.code_type = self.target();
+        Ok(())
+    }
+
+    /// Transcode every word of a list, returning the words that failed
+    pub fn apply_all(self, words: &mut WordLibraryList) -> Vec<(String, Error)> {
+        let mut failures = Vec::new();
+        words.filter_in_place(|w| match self.apply(w) {
+            Ok(()) => true,
+            Err(e) => {
+                failures.push((w.word.clone(), e));
+                false
+            }
+        });
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    #[test]
+    fn test_syllables() {
+        assert_eq!(zhuyin_to_pinyin("ㄏㄠˇ").as_deref(), Some("hao3"));
+        assert_eq!(zhuyin_to_pinyin("˙ㄇㄚ").as_deref(), Some("ma5"));
+        assert_eq!(zhuyin_to_pinyin("ㄓ").as_deref(), Some("zhi"));
+        assert_eq!(zhuyin_to_pinyin("ㄧ").as_deref(), Some("yi"));
+        assert_eq!(pinyin_to_zhuyin("lv4").as_deref(), Some("ㄌㄩˋ"));
+        assert_eq!(pinyin_to_zhuyin("lve4").as_deref(), Some("ㄌㄩㄝˋ"));
+        assert_eq!(pinyin_to_zhuyin("de5").as_deref(), Some("˙ㄉㄜ"));
+        assert_eq!(pinyin_to_zhuyin("zhong1").as_deref(), Some("ㄓㄨㄥ"));
+        assert_eq!(pinyin_to_zhuyin("xyz"), None);
+    }
+
+    #[test]
+    fn test_transcode_words() {
+        let mut word = WordLibrary::new("你好".to_string());
+        word.codes = Code::from_char_list(vec!["ㄋㄧˇ".to_string(), "ㄏㄠˇ".to_string()]);
+        let mut bad = word.clone();
+        bad.codes = Code::from_char_list(vec!["ㄋㄧ".to_string(), "ㄅㄩ".to_string()]);
+        let mut words: WordLibraryList = vec![word, bad].into();
+
+        let failures = Transcode::ToPinyin.apply_all(&mut words);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].code_type, CodeType::Pinyin);
+        assert_eq!(words[0].get_pinyin_string(" "), "ni3 hao3");
+
+        Transcode::ToZhuyin.apply(&mut words[0]).unwrap();
+        assert_eq!(words[0].code_type, CodeType::Zhuyin);
+        assert_eq!(words[0].codes.to_string_with_separator(" "), "ㄋㄧˇ ㄏㄠˇ");
+    }
+}
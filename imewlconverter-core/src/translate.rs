@@ -1,16 +1,33 @@
 //! Chinese Simplified/Traditional translation
 
-use crate::Result;
+use crate::generate::{generator_for, CodeGenerator};
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, Error, Result, WordLibraryList};
+use serde::Deserialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 
 /// Type of Chinese translation
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum TranslationType {
     /// No translation
     None,
     /// Translate to Simplified Chinese
+    #[serde(rename = "t2s")]
     ToSimplified,
     /// Translate to Traditional Chinese
+    #[serde(rename = "s2t")]
     ToTraditional,
+    /// Translate Simplified to Traditional Chinese (Taiwan standard)
+    #[serde(rename = "s2tw")]
+    ToTraditionalTaiwan,
+    /// Translate Simplified to Traditional Chinese (Hong Kong standard)
+    #[serde(rename = "s2hk")]
+    ToTraditionalHongKong,
+    /// Translate Traditional Chinese to Japanese Shinjitai
+    #[serde(rename = "t2jp")]
+    ToJapanese,
 }
 
 /// Trait for Chinese character converters
@@ -20,37 +37,218 @@ pub trait ChineseConverter {
 
     /// Convert to Traditional Chinese
     fn to_traditional(&self, text: &str) -> Result<String>;
+
+    /// Convert according to the translation type
+    ///
+    /// Regional variants fall back to plain traditional conversion unless the
+    /// converter supports them.
+    fn convert(&self, text: &str, translation: TranslationType) -> Result<String> {
+        match translation {
+            TranslationType::None => Ok(text.to_string()),
+            TranslationType::ToSimplified => self.to_simplified(text),
+            TranslationType::ToTraditional
+            | TranslationType::ToTraditionalTaiwan
+            | TranslationType::ToTraditionalHongKong => self.to_traditional(text),
+            TranslationType::ToJapanese => Err(Error::Unsupported(
+                "Japanese Shinjitai conversion".to_string(),
+            )),
+        }
+    }
+}
+
+/// Translate the words of a word library list in place
+///
+/// Returns the indices of the words whose text changed.
+pub fn translate_word_list(
+    converter: &dyn ChineseConverter,
+    word_list: &mut WordLibraryList,
+    translation: TranslationType,
+) -> Result<Vec<usize>> {
+    let mut changed = Vec::new();
+    if translation == TranslationType::None {
+        return Ok(changed);
+    }
+    for (index, word) in word_list.iter_mut().enumerate() {
+        let translated = converter.convert(&word.word, translation)?;
+        if translated != word.word {
+            word.word = translated;
+            changed.push(index);
+        }
+    }
+    Ok(changed)
+}
+
+/// Regenerate glyph-based codes of translated words
+///
+/// Wubi, Cangjie and similar codes describe the glyphs of the original script
+/// and are invalid once a word has been translated, so they are generated
+/// again for the words at `changed`, as returned by `translate_word_list`.
+/// Phonetic codes such as pinyin are kept.
+///
+/// Words whose codes cannot be regenerated keep their old codes and are
+/// returned with the error.
+pub fn regenerate_codes(
+    word_list: &mut WordLibraryList,
+    changed: &[usize],
+) -> Vec<(String, Error)> {
+    let mut generators: HashMap<CodeType, Option<Box<dyn CodeGenerator>>> = HashMap::new();
+    let mut failures = Vec::new();
+
+    for &index in changed {
+        let Some(word) = word_list.get_mut(index) else {
+            continue;
+        };
+        if !word.code_type.is_glyph_based() {
+            continue;
+        }
+
+        let generator = match generators.entry(word.code_type) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(generator_for(word.code_type, ResourceManager::shared()).ok())
+            }
+        };
+
+        let Some(generator) = generator else {
+            let error = Error::Unsupported(format!("{:?} code generation", word.code_type));
+            failures.push((word.word.clone(), error));
+            continue;
+        };
+        // Generators skip words that already carry codes of their type
+        let mut regenerated = word.clone();
+        regenerated.codes = Code::new();
+        match generator.generate_code(&mut regenerated) {
+            Ok(()) => word.codes = regenerated.codes,
+            Err(e) => failures.push((word.word.clone(), e)),
+        }
+    }
+
+    failures
+}
+
+/// OpenCC dictionaries (Apache-2.0), `key\tvalue value...` per line
+const ST_CHARACTERS: &str = include_str!("../resources/STCharacters.txt");
+const ST_PHRASES: &str = include_str!("../resources/STPhrases.txt");
+const TS_CHARACTERS: &str = include_str!("../resources/TSCharacters.txt");
+const TS_PHRASES: &str = include_str!("../resources/TSPhrases.txt");
+const TW_VARIANTS: &str = include_str!("../resources/TWVariants.txt");
+const HK_VARIANTS: &str = include_str!("../resources/HKVariants.txt");
+const JP_VARIANTS: &str = include_str!("../resources/JPVariants.txt");
+
+/// Word and character mapping with forward longest-match conversion
+#[derive(Debug, Default)]
+struct Dictionary {
+    entries: HashMap<String, String>,
+    /// Length in characters of the longest key
+    max_len: usize,
 }
 
-/// OpenCC-based converter (for cross-platform use)
-#[derive(Default)]
+impl Dictionary {
+    /// Load OpenCC dictionary files, later files overriding earlier ones
+    ///
+    /// Only the first of several values is used.
+    fn load(contents: &[&str]) -> Result<Self> {
+        let mut dictionary = Dictionary::default();
+        for content in contents {
+            for line in content.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let (key, value) = line
+                    .split_once('\t')
+                    .and_then(|(key, values)| Some((key, values.split_whitespace().next()?)))
+                    .ok_or_else(|| Error::Parse(format!("Invalid translation entry: {}", line)))?;
+                dictionary.insert(key, value);
+            }
+        }
+        Ok(dictionary)
+    }
+
+    fn insert(&mut self, from: &str, to: &str) {
+        self.max_len = self.max_len.max(from.chars().count());
+        self.entries.insert(from.to_string(), to.to_string());
+    }
+
+    fn convert(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result = String::with_capacity(text.len());
+
+        let mut i = 0;
+        'outer: while i < chars.len() {
+            for len in (1..=self.max_len.min(chars.len() - i)).rev() {
+                let key: String = chars[i..i + len].iter().collect();
+                if let Some(value) = self.entries.get(&key) {
+                    result.push_str(value);
+                    i += len;
+                    continue 'outer;
+                }
+            }
+            result.push(chars[i]);
+            i += 1;
+        }
+
+        result
+    }
+}
+
+/// OpenCC converter backed by the embedded OpenCC dictionaries
+///
+/// Phrases take precedence over single characters, so that e.g. 头发 becomes
+/// 頭髮 while 发现 becomes 發現. Regional and Japanese variants are applied
+/// on top as in the OpenCC `s2tw`, `s2hk` and `t2jp` configurations.
 pub struct OpenCCConverter {
-    // Will use opencc-rust library
+    s2t: Dictionary,
+    t2s: Dictionary,
+    tw: Dictionary,
+    hk: Dictionary,
+    jp: Dictionary,
 }
 
 impl OpenCCConverter {
+    /// Load the embedded dictionaries
     pub fn new() -> Result<Self> {
-        Ok(OpenCCConverter {})
+        Ok(OpenCCConverter {
+            s2t: Dictionary::load(&[ST_CHARACTERS, ST_PHRASES])?,
+            t2s: Dictionary::load(&[TS_CHARACTERS, TS_PHRASES])?,
+            tw: Dictionary::load(&[TW_VARIANTS])?,
+            hk: Dictionary::load(&[HK_VARIANTS])?,
+            jp: Dictionary::load(&[JP_VARIANTS])?,
+        })
+    }
+}
+
+impl Default for OpenCCConverter {
+    fn default() -> Self {
+        Self::new().expect("Failed to load translation table")
     }
 }
 
 impl ChineseConverter for OpenCCConverter {
     fn to_simplified(&self, text: &str) -> Result<String> {
-        // TODO: Implement using opencc-rust
-        // For now, return as-is
-        Ok(text.to_string())
+        Ok(self.t2s.convert(text))
     }
 
     fn to_traditional(&self, text: &str) -> Result<String> {
-        // TODO: Implement using opencc-rust
-        // For now, return as-is
-        Ok(text.to_string())
+        Ok(self.s2t.convert(text))
+    }
+
+    fn convert(&self, text: &str, translation: TranslationType) -> Result<String> {
+        Ok(match translation {
+            TranslationType::None => text.to_string(),
+            TranslationType::ToSimplified => self.t2s.convert(text),
+            TranslationType::ToTraditional => self.s2t.convert(text),
+            TranslationType::ToTraditionalTaiwan => self.tw.convert(&self.s2t.convert(text)),
+            TranslationType::ToTraditionalHongKong => self.hk.convert(&self.s2t.convert(text)),
+            TranslationType::ToJapanese => self.jp.convert(text),
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::WordLibrary;
 
     #[test]
     fn test_translation_type() {
@@ -67,4 +265,84 @@ mod tests {
         let result = converter.to_simplified("测试").unwrap();
         assert_eq!(result, "测试");
     }
+
+    #[test]
+    fn test_simplified_traditional() {
+        let converter = OpenCCConverter::new().unwrap();
+
+        assert_eq!(converter.to_traditional("计算机").unwrap(), "計算機");
+        assert_eq!(
+            converter.to_traditional("发现头发很干净").unwrap(),
+            "發現頭髮很乾淨"
+        );
+        assert_eq!(
+            converter.to_simplified("發現頭髮很乾淨").unwrap(),
+            "发现头发很干净"
+        );
+        assert_eq!(converter.to_traditional("忧郁的龟").unwrap(), "憂鬱的龜");
+        assert_eq!(converter.to_simplified("憂鬱的龜").unwrap(), "忧郁的龟");
+    }
+
+    #[test]
+    fn test_regional_variants() {
+        let converter = OpenCCConverter::new().unwrap();
+
+        let tw = converter
+            .convert("里面", TranslationType::ToTraditionalTaiwan)
+            .unwrap();
+        assert_eq!(tw, "裡面");
+
+        let hk = converter
+            .convert("卫星", TranslationType::ToTraditionalHongKong)
+            .unwrap();
+        assert_eq!(hk, "衞星");
+
+        let jp = converter
+            .convert("國語", TranslationType::ToJapanese)
+            .unwrap();
+        assert_eq!(jp, "国語");
+    }
+
+    #[test]
+    fn test_regenerate_codes() {
+        let converter = OpenCCConverter::new().unwrap();
+
+        let mut wubi = WordLibrary::new("中国".to_string());
+        wubi.set_code(CodeType::Wubi, Code::from_single("khlg".to_string()));
+        let mut pinyin = WordLibrary::new("中国".to_string());
+        pinyin.set_code(
+            CodeType::Pinyin,
+            Code::from_char_list(vec!["zhong".to_string(), "guo".to_string()]),
+        );
+
+        let mut unknown = WordLibrary::new("国㐀".to_string());
+        unknown.set_code(CodeType::Wubi, Code::from_single("lgaa".to_string()));
+        let mut unchanged = WordLibrary::new("中".to_string());
+        unchanged.set_code(CodeType::Wubi, Code::from_single("k".to_string()));
+        let mut words: WordLibraryList = vec![wubi, pinyin, unknown, unchanged].into();
+
+        let changed =
+            translate_word_list(&converter, &mut words, TranslationType::ToTraditional).unwrap();
+        assert_eq!(changed, vec![0, 1, 2]);
+        let failures = regenerate_codes(&mut words, &changed);
+
+        // 國 is lakg rather than 国 lgyi
+        assert_eq!(words[0].word, "中國");
+        assert_eq!(words[0].get_single_code(), Some("khla"));
+        assert_eq!(words[1].get_pinyin_string("'"), "zhong'guo");
+        // Failed words keep their codes, untranslated words are not touched
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "國㐀");
+        assert_eq!(words[2].get_single_code(), Some("lgaa"));
+        assert_eq!(words[3].get_single_code(), Some("k"));
+    }
+
+    #[test]
+    fn test_translate_word_list() {
+        let converter = OpenCCConverter::new().unwrap();
+        let mut words: WordLibraryList = vec![WordLibrary::new("中国".to_string())].into();
+
+        translate_word_list(&converter, &mut words, TranslationType::ToTraditional).unwrap();
+        assert_eq!(words[0].word, "中國");
+    }
 }
@@ -0,0 +1,131 @@
+//! Structural validation of word libraries
+//!
+//! A library is imported in strict mode, so that bad offsets, truncated
+//! entries and malformed lines fail the import, and the imported words are
+//! then checked for text that did not decode cleanly (invalid UTF-16 or GBK
+//! turns into U+FFFD) and for pinyin codes that are not legal syllables.
+
+use crate::helpers::pinyin::is_pinyin_syllable;
+use crate::import::{ErrorPolicy, ImportOptions, WordLibraryImport};
+use crate::{CodeType, WordLibrary, WordLibraryList};
+use serde::Serialize;
+
+/// A structural problem of a word library
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationProblem {
+    /// Position of the word in the library, None when the import failed
+    pub index: Option<usize>,
+    pub word: Option<String>,
+    pub message: String,
+}
+
+/// Result of validating a word library
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ValidationReport {
+    /// Words imported
+    pub words: usize,
+    pub problems: Vec<ValidationProblem>,
+}
+
+impl ValidationReport {
+    /// Whether no problem was found
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Import a file in strict mode and check the imported words
+pub fn validate_file(
+    importer: &dyn WordLibraryImport,
+    path: &str,
+    options: &ImportOptions,
+) -> ValidationReport {
+    let options = options.clone().with_error_policy(ErrorPolicy::Strict);
+    match importer.import_library(path, &options) {
+        Ok(words) => validate_words(&words),
+        Err(e) => ValidationReport {
+            words: 0,
+            problems: vec![ValidationProblem {
+                index: None,
+                word: None,
+                message: e.to_string(),
+            }],
+        },
+    }
+}
+
+/// Check imported words for undecodable text and illegal pinyin
+pub fn validate_words(words: &WordLibraryList) -> ValidationReport {
+    let problems = words
+        .iter()
+        .enumerate()
+        .filter_map(|(index, word)| {
+            check(word).map(|message| ValidationProblem {
+                index: Some(index),
+                word: Some(word.word.clone()),
+                message,
+            })
+        })
+        .collect();
+    ValidationReport {
+        words: words.len(),
+        problems,
+    }
+}
+
+fn check(word: &WordLibrary) -> Option<String> {
+    if word.word.trim().is_empty() {
+        return Some("Empty word".into());
+    }
+    if word.word.contains('\u{fffd}') {
+        return Some("Word is not valid text in the file encoding".into());
+    }
+    if let Some(c) = word.word.chars().find(|c| c.is_control()) {
+        return Some(format!("Control character U+{:04X} in word", c as u32));
+    }
+    if matches!(word.code_type, CodeType::Pinyin | CodeType::TerraPinyin) {
+        let illegal: Vec<&str> = word
+            .codes
+            .0
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|s| !is_pinyin_syllable(s))
+            .collect();
+        if !illegal.is_empty() {
+            return Some(format!("Illegal pinyin: {}", illegal.join(" ")));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Code;
+
+    fn word(text: &str, code_type: CodeType, codes: &[&str]) -> WordLibrary {
+        let mut wl = WordLibrary::new(text.to_string());
+        wl.code_type = code_type;
+        wl.codes = Code::from_char_list(codes.iter().map(|c| c.to_string()).collect());
+        wl
+    }
+
+    #[test]
+    fn test_validate_words() {
+        let words: WordLibraryList = vec![
+            word("你好", CodeType::Pinyin, &["ni3", "hao3"]),
+            word("你好", CodeType::Pinyin, &["ni", "hoa"]),
+            word("你\u{fffd}", CodeType::Pinyin, &["ni", "hao"]),
+            word("工", CodeType::Wubi, &["aaaa"]),
+        ]
+        .into();
+
+        let report = validate_words(&words);
+        assert_eq!(report.words, 4);
+        let indices: Vec<_> = report.problems.iter().map(|p| p.index).collect();
+        assert_eq!(indices, vec![Some(1), Some(2)]);
+        assert_eq!(report.problems[0].message, "Illegal pinyin: hoa");
+        assert!(!report.is_valid());
+    }
+}
@@ -0,0 +1,182 @@
+//! Checking imported codes against generated ones
+//!
+//! Codes are regenerated with the built-in generator of each word's code
+//! type. Per-character codes (pinyin) are accepted when every character's
+//! code is one of its readings; word codes (Wubi) must match a generated
+//! code, single characters may also use a short code.
+
+use crate::generate::{generator_for, CodeGenerator};
+use crate::resource::ResourceManager;
+use crate::{Code, CodeType, WordLibrary, WordLibraryList};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A word whose codes differ from the generated ones
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CodeMismatch {
+    /// Position of the word in the checked list
+    pub index: usize,
+    pub word: WordLibrary,
+    pub expected: Code,
+}
+
+/// Result of checking a word list
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VerifyReport {
+    /// Words whose codes were checked
+    pub checked: usize,
+    /// Words without codes, without a generator for their code type or with
+    /// characters missing from the tables
+    pub skipped: usize,
+    pub mismatches: Vec<CodeMismatch>,
+}
+
+/// Checks word codes against the built-in code generators
+pub struct CodeVerifier {
+    resources: Arc<ResourceManager>,
+}
+
+impl CodeVerifier {
+    pub fn new() -> Self {
+        Self::with_resources(ResourceManager::shared())
+    }
+
+    /// Initialize with existing resource manager (for sharing)
+    pub fn with_resources(resources: Arc<ResourceManager>) -> Self {
+        CodeVerifier { resources }
+    }
+
+    /// Check the codes of every word
+    pub fn verify(&self, words: &WordLibraryList) -> VerifyReport {
+        let mut generators: HashMap<CodeType, Option<Box<dyn CodeGenerator>>> = HashMap::new();
+        let mut report = VerifyReport::default();
+
+        for (index, word) in words.iter().enumerate() {
+            let generator = generators
+                .entry(word.code_type)
+                .or_insert_with(|| generator_for(word.code_type, self.resources.clone()).ok());
+            let Some(generator) = generator.as_deref() else {
+                report.skipped += 1;
+                continue;
+            };
+            if !word.has_code() {
+                report.skipped += 1;
+                continue;
+            }
+            match check(generator, word) {
+                Some(true) => report.checked += 1,
+                Some(false) => match generator.generate_code_for_string(&word.word) {
+                    Ok(expected) => {
+                        let expected = match_tones(expected, &word.codes);
+                        report.checked += 1;
+                        report.mismatches.push(CodeMismatch {
+                            index,
+                            word: word.clone(),
+                            expected,
+                        });
+                    }
+                    Err(_) => report.skipped += 1,
+                },
+                None => report.skipped += 1,
+            }
+        }
+        report
+    }
+
+    /// Check the codes of every word, replacing mismatched codes with the
+    /// generated ones
+    pub fn fix(&self, words: &mut WordLibraryList) -> VerifyReport {
+        let report = self.verify(words);
+        for mismatch in &report.mismatches {
+            words[mismatch.index].codes = mismatch.expected.clone();
+        }
+        report
+    }
+}
+
+impl Default for CodeVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pinyin tables may carry tone numbers
+fn without_tone(code: &str) -> &str {
+    code.trim_end_matches(|c: char| c.is_ascii_digit())
+}
+
+/// Drop the tone numbers of generated codes unless the word's codes have them
+fn match_tones(expected: Code, codes: &Code) -> Code {
+    let has_tones = codes.0.iter().flatten().any(|c| without_tone(c) != c);
+    if has_tones {
+        return expected;
+    }
+    Code::from_chars(
+        expected
+            .0
+            .iter()
+            .map(|part| part.iter().map(|c| without_tone(c).to_string()).collect())
+            .collect(),
+    )
+}
+
+/// Check a word's codes, None when they cannot be generated
+fn check(generator: &dyn CodeGenerator, word: &WordLibrary) -> Option<bool> {
+    if generator.is_one_code_per_char() {
+        let parts = word.codes.get_default_codes();
+        if parts.len() != word.len() {
+            return Some(false);
+        }
+        for (part, c) in parts.iter().zip(word.word.chars()) {
+            let readings = generator.get_codes_for_char(c).ok()?;
+            if !readings
+                .iter()
+                .any(|r| without_tone(r).eq_ignore_ascii_case(without_tone(part)))
+            {
+                return Some(false);
+            }
+        }
+        return Some(true);
+    }
+
+    let code = word.get_word_code()?;
+    let expected = generator.generate_code_for_string(&word.word).ok()?;
+    let mut candidates = expected.0.iter().flatten();
+    // Single characters are often typed with a short code (简码)
+    let short_code = word.len() == 1 && generator.code_type().is_glyph_based();
+    Some(candidates.any(|c| *c == code || (short_code && c.starts_with(&code))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, code_type: CodeType, codes: &[&str]) -> WordLibrary {
+        let mut wl = WordLibrary::new(text.to_string());
+        wl.code_type = code_type;
+        wl.codes = Code::from_char_list(codes.iter().map(|c| c.to_string()).collect());
+        wl
+    }
+
+    #[test]
+    fn test_verify_and_fix() {
+        let mut words: WordLibraryList = vec![
+            word("银行", CodeType::Pinyin, &["yin", "hang"]),
+            word("长城", CodeType::Pinyin, &["chang", "cheng"]),
+            word("你好", CodeType::Pinyin, &["ni", "hai"]),
+            word("你好", CodeType::Wubi, &["wqvb"]),
+            word("你", CodeType::Wubi, &["wq"]),
+            word("中国人", CodeType::Wubi, &["kl"]),
+            word("你好", CodeType::UserDefinePhrase, &["nh"]),
+        ]
+        .into();
+
+        let report = CodeVerifier::new().fix(&mut words);
+        let indices: Vec<usize> = report.mismatches.iter().map(|m| m.index).collect();
+        assert_eq!(indices, vec![2, 5]);
+        assert_eq!((report.checked, report.skipped), (6, 1));
+        assert_eq!(words[2].get_pinyin_string(" "), "ni hao");
+        assert_eq!(words[5].get_word_code().as_deref(), Some("klww"));
+    }
+}
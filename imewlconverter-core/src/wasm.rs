@@ -0,0 +1,23 @@
+//! JavaScript bindings for the WebAssembly build (feature `wasm`)
+//!
+//! Build for `wasm32-unknown-unknown`:
+//!
+//! ```text
+//! cargo build -p imewlconverter-core --target wasm32-unknown-unknown \
+//!     --features wasm
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+/// Convert a word library in memory between two built-in formats
+///
+/// Formats are names or aliases as listed by `imewlconverter list-formats`.
+#[wasm_bindgen(js_name = convertBytes)]
+pub fn convert_bytes(
+    input: &[u8],
+    input_format: &str,
+    output_format: &str,
+) -> Result<Vec<u8>, JsError> {
+    crate::pipeline::convert_bytes(input, input_format, output_format)
+        .map_err(|e| JsError::new(&e.to_string()))
+}
@@ -0,0 +1,35 @@
+#ifndef IMEWLCONVERTER_H
+#define IMEWLCONVERTER_H
+
+/* Generated by cbindgen from imewlconverter-ffi; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Convert a word library file between two formats
+//
+// Formats are names or aliases of the built-in formats. Split outputs are
+// written next to `output_path` with a number appended to the file stem.
+// `options_json` may be null or a JSON object of options.
+//
+// Returns the number of exported words, or -1 on failure; the error message
+// is then available from `imewlconverter_last_error`.
+//
+// # Safety
+// All non-null arguments must point to NUL-terminated strings; only
+// `options_json` may be null.
+int64_t imewlconverter_convert(const char *input_path,
+                               const char *input_format,
+                               const char *output_path,
+                               const char *output_format,
+                               const char *options_json);
+
+// Message of the last failed call on the calling thread, or null
+//
+// The string is owned by the library and stays valid until the next call of
+// `imewlconverter_convert` fails on the same thread.
+const char *imewlconverter_last_error(void);
+
+#endif  /* IMEWLCONVERTER_H */
@@ -0,0 +1,264 @@
+//! C bindings for the conversion pipeline
+//!
+//! Frontends written in other languages call `imewlconverter_convert` with
+//! paths and format names (see `imewlconverter list-formats`) and read the
+//! message of a failed call with `imewlconverter_last_error`. The C header is
+//! `include/imewlconverter.h`, generated by cbindgen.
+//!
+//! Options are passed as a JSON object, all fields optional:
+//!
+//! ```json
+//! {
+//!   "input_encoding": "gbk",
+//!   "on_error": "skip",
+//!   "sort": "pinyin",
+//!   "english": "keep",
+//!   "dedup": true
+//! }
+//! ```
+//!
+//! With the `python` feature the library is also the `imewlconverter` Python
+//! module, built with maturin.
+
+#[cfg(feature = "python")]
+mod python;
+
+use imewlconverter_core::batch::numbered_path;
+use imewlconverter_core::export::EnglishPolicy;
+use imewlconverter_core::helpers::write_file;
+use imewlconverter_core::import::ErrorPolicy;
+use imewlconverter_core::pipeline::ConversionPipeline;
+use imewlconverter_core::registry::FormatRegistry;
+use imewlconverter_core::sort::{SortOrder, WordLibrarySorter};
+use imewlconverter_core::{Error, Result};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+
+thread_local! {
+    /// Message of the last failed call on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Conversion options of `imewlconverter_convert`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConvertOptions {
+    input_encoding: Option<String>,
+    on_error: Option<String>,
+    sort: Option<String>,
+    english: Option<String>,
+    dedup: bool,
+}
+
+impl ConvertOptions {
+    fn parse(json: Option<&str>) -> Result<Self> {
+        match json {
+            Some(json) if !json.trim().is_empty() => serde_json::from_str(json)
+                .map_err(|e| Error::Parse(format!("Invalid options: {}", e))),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Apply the options to a pipeline
+    fn configure(&self, mut pipeline: ConversionPipeline) -> Result<ConversionPipeline> {
+        if let Some(encoding) = &self.input_encoding {
+            pipeline = pipeline.with_input_encoding(encoding);
+        }
+        if let Some(policy) = &self.on_error {
+            pipeline = pipeline.with_error_policy(match policy.as_str() {
+                "strict" => ErrorPolicy::Strict,
+                "warn" => ErrorPolicy::Warn,
+                "skip" => ErrorPolicy::Skip,
+                other => return Err(invalid_option("on_error", other)),
+            });
+        }
+        if let Some(order) = &self.sort {
+            let order = match order.as_str() {
+                "input" => SortOrder::Input,
+                "rank" => SortOrder::RankDescending,
+                "code" => SortOrder::Code,
+                "word" => SortOrder::Word,
+                "pinyin" => SortOrder::WordPinyin,
+                other => return Err(invalid_option("sort", other)),
+            };
+            pipeline = pipeline.with_sorter(WordLibrarySorter::new(order));
+        }
+        if let Some(policy) = &self.english {
+            pipeline = pipeline.english_policy(match policy.as_str() {
+                "keep" => EnglishPolicy::Keep,
+                "drop" => EnglishPolicy::Drop,
+                "separate" => EnglishPolicy::Separate,
+                other => return Err(invalid_option("english", other)),
+            });
+        }
+        Ok(pipeline.merge_duplicates(self.dedup))
+    }
+}
+
+fn invalid_option(name: &str, value: &str) -> Error {
+    Error::Parse(format!("Invalid value '{}' for option {}", value, name))
+}
+
+/// Borrow a required C string argument
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(Error::Parse(format!("{} is null", name)));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| Error::Encoding(format!("{} is not valid UTF-8", name)))
+}
+
+/// Convert one file, returning the number of exported words
+fn convert(
+    input_path: &str,
+    input_format: &str,
+    output_path: &str,
+    output_format: &str,
+    options: Option<&str>,
+) -> Result<usize> {
+    let options = ConvertOptions::parse(options)?;
+    let registry = FormatRegistry::with_builtin_formats();
+    let pipeline = options.configure(
+        ConversionPipeline::new(
+            registry.importer(input_format)?,
+            registry.exporter(output_format)?,
+        )
+        .with_source_format(input_format),
+    )?;
+
+    let words = pipeline.import(&[input_path])?;
+    let words = pipeline.process(words)?;
+    let contents = pipeline.export(&words)?;
+    for (i, content) in contents.iter().enumerate() {
+        write_file(
+            &numbered_path(Path::new(output_path), i),
+            content,
+            pipeline.exporter().encoding(),
+        )?;
+    }
+
+    Ok(words.len())
+}
+
+fn set_last_error(message: String) {
+    // Interior NUL bytes would truncate the message
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+/// Convert a word library file between two formats
+///
+/// Formats are names or aliases of the built-in formats. Split outputs are
+/// written next to `output_path` with a number appended to the file stem.
+/// `options_json` may be null or a JSON object of options.
+///
+/// Returns the number of exported words, or -1 on failure; the error message
+/// is then available from `imewlconverter_last_error`.
+///
+/// # Safety
+/// All non-null arguments must point to NUL-terminated strings; only
+/// `options_json` may be null.
+#[no_mangle]
+pub unsafe extern "C" fn imewlconverter_convert(
+    input_path: *const c_char,
+    input_format: *const c_char,
+    output_path: *const c_char,
+    output_format: *const c_char,
+    options_json: *const c_char,
+) -> i64 {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let options = if options_json.is_null() {
+            None
+        } else {
+            Some(str_arg(options_json, "options_json")?)
+        };
+        convert(
+            str_arg(input_path, "input_path")?,
+            str_arg(input_format, "input_format")?,
+            str_arg(output_path, "output_path")?,
+            str_arg(output_format, "output_format")?,
+            options,
+        )
+    }));
+
+    match result {
+        Ok(Ok(count)) => i64::try_from(count).unwrap_or(i64::MAX),
+        Ok(Err(e)) => {
+            set_last_error(e.to_string());
+            -1
+        }
+        Err(_) => {
+            set_last_error("Conversion panicked".to_string());
+            -1
+        }
+    }
+}
+
+/// Message of the last failed call on the calling thread, or null
+///
+/// The string is owned by the library and stays valid until the next call of
+/// `imewlconverter_convert` fails on the same thread.
+#[no_mangle]
+pub extern "C" fn imewlconverter_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn test_convert() {
+        let dir = std::env::temp_dir().join("imewlconverter_ffi_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("in.txt");
+        let output = dir.join("out.txt");
+        std::fs::write(&input, "你好\tni hao\t1\n你好\tni hao\t1\n").unwrap();
+
+        let input = c(input.to_str().unwrap());
+        let output_path = c(output.to_str().unwrap());
+        let count = unsafe {
+            imewlconverter_convert(
+                input.as_ptr(),
+                c("rime").as_ptr(),
+                output_path.as_ptr(),
+                c("rime").as_ptr(),
+                c(r#"{"dedup": true}"#).as_ptr(),
+            )
+        };
+        let converted = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(count, 1);
+        assert_eq!(converted, "你好\tni hao\t1");
+
+        let count = unsafe {
+            imewlconverter_convert(
+                input.as_ptr(),
+                c("nope").as_ptr(),
+                output_path.as_ptr(),
+                c("rime").as_ptr(),
+                ptr::null(),
+            )
+        };
+        assert_eq!(count, -1);
+        let message = unsafe { CStr::from_ptr(imewlconverter_last_error()) };
+        assert!(message.to_str().unwrap().contains("nope"));
+    }
+}
@@ -0,0 +1,296 @@
+//! Python bindings (feature `python`)
+//!
+//! Build the `imewlconverter` module with maturin, see `pyproject.toml`:
+//!
+//! ```python
+//! import imewlconverter
+//!
+//! words = imewlconverter.Importer("sogou-scel").import_file("cell.scel")
+//! kept = [w for w in words if w.rank > 10]
+//! imewlconverter.Exporter("rime").write_file(kept, "out.dict.yaml")
+//! ```
+
+use imewlconverter_core::batch::numbered_path;
+use imewlconverter_core::export::{write_outputs, WordLibraryExport};
+use imewlconverter_core::helpers::write_file;
+use imewlconverter_core::import::{ImportOptions, WordLibraryImport};
+use imewlconverter_core::registry::FormatRegistry;
+use imewlconverter_core::{Code, Error, WordLibrary, WordLibraryList};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyIndexError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::path::Path;
+
+create_exception!(imewlconverter, ConversionError, PyException);
+
+fn py_err(error: Error) -> PyErr {
+    ConversionError::new_err(error.to_string())
+}
+
+/// Dictionary entry
+#[pyclass(name = "WordLibrary", module = "imewlconverter")]
+#[derive(Clone)]
+struct PyWordLibrary(WordLibrary);
+
+#[pymethods]
+impl PyWordLibrary {
+    /// Create an entry; `codes` holds the codes of each character
+    #[new]
+    #[pyo3(signature = (word, rank = 0, codes = None))]
+    fn new(word: String, rank: i32, codes: Option<Vec<Vec<String>>>) -> Self {
+        let mut entry = WordLibrary::with_rank(word, rank);
+        if let Some(codes) = codes {
+            entry.codes = Code::from_chars(codes);
+        }
+        PyWordLibrary(entry)
+    }
+
+    #[getter]
+    fn word(&self) -> &str {
+        &self.0.word
+    }
+
+    #[setter]
+    fn set_word(&mut self, word: String) {
+        self.0.word = word;
+    }
+
+    #[getter]
+    fn rank(&self) -> i32 {
+        self.0.rank
+    }
+
+    #[setter]
+    fn set_rank(&mut self, rank: i32) {
+        self.0.rank = rank;
+    }
+
+    #[getter]
+    fn codes(&self) -> Vec<Vec<String>> {
+        self.0.codes.0.clone()
+    }
+
+    #[setter]
+    fn set_codes(&mut self, codes: Vec<Vec<String>>) {
+        self.0.codes = Code::from_chars(codes);
+    }
+
+    #[getter]
+    fn code_type(&self) -> String {
+        format!("{:?}", self.0.code_type)
+    }
+
+    #[getter]
+    fn is_english(&self) -> bool {
+        self.0.is_english
+    }
+
+    #[getter]
+    fn is_symbol(&self) -> bool {
+        self.0.is_symbol_phrase()
+    }
+
+    #[getter]
+    fn position(&self) -> Option<u8> {
+        self.0.position
+    }
+
+    /// Pinyin of the entry, syllables joined by `separator`
+    #[pyo3(signature = (separator = " "))]
+    fn pinyin(&self, separator: &str) -> String {
+        self.0.get_pinyin_string(separator)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "WordLibrary({:?}, rank={}, codes={:?})",
+            self.0.word, self.0.rank, self.0.codes.0
+        )
+    }
+}
+
+/// Imported entries, a read-only sequence of `WordLibrary`
+#[pyclass(name = "WordList", module = "imewlconverter", sequence)]
+struct WordList(WordLibraryList);
+
+#[pymethods]
+impl WordList {
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __getitem__(&self, index: isize) -> PyResult<PyWordLibrary> {
+        let len = self.0.len() as isize;
+        let index = if index < 0 { index + len } else { index };
+        if !(0..len).contains(&index) {
+            return Err(PyIndexError::new_err("WordList index out of range"));
+        }
+        Ok(PyWordLibrary(self.0[index as usize].clone()))
+    }
+
+    fn __iter__(&self) -> WordListIter {
+        WordListIter(self.0.clone().into_iter())
+    }
+}
+
+#[pyclass(module = "imewlconverter")]
+struct WordListIter(std::vec::IntoIter<WordLibrary>);
+
+#[pymethods]
+impl WordListIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<PyWordLibrary> {
+        self.0.next().map(PyWordLibrary)
+    }
+}
+
+/// Collect the entries of any iterable of `WordLibrary`
+fn collect_words(words: &Bound<'_, PyAny>) -> PyResult<WordLibraryList> {
+    words
+        .try_iter()?
+        .map(|word| Ok(word?.extract::<PyWordLibrary>()?.0))
+        .collect()
+}
+
+/// Importer of a built-in format
+#[pyclass(module = "imewlconverter", unsendable)]
+struct Importer(Box<dyn WordLibraryImport>);
+
+#[pymethods]
+impl Importer {
+    #[new]
+    fn new(format: &str) -> PyResult<Self> {
+        let registry = FormatRegistry::with_builtin_formats();
+        Ok(Importer(registry.importer(format).map_err(py_err)?))
+    }
+
+    /// Import a file, in the format's default encoding unless given
+    #[pyo3(signature = (path, encoding = None))]
+    fn import_file(&self, path: &str, encoding: Option<&str>) -> PyResult<WordList> {
+        let words = self
+            .0
+            .import_library(path, &options(encoding))
+            .map_err(py_err)?;
+        Ok(WordList(words))
+    }
+
+    /// Import the content of a file
+    #[pyo3(signature = (data, encoding = None))]
+    fn import_bytes(&self, data: &[u8], encoding: Option<&str>) -> PyResult<WordList> {
+        let words = self
+            .0
+            .import_library_from_reader(&mut &data[..], &options(encoding))
+            .map_err(py_err)?;
+        Ok(WordList(words))
+    }
+}
+
+fn options(encoding: Option<&str>) -> ImportOptions {
+    match encoding {
+        Some(encoding) => ImportOptions::new().with_encoding(encoding),
+        None => ImportOptions::new(),
+    }
+}
+
+/// Exporter of a built-in format
+#[pyclass(module = "imewlconverter", unsendable)]
+struct Exporter(Box<dyn WordLibraryExport>);
+
+#[pymethods]
+impl Exporter {
+    #[new]
+    fn new(format: &str) -> PyResult<Self> {
+        let registry = FormatRegistry::with_builtin_formats();
+        Ok(Exporter(registry.exporter(format).map_err(py_err)?))
+    }
+
+    /// Encoding of the exported files
+    #[getter]
+    fn encoding(&self) -> &'static str {
+        self.0.encoding()
+    }
+
+    /// Export entries to text, one string per output file
+    fn export(&self, words: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+        self.0.export(&collect_words(words)?).map_err(py_err)
+    }
+
+    /// Export entries to encoded bytes, split outputs separated by line breaks
+    fn export_bytes<'py>(
+        &self,
+        py: Python<'py>,
+        words: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let outputs = self.export(words)?;
+        let mut bytes = Vec::new();
+        write_outputs(&outputs, self.0.encoding(), &mut bytes).map_err(py_err)?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    /// Export entries to a file; split outputs get a number appended
+    fn write_file(&self, words: &Bound<'_, PyAny>, path: &str) -> PyResult<()> {
+        for (i, output) in self.export(words)?.iter().enumerate() {
+            let path = numbered_path(Path::new(path), i);
+            write_file(&path, output, self.0.encoding()).map_err(py_err)?;
+        }
+        Ok(())
+    }
+}
+
+#[pymodule]
+fn imewlconverter(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWordLibrary>()?;
+    m.add_class::<WordList>()?;
+    m.add_class::<Importer>()?;
+    m.add_class::<Exporter>()?;
+    m.add("ConversionError", m.py().get_type::<ConversionError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::ffi::c_str;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn test_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "imewlconverter").unwrap();
+            imewlconverter(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("imewlconverter", module).unwrap();
+
+            py.run(
+                c_str!(
+                    r#"
+data = "你好\tni hao\t10\n世界\tshi jie\t5\n".encode()
+words = imewlconverter.Importer("rime").import_bytes(data)
+assert len(words) == 2 and words[-1].word == "世界"
+assert [w.pinyin() for w in words] == ["ni hao", "shi jie"]
+
+kept = [w for w in words if w.rank > 5]
+kept.append(imewlconverter.WordLibrary("中国", 3, [["zhong"], ["guo"]]))
+exporter = imewlconverter.Exporter("rime")
+assert exporter.export(kept) == ["你好\tni hao\t10\n中国\tzhong guo\t3"]
+assert exporter.export_bytes(words) == data.rstrip(b"\n")
+
+try:
+    imewlconverter.Importer("nope")
+    raise AssertionError("unknown format accepted")
+except imewlconverter.ConversionError:
+    pass
+"#
+                ),
+                Some(&globals),
+                None,
+            )
+            .unwrap();
+        });
+    }
+}